@@ -0,0 +1,175 @@
+//! Ownership proofs, purchase and compliance attestations, and warranty
+//! certificates.
+use soroban_sdk::xdr::{FromXdr, ToXdr};
+use soroban_sdk::{symbol_short, Address, Bytes, BytesN, Env, Vec};
+
+use super::*;
+
+pub(crate) fn prove_ownership(
+    env: Env,
+    warranty_id: u64,
+    challenge: BytesN<32>,
+) -> Result<(), WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    OwnershipProved {
+        warranty_id,
+        challenge,
+        owner: warranty.owner,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+pub(crate) fn attach_proof(
+    env: Env,
+    warranty_id: u64,
+    attester: Address,
+    proof_hash: BytesN<32>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    attester.require_auth();
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    if attester != warranty.owner && !is_warranty_manufacturer(&env, &attester, &warranty) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if warranty.receipt_hash.is_some() {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    warranty.receipt_hash = Some(proof_hash);
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+
+    notify_change(&env, symbol_short!("proof"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn verify_proof(
+    env: Env,
+    warranty_id: u64,
+    document: Bytes,
+) -> Result<bool, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+    let hash: BytesN<32> = env.crypto().sha256(&document).into();
+    Ok(warranty.receipt_hash == Some(hash))
+}
+
+pub(crate) fn attest_purchase(
+    env: Env,
+    warranty_id: u64,
+    retailer: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "attestation")?;
+
+    let since = audit_tail(&env);
+
+    retailer.require_auth();
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    if !roles::is_authorized_retailer(env.clone(), warranty.manufacturer.clone(), retailer.clone())
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if warranty.attestation != SaleAttestation::Unattested {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    warranty.attestation = SaleAttestation::Attested(PurchaseAttestation {
+        retailer: retailer.clone(),
+        attested_at: env.ledger().timestamp(),
+    });
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+
+    record_history(
+        &env,
+        warranty_id,
+        &retailer,
+        symbol_short!("attest"),
+        HistoryDetails::PurchaseAttested(retailer.clone()),
+    );
+    notify_change(&env, symbol_short!("attest"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn attest_compliance(
+    env: Env,
+    provider: Address,
+    warranty_id: u64,
+    compliant: bool,
+    evidence_hash: BytesN<32>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    provider.require_auth();
+
+    if !roles::is_logistics_provider(env.clone(), provider.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if load_warranty(&env, warranty_id).is_none() {
+        return Err(WarrantyError::NotFound);
+    }
+
+    let key = DataKey::ComplianceTrail(warranty_id);
+    let mut trail: Vec<ComplianceAttestation> = read_record(&env, &key).unwrap_or(Vec::new(&env));
+    if trail.len() >= MAX_COMPLIANCE_ATTESTATIONS {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    trail.push_back(ComplianceAttestation {
+        provider,
+        compliant,
+        evidence_hash,
+        attested_at: env.ledger().timestamp(),
+    });
+    write_record(&env, &key, &trail);
+    Ok(())
+}
+
+pub(crate) fn get_compliance_trail(env: Env, warranty_id: u64) -> Vec<ComplianceAttestation> {
+    read_record(&env, &DataKey::ComplianceTrail(warranty_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn has_clean_compliance(env: Env, warranty_id: u64) -> bool {
+    get_compliance_trail(env, warranty_id)
+        .iter()
+        .all(|attestation| attestation.compliant)
+}
+
+pub(crate) fn export_certificate(env: Env, warranty_id: u64) -> Result<Bytes, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+    if warranty.private {
+        warranty.owner.require_auth();
+    }
+
+    Ok(WarrantyCertificate {
+        contract: env.current_contract_address(),
+        warranty,
+        issued_at: env.ledger().timestamp(),
+        issued_ledger: env.ledger().sequence(),
+    }
+    .to_xdr(&env))
+}
+
+pub(crate) fn verify_certificate(env: Env, certificate: Bytes) -> bool {
+    let Ok(certificate) = WarrantyCertificate::from_xdr(&env, &certificate) else {
+        return false;
+    };
+    if certificate.contract != env.current_contract_address() {
+        return false;
+    }
+
+    load_warranty(&env, certificate.warranty.id) == Some(certificate.warranty)
+}
@@ -0,0 +1,408 @@
+//! Product catalog and manufacturer programs: campaigns, terms templates,
+//! badges, policy documents, extended warranty sales and retailer reports.
+use soroban_sdk::{token, Address, BytesN, Env, Map, String, Vec};
+
+use super::*;
+
+pub(crate) fn add_product(
+    env: Env,
+    manufacturer: Address,
+    model_id: String,
+    name: String,
+    category: Option<String>,
+    default_warranty_duration: u64,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "product_catalog")?;
+
+    let registered = require_manufacturer(&env, &manufacturer)?;
+    if model_id.is_empty() || name.is_empty() || default_warranty_duration == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let key = CatalogKey::Product(model_id.clone());
+    if env.storage().persistent().has(&key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    env.storage().persistent().set(
+        &key,
+        &Product {
+            model_id,
+            name,
+            manufacturer: registered.name,
+            category,
+            default_warranty_duration,
+            added_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn get_product(env: Env, model_id: String) -> Option<Product> {
+    env.storage()
+        .persistent()
+        .get(&CatalogKey::Product(model_id))
+}
+
+pub(crate) fn create_campaign(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    extra_months: u32,
+    start: u64,
+    end: u64,
+    category_filter: Option<String>,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "campaigns")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if extra_months == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if end < start {
+        return Err(WarrantyError::InvalidDates);
+    }
+    if end < env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+
+    let campaign_id: u64 = env
+        .storage()
+        .instance()
+        .get(&CampaignKey::Count)
+        .unwrap_or(0)
+        + 1;
+    env.storage().persistent().set(
+        &CampaignKey::Campaign(campaign_id),
+        &Campaign {
+            issuer,
+            manufacturer: manufacturer.clone(),
+            extra_months,
+            start,
+            end,
+            category_filter,
+        },
+    );
+    env.storage()
+        .instance()
+        .set(&CampaignKey::Count, &campaign_id);
+
+    let running_key = CampaignKey::Running(manufacturer);
+    let mut running: Vec<u64> = read_record(&env, &running_key).unwrap_or(Vec::new(&env));
+    running.push_back(campaign_id);
+    write_record(&env, &running_key, &running);
+
+    Ok(campaign_id)
+}
+
+pub(crate) fn get_campaign(env: Env, campaign_id: u64) -> Option<Campaign> {
+    env.storage()
+        .persistent()
+        .get(&CampaignKey::Campaign(campaign_id))
+}
+
+pub(crate) fn get_campaign_stats(env: Env, campaign_id: u64) -> CampaignStats {
+    read_record(&env, &CampaignKey::Stats(campaign_id)).unwrap_or_default()
+}
+
+pub(crate) fn create_terms_template(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    terms: WarrantyTerms,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "terms_templates")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if terms.max_claims == Some(0)
+        || terms.max_payout.is_some_and(|cap| cap <= 0)
+        || terms.deductible < 0
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let terms_id: u64 = env.storage().instance().get(&TermsKey::Count).unwrap_or(0) + 1;
+    env.storage().persistent().set(
+        &TermsKey::Template(terms_id),
+        &TermsTemplate {
+            terms_id,
+            manufacturer,
+            terms,
+            created_at: env.ledger().timestamp(),
+        },
+    );
+    env.storage().instance().set(&TermsKey::Count, &terms_id);
+    Ok(terms_id)
+}
+
+pub(crate) fn get_terms_template(env: Env, terms_id: u64) -> Option<TermsTemplate> {
+    terms_template(&env, terms_id)
+}
+
+pub(crate) fn get_warranty_terms(
+    env: Env,
+    warranty_id: u64,
+) -> Result<Option<WarrantyTerms>, WarrantyError> {
+    Ok(warranty_terms(&env, &require_warranty(&env, warranty_id)?))
+}
+
+pub(crate) fn set_badges_enabled(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    enabled: bool,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "customer_badges")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let key = BadgeKey::Enabled(manufacturer);
+    if enabled {
+        write_record(&env, &key, &true);
+    } else {
+        remove_record(&env, &key);
+    }
+    Ok(())
+}
+
+pub(crate) fn has_badge(env: Env, owner: Address, manufacturer: String) -> bool {
+    has_record(&env, &BadgeKey::Badge(owner, manufacturer))
+}
+
+pub(crate) fn get_badge(env: Env, owner: Address, manufacturer: String) -> Option<Badge> {
+    read_record(&env, &BadgeKey::Badge(owner, manufacturer))
+}
+
+pub(crate) fn publish_policy_doc(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    hash: BytesN<32>,
+    uri: String,
+    effective_date: u64,
+) -> Result<u32, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "policy_docs")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let key = PolicyKey::Docs(manufacturer);
+    let mut docs: Vec<PolicyDoc> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(&env));
+    if let Some(latest) = docs.last() {
+        if effective_date < latest.effective_date {
+            return Err(WarrantyError::InvalidDates);
+        }
+    }
+
+    let version = docs.len() + 1;
+    docs.push_back(PolicyDoc {
+        version,
+        hash,
+        uri,
+        effective_date,
+        published_at: env.ledger().timestamp(),
+    });
+    env.storage().persistent().set(&key, &docs);
+
+    Ok(version)
+}
+
+pub(crate) fn get_policy_doc(env: Env, manufacturer: String, version: u32) -> Option<PolicyDoc> {
+    if version == 0 {
+        return None;
+    }
+    env.storage()
+        .persistent()
+        .get::<_, Vec<PolicyDoc>>(&PolicyKey::Docs(manufacturer))?
+        .get(version - 1)
+}
+
+pub(crate) fn get_current_policy(env: Env, manufacturer: String) -> Option<PolicyDoc> {
+    policy_in_force(&env, &manufacturer)
+}
+
+pub(crate) fn set_pricing(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    category: String,
+    token: Address,
+    prices: Map<u32, i128>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "extended_warranty_sales")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    for (duration_months, price) in prices.iter() {
+        if duration_months == 0 || price < 0 {
+            return Err(WarrantyError::InvalidInput);
+        }
+    }
+
+    let key = SalesKey::Pricing(manufacturer, category);
+    if prices.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(
+            &key,
+            &PricingTable {
+                issuer,
+                token,
+                prices,
+            },
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn get_pricing(
+    env: Env,
+    manufacturer: String,
+    category: String,
+) -> Option<PricingTable> {
+    env.storage()
+        .persistent()
+        .get(&SalesKey::Pricing(manufacturer, category))
+}
+
+pub(crate) fn quote_extended_warranty(
+    env: Env,
+    manufacturer: String,
+    category: String,
+    duration_months: u32,
+) -> Result<Quote, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "extended_warranty_sales")?;
+
+    let pricing = get_pricing(env.clone(), manufacturer.clone(), category.clone())
+        .ok_or(WarrantyError::NotFound)?;
+    let price = pricing
+        .prices
+        .get(duration_months)
+        .ok_or(WarrantyError::NotFound)?;
+
+    let quote_id: u64 = env
+        .storage()
+        .instance()
+        .get(&SalesKey::QuoteCount)
+        .unwrap_or(0)
+        + 1;
+    let quote = Quote {
+        quote_id,
+        manufacturer,
+        category,
+        duration_months,
+        price,
+        token: pricing.token,
+        issuer: pricing.issuer,
+        expires_at: env.ledger().timestamp() + QUOTE_VALIDITY_SECS,
+    };
+    env.storage()
+        .temporary()
+        .set(&SalesKey::Quote(quote_id), &quote);
+    env.storage()
+        .instance()
+        .set(&SalesKey::QuoteCount, &quote_id);
+
+    Ok(quote)
+}
+
+pub(crate) fn get_quote(env: Env, quote_id: u64) -> Option<Quote> {
+    env.storage().temporary().get(&SalesKey::Quote(quote_id))
+}
+
+pub(crate) fn purchase_extended_warranty(
+    env: Env,
+    quote_id: u64,
+    owner: Address,
+    serial_number: String,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "extended_warranty_sales")?;
+
+    owner.require_auth();
+    let quote_key = SalesKey::Quote(quote_id);
+    let quote: Quote = env
+        .storage()
+        .temporary()
+        .get(&quote_key)
+        .ok_or(WarrantyError::NotFound)?;
+    let now = env.ledger().timestamp();
+    if now > quote.expires_at {
+        return Err(WarrantyError::OutsideWindow);
+    }
+    env.storage().temporary().remove(&quote_key);
+
+    check_compliance(&env, &owner, &quote.issuer)?;
+    token::Client::new(&env, &quote.token).transfer(&owner, &quote.issuer, &quote.price);
+
+    // The buyer already authorized the call, so skip the check in
+    // `create_warranty`; a second one in the same frame fails
+    record_activity(&env, &owner);
+    insert_warranty(
+        &env,
+        None,
+        owner.clone(),
+        owner,
+        WarrantyInput::new(
+            quote.category.clone(),
+            serial_number,
+            quote.manufacturer,
+            now,
+            now + quote.duration_months as u64 * SECS_PER_MONTH,
+        )
+        .with_category(quote.category)
+        .with_price(quote.price),
+        None,
+    )
+}
+
+pub(crate) fn get_activation_stats(env: Env, manufacturer: String) -> ActivationStats {
+    read_record(&env, &DataKey::ActivationStats(manufacturer)).unwrap_or_default()
+}
+
+pub(crate) fn get_retailer_report(
+    env: Env,
+    retailer: Address,
+    from_bucket: u64,
+    to_bucket: u64,
+) -> Result<Vec<RetailerBucket>, WarrantyError> {
+    require_feature(&env, "retailer_reports")?;
+    check_batch_range(&env, from_bucket, to_bucket)?;
+
+    let mut report = Vec::new(&env);
+    for bucket in from_bucket..=to_bucket {
+        report.push_back(
+            read_record(&env, &RetailerKey::Bucket(retailer.clone(), bucket)).unwrap_or(
+                RetailerBucket {
+                    bucket,
+                    registrations: 0,
+                    price_total: 0,
+                },
+            ),
+        );
+    }
+    Ok(report)
+}
@@ -0,0 +1,2149 @@
+//! Claims, service history, coverage terms, payout estimates, insurer policies
+//! and owner feedback.
+use soroban_sdk::{
+    contractclient, contracttype, symbol_short, token, Address, BytesN, Env, IntoVal, Map, String,
+    Symbol, Val, Vec,
+};
+
+use super::*;
+
+/// External insurance policy underwriting extended coverage on a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttachedPolicy {
+    pub insurer: Address,
+    pub policy_id: u64,
+    pub attached_at: u64,
+}
+
+/// Extended coverage an underwriter sold on top of a warranty; it picks up
+/// claims once the manufacturer's term has lapsed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Underwriting {
+    pub underwriter: Address,
+    /// Deductible, payout cap and exclusions of the extended coverage
+    pub terms: CoverageTerms,
+    /// When the extended coverage ends
+    pub covered_until: u64,
+    pub premium_token: Address,
+    pub premium: i128,
+    pub underwritten_at: u64,
+}
+
+/// Interface insurer contracts implement to be notified about claim outcomes
+#[contractclient(name = "InsurerClient")]
+pub trait InsurerInterface {
+    /// Called when a claim on a warranty carrying the insurer's policy is approved
+    fn on_claim_approved(env: Env, warranty_id: u64, policy_id: u64, claim_id: u64);
+}
+
+/// Interface price oracles implement to quote conversion rates for claim
+/// settlements
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    /// Units of `token` one unit of `currency` buys, with 7 decimal places
+    fn rate(env: Env, currency: Symbol, token: Address) -> i128;
+}
+
+/// Where a claim stands
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    /// Filed by the owner and awaiting a decision
+    Filed,
+    Approved,
+    Rejected,
+    /// Approved and settled, e.g. the product was repaired or replaced
+    Resolved,
+    /// Rejected, then escalated by the owner and awaiting an arbitrator's ruling
+    Disputed,
+    /// Drafted from a fault the bound device reported, awaiting the owner's
+    /// confirmation before it counts as filed
+    Draft,
+    /// Settled, then found fraudulent and its settlement reversed
+    ChargedBack,
+}
+
+/// Why a reviewer rejected a claim
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DenialReason {
+    /// The problem is not covered by the warranty's terms
+    OutOfCoverage,
+    /// The warranty had run out when the claim was filed
+    Expired,
+    EvidenceInsufficient,
+    FraudSuspected,
+    /// The product was repaired by someone not authorized to service it
+    UnauthorizedRepair,
+    Other,
+}
+
+/// Whether a claim stands rejected, and why
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimDenial {
+    NotDenied,
+    Denied(DenialReason),
+}
+
+impl ClaimDenial {
+    /// The reason the claim was rejected, if it was
+    pub fn reason(&self) -> Option<DenialReason> {
+        match self {
+            ClaimDenial::NotDenied => None,
+            ClaimDenial::Denied(reason) => Some(*reason),
+        }
+    }
+}
+
+/// A claim an owner filed against a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimData {
+    pub claim_id: u64,
+    pub warranty_id: u64,
+    /// Owner of the warranty when the claim was filed
+    pub claimant: Address,
+    pub category: ClaimCategory,
+    pub description: String,
+    pub status: ClaimStatus,
+    /// When the problem occurred, as reported by the claimant
+    pub incident_date: u64,
+    pub filed_at: u64,
+    /// When the claim was approved or rejected
+    pub decided_at: Option<u64>,
+    pub resolved_at: Option<u64>,
+    /// Why the claim was rejected, if it was
+    pub denial: ClaimDenial,
+    /// Amount the claimant asked for, if they named one
+    pub requested_amount: Option<i128>,
+    /// Amount approved, which may be less than requested. None when the
+    /// claim is undecided, or was approved without an amount asked for or
+    /// named
+    pub approved_amount: Option<i128>,
+    /// Amount paid out so far against the approved amount
+    pub paid_amount: i128,
+    /// Token the latest payout was made in, when the warranty names a
+    /// currency and a price oracle is set
+    pub settlement_token: Option<Address>,
+    /// Oracle rate from the warranty's currency into `settlement_token`
+    /// used for the latest payout
+    pub settlement_rate: Option<i128>,
+    /// When `settlement_rate` was quoted
+    pub rate_quoted_at: Option<u64>,
+}
+
+/// A newly filed claim and what filing it changed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FiledClaim {
+    pub claim_id: u64,
+    pub receipt: MutationReceipt,
+}
+
+/// Owner's escalation of a rejected claim to an arbitrator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimDispute {
+    pub claim_id: u64,
+    pub disputed_at: u64,
+    /// Arbitrator whose vote carried the ruling, once one has
+    pub arbitrator: Option<Address>,
+    /// Whether the panel sided with the owner
+    pub upheld: Option<bool>,
+    pub ruled_at: Option<u64>,
+    /// Dispute quorum when the dispute was raised; an outcome carries once
+    /// more than half this many arbitrators voted for it
+    pub quorum: u32,
+    pub votes_cast: u32,
+    /// Owner that raised the dispute and put down its fee
+    pub disputed_by: Address,
+    /// Token of the dispute fee, None if no fee was charged
+    pub fee_token: Option<Address>,
+    /// Dispute fee the losing party pays the arbitrators
+    pub fee: i128,
+}
+
+/// Manufacturers sharing an arbitration panel, claims escrow and claim cap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Consortium {
+    pub consortium_id: u64,
+    pub name: String,
+    /// Manages the members, the panel and the claim cap
+    pub operator: Address,
+    /// Manufacturer addresses whose claims the consortium backs
+    pub members: Vec<Address>,
+    /// Arbitrators that rule on disputes over members' claims; while empty
+    /// any registered arbitrator does
+    pub panel: Vec<Address>,
+    pub created_at: u64,
+}
+
+/// A consortium member's share of the shared escrow in one token
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemberAccount {
+    /// Paid into the shared escrow by the member
+    pub contributed: i128,
+    /// Paid out of the shared escrow to settle the member's claims
+    pub drawn: i128,
+    /// Refunded to the member out of the shared escrow after it left
+    pub withdrawn: i128,
+    /// What the member's approved claims still ask for, across tokens
+    pub liabilities: i128,
+}
+
+/// Recent claims on a warranty's serial number and owner, for spotting
+/// claim fraud
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FraudSignals {
+    /// Claims filed against the serial number in the last 30 days
+    pub serial_claims_30d: u32,
+    pub serial_claims_90d: u32,
+    /// Claims filed on any of the owner's warranties in the last 30 days
+    pub owner_claims_30d: u32,
+    pub owner_claims_90d: u32,
+    /// Settled claims of the owner's later charged back as fraudulent
+    pub owner_chargebacks: u32,
+    /// Whether a count is at its threshold or the owner had a chargeback,
+    /// so the next claim filed on the warranty is held for review
+    pub requires_review: bool,
+}
+
+/// Reversal of a settled claim later proven fraudulent
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chargeback {
+    pub claim_id: u64,
+    pub arbitrator: Address,
+    /// Token the claim was paid in
+    pub token: Address,
+    /// What was paid out on the claim
+    pub amount: i128,
+    /// Manufacturer whose claim pool repayments go to
+    pub payer: Address,
+    /// Repaid so far with `repay_chargeback`, at most `amount`
+    pub recovered: i128,
+    pub charged_at: u64,
+}
+
+/// Claim counts, per serial number or owner, at which new claims are held
+/// for review instead of being approvable straight away
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VelocityThresholds {
+    pub max_claims_30d: u32,
+    pub max_claims_90d: u32,
+}
+
+/// Refill of a manufacturer's claims escrow, pulled from the allowance it
+/// gave the contract on the token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoTopUp {
+    /// Balance a settlement must leave in escrow without a refill
+    pub floor: i128,
+    /// Pulled from the allowance when a settlement would go below `floor`
+    pub amount: i128,
+}
+
+/// Fee for ruling on a dispute, put down by the owner raising it and paid
+/// to the arbitrators by the losing party
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeFee {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Ruling an arbitrator votes for on a disputed claim
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisputeOutcome {
+    /// Confirm the rejection
+    Dismiss,
+    /// Approve the claim without a payout
+    Uphold,
+    /// Approve the claim and pay out of the manufacturer's claim pool
+    UpholdWithPayout(ClaimPayout),
+}
+
+/// Rolling cap on how many claims one claimant can file against a
+/// manufacturer's warranties
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimLimit {
+    pub max_claims: u32,
+    /// Length of the window the claims are counted in
+    pub period_secs: u64,
+}
+
+/// What is left of a claimant's claim cap for a manufacturer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimQuota {
+    /// Claims the claimant may still file now, or None if uncapped
+    pub remaining: Option<u32>,
+    /// When the oldest counted claim stops counting, if any is counted
+    pub next_release_at: Option<u64>,
+}
+
+/// Amount paid out to the owner when a claim is approved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPayout {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Schedule settling an approved claim in installments out of a claim pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentPlan {
+    pub claim_id: u64,
+    /// Owner of the claim pool the installments are paid from
+    pub payer: Address,
+    pub token: Address,
+    pub installment_amount: i128,
+    pub count: u32,
+    /// Seconds between two installment deadlines
+    pub interval: u64,
+    pub paid_count: u32,
+    /// Deadline of the next unpaid installment
+    pub next_due: u64,
+    /// First deadline the payer missed, once one has been noticed
+    pub defaulted_at: Option<u64>,
+}
+
+/// Message posted to a claim's thread; the body itself lives off-chain
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimMessage {
+    pub author: Address,
+    /// SHA-256 of the message body, so the off-chain copy can be verified
+    pub body_hash: BytesN<32>,
+    /// Where the body can be fetched
+    pub uri: String,
+    pub posted_at: u64,
+}
+
+/// Repair or maintenance work logged against a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceRecord {
+    /// Service center or manufacturer address that did the work
+    pub technician: Address,
+    pub description: String,
+    /// When the work was done
+    pub date: u64,
+    /// When the record was logged
+    pub recorded_at: u64,
+    /// Part warranties registered for the components fitted, at most
+    /// `MAX_SERVICE_PARTS`
+    pub parts: Vec<u64>,
+}
+
+/// Record an owner's feedback is about
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeedbackSubject {
+    /// A resolved claim, rated by its claimant
+    Claim(u64),
+    /// An expired warranty, rated by its owner
+    Warranty(u64),
+}
+
+/// Scores an owner gives a manufacturer, each from 1 to 5
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeedbackScores {
+    pub overall: u32,
+    /// How the manufacturer handled claims and support
+    pub service: u32,
+    pub product_quality: u32,
+}
+
+/// Feedback totals of a manufacturer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManufacturerRating {
+    pub responses: u32,
+    pub overall_total: u64,
+    pub service_total: u64,
+    pub product_quality_total: u64,
+}
+
+/// Kind of problem a claim is about
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimCategory {
+    Defect,
+    AccidentalDamage,
+    BatteryDegradation,
+    Cosmetic,
+    Other,
+}
+
+/// Financial terms applied when estimating payouts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoverageTerms {
+    /// Amount the owner bears on every claim
+    pub deductible: i128,
+    /// Cap on the gross amount of a single claim, if any
+    pub max_payout: Option<i128>,
+    /// Claim categories the warranty does not cover
+    pub excluded_categories: Vec<ClaimCategory>,
+    /// Cap on what a single claim in a category may pay out
+    pub category_caps: Map<ClaimCategory, i128>,
+}
+
+/// Breakdown of what a claim would pay out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutEstimate {
+    /// Claim amount after the payout cap
+    pub gross: i128,
+    pub deductible: i128,
+    /// Reduction for the product's age
+    pub depreciation: i128,
+    pub net: i128,
+}
+
+/// Share of the product's value, in basis points, still covered at its
+/// current age according to the manufacturer's curve for its category.
+pub(crate) fn retained_bps(env: &Env, warranty: &WarrantyData) -> u32 {
+    retained_bps_at(env, warranty, env.ledger().timestamp())
+}
+
+/// Share of the product's value, in basis points, covered at its age at
+/// time `at`.
+fn retained_bps_at(env: &Env, warranty: &WarrantyData, at: u64) -> u32 {
+    let Some(category) = warranty.category.clone() else {
+        return 10_000;
+    };
+    let curve: Vec<(u32, u32)> = match read_record(
+        env,
+        &DataKey::DepreciationCurve(warranty.manufacturer.clone(), category),
+    ) {
+        Some(curve) => curve,
+        None => return 10_000,
+    };
+
+    let age_months = at.saturating_sub(warranty.purchase_date) / SECS_PER_MONTH;
+    let mut retained = 10_000;
+    for (point_age, point_bps) in curve.iter() {
+        if point_age as u64 > age_months {
+            break;
+        }
+        retained = point_bps;
+    }
+    retained
+}
+
+/// Read a claim or fail with `NotFound`.
+pub(crate) fn require_claim(env: &Env, claim_id: u64) -> Result<ClaimData, WarrantyError> {
+    read_record(env, &ClaimKey::Claim(claim_id)).ok_or(WarrantyError::NotFound)
+}
+
+/// Whether a claim still binds its manufacturer, and the amount approved
+/// on it, or asked for until it is decided, that has not been paid out.
+fn claim_exposure(claim: &ClaimData) -> (bool, i128) {
+    match claim.status {
+        ClaimStatus::Filed | ClaimStatus::Approved | ClaimStatus::Disputed => {
+            let owed = claim
+                .approved_amount
+                .or(claim.requested_amount)
+                .unwrap_or(0)
+                - claim.paid_amount;
+            (true, owed.max(0))
+        }
+        _ => (false, 0),
+    }
+}
+
+/// Amount approved on an approved claim that is still to be paid.
+pub(crate) fn approved_liability(claim: &ClaimData) -> i128 {
+    if claim.status != ClaimStatus::Approved {
+        return 0;
+    }
+    (claim.approved_amount.unwrap_or(0) - claim.paid_amount).max(0)
+}
+
+/// Write a claim, moving it in or out of its manufacturer's open claims,
+/// pending settlement amounts and approved-claim liabilities as its status
+/// and payout change.
+pub(crate) fn save_claim(env: &Env, claim: &ClaimData) {
+    let key = ClaimKey::Claim(claim.claim_id);
+    let previous: Option<ClaimData> = env.storage().persistent().get(&key);
+    write_record(env, &key, claim);
+    if previous.is_none() {
+        let stored: u64 = env.storage().instance().get(&ClaimKey::Stored).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&ClaimKey::Stored, &(stored + 1));
+    }
+
+    let Some(warranty) = load_warranty(env, claim.warranty_id) else {
+        return;
+    };
+    let (was_open, was_owed) = previous.as_ref().map_or((false, 0), claim_exposure);
+    let (open, owed) = claim_exposure(claim);
+    if was_open != open {
+        let key = ObligationKey::OpenClaims(warranty.manufacturer.clone());
+        let count: u32 = read_record(env, &key).unwrap_or(0);
+        let count = if open {
+            count + 1
+        } else {
+            count.saturating_sub(1)
+        };
+        write_record(env, &key, &count);
+        let total: u32 = env
+            .storage()
+            .instance()
+            .get(&ClaimKey::OpenCount)
+            .unwrap_or(0);
+        let total = if open {
+            total + 1
+        } else {
+            total.saturating_sub(1)
+        };
+        env.storage().instance().set(&ClaimKey::OpenCount, &total);
+    }
+    if was_owed != owed {
+        let key = ObligationKey::PendingSettlement(warranty.manufacturer.clone());
+        let pending: i128 = read_record(env, &key).unwrap_or(0);
+        write_record(env, &key, &(pending - was_owed + owed).max(0));
+    }
+
+    // The old amount comes off whoever it was counted against, even if
+    // another address pays for the warranty by now
+    let was_liable = previous.as_ref().map_or(0, approved_liability);
+    let liable = approved_liability(claim);
+    if was_liable != liable {
+        let party_key = ObligationKey::LiableParty(claim.claim_id);
+        if let Some(party) = read_record::<_, Address>(env, &party_key) {
+            add_liability(env, &party, -was_liable);
+            remove_record(env, &party_key);
+        }
+        if let Some(payer) = claim_payer(env, &warranty).filter(|_| liable > 0) {
+            add_liability(env, &payer, liable);
+            write_record(env, &party_key, &payer);
+        }
+    }
+}
+
+fn add_liability(env: &Env, party: &Address, amount: i128) {
+    let key = ObligationKey::Liabilities(party.clone());
+    let liabilities: i128 = read_record(env, &key).unwrap_or(0);
+    write_record(env, &key, &(liabilities + amount).max(0));
+}
+
+/// Remember that `manufacturer` holds a claim pool or escrow in `token`, so
+/// it counts towards its collateral.
+fn note_collateral_token(env: &Env, manufacturer: &Address, token: &Address) {
+    let key = ObligationKey::CollateralTokens(manufacturer.clone());
+    let mut tokens: Vec<Address> = read_record(env, &key).unwrap_or(Vec::new(env));
+    if !tokens.contains(token) {
+        tokens.push_back(token.clone());
+        write_record(env, &key, &tokens);
+    }
+}
+
+/// How `manufacturer`'s claim pools and escrow, and the shared escrow of
+/// its consortium, cover its approved claims. Balances in different tokens
+/// are not added up: a claim is settled in one token, so the best-funded
+/// token is what counts.
+pub(crate) fn solvency(env: &Env, manufacturer: &Address) -> SolvencyStatus {
+    let liabilities: i128 =
+        read_record(env, &ObligationKey::Liabilities(manufacturer.clone())).unwrap_or(0);
+    let mut tokens: Vec<Address> =
+        read_record(env, &ObligationKey::CollateralTokens(manufacturer.clone()))
+            .unwrap_or(Vec::new(env));
+    let consortium_id = consortium::get_member_consortium(env.clone(), manufacturer.clone());
+    if let Some(consortium_id) = consortium_id {
+        for token in consortium::escrow_tokens(env, consortium_id).iter() {
+            if !tokens.contains(&token) {
+                tokens.push_back(token);
+            }
+        }
+    }
+    let mut collateral_by_token = Map::new(env);
+    let mut collateral: i128 = 0;
+    for token in tokens.iter() {
+        let mut held = get_pool_balance(env.clone(), manufacturer.clone(), token.clone())
+            + get_escrow_balance(env.clone(), manufacturer.clone(), token.clone());
+        if let Some(consortium_id) = consortium_id {
+            held += consortium::get_consortium_escrow(env.clone(), consortium_id, token.clone());
+        }
+        collateral = collateral.max(held);
+        collateral_by_token.set(token, held);
+    }
+    let ratio_bps = if liabilities == 0 {
+        0
+    } else if collateral == 0 {
+        u32::MAX
+    } else {
+        (liabilities.saturating_mul(10_000) / collateral).min(u32::MAX as i128) as u32
+    };
+    let threshold_bps = settings::get_solvency_threshold(env.clone());
+    SolvencyStatus {
+        liabilities,
+        collateral,
+        collateral_by_token,
+        ratio_bps,
+        threshold_bps,
+        under_collateralized: ratio_bps > threshold_bps,
+    }
+}
+
+/// Publish a `SolvencyWarning` when `manufacturer` became
+/// under-collateralized since it was last reviewed.
+pub(crate) fn review_solvency(env: &Env, manufacturer: &Address) {
+    let status = solvency(env, manufacturer);
+    let key = ObligationKey::Undercollateralized(manufacturer.clone());
+    let warned = has_record(env, &key);
+    if status.under_collateralized && !warned {
+        write_record(env, &key, &true);
+        SolvencyWarning {
+            manufacturer: manufacturer.clone(),
+            liabilities: status.liabilities,
+            collateral: status.collateral,
+        }
+        .publish(env);
+    } else if !status.under_collateralized && warned {
+        remove_record(env, &key);
+    }
+}
+
+/// Review the solvency of the address paying claims on a warranty.
+pub(crate) fn review_claim_payer(env: &Env, warranty_id: u64) {
+    if let Some(payer) = load_warranty(env, warranty_id).and_then(|w| claim_payer(env, &w)) {
+        review_solvency(env, &payer);
+    }
+}
+
+/// Whether `address` may decide a claim: the admin or the warranty's
+/// manufacturer (see `is_warranty_manufacturer`).
+pub(crate) fn is_claim_reviewer(
+    env: &Env,
+    address: &Address,
+    claim: &ClaimData,
+) -> Result<bool, WarrantyError> {
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    if admin.as_ref() == Some(address) {
+        return Ok(true);
+    }
+    let warranty = require_warranty(env, claim.warranty_id)?;
+    Ok(is_warranty_manufacturer(env, address, &warranty))
+}
+
+/// Whether `address` may rule on disputes over `claim`: the panel of the
+/// consortium paying it, if it has one, otherwise any registered arbitrator.
+pub(crate) fn may_arbitrate_claim(env: &Env, address: &Address, claim: &ClaimData) -> bool {
+    let payer =
+        load_warranty(env, claim.warranty_id).and_then(|warranty| claim_payer(env, &warranty));
+    consortium::may_arbitrate(env, address, payer)
+}
+
+/// Whether `address` arbitrates the dispute over a claim: anyone who may
+/// rule on it while the dispute awaits a ruling, then the one that ruled.
+pub(crate) fn is_claim_arbitrator(env: &Env, address: &Address, claim_id: u64) -> bool {
+    let Some(dispute) = read_record::<_, ClaimDispute>(env, &ArbitrationKey::Dispute(claim_id))
+    else {
+        return false;
+    };
+    require_claim(env, claim_id).is_ok_and(|claim| may_arbitrate_claim(env, address, &claim))
+        && dispute
+            .arbitrator
+            .is_none_or(|arbitrator| arbitrator == *address)
+}
+
+/// Address whose claim pool pays out claims on `warranty`: the registered
+/// manufacturer that issued it (or the address that superseded it), otherwise
+/// the namespace issuer of its manufacturer name.
+pub(crate) fn claim_payer(env: &Env, warranty: &WarrantyData) -> Option<Address> {
+    match &warranty.verified_manufacturer {
+        Some(issuer) => Some(current_manufacturer(env, issuer)),
+        None => roles::get_namespace_issuer(env.clone(), warranty.manufacturer.clone()),
+    }
+}
+
+/// Take `amount` of `token` out of a manufacturer's claim pool and send it
+/// to `to`, failing with `InsufficientFunds` if the pool holds less.
+pub(crate) fn pay_from_pool(
+    env: &Env,
+    manufacturer: &Address,
+    token: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), WarrantyError> {
+    pay_from(
+        env,
+        &ClaimKey::Pool(manufacturer.clone(), token.clone()),
+        token,
+        to,
+        amount,
+    )
+}
+
+/// Pay `amount` of `token` out of the balance under `key`, a claim pool or
+/// escrow, failing with `InsufficientFunds` if it holds less.
+pub(crate) fn pay_from<K: IntoVal<Env, Val>>(
+    env: &Env,
+    key: &K,
+    token: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), WarrantyError> {
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let balance: i128 = read_record(env, key).unwrap_or(0);
+    if balance < amount {
+        return Err(WarrantyError::InsufficientFunds);
+    }
+
+    check_compliance(env, &env.current_contract_address(), to)?;
+    write_record(env, key, &(balance - amount));
+    token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+    Ok(())
+}
+
+/// Deductible and payout cap of `warranty`: its own coverage terms, or
+/// those of its terms template.
+pub(crate) fn coverage_terms(env: &Env, warranty: &WarrantyData) -> CoverageTerms {
+    read_record(env, &DataKey::CoverageTerms(warranty.id)).unwrap_or_else(|| {
+        let template = warranty_terms(env, warranty);
+        CoverageTerms {
+            deductible: template.as_ref().map_or(0, |terms| terms.deductible),
+            max_payout: template.and_then(|terms| terms.max_payout),
+            excluded_categories: Vec::new(env),
+            category_caps: Map::new(env),
+        }
+    })
+}
+
+/// Most a single claim in the category of `claim` may pay out on
+/// `warranty`, if its coverage terms cap it.
+pub(crate) fn category_cap(env: &Env, warranty: &WarrantyData, claim: &ClaimData) -> Option<i128> {
+    coverage_terms(env, warranty)
+        .category_caps
+        .get(claim.category)
+}
+
+/// Most `claim` may pay out once its requested amount is depreciated to the
+/// product's age on the incident date. None when no amount was requested.
+pub(crate) fn depreciated_amount(
+    env: &Env,
+    warranty: &WarrantyData,
+    claim: &ClaimData,
+) -> Option<i128> {
+    let retained = retained_bps_at(env, warranty, claim.incident_date);
+    claim
+        .requested_amount
+        .map(|requested| retained_share(requested, retained))
+}
+
+/// `retained_bps` basis points of a non-negative `amount`, rounded down,
+/// without overflowing for amounts near `i128::MAX`.
+fn retained_share(amount: i128, retained_bps: u32) -> i128 {
+    let bps = retained_bps as i128;
+    amount / 10_000 * bps + amount % 10_000 * bps / 10_000
+}
+
+/// Most the approved claims on `warranty` may pay out in total: the payout
+/// cap, or the purchase price when no cap is set, less the deductible. None
+/// when neither is known.
+pub(crate) fn payout_limit(env: &Env, warranty: &WarrantyData) -> Option<i128> {
+    let terms = coverage_terms(env, warranty);
+    terms
+        .max_payout
+        .or(warranty.price)
+        .map(|cap| cap.saturating_sub(terms.deductible).max(0))
+}
+
+/// Total paid out so far on the approved claims of a warranty.
+pub(crate) fn paid_out(env: &Env, warranty_id: u64) -> i128 {
+    read_record(env, &ClaimKey::PaidOut(warranty_id)).unwrap_or(0)
+}
+
+/// Record the amount approved on a claim and pay its payout, if any, to
+/// the warranty's current owner out of the issuing manufacturer's claim
+/// pool, then notify the insurer of an attached policy.
+///
+/// The approved amount defaults to the payout, then to the amount asked
+/// for. It may be less than the claimant asked for but not more, nor more
+/// than the cap on the claim's category, and the warranty's earlier payouts
+/// count against its payout limit. Only the
+/// admin, an arbitrator or the address whose pool pays may attach a
+/// payout, so other reviewers cannot spend a manufacturer's funds. The
+/// payout may not exceed the approved amount; what it leaves unpaid is
+/// settled later from the claims escrow or a payment plan.
+pub(crate) fn settle_approved_claim(
+    env: &Env,
+    approver: &Address,
+    claim: &ClaimData,
+    approved_amount: Option<i128>,
+    payout: Option<ClaimPayout>,
+) -> Result<(), WarrantyError> {
+    let warranty = require_warranty(env, claim.warranty_id)?;
+    let depreciated = depreciated_amount(env, &warranty, claim);
+    let mut approved = claim.clone();
+    approved.approved_amount = approved_amount
+        .or(payout.as_ref().map(|payout| payout.amount))
+        .or(depreciated);
+    if let Some(amount) = approved.approved_amount {
+        let paid = paid_out(env, warranty.id);
+        if amount < 0
+            || payout_limit(env, &warranty).is_some_and(|limit| amount > limit - paid)
+            || category_cap(env, &warranty, claim).is_some_and(|cap| amount > cap)
+            || depreciated.is_some_and(|depreciated| amount > depreciated)
+        {
+            return Err(WarrantyError::InvalidInput);
+        }
+    }
+    if let Some(payout) = payout {
+        require_feature(env, "claim_payouts")?;
+        let payer = claim_payer(env, &warranty).ok_or(WarrantyError::NotFound)?;
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if *approver != payer
+            && admin.as_ref() != Some(approver)
+            && !may_arbitrate_claim(env, approver, claim)
+        {
+            return Err(WarrantyError::Unauthorized);
+        }
+        if payout.amount > approved.approved_amount.unwrap_or(0) {
+            return Err(WarrantyError::InvalidInput);
+        }
+        quote_settlement(env, &warranty, &mut approved, &payout.token)?;
+        pay_from_pool(env, &payer, &payout.token, &warranty.owner, payout.amount)?;
+        write_record(
+            env,
+            &ClaimKey::PaidOut(warranty.id),
+            &(paid_out(env, warranty.id) + payout.amount),
+        );
+        write_record(env, &ClaimKey::PaidIn(claim.claim_id), &payout.token);
+        approved.paid_amount = payout.amount;
+    }
+
+    save_claim(env, &approved);
+    publish_payout_approved(env, &approved, approved.approved_amount.unwrap_or(0));
+
+    if let Some(policy) = get_attached_policy(env.clone(), claim.warranty_id) {
+        // A failing insurer must not roll back the settlement
+        let _ = InsurerClient::new(env, &policy.insurer).try_on_claim_approved(
+            &claim.warranty_id,
+            &policy.policy_id,
+            &claim.claim_id,
+        );
+    }
+    review_claim_payer(env, claim.warranty_id);
+    Ok(())
+}
+
+/// Record on `claim` the oracle rate from `warranty`'s currency into the
+/// `token` it is being paid in. Nothing is recorded when the warranty names
+/// no currency or no oracle is set; an oracle that fails or quotes a rate
+/// of 0 or less blocks the payout with `RateUnavailable`.
+pub(crate) fn quote_settlement(
+    env: &Env,
+    warranty: &WarrantyData,
+    claim: &mut ClaimData,
+    token: &Address,
+) -> Result<(), WarrantyError> {
+    let (Some(currency), Some(oracle)) =
+        (&warranty.currency, settings::get_price_oracle(env.clone()))
+    else {
+        return Ok(());
+    };
+    let rate = match PriceOracleClient::new(env, &oracle).try_rate(currency, token) {
+        Ok(Ok(rate)) if rate > 0 => rate,
+        _ => return Err(WarrantyError::RateUnavailable),
+    };
+    claim.settlement_token = Some(token.clone());
+    claim.settlement_rate = Some(rate);
+    claim.rate_quoted_at = Some(env.ledger().timestamp());
+    Ok(())
+}
+
+/// Who may give feedback on `subject`, and the warranty it concerns. Fails
+/// with `OutsideWindow` until the claim is resolved or the warranty expired.
+pub(crate) fn feedback_author(
+    env: &Env,
+    subject: &FeedbackSubject,
+) -> Result<(Address, WarrantyData), WarrantyError> {
+    match subject {
+        FeedbackSubject::Claim(claim_id) => {
+            let claim = require_claim(env, *claim_id)?;
+            if claim.status != ClaimStatus::Resolved {
+                return Err(WarrantyError::OutsideWindow);
+            }
+            Ok((claim.claimant, require_warranty(env, claim.warranty_id)?))
+        }
+        FeedbackSubject::Warranty(warranty_id) => {
+            let warranty = require_warranty(env, *warranty_id)?;
+            if effective_status(env, &warranty) != WarrantyStatus::Expired {
+                return Err(WarrantyError::OutsideWindow);
+            }
+            Ok((warranty.owner.clone(), warranty))
+        }
+    }
+}
+
+/// Whether `technician` may log service work on `warranty`: an authorized
+/// service center or the warranty's manufacturer.
+pub(crate) fn can_service(env: &Env, technician: &Address, warranty: &WarrantyData) -> bool {
+    has_record(env, &ServiceKey::Center(technician.clone()))
+        || is_warranty_manufacturer(env, technician, warranty)
+}
+
+/// Filing times of `claimant`'s claims that still count against
+/// `manufacturer`'s cap.
+fn counted_filings(
+    env: &Env,
+    claimant: &Address,
+    manufacturer: &String,
+    limit: &ClaimLimit,
+) -> Vec<u64> {
+    let now = env.ledger().timestamp();
+    let filed: Vec<u64> = read_record(
+        env,
+        &ClaimKey::Filings(claimant.clone(), manufacturer.clone()),
+    )
+    .unwrap_or(Vec::new(env));
+    let mut counted = Vec::new(env);
+    for filed_at in filed.iter() {
+        if now.saturating_sub(filed_at) < limit.period_secs {
+            counted.push_back(filed_at);
+        }
+    }
+    counted
+}
+
+/// Count a claim by `claimant` against `manufacturer`'s cap, failing with
+/// `RateLimited` once it is used up.
+pub(crate) fn use_claim_quota(
+    env: &Env,
+    claimant: &Address,
+    manufacturer: &String,
+) -> Result<(), WarrantyError> {
+    let Some(limit) = settings::get_claim_limit(env.clone(), manufacturer.clone()) else {
+        return Ok(());
+    };
+    let mut counted = counted_filings(env, claimant, manufacturer, &limit);
+    if counted.len() >= limit.max_claims {
+        return Err(WarrantyError::RateLimited);
+    }
+    counted.push_back(env.ledger().timestamp());
+    write_record(
+        env,
+        &ClaimKey::Filings(claimant.clone(), manufacturer.clone()),
+        &counted,
+    );
+    Ok(())
+}
+
+/// What `claimant` has left of `manufacturer`'s cap.
+pub(crate) fn claim_quota(env: &Env, claimant: &Address, manufacturer: &String) -> ClaimQuota {
+    let Some(limit) = settings::get_claim_limit(env.clone(), manufacturer.clone()) else {
+        return ClaimQuota {
+            remaining: None,
+            next_release_at: None,
+        };
+    };
+    let counted = counted_filings(env, claimant, manufacturer, &limit);
+    ClaimQuota {
+        remaining: Some(limit.max_claims.saturating_sub(counted.len())),
+        next_release_at: counted
+            .first()
+            .map(|filed_at| filed_at.saturating_add(limit.period_secs)),
+    }
+}
+
+/// When claims on a warranty open again after it changed hands, while the
+/// post-transfer cooldown runs.
+pub(crate) fn claim_cooldown_until(env: &Env, warranty_id: u64) -> Option<u64> {
+    let cooldown_secs = settings::get_transfer_claim_cooldown_secs(env.clone());
+    if cooldown_secs == 0 {
+        return None;
+    }
+    let transferred_at: u64 = read_record(env, &ClaimKey::TransferredAt(warranty_id))?;
+    let open_at = transferred_at.saturating_add(cooldown_secs);
+    (env.ledger().timestamp() < open_at).then_some(open_at)
+}
+
+/// Reserve the next claim ID.
+pub(crate) fn next_claim_id(env: &Env) -> u64 {
+    let claim_id: u64 = env.storage().instance().get(&ClaimKey::Count).unwrap_or(0) + 1;
+    env.storage().instance().set(&ClaimKey::Count, &claim_id);
+    claim_id
+}
+
+/// Description a device's fault report pre-fills, e.g. "Device fault 42".
+pub(crate) fn fault_description(env: &Env, fault_code: u32) -> String {
+    const PREFIX: &[u8] = b"Device fault ";
+
+    let mut digits = [0u8; 10];
+    let mut start = digits.len();
+    let mut rest = fault_code;
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+
+    let len = PREFIX.len() + digits.len() - start;
+    let mut buffer = [0u8; PREFIX.len() + 10];
+    buffer[..PREFIX.len()].copy_from_slice(PREFIX);
+    buffer[PREFIX.len()..len].copy_from_slice(&digits[start..]);
+    String::from_bytes(env, &buffer[..len])
+}
+
+/// File a claim against `warranty` for its owner, once the caller has been
+/// authorized. Its status is recomputed first and must then be Active.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn open_claim(
+    env: &Env,
+    actor: &Address,
+    warranty: WarrantyData,
+    category: ClaimCategory,
+    description: String,
+    incident_date: u64,
+    requested_amount: Option<i128>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    let claim = ClaimData {
+        claim_id: next_claim_id(env),
+        warranty_id: warranty.id,
+        claimant: warranty.owner.clone(),
+        category,
+        description,
+        status: ClaimStatus::Filed,
+        incident_date,
+        filed_at: env.ledger().timestamp(),
+        decided_at: None,
+        resolved_at: None,
+        denial: ClaimDenial::NotDenied,
+        requested_amount,
+        approved_amount: None,
+        paid_amount: 0,
+        settlement_token: None,
+        settlement_rate: None,
+        rate_quoted_at: None,
+    };
+    submit_claim(env, actor, warranty, claim, correlation_id)
+}
+
+/// File `claim`, new or drafted, against `warranty` for its current owner,
+/// once the caller has been authorized. The warranty's status is
+/// recomputed first and must then be Active.
+pub(crate) fn submit_claim(
+    env: &Env,
+    actor: &Address,
+    mut warranty: WarrantyData,
+    mut claim: ClaimData,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    let since = audit_tail(env);
+
+    require_not_frozen(env, warranty.id)?;
+    refresh_status(env, &mut warranty);
+    if warranty.status != WarrantyStatus::Active {
+        return Err(WarrantyError::NotActive);
+    }
+    if claim.description.is_empty() || claim.requested_amount.is_some_and(|amount| amount <= 0) {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if coverage_terms(env, &warranty)
+        .excluded_categories
+        .contains(claim.category)
+    {
+        return Err(WarrantyError::NotCovered);
+    }
+    let now = env.ledger().timestamp();
+    let incident_date = claim.incident_date;
+    if incident_date > now || incident_date < warranty.purchase_date {
+        return Err(WarrantyError::InvalidDates);
+    }
+    let window_secs = settings::get_claim_window_secs(env.clone());
+    if window_secs > 0 && now - incident_date > window_secs {
+        return Err(WarrantyError::OutsideWindow);
+    }
+    if claim_cooldown_until(env, warranty.id).is_some() {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    let claims_key = ClaimKey::Claims(warranty.id);
+    let mut claims: Vec<u64> = read_record(env, &claims_key).unwrap_or(Vec::new(env));
+    if !claims.contains(claim.claim_id) {
+        if claims.len() >= read_limits(env).max_page_size {
+            return Err(WarrantyError::LimitExceeded);
+        }
+        claims.push_back(claim.claim_id);
+    }
+    if let Some(terms) = warranty_terms(env, &warranty) {
+        if terms
+            .max_claims
+            .is_some_and(|max_claims| warranty.claims_filed >= max_claims)
+        {
+            return Err(WarrantyError::LimitExceeded);
+        }
+        if terms.requires_attestation && warranty.attestation == SaleAttestation::Unattested {
+            return Err(WarrantyError::NotAttested);
+        }
+    }
+    use_claim_quota(env, &warranty.owner, &warranty.manufacturer)?;
+    consortium::use_shared_quota(env, &warranty)?;
+
+    warranty.claims_filed += 1;
+    save_warranty(env, &warranty);
+    lock_amendments(env, warranty.id);
+
+    let claim_id = claim.claim_id;
+    fraud::record_filing(env, &warranty, claim_id);
+    claim.claimant = warranty.owner;
+    claim.status = ClaimStatus::Filed;
+    claim.filed_at = now;
+    save_claim(env, &claim);
+    write_record(env, &claims_key, &claims);
+    record_history(
+        env,
+        claim.warranty_id,
+        actor,
+        symbol_short!("claim"),
+        HistoryDetails::ClaimFiled(claim_id),
+    );
+    publish_claim(env, &claim);
+
+    notify_change(
+        env,
+        symbol_short!("claim"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(FiledClaim {
+        claim_id,
+        receipt: receipt_since(env, since),
+    })
+}
+
+/// Move a claim from `from` to `to` on behalf of the admin or the warranty's
+/// manufacturer, recording `denial_reason` when it is rejected.
+/// `correlation_id` is echoed into the outbox and audit log.
+pub(crate) fn decide_claim(
+    env: &Env,
+    approver: &Address,
+    claim_id: u64,
+    from: ClaimStatus,
+    to: ClaimStatus,
+    denial_reason: Option<DenialReason>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<ClaimData, WarrantyError> {
+    approver.require_auth();
+
+    let mut claim = require_claim(env, claim_id)?;
+    if !is_claim_reviewer(env, approver, &claim)? {
+        return Err(WarrantyError::Unauthorized);
+    }
+    require_not_frozen(env, claim.warranty_id)?;
+    if claim.status != from {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+
+    let now = env.ledger().timestamp();
+    if to == ClaimStatus::Resolved {
+        claim.resolved_at = Some(now);
+    } else {
+        claim.decided_at = Some(now);
+    }
+    claim.status = to;
+    if let Some(reason) = denial_reason {
+        claim.denial = ClaimDenial::Denied(reason);
+    }
+    save_claim(env, &claim);
+    record_history(
+        env,
+        claim.warranty_id,
+        approver,
+        symbol_short!("claim"),
+        HistoryDetails::ClaimDecided(claim_id, claim.status),
+    );
+    publish_claim(env, &claim);
+
+    notify_change(
+        env,
+        symbol_short!("claim"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(claim)
+}
+
+pub(crate) fn confirm_claim_draft(
+    env: Env,
+    claim_id: u64,
+    requested_amount: Option<i128>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let mut claim = require_claim(&env, claim_id)?;
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if claim.status != ClaimStatus::Draft {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    claim.requested_amount = requested_amount;
+
+    submit_claim(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        claim,
+        correlation_id,
+    )
+}
+
+pub(crate) fn discard_claim_draft(env: Env, claim_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let claim = require_claim(&env, claim_id)?;
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if claim.status != ClaimStatus::Draft {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+
+    let claims_key = ClaimKey::Claims(warranty.id);
+    let claims: Vec<u64> = read_record(&env, &claims_key).unwrap_or(Vec::new(&env));
+    write_record(&env, &claims_key, &without_id(&env, &claims, claim_id));
+    remove_record(&env, &ClaimKey::Claim(claim_id));
+    let stored: u64 = env.storage().instance().get(&ClaimKey::Stored).unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&ClaimKey::Stored, &stored.saturating_sub(1));
+    Ok(())
+}
+
+pub(crate) fn add_service_record(
+    env: Env,
+    warranty_id: u64,
+    technician: Address,
+    description: String,
+    date: u64,
+    parts: Vec<u64>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "service_history")?;
+
+    let since = audit_tail(&env);
+
+    technician.require_auth();
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    if !can_service(&env, &technician, &warranty) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    let now = env.ledger().timestamp();
+    if date < warranty.purchase_date || date > now {
+        return Err(WarrantyError::InvalidDates);
+    }
+    if description.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if parts.len() > MAX_SERVICE_PARTS {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    // Only part warranties registered under this warranty can be listed
+    for part_id in parts.iter() {
+        let part: Option<PartRecord> = read_record(&env, &PartKey::Part(part_id));
+        if part.map(|part| part.parent_id) != Some(warranty_id) {
+            return Err(WarrantyError::NotFound);
+        }
+    }
+
+    let key = ServiceKey::History(warranty_id);
+    let mut history: Vec<ServiceRecord> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(&env));
+    if history.len() >= MAX_SERVICE_RECORDS {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    // Keep the history in service-date order, even for work logged late
+    let index = history
+        .iter()
+        .position(|record| record.date > date)
+        .map_or(history.len(), |index| index as u32);
+    history.insert(
+        index,
+        ServiceRecord {
+            technician,
+            description,
+            date,
+            recorded_at: now,
+            parts,
+        },
+    );
+    env.storage().persistent().set(&key, &history);
+    extend_persistent(&env, &key);
+
+    notify_change(&env, symbol_short!("service"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_service_history(env: Env, warranty_id: u64) -> Vec<ServiceRecord> {
+    let key = ServiceKey::History(warranty_id);
+    let Some(history) = env.storage().persistent().get(&key) else {
+        return Vec::new(&env);
+    };
+    bump_persistent(&env, &key);
+    history
+}
+
+pub(crate) fn set_coverage_terms(
+    env: Env,
+    caller: Address,
+    warranty_id: u64,
+    terms: CoverageTerms,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    caller.require_auth();
+    let warranty = require_warranty(&env, warranty_id)?;
+    // Owners pay for coverage, so only the issuing side may set it
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    if admin.as_ref() != Some(&caller)
+        && roles::get_namespace_issuer(env.clone(), warranty.manufacturer.clone()).as_ref()
+            != Some(&caller)
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+    record_activity(&env, &caller);
+
+    if terms.deductible < 0
+        || terms.max_payout.is_some_and(|max| max < 0)
+        || terms.category_caps.values().iter().any(|cap| cap < 0)
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    write_record(&env, &DataKey::CoverageTerms(warranty_id), &terms);
+    Ok(())
+}
+
+pub(crate) fn get_coverage_terms(env: Env, warranty_id: u64) -> Option<CoverageTerms> {
+    read_record(&env, &DataKey::CoverageTerms(warranty_id))
+}
+
+pub(crate) fn covers_claim_category(env: Env, warranty_id: u64, category: ClaimCategory) -> bool {
+    match get_coverage_terms(env, warranty_id) {
+        Some(terms) => !terms.excluded_categories.contains(category),
+        None => true,
+    }
+}
+
+pub(crate) fn estimate_payout(
+    env: Env,
+    warranty_id: u64,
+    claim_amount: i128,
+) -> Result<PayoutEstimate, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    if claim_amount < 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let terms = coverage_terms(&env, &warranty);
+    let gross = match terms.max_payout.or(warranty.price) {
+        Some(cap) => claim_amount.min(cap),
+        None => claim_amount,
+    };
+    let depreciation = gross - retained_share(gross, retained_bps(&env, &warranty));
+    let net = (gross - depreciation - terms.deductible).max(0);
+
+    Ok(PayoutEstimate {
+        gross,
+        deductible: terms.deductible,
+        depreciation,
+        net,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn agent_file_claim(
+    env: Env,
+    agent: Address,
+    warranty_id: u64,
+    category: ClaimCategory,
+    description: String,
+    incident_date: u64,
+    requested_amount: Option<i128>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "agents")?;
+    let warranty = require_warranty(&env, warranty_id)?;
+    require_agent(&env, &agent, &warranty)?;
+
+    open_claim(
+        &env,
+        &agent,
+        warranty,
+        category,
+        description,
+        incident_date,
+        requested_amount,
+        correlation_id,
+    )
+}
+
+pub(crate) fn attach_policy(
+    env: Env,
+    warranty_id: u64,
+    insurer: Address,
+    policy_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "insurance_policies")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    write_record(
+        &env,
+        &DataKey::AttachedPolicy(warranty_id),
+        &AttachedPolicy {
+            insurer,
+            policy_id,
+            attached_at: env.ledger().timestamp(),
+        },
+    );
+
+    notify_change(&env, symbol_short!("policy"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn detach_policy(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    remove_record(&env, &DataKey::AttachedPolicy(warranty_id));
+    Ok(())
+}
+
+pub(crate) fn get_attached_policy(env: Env, warranty_id: u64) -> Option<AttachedPolicy> {
+    read_record(&env, &DataKey::AttachedPolicy(warranty_id))
+}
+
+pub(crate) fn underwrite(
+    env: Env,
+    underwriter: Address,
+    warranty_id: u64,
+    coverage_terms: CoverageTerms,
+    covered_until: u64,
+    premium_token: Address,
+    premium: i128,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "underwriting")?;
+
+    underwriter.require_auth();
+    if !roles::is_underwriter(env.clone(), underwriter.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if premium < 0
+        || coverage_terms.deductible < 0
+        || coverage_terms.max_payout.is_some_and(|max| max < 0)
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+    refresh_status(&env, &mut warranty);
+    if warranty.status != WarrantyStatus::Active {
+        return Err(WarrantyError::NotActive);
+    }
+    if covered_until <= warranty.expiration_date {
+        return Err(WarrantyError::InvalidDates);
+    }
+    let key = UnderwritingKey::Record(warranty_id);
+    if env.storage().persistent().has(&key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    if premium > 0 {
+        check_compliance(&env, &warranty.owner, &underwriter)?;
+        token::Client::new(&env, &premium_token).transfer(&warranty.owner, &underwriter, &premium);
+    }
+
+    env.storage().persistent().set(
+        &key,
+        &Underwriting {
+            underwriter,
+            terms: coverage_terms,
+            covered_until,
+            premium_token,
+            premium,
+            underwritten_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn get_underwriting(env: Env, warranty_id: u64) -> Option<Underwriting> {
+    env.storage()
+        .persistent()
+        .get(&UnderwritingKey::Record(warranty_id))
+}
+
+pub(crate) fn file_claim(
+    env: Env,
+    warranty_id: u64,
+    category: ClaimCategory,
+    description: String,
+    incident_date: u64,
+    requested_amount: Option<i128>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    open_claim(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        category,
+        description,
+        incident_date,
+        requested_amount,
+        correlation_id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn co_owner_file_claim(
+    env: Env,
+    co_owner: Address,
+    warranty_id: u64,
+    category: ClaimCategory,
+    description: String,
+    incident_date: u64,
+    requested_amount: Option<i128>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "co_ownership")?;
+
+    co_owner.require_auth();
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    if !warranty.co_owners.contains(&co_owner) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    record_activity(&env, &co_owner);
+
+    open_claim(
+        &env,
+        &co_owner,
+        warranty,
+        category,
+        description,
+        incident_date,
+        requested_amount,
+        correlation_id,
+    )
+}
+
+pub(crate) fn get_claims(env: Env, warranty_id: u64) -> Result<Vec<ClaimData>, WarrantyError> {
+    let claim_ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&ClaimKey::Claims(warranty_id))
+        .unwrap_or(Vec::new(&env));
+    let mut claims = Vec::new(&env);
+    for claim_id in claim_ids.iter() {
+        claims.push_back(require_claim(&env, claim_id)?);
+    }
+    Ok(claims)
+}
+
+pub(crate) fn fund_claim_pool(
+    env: Env,
+    manufacturer: Address,
+    token: Address,
+    amount: i128,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claim_payouts")?;
+
+    let since = audit_tail(&env);
+    manufacturer.require_auth();
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    token::Client::new(&env, &token).transfer(
+        &manufacturer,
+        env.current_contract_address(),
+        &amount,
+    );
+    credit_pool(&env, &manufacturer, &token, amount);
+    review_solvency(&env, &manufacturer);
+
+    notify_change(
+        &env,
+        symbol_short!("poolfund"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn withdraw_claim_pool(
+    env: Env,
+    manufacturer: Address,
+    token: Address,
+    amount: i128,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    manufacturer.require_auth();
+
+    pay_from_pool(&env, &manufacturer, &token, &manufacturer, amount)?;
+    review_solvency(&env, &manufacturer);
+
+    notify_change(
+        &env,
+        symbol_short!("poolout"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_pool_balance(env: Env, manufacturer: Address, token: Address) -> i128 {
+    read_record(&env, &ClaimKey::Pool(manufacturer, token)).unwrap_or(0)
+}
+
+pub(crate) fn fund_claims_escrow(
+    env: Env,
+    manufacturer: Address,
+    token: Address,
+    amount: i128,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claims_escrow")?;
+
+    let since = audit_tail(&env);
+    manufacturer.require_auth();
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    token::Client::new(&env, &token).transfer(
+        &manufacturer,
+        env.current_contract_address(),
+        &amount,
+    );
+    credit_escrow(&env, &manufacturer, &token, amount);
+    review_solvency(&env, &manufacturer);
+
+    notify_change(
+        &env,
+        symbol_short!("escrow"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_escrow_balance(env: Env, manufacturer: Address, token: Address) -> i128 {
+    read_record(&env, &ClaimKey::Escrow(manufacturer, token)).unwrap_or(0)
+}
+
+pub(crate) fn credit_pool(env: &Env, manufacturer: &Address, token: &Address, amount: i128) {
+    let key = ClaimKey::Pool(manufacturer.clone(), token.clone());
+    let balance: i128 = read_record(env, &key).unwrap_or(0);
+    write_record(env, &key, &(balance + amount));
+    note_collateral_token(env, manufacturer, token);
+}
+
+fn credit_escrow(env: &Env, manufacturer: &Address, token: &Address, amount: i128) {
+    let key = ClaimKey::Escrow(manufacturer.clone(), token.clone());
+    let balance: i128 = read_record(env, &key).unwrap_or(0);
+    write_record(env, &key, &(balance + amount));
+    note_collateral_token(env, manufacturer, token);
+}
+
+pub(crate) fn set_auto_topup(
+    env: Env,
+    manufacturer: Address,
+    token: Address,
+    floor: i128,
+    amount: i128,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claims_escrow")?;
+
+    manufacturer.require_auth();
+    if floor < 0 || amount < 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let key = ClaimKey::AutoTopUp(manufacturer, token);
+    if amount == 0 {
+        remove_record(&env, &key);
+    } else {
+        write_record(&env, &key, &AutoTopUp { floor, amount });
+    }
+    Ok(())
+}
+
+pub(crate) fn get_auto_topup(env: Env, manufacturer: Address, token: Address) -> Option<AutoTopUp> {
+    read_record(&env, &ClaimKey::AutoTopUp(manufacturer, token))
+}
+
+/// Refill `manufacturer`'s escrow from its allowance if paying `amount`
+/// would take it below its floor. A refill the allowance or balance cannot
+/// cover is skipped, and the settlement goes ahead with what is escrowed.
+fn top_up_escrow(env: &Env, manufacturer: &Address, token: &Address, amount: i128) {
+    let Some(topup) = get_auto_topup(env.clone(), manufacturer.clone(), token.clone()) else {
+        return;
+    };
+    let balance = get_escrow_balance(env.clone(), manufacturer.clone(), token.clone());
+    if balance - amount >= topup.floor {
+        return;
+    }
+    let contract = env.current_contract_address();
+    let pulled = token::Client::new(env, token).try_transfer_from(
+        &contract,
+        manufacturer,
+        &contract,
+        &topup.amount,
+    );
+    if matches!(pulled, Ok(Ok(()))) {
+        credit_escrow(env, manufacturer, token, topup.amount);
+    }
+}
+
+pub(crate) fn settle_claim(
+    env: Env,
+    claim_id: u64,
+    token: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claims_escrow")?;
+
+    let since = audit_tail(&env);
+
+    let mut claim = require_claim(&env, claim_id)?;
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+    let payer = claim_payer(&env, &warranty).ok_or(WarrantyError::NotFound)?;
+    payer.require_auth();
+    require_not_frozen(&env, claim.warranty_id)?;
+    // A claim on a payment plan is settled by its final installment
+    if claim.status != ClaimStatus::Approved || has_record(&env, &ClaimKey::Plan(claim_id)) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let amount = approved_liability(&claim);
+    let paid = paid_out(&env, warranty.id);
+    if amount == 0
+        || payout_limit(&env, &warranty).is_some_and(|limit| amount > limit - paid)
+        || category_cap(&env, &warranty, &claim).is_some_and(|cap| claim.paid_amount + amount > cap)
+        || depreciated_amount(&env, &warranty, &claim)
+            .is_some_and(|depreciated| claim.paid_amount + amount > depreciated)
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    quote_settlement(&env, &warranty, &mut claim, &token)?;
+    top_up_escrow(&env, &payer, &token, amount);
+    // Members of a consortium fall back on its shared escrow
+    if get_escrow_balance(env.clone(), payer.clone(), token.clone()) >= amount
+        || !consortium::pay_from_shared_escrow(&env, &payer, &token, &warranty.owner, amount)?
+    {
+        pay_from(
+            &env,
+            &ClaimKey::Escrow(payer.clone(), token.clone()),
+            &token,
+            &warranty.owner,
+            amount,
+        )?;
+    }
+    write_record(&env, &ClaimKey::PaidOut(warranty.id), &(paid + amount));
+    write_record(&env, &ClaimKey::PaidIn(claim_id), &token);
+
+    claim.paid_amount += amount;
+    claim.status = ClaimStatus::Resolved;
+    claim.resolved_at = Some(env.ledger().timestamp());
+    save_claim(&env, &claim);
+    record_history(
+        &env,
+        claim.warranty_id,
+        &payer,
+        symbol_short!("claim"),
+        HistoryDetails::ClaimDecided(claim_id, claim.status),
+    );
+    publish_claim(&env, &claim);
+    review_solvency(&env, &payer);
+
+    notify_change(
+        &env,
+        symbol_short!("settle"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_paid_out(env: Env, warranty_id: u64) -> i128 {
+    paid_out(&env, warranty_id)
+}
+
+pub(crate) fn approve_claim(
+    env: Env,
+    approver: Address,
+    claim_id: u64,
+    approved_amount: Option<i128>,
+    payout: Option<ClaimPayout>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    if fraud::is_held_for_review(env.clone(), claim_id) {
+        return Err(WarrantyError::ReviewRequired);
+    }
+    let claim = decide_claim(
+        &env,
+        &approver,
+        claim_id,
+        ClaimStatus::Filed,
+        ClaimStatus::Approved,
+        None,
+        correlation_id,
+    )?;
+    settle_approved_claim(&env, &approver, &claim, approved_amount, payout)?;
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn post_claim_message(
+    env: Env,
+    author: Address,
+    claim_id: u64,
+    body_hash: BytesN<32>,
+    uri: String,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    author.require_auth();
+
+    let claim = require_claim(&env, claim_id)?;
+    if claim.claimant != author
+        && !is_claim_reviewer(&env, &author, &claim)?
+        && !is_claim_arbitrator(&env, &author, claim_id)
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let key = ClaimKey::Messages(claim_id);
+    let mut messages: Vec<ClaimMessage> = read_record(&env, &key).unwrap_or(Vec::new(&env));
+    if messages.len() >= MAX_CLAIM_MESSAGES {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    messages.push_back(ClaimMessage {
+        author,
+        body_hash,
+        uri,
+        posted_at: env.ledger().timestamp(),
+    });
+    write_record(&env, &key, &messages);
+
+    notify_change(
+        &env,
+        symbol_short!("message"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_claim_messages(env: Env, claim_id: u64) -> Vec<ClaimMessage> {
+    read_record(&env, &ClaimKey::Messages(claim_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn reject_claim(
+    env: Env,
+    approver: Address,
+    claim_id: u64,
+    reason: DenialReason,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    decide_claim(
+        &env,
+        &approver,
+        claim_id,
+        ClaimStatus::Filed,
+        ClaimStatus::Rejected,
+        Some(reason),
+        correlation_id,
+    )?;
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn resolve_claim(
+    env: Env,
+    approver: Address,
+    claim_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    // A claim on a payment plan is settled by its final installment
+    if read_record::<_, PaymentPlan>(&env, &ClaimKey::Plan(claim_id)).is_some() {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let claim = decide_claim(
+        &env,
+        &approver,
+        claim_id,
+        ClaimStatus::Approved,
+        ClaimStatus::Resolved,
+        None,
+        correlation_id,
+    )?;
+    check_compliance(&env, &approver, &claim.claimant)?;
+    review_claim_payer(&env, claim.warranty_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn create_payment_plan(
+    env: Env,
+    claim_id: u64,
+    token: Address,
+    installment_amount: i128,
+    count: u32,
+    interval: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claim_payouts")?;
+
+    let since = audit_tail(&env);
+
+    let claim = require_claim(&env, claim_id)?;
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+    let payer = claim_payer(&env, &warranty).ok_or(WarrantyError::NotFound)?;
+    payer.require_auth();
+    require_not_frozen(&env, claim.warranty_id)?;
+    if claim.status != ClaimStatus::Approved {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let key = ClaimKey::Plan(claim_id);
+    if has_record(&env, &key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    if installment_amount <= 0 || count == 0 || interval == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if count > MAX_PLAN_INSTALLMENTS {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    // The whole plan must fit the payout limits a single payout would
+    let total = installment_amount
+        .checked_mul(count as i128)
+        .ok_or(WarrantyError::InvalidInput)?;
+    let paid = paid_out(&env, warranty.id);
+    if payout_limit(&env, &warranty).is_some_and(|limit| total > limit - paid)
+        || claim
+            .approved_amount
+            .is_some_and(|approved| total > approved - claim.paid_amount)
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    write_record(
+        &env,
+        &key,
+        &PaymentPlan {
+            claim_id,
+            payer,
+            token,
+            installment_amount,
+            count,
+            interval,
+            paid_count: 0,
+            next_due: env.ledger().timestamp().saturating_add(interval),
+            defaulted_at: None,
+        },
+    );
+
+    notify_change(
+        &env,
+        symbol_short!("plan"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+/// Note the first deadline of an unfinished plan that has passed unpaid.
+/// Returns whether the plan is in default.
+fn note_default(env: &Env, plan: &mut PaymentPlan) -> bool {
+    if plan.defaulted_at.is_none()
+        && plan.paid_count < plan.count
+        && env.ledger().timestamp() > plan.next_due
+    {
+        plan.defaulted_at = Some(plan.next_due);
+    }
+    plan.defaulted_at.is_some()
+}
+
+pub(crate) fn pay_installment(
+    env: Env,
+    claim_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let key = ClaimKey::Plan(claim_id);
+    let mut plan: PaymentPlan = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    plan.payer.require_auth();
+    let mut claim = require_claim(&env, claim_id)?;
+    require_not_frozen(&env, claim.warranty_id)?;
+    if claim.status != ClaimStatus::Approved {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+
+    // A late installment still pays, but the default stays on record
+    note_default(&env, &mut plan);
+    quote_settlement(&env, &warranty, &mut claim, &plan.token)?;
+    pay_from_pool(
+        &env,
+        &plan.payer,
+        &plan.token,
+        &warranty.owner,
+        plan.installment_amount,
+    )?;
+    write_record(
+        &env,
+        &ClaimKey::PaidOut(warranty.id),
+        &(paid_out(&env, warranty.id) + plan.installment_amount),
+    );
+    write_record(&env, &ClaimKey::PaidIn(claim_id), &plan.token);
+    plan.paid_count += 1;
+    plan.next_due = plan.next_due.saturating_add(plan.interval);
+    write_record(&env, &key, &plan);
+
+    claim.paid_amount += plan.installment_amount;
+    if plan.paid_count == plan.count {
+        claim.status = ClaimStatus::Resolved;
+        claim.resolved_at = Some(env.ledger().timestamp());
+        save_claim(&env, &claim);
+        record_history(
+            &env,
+            claim.warranty_id,
+            &plan.payer,
+            symbol_short!("claim"),
+            HistoryDetails::ClaimDecided(claim_id, claim.status),
+        );
+        publish_claim(&env, &claim);
+    } else {
+        save_claim(&env, &claim);
+    }
+    review_solvency(&env, &plan.payer);
+
+    notify_change(
+        &env,
+        symbol_short!("install"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn check_payment_plan(env: Env, claim_id: u64) -> Result<bool, WarrantyError> {
+    let key = ClaimKey::Plan(claim_id);
+    let mut plan: PaymentPlan = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    if plan.defaulted_at.is_some() {
+        return Ok(true);
+    }
+    if !note_default(&env, &mut plan) {
+        return Ok(false);
+    }
+    write_record(&env, &key, &plan);
+
+    let claim = require_claim(&env, claim_id)?;
+    notify_change(&env, symbol_short!("default"), claim.warranty_id, None);
+    Ok(true)
+}
+
+pub(crate) fn get_payment_plan(env: Env, claim_id: u64) -> Option<PaymentPlan> {
+    read_record(&env, &ClaimKey::Plan(claim_id))
+}
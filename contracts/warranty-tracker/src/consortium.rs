@@ -0,0 +1,391 @@
+//! Consortiums of manufacturers sharing an arbitration panel, escrow and
+//! claim cap.
+use soroban_sdk::{symbol_short, token, Address, BytesN, Env, String, Vec};
+
+use super::*;
+
+pub(crate) fn create_consortium(
+    env: Env,
+    operator: Address,
+    name: String,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    require_sole_admin(&env)?;
+    operator.require_auth();
+    if name.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let consortium_id: u64 = env
+        .storage()
+        .instance()
+        .get(&ConsortiumKey::LastConsortiumId)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .instance()
+        .set(&ConsortiumKey::LastConsortiumId, &consortium_id);
+    write_record(
+        &env,
+        &ConsortiumKey::Consortium(consortium_id),
+        &Consortium {
+            consortium_id,
+            name,
+            operator,
+            members: Vec::new(&env),
+            panel: Vec::new(&env),
+            created_at: env.ledger().timestamp(),
+        },
+    );
+
+    notify_change(
+        &env,
+        symbol_short!("consort"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(consortium_id)
+}
+
+pub(crate) fn get_consortium(env: Env, consortium_id: u64) -> Option<Consortium> {
+    read_record(&env, &ConsortiumKey::Consortium(consortium_id))
+}
+
+pub(crate) fn get_member_consortium(env: Env, member: Address) -> Option<u64> {
+    read_record(&env, &ConsortiumKey::MemberOf(member))
+}
+
+/// Load a consortium and check that its operator signed.
+fn require_operator(env: &Env, consortium_id: u64) -> Result<Consortium, WarrantyError> {
+    let consortium = get_consortium(env.clone(), consortium_id).ok_or(WarrantyError::NotFound)?;
+    consortium.operator.require_auth();
+    Ok(consortium)
+}
+
+pub(crate) fn add_consortium_member(
+    env: Env,
+    consortium_id: u64,
+    member: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    let since = audit_tail(&env);
+
+    let mut consortium = require_operator(&env, consortium_id)?;
+    member.require_auth();
+    let member_key = ConsortiumKey::MemberOf(member.clone());
+    if has_record(&env, &member_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    if consortium.members.len() >= MAX_CONSORTIUM_SIZE {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    consortium.members.push_back(member);
+    write_record(&env, &member_key, &consortium_id);
+    write_record(&env, &ConsortiumKey::Consortium(consortium_id), &consortium);
+
+    notify_change(
+        &env,
+        symbol_short!("member"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn remove_consortium_member(
+    env: Env,
+    caller: Address,
+    consortium_id: u64,
+    member: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    let since = audit_tail(&env);
+
+    caller.require_auth();
+    let mut consortium =
+        get_consortium(env.clone(), consortium_id).ok_or(WarrantyError::NotFound)?;
+    if caller != consortium.operator && caller != member {
+        return Err(WarrantyError::Unauthorized);
+    }
+    let Some(index) = consortium.members.first_index_of(&member) else {
+        return Err(WarrantyError::NotFound);
+    };
+
+    // The member's account stays behind as a record of what it paid in and
+    // drew, and of what it may take back with `withdraw_consortium_share`
+    consortium.members.remove(index);
+    remove_record(&env, &ConsortiumKey::MemberOf(member.clone()));
+    write_record(&env, &ConsortiumKey::Consortium(consortium_id), &consortium);
+    review_solvency(&env, &member);
+
+    notify_change(
+        &env,
+        symbol_short!("member"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_consortium_arbitrator(
+    env: Env,
+    consortium_id: u64,
+    arbitrator: Address,
+    authorized: bool,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    let mut consortium = require_operator(&env, consortium_id)?;
+    // Every member is bound by the panel's rulings, so each must agree to it
+    for member in consortium.members.iter() {
+        member.require_auth();
+    }
+    match (consortium.panel.first_index_of(&arbitrator), authorized) {
+        (None, true) => {
+            if consortium.panel.len() >= MAX_CONSORTIUM_SIZE {
+                return Err(WarrantyError::LimitExceeded);
+            }
+            consortium.panel.push_back(arbitrator);
+        }
+        (Some(index), false) => {
+            consortium.panel.remove(index);
+        }
+        _ => return Ok(()),
+    }
+    write_record(&env, &ConsortiumKey::Consortium(consortium_id), &consortium);
+    Ok(())
+}
+
+/// Whether `arbitrator` may rule on disputes over claims paid by `payer`:
+/// a member of the panel of the payer's consortium if that panel has
+/// anyone on it, otherwise any registered arbitrator.
+pub(crate) fn may_arbitrate(env: &Env, arbitrator: &Address, payer: Option<Address>) -> bool {
+    let consortium = payer
+        .and_then(|payer| get_member_consortium(env.clone(), payer))
+        .and_then(|consortium_id| get_consortium(env.clone(), consortium_id));
+    match consortium {
+        Some(consortium) if !consortium.panel.is_empty() => consortium.panel.contains(arbitrator),
+        _ => disputes::is_arbitrator(env.clone(), arbitrator.clone()),
+    }
+}
+
+pub(crate) fn set_consortium_claim_limit(
+    env: Env,
+    consortium_id: u64,
+    limit: Option<ClaimLimit>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    require_operator(&env, consortium_id)?;
+    let key = ConsortiumKey::ClaimCap(consortium_id);
+    match limit {
+        Some(limit) if limit.max_claims == 0 || limit.period_secs == 0 => {
+            return Err(WarrantyError::InvalidInput)
+        }
+        Some(limit) => write_record(&env, &key, &limit),
+        None => remove_record(&env, &key),
+    }
+    Ok(())
+}
+
+pub(crate) fn get_consortium_claim_limit(env: Env, consortium_id: u64) -> Option<ClaimLimit> {
+    read_record(&env, &ConsortiumKey::ClaimCap(consortium_id))
+}
+
+/// Count a claim on `warranty` against the cap of the consortium paying it,
+/// which covers claims by the same owner on any member's warranties,
+/// failing with `RateLimited` once it is used up.
+pub(crate) fn use_shared_quota(env: &Env, warranty: &WarrantyData) -> Result<(), WarrantyError> {
+    let Some(consortium_id) =
+        claim_payer(env, warranty).and_then(|payer| get_member_consortium(env.clone(), payer))
+    else {
+        return Ok(());
+    };
+    let Some(limit) = get_consortium_claim_limit(env.clone(), consortium_id) else {
+        return Ok(());
+    };
+    let key = ConsortiumKey::SharedFilings(consortium_id, warranty.owner.clone());
+    let now = env.ledger().timestamp();
+    let mut counted = Vec::new(env);
+    for filed_at in read_record::<_, Vec<u64>>(env, &key)
+        .unwrap_or(Vec::new(env))
+        .iter()
+    {
+        if now.saturating_sub(filed_at) < limit.period_secs {
+            counted.push_back(filed_at);
+        }
+    }
+    if counted.len() >= limit.max_claims {
+        return Err(WarrantyError::RateLimited);
+    }
+    counted.push_back(now);
+    write_record(env, &key, &counted);
+    Ok(())
+}
+
+pub(crate) fn fund_consortium_escrow(
+    env: Env,
+    member: Address,
+    token: Address,
+    amount: i128,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    let since = audit_tail(&env);
+    member.require_auth();
+    let consortium_id =
+        get_member_consortium(env.clone(), member.clone()).ok_or(WarrantyError::NotFound)?;
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    token::Client::new(&env, &token).transfer(&member, env.current_contract_address(), &amount);
+    let escrow_key = ConsortiumKey::SharedEscrow(consortium_id, token.clone());
+    let balance: i128 = read_record(&env, &escrow_key).unwrap_or(0);
+    write_record(&env, &escrow_key, &(balance + amount));
+    let tokens_key = ConsortiumKey::EscrowTokens(consortium_id);
+    let mut tokens = escrow_tokens(&env, consortium_id);
+    if !tokens.contains(&token) {
+        tokens.push_back(token.clone());
+        write_record(&env, &tokens_key, &tokens);
+    }
+    let account_key = ConsortiumKey::Account(consortium_id, member.clone(), token);
+    let mut account: MemberAccount = read_record(&env, &account_key).unwrap_or_default();
+    account.contributed += amount;
+    write_record(&env, &account_key, &account);
+    review_solvency(&env, &member);
+
+    notify_change(
+        &env,
+        symbol_short!("escrow"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_consortium_escrow(env: Env, consortium_id: u64, token: Address) -> i128 {
+    read_record(&env, &ConsortiumKey::SharedEscrow(consortium_id, token)).unwrap_or(0)
+}
+
+/// Tokens a consortium's shared escrow was ever funded in.
+pub(crate) fn escrow_tokens(env: &Env, consortium_id: u64) -> Vec<Address> {
+    read_record(env, &ConsortiumKey::EscrowTokens(consortium_id)).unwrap_or(Vec::new(env))
+}
+
+pub(crate) fn withdraw_consortium_share(
+    env: Env,
+    member: Address,
+    consortium_id: u64,
+    token: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "consortium")?;
+
+    let since = audit_tail(&env);
+    member.require_auth();
+    let consortium = get_consortium(env.clone(), consortium_id).ok_or(WarrantyError::NotFound)?;
+    // Members keep their share in while the others may still draw on it
+    if consortium.members.contains(&member) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let account_key = ConsortiumKey::Account(consortium_id, member.clone(), token.clone());
+    let mut account: MemberAccount =
+        read_record(&env, &account_key).ok_or(WarrantyError::NotFound)?;
+
+    // What the member paid in and did not draw, as far as the escrow still
+    // holds it
+    let share = account.contributed - account.drawn - account.withdrawn;
+    let amount = share.min(get_consortium_escrow(
+        env.clone(),
+        consortium_id,
+        token.clone(),
+    ));
+    if amount <= 0 {
+        return Err(WarrantyError::InsufficientFunds);
+    }
+    pay_from(
+        &env,
+        &ConsortiumKey::SharedEscrow(consortium_id, token.clone()),
+        &token,
+        &member,
+        amount,
+    )?;
+    account.withdrawn += amount;
+    write_record(&env, &account_key, &account);
+    for remaining in consortium.members.iter() {
+        review_solvency(&env, &remaining);
+    }
+
+    notify_change(
+        &env,
+        symbol_short!("escrow"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_member_account(
+    env: Env,
+    consortium_id: u64,
+    member: Address,
+    token: Address,
+) -> MemberAccount {
+    let mut account: MemberAccount = read_record(
+        &env,
+        &ConsortiumKey::Account(consortium_id, member.clone(), token),
+    )
+    .unwrap_or_default();
+    account.liabilities = read_record(&env, &ObligationKey::Liabilities(member)).unwrap_or(0);
+    account
+}
+
+/// Pay a settlement on `payer`'s claim out of its consortium's shared
+/// escrow, charging it to the payer's account, when the payer belongs to a
+/// consortium.
+///
+/// Returns false, paying nothing, for payers outside any consortium.
+pub(crate) fn pay_from_shared_escrow(
+    env: &Env,
+    payer: &Address,
+    token: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<bool, WarrantyError> {
+    let Some(consortium_id) = get_member_consortium(env.clone(), payer.clone()) else {
+        return Ok(false);
+    };
+    pay_from(
+        env,
+        &ConsortiumKey::SharedEscrow(consortium_id, token.clone()),
+        token,
+        to,
+        amount,
+    )?;
+    let account_key = ConsortiumKey::Account(consortium_id, payer.clone(), token.clone());
+    let mut account: MemberAccount = read_record(env, &account_key).unwrap_or_default();
+    account.drawn += amount;
+    write_record(env, &account_key, &account);
+    Ok(true)
+}
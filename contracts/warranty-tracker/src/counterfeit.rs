@@ -0,0 +1,106 @@
+//! Counterfeit reports, their review and reporter reputation.
+use soroban_sdk::{symbol_short, Address, BytesN, Env};
+
+use super::*;
+
+pub(crate) fn report_counterfeit(
+    env: Env,
+    reporter: Address,
+    warranty_id: u64,
+    evidence_hash: BytesN<32>,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "counterfeit_reports")?;
+
+    reporter.require_auth();
+    record_activity(&env, &reporter);
+
+    if load_warranty(&env, warranty_id).is_none() {
+        return Err(WarrantyError::NotFound);
+    }
+
+    let reputation = get_reporter_reputation(env.clone(), reporter.clone());
+    if reputation.dismissed >= reputation.confirmed + MAX_NET_DISMISSED_REPORTS {
+        return Err(WarrantyError::Unauthorized);
+    }
+    use_report_quota(&env, &reporter)?;
+
+    let report_id = report_count(&env) + 1;
+    let report = CounterfeitReport {
+        id: report_id,
+        warranty_id,
+        reporter,
+        evidence_hash,
+        status: ReportStatus::Pending,
+        created_at: env.ledger().timestamp(),
+    };
+    save_report(&env, &report);
+    env.storage()
+        .instance()
+        .set(&CounterfeitKey::Count, &report_id);
+    count_pending_report(&env, true);
+
+    Ok(report_id)
+}
+
+pub(crate) fn get_counterfeit_report(env: Env, report_id: u64) -> Option<CounterfeitReport> {
+    load_report(&env, report_id)
+}
+
+pub(crate) fn get_pending_reports(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<WarrantyIdPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    Ok(pending_report_page(&env, cursor, limit))
+}
+
+pub(crate) fn confirm_counterfeit(
+    env: Env,
+    report_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    let since = audit_tail(&env);
+
+    let admin = require_admin(&env)?;
+
+    let report = resolve_report(&env, report_id, ReportStatus::Confirmed)?;
+
+    let warranty = load_warranty(&env, report.warranty_id).ok_or(WarrantyError::NotFound)?;
+    if !status_transition_allowed(
+        &warranty.status,
+        &WarrantyStatus::Revoked,
+        StatusAuthority::Admin,
+    ) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    apply_status(
+        &env,
+        &admin,
+        warranty,
+        WarrantyStatus::Revoked,
+        StatusAuthority::Admin,
+    )?;
+
+    notify_change(
+        &env,
+        symbol_short!("status"),
+        report.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn dismiss_counterfeit(env: Env, report_id: u64) -> Result<(), WarrantyError> {
+    require_admin(&env)?;
+
+    resolve_report(&env, report_id, ReportStatus::Dismissed)?;
+    Ok(())
+}
+
+pub(crate) fn get_reporter_reputation(env: Env, reporter: Address) -> ReporterReputation {
+    read_record(&env, &DataKey::ReporterReputation(reporter)).unwrap_or_default()
+}
@@ -0,0 +1,215 @@
+//! Device keys bound to warranties, device event logs and usage reports.
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{symbol_short, BytesN, Env, Vec};
+
+use super::*;
+
+pub(crate) fn bind_device_key(
+    env: Env,
+    warranty_id: u64,
+    public_key: BytesN<32>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "device_binding")?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    write_record(&env, &DataKey::DeviceKey(warranty_id), &public_key);
+    Ok(())
+}
+
+pub(crate) fn unbind_device_key(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    remove_record(&env, &DataKey::DeviceKey(warranty_id));
+    Ok(())
+}
+
+pub(crate) fn get_device_key(env: Env, warranty_id: u64) -> Option<BytesN<32>> {
+    read_record(&env, &DataKey::DeviceKey(warranty_id))
+}
+
+pub(crate) fn log_device_event(
+    env: Env,
+    warranty_id: u64,
+    event_code: u32,
+    nonce: u64,
+    signature: BytesN<64>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "device_binding")?;
+
+    let since = audit_tail(&env);
+
+    let message = (
+        env.current_contract_address(),
+        warranty_id,
+        event_code,
+        nonce,
+    )
+        .to_xdr(&env);
+    verify_device_message(&env, warranty_id, nonce, &message, &signature)?;
+
+    let count_key = DeviceLogKey::EventCount(warranty_id);
+    let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let entry_key = DeviceLogKey::Event(warranty_id, index);
+    env.storage().persistent().set(
+        &entry_key,
+        &DeviceEvent {
+            event_code,
+            nonce,
+            logged_at: env.ledger().timestamp(),
+        },
+    );
+    extend_persistent(&env, &entry_key);
+    env.storage().persistent().set(&count_key, &(index + 1));
+    extend_persistent(&env, &count_key);
+
+    notify_change(&env, symbol_short!("device"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_device_events(
+    env: Env,
+    warranty_id: u64,
+    start: u32,
+    limit: u32,
+) -> Result<Vec<DeviceEvent>, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DeviceLogKey::EventCount(warranty_id))
+        .unwrap_or(0);
+    let mut events = Vec::new(&env);
+    for index in start..count.min(start.saturating_add(limit)) {
+        let key = DeviceLogKey::Event(warranty_id, index);
+        if let Some(event) = env.storage().persistent().get(&key) {
+            bump_persistent(&env, &key);
+            events.push_back(event);
+        }
+    }
+    Ok(events)
+}
+
+pub(crate) fn report_usage(
+    env: Env,
+    warranty_id: u64,
+    reading: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    record_usage(&env, warranty, reading)?;
+
+    notify_change(&env, symbol_short!("usage"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn report_device_usage(
+    env: Env,
+    warranty_id: u64,
+    reading: u64,
+    nonce: u64,
+    signature: BytesN<64>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "device_binding")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let message = (env.current_contract_address(), warranty_id, reading, nonce).to_xdr(&env);
+    verify_device_message(&env, warranty_id, nonce, &message, &signature)?;
+
+    record_usage(&env, warranty, reading)?;
+
+    notify_change(&env, symbol_short!("usage"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn device_report_fault(
+    env: Env,
+    warranty_id: u64,
+    fault_code: u32,
+    nonce: u64,
+    signature: BytesN<64>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<FiledClaim, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "device_binding")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let message = (
+        env.current_contract_address(),
+        symbol_short!("fault"),
+        warranty_id,
+        fault_code,
+        nonce,
+    )
+        .to_xdr(&env);
+    verify_device_message(&env, warranty_id, nonce, &message, &signature)?;
+
+    let claims_key = ClaimKey::Claims(warranty_id);
+    let mut claims: Vec<u64> = read_record(&env, &claims_key).unwrap_or(Vec::new(&env));
+    if claims.len() >= read_limits(&env).max_page_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    let now = env.ledger().timestamp();
+    let claim = ClaimData {
+        claim_id: next_claim_id(&env),
+        warranty_id,
+        claimant: warranty.owner,
+        category: ClaimCategory::Defect,
+        description: fault_description(&env, fault_code),
+        status: ClaimStatus::Draft,
+        incident_date: now,
+        filed_at: now,
+        decided_at: None,
+        resolved_at: None,
+        denial: ClaimDenial::NotDenied,
+        requested_amount: None,
+        approved_amount: None,
+        paid_amount: 0,
+        settlement_token: None,
+        settlement_rate: None,
+        rate_quoted_at: None,
+    };
+    save_claim(&env, &claim);
+    claims.push_back(claim.claim_id);
+    write_record(&env, &claims_key, &claims);
+    publish_claim(&env, &claim);
+
+    notify_change(&env, symbol_short!("fault"), warranty_id, correlation_id);
+
+    Ok(FiledClaim {
+        claim_id: claim.claim_id,
+        receipt: receipt_since(&env, since),
+    })
+}
@@ -0,0 +1,397 @@
+//! Arbitrators, claim disputes and serial number conflicts.
+use soroban_sdk::{symbol_short, token, Address, BytesN, Env, String, Vec};
+
+use super::*;
+
+pub(crate) fn set_arbitrator(
+    env: Env,
+    arbitrator: Address,
+    authorized: bool,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_sole_admin(&env)?;
+
+    let since = audit_tail(&env);
+
+    apply_config(&env, ConfigChange::SetArbitrator(arbitrator, authorized))?;
+
+    notify_change(
+        &env,
+        symbol_short!("arbiter"),
+        NO_WARRANTY_ID,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn is_arbitrator(env: Env, arbitrator: Address) -> bool {
+    has_record(&env, &ArbitrationKey::Arbitrator(arbitrator))
+}
+
+pub(crate) fn set_dispute_quorum(env: Env, quorum: u32) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetDisputeQuorum(quorum))
+}
+
+pub(crate) fn get_dispute_quorum(env: Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ArbitrationKey::Quorum)
+        .unwrap_or(1)
+}
+
+pub(crate) fn set_dispute_fee(env: Env, fee: Option<DisputeFee>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    match fee {
+        Some(fee) => apply_config(&env, ConfigChange::SetDisputeFee(fee)),
+        None => apply_config(&env, ConfigChange::ClearDisputeFee),
+    }
+}
+
+pub(crate) fn get_dispute_fee(env: Env) -> Option<DisputeFee> {
+    env.storage().instance().get(&ArbitrationKey::FeeTerms)
+}
+
+/// Split a ruled dispute's fee among the arbitrators that voted for the
+/// ruling, the remainder going to `decider`, and return the owner's fee
+/// when the claim's manufacturer lost.
+///
+/// The manufacturer's share comes out of its claim pool in the fee token,
+/// failing with `InsufficientFunds` as a payout would.
+fn settle_dispute_fee(
+    env: &Env,
+    claim: &ClaimData,
+    dispute: &ClaimDispute,
+    voters: &Vec<Address>,
+    decider: &Address,
+) -> Result<(), WarrantyError> {
+    let Some(token) = dispute.fee_token.clone() else {
+        return Ok(());
+    };
+    let fee = dispute.fee;
+
+    if dispute.upheld == Some(true) {
+        let warranty = require_warranty(env, claim.warranty_id)?;
+        let payer = claim_payer(env, &warranty).ok_or(WarrantyError::NotFound)?;
+        pay_from_pool(env, &payer, &token, &dispute.disputed_by, fee)?;
+        review_solvency(env, &payer);
+    }
+
+    let share = fee / voters.len() as i128;
+    for voter in voters.iter() {
+        let mut amount = share;
+        if voter == *decider {
+            amount += fee - share * voters.len() as i128;
+        }
+        let key = ArbitrationKey::FeesOwed(voter, token.clone());
+        let owed: i128 = read_record(env, &key).unwrap_or(0);
+        write_record(env, &key, &(owed + amount));
+    }
+    let pool_key = ArbitrationKey::FeePool(token);
+    let pool: i128 = read_record(env, &pool_key).unwrap_or(0);
+    write_record(env, &pool_key, &(pool + fee));
+    Ok(())
+}
+
+pub(crate) fn get_arbitrator_fees(env: Env, arbitrator: Address, token: Address) -> i128 {
+    read_record(&env, &ArbitrationKey::FeesOwed(arbitrator, token)).unwrap_or(0)
+}
+
+pub(crate) fn get_dispute_fee_pool(env: Env, token: Address) -> i128 {
+    read_record(&env, &ArbitrationKey::FeePool(token)).unwrap_or(0)
+}
+
+pub(crate) fn withdraw_arbitrator_fees(
+    env: Env,
+    arbitrator: Address,
+    token: Address,
+) -> Result<i128, WarrantyError> {
+    require_not_paused(&env)?;
+
+    arbitrator.require_auth();
+
+    let key = ArbitrationKey::FeesOwed(arbitrator.clone(), token.clone());
+    let owed: i128 = read_record(&env, &key).unwrap_or(0);
+    if owed == 0 {
+        return Ok(0);
+    }
+
+    check_compliance(&env, &env.current_contract_address(), &arbitrator)?;
+    remove_record(&env, &key);
+    let pool_key = ArbitrationKey::FeePool(token.clone());
+    let pool: i128 = read_record(&env, &pool_key).unwrap_or(0);
+    write_record(&env, &pool_key, &(pool - owed));
+    token::Client::new(&env, &token).transfer(&env.current_contract_address(), &arbitrator, &owed);
+    Ok(owed)
+}
+
+pub(crate) fn dispute_claim(
+    env: Env,
+    claim_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "arbitration")?;
+
+    let since = audit_tail(&env);
+
+    let mut claim = require_claim(&env, claim_id)?;
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+
+    warranty.owner.require_auth();
+    require_not_frozen(&env, warranty.id)?;
+    if claim.status != ClaimStatus::Rejected {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    let dispute_key = ArbitrationKey::Dispute(claim_id);
+    if has_record(&env, &dispute_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    let rejected_at = claim.decided_at.unwrap_or(claim.filed_at);
+    if env.ledger().timestamp() > rejected_at.saturating_add(DISPUTE_WINDOW_SECS) {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    // The owner puts the fee down; it comes back if the dispute is upheld
+    let fee = get_dispute_fee(env.clone());
+    if let Some(fee) = &fee {
+        check_compliance(&env, &warranty.owner, &env.current_contract_address())?;
+        token::Client::new(&env, &fee.token).transfer(
+            &warranty.owner,
+            env.current_contract_address(),
+            &fee.amount,
+        );
+    }
+
+    write_record(
+        &env,
+        &dispute_key,
+        &ClaimDispute {
+            claim_id,
+            disputed_at: env.ledger().timestamp(),
+            arbitrator: None,
+            upheld: None,
+            ruled_at: None,
+            quorum: get_dispute_quorum(env.clone()),
+            votes_cast: 0,
+            disputed_by: warranty.owner.clone(),
+            fee_token: fee.as_ref().map(|fee| fee.token.clone()),
+            fee: fee.map_or(0, |fee| fee.amount),
+        },
+    );
+    claim.status = ClaimStatus::Disputed;
+    save_claim(&env, &claim);
+    record_history(
+        &env,
+        warranty.id,
+        &warranty.owner,
+        symbol_short!("dispute"),
+        HistoryDetails::ClaimDecided(claim_id, ClaimStatus::Disputed),
+    );
+    publish_claim(&env, &claim);
+
+    notify_change(&env, symbol_short!("dispute"), warranty.id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn rule_on_dispute(
+    env: Env,
+    arbitrator: Address,
+    claim_id: u64,
+    uphold: bool,
+    payout: Option<ClaimPayout>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    let outcome = match (uphold, payout) {
+        (false, None) => DisputeOutcome::Dismiss,
+        (false, Some(_)) => return Err(WarrantyError::InvalidInput),
+        (true, None) => DisputeOutcome::Uphold,
+        (true, Some(payout)) => DisputeOutcome::UpholdWithPayout(payout),
+    };
+    vote_on_dispute(env, arbitrator, claim_id, outcome, correlation_id)
+}
+
+pub(crate) fn vote_on_dispute(
+    env: Env,
+    arbitrator: Address,
+    claim_id: u64,
+    outcome: DisputeOutcome,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "arbitration")?;
+
+    let since = audit_tail(&env);
+
+    arbitrator.require_auth();
+    let claim = require_claim(&env, claim_id);
+    let authorized = match &claim {
+        Ok(claim) => may_arbitrate_claim(&env, &arbitrator, claim),
+        Err(_) => is_arbitrator(env.clone(), arbitrator.clone()),
+    };
+    if !authorized {
+        return Err(WarrantyError::Unauthorized);
+    }
+    let mut claim = claim?;
+    require_not_frozen(&env, claim.warranty_id)?;
+    if claim.status != ClaimStatus::Disputed {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+
+    let dispute_key = ArbitrationKey::Dispute(claim_id);
+    let mut dispute: ClaimDispute =
+        read_record(&env, &dispute_key).ok_or(WarrantyError::NotFound)?;
+    let ballot_key = ArbitrationKey::Ballot(claim_id, arbitrator.clone());
+    if has_record(&env, &ballot_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    write_record(&env, &ballot_key, &outcome);
+    let tally_key = ArbitrationKey::Tally(claim_id, outcome.clone());
+    let mut voters: Vec<Address> = read_record(&env, &tally_key).unwrap_or(Vec::new(&env));
+    voters.push_back(arbitrator.clone());
+    write_record(&env, &tally_key, &voters);
+    dispute.votes_cast += 1;
+
+    // The ruling waits until one outcome holds a majority of the quorum
+    if voters.len() <= dispute.quorum / 2 {
+        write_record(&env, &dispute_key, &dispute);
+        notify_change(
+            &env,
+            symbol_short!("vote"),
+            claim.warranty_id,
+            correlation_id,
+        );
+        return Ok(receipt_since(&env, since));
+    }
+
+    let (uphold, payout) = match outcome {
+        DisputeOutcome::Dismiss => (false, None),
+        DisputeOutcome::Uphold => (true, None),
+        DisputeOutcome::UpholdWithPayout(payout) => (true, Some(payout)),
+    };
+    let now = env.ledger().timestamp();
+    dispute.arbitrator = Some(arbitrator.clone());
+    dispute.upheld = Some(uphold);
+    dispute.ruled_at = Some(now);
+    write_record(&env, &dispute_key, &dispute);
+
+    if uphold {
+        claim.status = ClaimStatus::Approved;
+        claim.denial = ClaimDenial::NotDenied;
+    } else {
+        claim.status = ClaimStatus::Rejected;
+    }
+    claim.decided_at = Some(now);
+    save_claim(&env, &claim);
+    record_history(
+        &env,
+        claim.warranty_id,
+        &arbitrator,
+        symbol_short!("ruling"),
+        HistoryDetails::ClaimDecided(claim_id, claim.status),
+    );
+    publish_claim(&env, &claim);
+
+    if uphold {
+        settle_approved_claim(&env, &arbitrator, &claim, None, payout)?;
+    }
+    settle_dispute_fee(&env, &claim, &dispute, &voters, &arbitrator)?;
+
+    notify_change(
+        &env,
+        symbol_short!("ruling"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_dispute_vote(
+    env: Env,
+    claim_id: u64,
+    arbitrator: Address,
+) -> Option<DisputeOutcome> {
+    read_record(&env, &ArbitrationKey::Ballot(claim_id, arbitrator))
+}
+
+pub(crate) fn get_dispute(env: Env, claim_id: u64) -> Option<ClaimDispute> {
+    read_record(&env, &ArbitrationKey::Dispute(claim_id))
+}
+
+pub(crate) fn resolve_serial_conflict(
+    env: Env,
+    arbitrator: Address,
+    serial_number: String,
+    winner_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "arbitration")?;
+
+    let since = audit_tail(&env);
+
+    arbitrator.require_auth();
+    if !is_arbitrator(env.clone(), arbitrator.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let winner = require_warranty(&env, winner_id)?;
+    let key = ConflictKey::Serial(winner.manufacturer.clone(), serial_number);
+    let mut conflict: SerialConflict = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    if conflict.winner_id.is_some() {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    if !conflict.warranty_ids.contains(winner_id) {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    for loser_id in conflict.warranty_ids.iter() {
+        if loser_id == winner_id {
+            continue;
+        }
+        let Some(loser) = load_warranty(&env, loser_id) else {
+            continue;
+        };
+        if loser.status == WarrantyStatus::Revoked
+            || !status_transition_allowed(
+                &loser.status,
+                &WarrantyStatus::Revoked,
+                StatusAuthority::Admin,
+            )
+        {
+            continue;
+        }
+        apply_status(
+            &env,
+            &arbitrator,
+            loser,
+            WarrantyStatus::Revoked,
+            StatusAuthority::Admin,
+        )?;
+        notify_change(
+            &env,
+            symbol_short!("status"),
+            loser_id,
+            correlation_id.clone(),
+        );
+    }
+    record_serial(&env, &winner);
+
+    conflict.winner_id = Some(winner_id);
+    conflict.arbitrator = Some(arbitrator);
+    conflict.resolved_at = Some(env.ledger().timestamp());
+    write_record(&env, &key, &conflict);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_serial_conflict(
+    env: Env,
+    manufacturer: String,
+    serial_number: String,
+) -> Option<SerialConflict> {
+    read_record(&env, &ConflictKey::Serial(manufacturer, serial_number))
+}
@@ -0,0 +1,59 @@
+//! Contract error codes.
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum WarrantyError {
+    /// The destination is not on the current owner's transfer allowlist
+    TransferNotAllowed = 1,
+    /// A page or batch size of zero was requested
+    InvalidLimit = 2,
+    /// A page or batch size above the configured read limits was requested
+    LimitExceeded = 3,
+    /// The caller does not hold the role the entry point requires
+    Unauthorized = 4,
+    /// The warranty cannot move from its current status to the requested one
+    InvalidStatusTransition = 5,
+    /// The deployment was configured without the feature the entry point belongs to
+    FeatureDisabled = 6,
+    /// The warranty, or another record the call refers to, does not exist
+    NotFound = 7,
+    /// A date or timestamp argument is out of order or out of range
+    InvalidDates = 8,
+    /// The warranty must be Active for this operation
+    NotActive = 9,
+    /// The record, registration or value being created already exists
+    AlreadyExists = 10,
+    /// An argument other than a date failed validation
+    InvalidInput = 11,
+    /// The time window for this action has closed or not opened yet
+    OutsideWindow = 12,
+    /// No admin is set; deployments get one from the constructor, so this
+    /// code is kept only to leave the numbering unchanged
+    NotInitialized = 13,
+    /// No longer returned now that the constructor sets the admin
+    AlreadyInitialized = 14,
+    /// The compliance hook refused, or could not judge, a transfer or payment
+    /// between the parties
+    TransferDenied = 15,
+    /// A claim pool holds less than the payout or withdrawal asks for
+    InsufficientFunds = 16,
+    /// The deployment registered as many warranties as its capacity allows
+    CapacityReached = 17,
+    /// The admin paused the contract; only reads and admin calls go through
+    Paused = 18,
+    /// The warranty is frozen while under investigation
+    Frozen = 19,
+    /// The warranty's terms require a retailer's purchase attestation it lacks
+    NotAttested = 20,
+    /// A registration, counterfeit report or claim cap is used up for now
+    RateLimited = 21,
+    /// The price oracle gave no usable rate to settle a claim in the token
+    RateUnavailable = 22,
+    /// The claim was filed at a high claim velocity and is held until a
+    /// reviewer clears it
+    ReviewRequired = 23,
+    /// The warranty's coverage terms exclude the claim's category
+    NotCovered = 24,
+}
@@ -0,0 +1,730 @@
+//! Contract events, the outbox, the audit log and hook notifications.
+use soroban_sdk::{
+    contractclient, contractevent, contracttype, Address, BytesN, Env, String, Symbol, Vec,
+};
+
+use super::*;
+
+/// Interface hook contracts implement to react to warranty state changes
+#[contractclient(name = "HookClient")]
+pub trait WarrantyHookInterface {
+    /// Called after `event_type` happened to `warranty_id`, which is 0 for
+    /// changes not tied to one warranty
+    fn on_warranty_event(env: Env, event_type: Symbol, warranty_id: u64);
+}
+
+/// Published on registration while analytics are enabled
+///
+/// Carries no warranty ID or address, so market statistics can be built
+/// without linking registrations to owners.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistrationAnalytics {
+    pub category: Option<String>,
+    /// Coverage length rounded down to 6, 12, 24 or 36 months (0 below 6)
+    pub duration_bucket_months: u32,
+    pub region: Option<String>,
+}
+
+/// Published when registration extends a warranty to its statutory minimum
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatutoryAdjustment {
+    #[topic]
+    pub warranty_id: u64,
+    pub requested_expiration: u64,
+    pub adjusted_expiration: u64,
+}
+
+/// Published when a manufacturer's unsettled approved claims grow past the
+/// solvency threshold of its claim pools and escrow
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolvencyWarning {
+    #[topic]
+    pub manufacturer: Address,
+    pub liabilities: i128,
+    pub collateral: i128,
+}
+
+/// Published when an owner earns a manufacturer's Verified Customer badge
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BadgeIssued {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub manufacturer: String,
+    pub warranty_id: u64,
+}
+
+/// Published when a claim is filed or changes status
+#[contractevent(topics = ["warranty", "claim"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyClaimUpdated {
+    #[topic]
+    pub warranty_id: u64,
+    pub claim_id: u64,
+    pub status: ClaimStatus,
+    /// Set when the claim was rejected
+    pub denial_reason: Option<DenialReason>,
+}
+
+/// Published when a claim is approved, with the amount approved, possibly
+/// less than the claimant asked or 0
+#[contractevent(topics = ["warranty", "payout"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPayoutApproved {
+    #[topic]
+    pub warranty_id: u64,
+    pub claim_id: u64,
+    pub requested_amount: Option<i128>,
+    pub approved_amount: i128,
+    /// How much less than requested was approved, 0 without a request
+    pub difference: i128,
+}
+
+/// Published when a manufacturer recalls or voids a warranty
+#[contractevent(topics = ["warranty", "recall"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyRecalled {
+    #[topic]
+    pub warranty_id: u64,
+    pub manufacturer: Address,
+    /// Recalled or Voided
+    pub status: WarrantyStatus,
+    pub reason: Option<String>,
+}
+
+/// Published when an owner rates a manufacturer
+#[contractevent(topics = ["warranty", "feedback"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeedbackSubmitted {
+    #[topic]
+    pub manufacturer: String,
+    pub subject: FeedbackSubject,
+    pub scores: FeedbackScores,
+}
+
+/// Published when an owner answers a verifier's challenge for a warranty
+#[contractevent(topics = ["warranty", "proof"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnershipProved {
+    #[topic]
+    pub warranty_id: u64,
+    #[topic]
+    pub challenge: BytesN<32>,
+    pub owner: Address,
+}
+
+/// Published when a scheduled reversion returns a warranty
+#[contractevent(topics = ["warranty", "reversion"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyReverted {
+    #[topic]
+    pub warranty_id: u64,
+    pub from: Address,
+    pub to: Address,
+}
+
+/// Published when an issuer schedules a warranty's revocation
+#[contractevent(topics = ["warranty", "revocation_scheduled"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationScheduled {
+    #[topic]
+    pub warranty_id: u64,
+    pub revoke_at: u64,
+    pub reason: String,
+}
+
+/// Published when an issuer cancels a scheduled revocation
+#[contractevent(topics = ["warranty", "revocation_cancelled"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationCancelled {
+    #[topic]
+    pub warranty_id: u64,
+}
+
+/// Published when a scheduled revocation is executed
+#[contractevent(topics = ["warranty", "revocation_executed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationExecuted {
+    #[topic]
+    pub warranty_id: u64,
+    pub reason: String,
+}
+
+/// How much the lifecycle events carry
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventVerbosity {
+    /// Owners, statuses and product details, enough to follow the contract
+    /// without reading its storage
+    Full,
+    /// Only the action and warranty ID, for deployments that watch costs
+    Minimal,
+}
+
+/// Lifecycle event published instead of the full ones at `Minimal`
+/// verbosity; topics are `("warranty", action)` and the data is the ID
+#[contractevent(topics = ["warranty"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyActivity {
+    #[topic]
+    pub action: Symbol,
+    pub warranty_id: u64,
+}
+
+/// Published when a warranty is registered
+#[contractevent(topics = ["warranty", "registered"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyRegistered {
+    #[topic]
+    pub warranty_id: u64,
+    #[topic]
+    pub owner: Address,
+    pub product_name: String,
+    pub manufacturer: String,
+    pub expiration_date: u64,
+}
+
+/// Published whenever a warranty changes hands, however the transfer happened
+#[contractevent(topics = ["warranty", "transferred"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyTransferred {
+    #[topic]
+    pub warranty_id: u64,
+    pub from: Address,
+    pub to: Address,
+}
+
+/// Published whenever a warranty's status changes
+#[contractevent(topics = ["warranty", "status"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyStatusChanged {
+    #[topic]
+    pub warranty_id: u64,
+    pub owner: Address,
+    pub previous: WarrantyStatus,
+    pub status: WarrantyStatus,
+}
+
+/// Published when a sweep marks a warranty as expired
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyExpired {
+    #[topic]
+    pub warranty_id: u64,
+    pub expiration_date: u64,
+}
+
+/// One state change, as recorded in the pull-based outbox
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutboxEntry {
+    pub seq: u64,
+    pub event_type: Symbol,
+    /// 0 for changes not tied to one warranty, such as claim pool funding
+    pub warranty_id: u64,
+    pub ledger: u32,
+    pub timestamp: u64,
+    /// Reference the caller attached to the change, e.g. an ERP transaction ID
+    pub correlation_id: Option<BytesN<16>>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutboxPage {
+    pub entries: Vec<OutboxEntry>,
+    pub next: NextPage,
+}
+
+/// What a mutating call changed, so simulations can preview its effects
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MutationReceipt {
+    /// Warranties the call changed, in the order they were first changed
+    pub warranty_ids: Vec<u64>,
+    /// State changes recorded in the audit log and outbox
+    pub changes: u32,
+    /// Registration counter after the call
+    pub warranty_count: u64,
+}
+
+/// Status of the warranty an audit log entry refers to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuditedStatus {
+    /// The change removed the warranty or was not tied to one
+    Absent,
+    Present(WarrantyStatus),
+}
+
+/// One state change in the audit log, with the owner and status
+/// the warranty had right after it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// 0 for changes not tied to one warranty, such as claim pool funding
+    pub warranty_id: u64,
+    pub action: Symbol,
+    /// None once the change removed the warranty, and the contract's
+    /// address if the warranty was private then or is now
+    pub owner: Option<Address>,
+    pub status: AuditedStatus,
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub correlation_id: Option<BytesN<16>>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub next: NextPage,
+}
+
+/// What a warranty history entry changed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HistoryDetails {
+    Registered,
+    /// Previous and new owner
+    Transferred(Address, Address),
+    /// Previous and new status
+    StatusChanged(WarrantyStatus, WarrantyStatus),
+    /// Previous and new expiration date
+    ExpirationChanged(u64, u64),
+    /// Previous and new product name
+    ProductNameChanged(String, String),
+    /// Previous and new serial number
+    SerialChanged(String, String),
+    /// Previous and new purchase date
+    PurchaseDateChanged(u64, u64),
+    ClaimFiled(u64),
+    /// Claim ID and the status it was moved to
+    ClaimDecided(u64, ClaimStatus),
+    /// Retailer that attested the purchase
+    PurchaseAttested(Address),
+}
+
+/// One change in a warranty's own history, with the address behind it.
+/// Changes the contract makes on its own, such as expiry, name the
+/// contract as actor. Entries sharing a timestamp are ordered by `ledger`
+/// and then by their position in the history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    /// Ledger sequence the change was made in
+    pub ledger: u32,
+    pub actor: Address,
+    pub action: Symbol,
+    pub details: HistoryDetails,
+}
+
+/// Append a change made by `actor` to the history of `warranty_id`.
+pub(crate) fn record_history(
+    env: &Env,
+    warranty_id: u64,
+    actor: &Address,
+    action: Symbol,
+    details: HistoryDetails,
+) {
+    let count_key = HistoryKey::Count(warranty_id);
+    let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let entry_key = HistoryKey::Entry(warranty_id, index);
+    env.storage().persistent().set(
+        &entry_key,
+        &HistoryEntry {
+            timestamp: env.ledger().timestamp(),
+            ledger: env.ledger().sequence(),
+            actor: actor.clone(),
+            action,
+            details,
+        },
+    );
+    extend_persistent(env, &entry_key);
+    env.storage().persistent().set(&count_key, &(index + 1));
+    extend_persistent(env, &count_key);
+}
+
+/// Record a status change of `warranty` in its history and the per-status
+/// counters, if the status actually changed.
+pub(crate) fn record_status_change(
+    env: &Env,
+    warranty: &WarrantyData,
+    actor: &Address,
+    action: Symbol,
+    previous: WarrantyStatus,
+) {
+    if previous == warranty.status {
+        return;
+    }
+    move_status_count(
+        env,
+        &warranty.manufacturer,
+        Some(previous.clone()),
+        Some(warranty.status.clone()),
+    );
+    record_history(
+        env,
+        warranty.id,
+        actor,
+        action,
+        HistoryDetails::StatusChanged(previous, warranty.status.clone()),
+    );
+}
+
+/// Append a state change to the outbox and notify every registered hook
+/// contract about it.
+pub(crate) fn notify_change(
+    env: &Env,
+    event_type: Symbol,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) {
+    let seq: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutboxTail)
+        .unwrap_or(0)
+        + 1;
+    let entry = OutboxEntry {
+        seq,
+        event_type: event_type.clone(),
+        warranty_id,
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+        correlation_id: correlation_id.clone(),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::OutboxEntry(seq), &entry);
+    extend_persistent(env, &DataKey::OutboxEntry(seq));
+    env.storage().instance().set(&DataKey::OutboxTail, &seq);
+
+    let audit_seq: u64 = env.storage().instance().get(&AuditKey::Tail).unwrap_or(0) + 1;
+    let warranty = load_warranty(env, warranty_id);
+    env.storage().persistent().set(
+        &AuditKey::Entry(audit_seq),
+        &AuditEntry {
+            seq: audit_seq,
+            warranty_id,
+            action: event_type.clone(),
+            owner: warranty.as_ref().map(|warranty| audit_owner(env, warranty)),
+            status: warranty.map_or(AuditedStatus::Absent, |warranty| {
+                AuditedStatus::Present(warranty.status)
+            }),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            correlation_id,
+        },
+    );
+    extend_persistent(env, &AuditKey::Entry(audit_seq));
+    env.storage().instance().set(&AuditKey::Tail, &audit_seq);
+
+    let hooks: Vec<Address> = env
+        .storage()
+        .instance()
+        .get(&DataKey::Hooks)
+        .unwrap_or(Vec::new(env));
+    for hook in hooks.iter() {
+        // A failing hook must not roll back the warranty change that triggered it
+        let _ = HookClient::new(env, &hook).try_on_warranty_event(&event_type, &warranty_id);
+    }
+}
+
+/// The owner an audit entry names: the contract's own address while the
+/// warranty is private, as in `public_view`.
+pub(crate) fn audit_owner(env: &Env, warranty: &WarrantyData) -> Address {
+    if warranty.private {
+        env.current_contract_address()
+    } else {
+        warranty.owner.clone()
+    }
+}
+
+/// The configured lifecycle event verbosity, `Full` unless the admin chose
+/// otherwise.
+pub(crate) fn event_verbosity(env: &Env) -> EventVerbosity {
+    env.storage()
+        .instance()
+        .get(&DataKey::EventVerbosity)
+        .unwrap_or(EventVerbosity::Full)
+}
+
+/// Publish the minimal form of a lifecycle event if that verbosity is
+/// configured, or if the full form `names_owner` of a private warranty,
+/// returning whether it did.
+fn published_minimal(env: &Env, action: &str, warranty_id: u64, names_owner: bool) -> bool {
+    if event_verbosity(env) != EventVerbosity::Minimal
+        && !(names_owner && is_private(env, warranty_id))
+    {
+        return false;
+    }
+    WarrantyActivity {
+        action: Symbol::new(env, action),
+        warranty_id,
+    }
+    .publish(env);
+    true
+}
+
+/// Publish the registration of `warranty`.
+pub(crate) fn publish_registered(env: &Env, warranty: &WarrantyData) {
+    if published_minimal(env, "registered", warranty.id, true) {
+        return;
+    }
+    WarrantyRegistered {
+        warranty_id: warranty.id,
+        owner: warranty.owner.clone(),
+        product_name: warranty.product_name.clone(),
+        manufacturer: warranty.manufacturer.clone(),
+        expiration_date: warranty.expiration_date,
+    }
+    .publish(env);
+}
+
+/// Publish a change of owner.
+pub(crate) fn publish_transferred(env: &Env, warranty_id: u64, from: &Address, to: &Address) {
+    if published_minimal(env, "transferred", warranty_id, true) {
+        return;
+    }
+    WarrantyTransferred {
+        warranty_id,
+        from: from.clone(),
+        to: to.clone(),
+    }
+    .publish(env);
+}
+
+/// Publish a claim being filed or changing status.
+pub(crate) fn publish_claim(env: &Env, claim: &ClaimData) {
+    if published_minimal(env, "claim", claim.warranty_id, false) {
+        return;
+    }
+    WarrantyClaimUpdated {
+        warranty_id: claim.warranty_id,
+        claim_id: claim.claim_id,
+        status: claim.status,
+        denial_reason: claim.denial.reason(),
+    }
+    .publish(env);
+}
+
+/// Publish the amount approved on a claim next to the amount asked for. Minimal verbosity leaves this to the claim event.
+pub(crate) fn publish_payout_approved(env: &Env, claim: &ClaimData, approved_amount: i128) {
+    if event_verbosity(env) == EventVerbosity::Minimal {
+        return;
+    }
+    ClaimPayoutApproved {
+        warranty_id: claim.warranty_id,
+        claim_id: claim.claim_id,
+        requested_amount: claim.requested_amount,
+        approved_amount,
+        difference: claim
+            .requested_amount
+            .map_or(0, |requested| requested - approved_amount),
+    }
+    .publish(env);
+}
+
+/// Publish a manufacturer's recall or voiding of a warranty.
+pub(crate) fn publish_recalled(env: &Env, warranty_id: u64, info: &RecallInfo) {
+    if published_minimal(env, "recall", warranty_id, false) {
+        return;
+    }
+    WarrantyRecalled {
+        warranty_id,
+        manufacturer: info.manufacturer.clone(),
+        status: info.status.clone(),
+        reason: info.reason.clone(),
+    }
+    .publish(env);
+}
+
+/// Publish a status change event if the status actually changed.
+pub(crate) fn publish_status_change(env: &Env, warranty: &WarrantyData, previous: WarrantyStatus) {
+    if previous == warranty.status || published_minimal(env, "status", warranty.id, true) {
+        return;
+    }
+    WarrantyStatusChanged {
+        warranty_id: warranty.id,
+        owner: warranty.owner.clone(),
+        previous,
+        status: warranty.status.clone(),
+    }
+    .publish(env);
+}
+
+/// Sequence number of the latest audit log entry, 0 before any change.
+pub(crate) fn audit_tail(env: &Env) -> u64 {
+    env.storage().instance().get(&AuditKey::Tail).unwrap_or(0)
+}
+
+/// Summarize the audit log entries appended after `since`.
+pub(crate) fn receipt_since(env: &Env, since: u64) -> MutationReceipt {
+    let tail = audit_tail(env);
+    let mut warranty_ids = Vec::new(env);
+    for seq in since + 1..=tail {
+        let Some(entry) = env
+            .storage()
+            .persistent()
+            .get::<_, AuditEntry>(&AuditKey::Entry(seq))
+        else {
+            continue;
+        };
+        if entry.warranty_id != NO_WARRANTY_ID && !warranty_ids.contains(entry.warranty_id) {
+            warranty_ids.push_back(entry.warranty_id);
+        }
+    }
+    MutationReceipt {
+        warranty_ids,
+        changes: (tail - since) as u32,
+        warranty_count: env
+            .storage()
+            .instance()
+            .get(&DataKey::WarrantyCount)
+            .unwrap_or(0),
+    }
+}
+
+pub(crate) fn read_outbox(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<OutboxPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let head: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutboxHead)
+        .unwrap_or(1);
+    let tail: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutboxTail)
+        .unwrap_or(0);
+
+    let start = match cursor {
+        Some(cursor) => head.max(cursor.last_id.saturating_add(1)),
+        None => head,
+    };
+    let end = tail.min(start.saturating_add(limit as u64 - 1));
+
+    let mut entries = Vec::new(&env);
+    for seq in start..=end {
+        if let Some(entry) = env.storage().persistent().get(&DataKey::OutboxEntry(seq)) {
+            entries.push_back(entry);
+        }
+    }
+
+    let next = if start <= end && end < tail {
+        Some(Cursor {
+            last_id: end,
+            bucket: 0,
+        })
+    } else {
+        None
+    };
+    Ok(OutboxPage {
+        entries,
+        next: next.into(),
+    })
+}
+
+pub(crate) fn read_audit_log(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<AuditPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let head: u64 = env.storage().instance().get(&AuditKey::Head).unwrap_or(1);
+    let tail = audit_tail(&env);
+
+    let start = match cursor {
+        Some(cursor) => head.max(cursor.last_id.saturating_add(1)),
+        None => head,
+    };
+    let end = tail.min(start.saturating_add(limit as u64 - 1));
+
+    let mut entries = Vec::new(&env);
+    for seq in start..=end {
+        if let Some(mut entry) = env
+            .storage()
+            .persistent()
+            .get::<_, AuditEntry>(&AuditKey::Entry(seq))
+        {
+            if entry.owner.is_some() && is_private(&env, entry.warranty_id) {
+                entry.owner = Some(env.current_contract_address());
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    let next = if start <= end && end < tail {
+        Some(Cursor {
+            last_id: end,
+            bucket: 0,
+        })
+    } else {
+        None
+    };
+    Ok(AuditPage {
+        entries,
+        next: next.into(),
+    })
+}
+
+pub(crate) fn prune_outbox(env: Env, up_to_seq: u64) -> Result<u32, WarrantyError> {
+    require_admin(&env)?;
+
+    let head: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutboxHead)
+        .unwrap_or(1);
+    let tail: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutboxTail)
+        .unwrap_or(0);
+    let end = up_to_seq
+        .min(tail)
+        .min(head.saturating_add(read_limits(&env).max_batch_size as u64 - 1));
+    if end < head {
+        return Ok(0);
+    }
+
+    for seq in head..=end {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::OutboxEntry(seq));
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::OutboxHead, &(end + 1));
+    Ok((end - head + 1) as u32)
+}
+
+pub(crate) fn prune_audit_log(env: Env, up_to_seq: u64) -> Result<u32, WarrantyError> {
+    require_admin(&env)?;
+
+    let head: u64 = env.storage().instance().get(&AuditKey::Head).unwrap_or(1);
+    let end = up_to_seq
+        .min(audit_tail(&env))
+        .min(head.saturating_add(read_limits(&env).max_batch_size as u64 - 1));
+    if end < head {
+        return Ok(0);
+    }
+
+    for seq in head..=end {
+        env.storage().persistent().remove(&AuditKey::Entry(seq));
+    }
+    env.storage().instance().set(&AuditKey::Head, &(end + 1));
+    Ok((end - head + 1) as u32)
+}
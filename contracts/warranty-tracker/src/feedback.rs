@@ -0,0 +1,67 @@
+//! Owner feedback on manufacturers and their ratings.
+use soroban_sdk::{symbol_short, BytesN, Env, String};
+
+use super::*;
+
+pub(crate) fn submit_feedback(
+    env: Env,
+    subject: FeedbackSubject,
+    scores: FeedbackScores,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "feedback")?;
+
+    let since = audit_tail(&env);
+
+    let (author, warranty) = feedback_author(&env, &subject)?;
+    author.require_auth();
+
+    for score in [scores.overall, scores.service, scores.product_quality] {
+        if !(1..=MAX_FEEDBACK_SCORE).contains(&score) {
+            return Err(WarrantyError::InvalidInput);
+        }
+    }
+    let given_key = FeedbackKey::Given(subject.clone());
+    if has_record(&env, &given_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    let now = env.ledger().timestamp();
+    let last_key = FeedbackKey::Last(author.clone());
+    let last: Option<u64> = read_record(&env, &last_key);
+    if last.is_some_and(|last| now < last.saturating_add(FEEDBACK_INTERVAL_SECS)) {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    write_record(&env, &given_key, &now);
+    write_record(&env, &last_key, &now);
+    let rating_key = FeedbackKey::Rating(warranty.manufacturer.clone());
+    let mut rating = get_manufacturer_rating(env.clone(), warranty.manufacturer.clone());
+    rating.responses = rating.responses.saturating_add(1);
+    rating.overall_total = rating.overall_total.saturating_add(scores.overall as u64);
+    rating.service_total = rating.service_total.saturating_add(scores.service as u64);
+    rating.product_quality_total = rating
+        .product_quality_total
+        .saturating_add(scores.product_quality as u64);
+    write_record(&env, &rating_key, &rating);
+
+    FeedbackSubmitted {
+        manufacturer: warranty.manufacturer,
+        subject,
+        scores,
+    }
+    .publish(&env);
+
+    notify_change(&env, symbol_short!("feedback"), warranty.id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_manufacturer_rating(env: Env, manufacturer: String) -> ManufacturerRating {
+    read_record(&env, &FeedbackKey::Rating(manufacturer)).unwrap_or(ManufacturerRating {
+        responses: 0,
+        overall_total: 0,
+        service_total: 0,
+        product_quality_total: 0,
+    })
+}
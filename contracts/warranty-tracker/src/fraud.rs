@@ -0,0 +1,240 @@
+//! Claim velocity signals, review holds and chargebacks.
+use soroban_sdk::{symbol_short, token, Address, BytesN, Env, Vec};
+
+use super::*;
+
+pub(crate) fn set_velocity_thresholds(
+    env: Env,
+    thresholds: Option<VelocityThresholds>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    match thresholds {
+        Some(thresholds) => apply_config(&env, ConfigChange::SetVelocityThresholds(thresholds)),
+        None => apply_config(&env, ConfigChange::ClearVelocityThresholds),
+    }
+}
+
+pub(crate) fn get_velocity_thresholds(env: Env) -> Option<VelocityThresholds> {
+    env.storage().instance().get(&FraudKey::VelocityThresholds)
+}
+
+pub(crate) fn get_fraud_signals(env: Env, warranty_id: u64) -> Result<FraudSignals, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+    Ok(signals(&env, &warranty))
+}
+
+/// Recent claim counts and chargebacks for `warranty`'s serial number and
+/// owner, and whether the next claim on it will be held for review.
+fn signals(env: &Env, warranty: &WarrantyData) -> FraudSignals {
+    let serial_key = FraudKey::SerialFilings(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+    );
+    let owner_key = FraudKey::OwnerFilings(warranty.owner.clone());
+    let mut signals = FraudSignals {
+        serial_claims_30d: count_filings(env, &serial_key, SHORT_VELOCITY_WINDOW_SECS),
+        serial_claims_90d: count_filings(env, &serial_key, LONG_VELOCITY_WINDOW_SECS),
+        owner_claims_30d: count_filings(env, &owner_key, SHORT_VELOCITY_WINDOW_SECS),
+        owner_claims_90d: count_filings(env, &owner_key, LONG_VELOCITY_WINDOW_SECS),
+        owner_chargebacks: read_record(env, &FraudKey::Chargebacks(warranty.owner.clone()))
+            .unwrap_or(0),
+        requires_review: false,
+    };
+    signals.requires_review = signals.owner_chargebacks > 0;
+    if let Some(thresholds) = get_velocity_thresholds(env.clone()) {
+        signals.requires_review |= signals.serial_claims_30d.max(signals.owner_claims_30d)
+            >= thresholds.max_claims_30d
+            || signals.serial_claims_90d.max(signals.owner_claims_90d) >= thresholds.max_claims_90d;
+    }
+    signals
+}
+
+fn count_filings(env: &Env, key: &FraudKey, window_secs: u64) -> u32 {
+    let now = env.ledger().timestamp();
+    read_record::<_, Vec<u64>>(env, key)
+        .unwrap_or(Vec::new(env))
+        .iter()
+        .filter(|filed_at| now.saturating_sub(*filed_at) < window_secs)
+        .count() as u32
+}
+
+/// Count a claim being filed on `warranty` towards its serial number's and
+/// owner's velocity, holding it for review if they were already at a
+/// threshold.
+pub(crate) fn record_filing(env: &Env, warranty: &WarrantyData, claim_id: u64) {
+    if signals(env, warranty).requires_review {
+        write_record(env, &FraudKey::ReviewHold(claim_id), &true);
+    }
+    let serial_key = FraudKey::SerialFilings(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+    );
+    add_filing(env, &serial_key);
+    add_filing(env, &FraudKey::OwnerFilings(warranty.owner.clone()));
+}
+
+/// Append the current time to a filing list, dropping filings older than
+/// the long window and, past `MAX_TRACKED_FILINGS`, the oldest one.
+fn add_filing(env: &Env, key: &FraudKey) {
+    let now = env.ledger().timestamp();
+    let mut filings = Vec::new(env);
+    for filed_at in read_record::<_, Vec<u64>>(env, key)
+        .unwrap_or(Vec::new(env))
+        .iter()
+    {
+        if now.saturating_sub(filed_at) < LONG_VELOCITY_WINDOW_SECS {
+            filings.push_back(filed_at);
+        }
+    }
+    if filings.len() >= MAX_TRACKED_FILINGS {
+        filings.pop_front();
+    }
+    filings.push_back(now);
+    write_record(env, key, &filings);
+}
+
+pub(crate) fn is_held_for_review(env: Env, claim_id: u64) -> bool {
+    has_record(&env, &FraudKey::ReviewHold(claim_id))
+}
+
+pub(crate) fn clear_claim_review(
+    env: Env,
+    reviewer: Address,
+    claim_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    reviewer.require_auth();
+    let claim = require_claim(&env, claim_id)?;
+    if !is_claim_reviewer(&env, &reviewer, &claim)? {
+        return Err(WarrantyError::Unauthorized);
+    }
+    let key = FraudKey::ReviewHold(claim_id);
+    if !has_record(&env, &key) {
+        return Err(WarrantyError::NotFound);
+    }
+    remove_record(&env, &key);
+
+    notify_change(
+        &env,
+        symbol_short!("review"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn chargeback(
+    env: Env,
+    arbitrator: Address,
+    claim_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "chargebacks")?;
+
+    let since = audit_tail(&env);
+
+    arbitrator.require_auth();
+    let mut claim = require_claim(&env, claim_id)?;
+    if !may_arbitrate_claim(&env, &arbitrator, &claim) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    // Only settled claims that paid out can be reversed
+    let amount = claim.paid_amount;
+    let token: Option<Address> = read_record(&env, &ClaimKey::PaidIn(claim_id));
+    let Some(token) = token.filter(|_| claim.status == ClaimStatus::Resolved && amount > 0) else {
+        return Err(WarrantyError::InvalidStatusTransition);
+    };
+    let warranty = require_warranty(&env, claim.warranty_id)?;
+    let payer = claim_payer(&env, &warranty).ok_or(WarrantyError::NotFound)?;
+
+    // The payout left the contract with the claim, so nothing else the
+    // claimant is owed here is touched; it comes back through repayments
+    write_record(
+        &env,
+        &FraudKey::Chargeback(claim_id),
+        &Chargeback {
+            claim_id,
+            arbitrator: arbitrator.clone(),
+            token,
+            amount,
+            payer: payer.clone(),
+            recovered: 0,
+            charged_at: env.ledger().timestamp(),
+        },
+    );
+    let flag_key = FraudKey::Chargebacks(claim.claimant.clone());
+    let chargebacks: u32 = read_record(&env, &flag_key).unwrap_or(0);
+    write_record(&env, &flag_key, &(chargebacks + 1));
+
+    claim.status = ClaimStatus::ChargedBack;
+    save_claim(&env, &claim);
+    record_history(
+        &env,
+        claim.warranty_id,
+        &arbitrator,
+        symbol_short!("chargebk"),
+        HistoryDetails::ClaimDecided(claim_id, claim.status),
+    );
+    publish_claim(&env, &claim);
+    review_solvency(&env, &payer);
+
+    notify_change(
+        &env,
+        symbol_short!("chargebk"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_chargeback(env: Env, claim_id: u64) -> Option<Chargeback> {
+    read_record(&env, &FraudKey::Chargeback(claim_id))
+}
+
+/// Repay `amount` of a charged-back claim's payout from `from` into the
+/// payer's claim pool, up to what is still outstanding on it.
+pub(crate) fn repay_chargeback(
+    env: Env,
+    from: Address,
+    claim_id: u64,
+    amount: i128,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    from.require_auth();
+    let key = FraudKey::Chargeback(claim_id);
+    let mut chargeback: Chargeback = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    if amount <= 0 || amount > chargeback.amount - chargeback.recovered {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    token::Client::new(&env, &chargeback.token).transfer(
+        &from,
+        env.current_contract_address(),
+        &amount,
+    );
+    credit_pool(&env, &chargeback.payer, &chargeback.token, amount);
+    chargeback.recovered += amount;
+    write_record(&env, &key, &chargeback);
+    review_solvency(&env, &chargeback.payer);
+
+    let claim = require_claim(&env, claim_id)?;
+    notify_change(
+        &env,
+        symbol_short!("repaid"),
+        claim.warranty_id,
+        correlation_id,
+    );
+
+    Ok(receipt_since(&env, since))
+}
@@ -0,0 +1,493 @@
+//! Expiry sweeps, TTL maintenance and rent, keeper rewards, owner index
+//! compaction and storage migration.
+use soroban_sdk::{token, Address, Env};
+
+use super::*;
+
+pub(crate) fn rebuild_owner_index(
+    env: Env,
+    owner: Address,
+    from_id: u64,
+    to_id: u64,
+) -> Result<u32, WarrantyError> {
+    require_admin(&env)?;
+    check_batch_range(&env, from_id, to_id)?;
+
+    let owner_key = DataKey::OwnerWarranties(owner.clone());
+    let mut owner_warranties = read_index(&env, &owner_key);
+
+    let archive_key = DataKey::ArchivedOwnerWarranties(owner.clone());
+    let mut archived = read_index(&env, &archive_key);
+
+    let co_owned_key = DataKey::CoOwnedWarranties(owner.clone());
+    let mut co_owned = read_index(&env, &co_owned_key);
+
+    let mut changes = 0;
+    for warranty_id in from_id..=to_id {
+        let warranty = load_warranty(&env, warranty_id);
+        let owned = warranty
+            .as_ref()
+            .is_some_and(|warranty| warranty.owner == owner);
+
+        let shared = warranty.is_some_and(|warranty| warranty.co_owners.contains(&owner));
+        let listed = co_owned.iter().filter(|id| *id == warranty_id).count();
+        if (shared && listed != 1) || (!shared && listed != 0) {
+            co_owned = without_id(&env, &co_owned, warranty_id);
+            if shared {
+                insert_sorted(&mut co_owned, warranty_id);
+            }
+            changes += 1;
+        }
+
+        let occurrences = owner_warranties
+            .iter()
+            .filter(|id| *id == warranty_id)
+            .count();
+
+        if archived.contains(warranty_id) {
+            if owned && occurrences == 0 {
+                continue;
+            }
+            archived = without_id(&env, &archived, warranty_id);
+            changes += 1;
+        }
+
+        if owned && occurrences == 1 {
+            continue;
+        }
+        if !owned && occurrences == 0 {
+            continue;
+        }
+
+        owner_warranties = without_id(&env, &owner_warranties, warranty_id);
+        if owned {
+            insert_sorted(&mut owner_warranties, warranty_id);
+        }
+        changes += 1;
+    }
+
+    write_index(&env, &owner_key, &owner_warranties);
+    write_index(&env, &archive_key, &archived);
+    write_index(&env, &co_owned_key, &co_owned);
+    Ok(changes)
+}
+
+pub(crate) fn rebuild_serial_index(
+    env: Env,
+    from_id: u64,
+    to_id: u64,
+) -> Result<u32, WarrantyError> {
+    require_admin(&env)?;
+    check_batch_range(&env, from_id, to_id)?;
+
+    let mut repaired = 0;
+    for warranty_id in from_id..=to_id {
+        let Some(warranty) = load_warranty(&env, warranty_id) else {
+            continue;
+        };
+        let rule = read_record(&env, &UniquenessKey::Rule(warranty.manufacturer.clone()))
+            .unwrap_or(UniquenessRule::Serial);
+        let serial_held =
+            find_by_serial(&env, &warranty.manufacturer, &warranty.serial_number).is_some();
+        let product_held = rule != UniquenessRule::SerialAndProduct
+            || serial_holder(
+                &env,
+                &warranty.manufacturer,
+                &warranty.serial_number,
+                &warranty.product_name,
+                None,
+            )
+            .is_some();
+        if serial_held && product_held {
+            continue;
+        }
+        record_serial(&env, &warranty);
+        repaired += 1;
+    }
+    Ok(repaired)
+}
+
+pub(crate) fn migrate_storage(env: Env, limit: u32) -> Result<u32, WarrantyError> {
+    require_admin(&env)?;
+    if limit == 0 {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if limit > read_limits(&env).max_batch_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    Ok(migrate_legacy_warranties(&env, limit))
+}
+
+pub(crate) fn expire_warranty(env: Env, warranty_id: u64) -> Result<bool, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    if warranty.status != WarrantyStatus::Active
+        || effective_status(&env, &warranty) != WarrantyStatus::Expired
+    {
+        return Ok(false);
+    }
+
+    warranty.status = WarrantyStatus::Expired;
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    publish_expired(&env, &warranty);
+    Ok(true)
+}
+
+pub(crate) fn expire_batch(
+    env: Env,
+    from_id: u64,
+    to_id: u64,
+    limit: u32,
+) -> Result<u32, WarrantyError> {
+    require_not_paused(&env)?;
+
+    expire_range(&env, from_id, to_id, limit)
+}
+
+pub(crate) fn sweep_expired(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<SweepPage, WarrantyError> {
+    require_not_paused(&env)?;
+    if limit == 0 {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if limit > read_limits(&env).max_batch_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    let page = id_index_page(&env, cursor, limit);
+    Ok(SweepPage {
+        expired: expire_ids(&env, page.ids.iter(), limit),
+        next: page.next,
+    })
+}
+
+pub(crate) fn set_keeper_reward_token(env: Env, token: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetKeeperRewardToken(token))
+}
+
+pub(crate) fn set_keeper_fee(env: Env, task: KeeperTask, fee: i128) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetKeeperFee(task, fee))
+}
+
+pub(crate) fn get_keeper_fee(env: Env, task: KeeperTask) -> i128 {
+    env.storage()
+        .instance()
+        .get(&KeeperKey::Fee(task))
+        .unwrap_or(0)
+}
+
+pub(crate) fn extend_warranty_ttl(
+    env: Env,
+    warranty_id: u64,
+    ledgers: u32,
+) -> Result<TtlStatus, WarrantyError> {
+    if ledgers == 0 || ledgers > env.storage().max_ttl() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let warranty = require_warranty(&env, warranty_id)?;
+    if !env
+        .storage()
+        .persistent()
+        .has(&DataKey::Warranty(warranty_id))
+    {
+        save_warranty(&env, &warranty);
+    }
+    extend_warranty_entries(&env, &warranty, ledgers);
+    Ok(ttl_status(&env, warranty_id))
+}
+
+pub(crate) fn get_ttl_status(env: Env, warranty_id: u64) -> Result<TtlStatus, WarrantyError> {
+    require_warranty(&env, warranty_id)?;
+    Ok(ttl_status(&env, warranty_id))
+}
+
+pub(crate) fn set_rent_terms(env: Env, terms: Option<RentTerms>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    match terms {
+        Some(terms) => apply_config(&env, ConfigChange::SetRentTerms(terms)),
+        None => apply_config(&env, ConfigChange::ClearRentTerms),
+    }
+}
+
+pub(crate) fn get_rent_terms(env: Env) -> Option<RentTerms> {
+    env.storage().instance().get(&TtlKey::RentTerms)
+}
+
+/// Charge a new warranty's registrant rent for each started day of coverage
+/// beyond the free length, and hold it for keepers extending the record.
+/// Warranties within the free length, and all of them while no rent terms
+/// are set, pay nothing.
+pub(crate) fn collect_rent(env: &Env, warranty: &WarrantyData) {
+    let Some(terms) = get_rent_terms(env.clone()) else {
+        return;
+    };
+    let coverage = warranty.activation_duration.unwrap_or(
+        warranty
+            .expiration_date
+            .saturating_sub(env.ledger().timestamp()),
+    );
+    if coverage <= terms.free_secs {
+        return;
+    }
+    let days = (coverage - terms.free_secs).div_ceil(86400);
+    let rent = terms.daily_rent.saturating_mul(days as i128);
+    token::Client::new(env, &terms.token).transfer(
+        &warranty.registrant,
+        env.current_contract_address(),
+        &rent,
+    );
+    write_record(env, &TtlKey::Rent(warranty.id), &(terms.token, rent));
+}
+
+/// Pay the rent still held for a removed warranty back to its registrant.
+pub(crate) fn refund_rent(env: &Env, warranty: &WarrantyData) {
+    let key = TtlKey::Rent(warranty.id);
+    if let Some((token, balance)) = read_record::<_, (Address, i128)>(env, &key) {
+        if balance > 0 {
+            token::Client::new(env, &token).transfer(
+                &env.current_contract_address(),
+                &warranty.registrant,
+                &balance,
+            );
+        }
+        remove_record(env, &key);
+    }
+}
+
+pub(crate) fn get_rent_balance(env: Env, warranty_id: u64) -> i128 {
+    read_record::<_, (Address, i128)>(&env, &TtlKey::Rent(warranty_id))
+        .map_or(0, |(_, balance)| balance)
+}
+
+pub(crate) fn top_up_rent(
+    env: Env,
+    from: Address,
+    warranty_id: u64,
+    amount: i128,
+) -> Result<i128, WarrantyError> {
+    require_not_paused(&env)?;
+
+    from.require_auth();
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    require_warranty(&env, warranty_id)?;
+
+    // Rent stays in the token it was first paid in
+    let key = TtlKey::Rent(warranty_id);
+    let (token, balance) = match read_record::<_, (Address, i128)>(&env, &key) {
+        Some(rent) => rent,
+        None => (
+            get_rent_terms(env.clone())
+                .ok_or(WarrantyError::NotInitialized)?
+                .token,
+            0,
+        ),
+    };
+    token::Client::new(&env, &token).transfer(&from, env.current_contract_address(), &amount);
+    write_record(&env, &key, &(token, balance + amount));
+    Ok(balance + amount)
+}
+
+pub(crate) fn keeper_extend_warranty_ttl(
+    env: Env,
+    keeper: Address,
+    warranty_id: u64,
+) -> Result<TtlStatus, WarrantyError> {
+    require_feature(&env, "keeper_rewards")?;
+
+    require_keeper(&env, &keeper)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let key = TtlKey::Rent(warranty_id);
+    let (token, balance): (Address, i128) =
+        read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    let status = ttl_status(&env, warranty_id);
+    if !status.expiring_soon {
+        return Ok(status);
+    }
+    // Rent keeps the record alive through its coverage, not beyond
+    if warranty.expiration_date <= env.ledger().timestamp() {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    let fee = get_rent_terms(env.clone()).map_or(0, |terms| terms.bump_fee);
+    if fee > balance {
+        return Err(WarrantyError::InsufficientFunds);
+    }
+    if fee > 0 {
+        check_compliance(&env, &env.current_contract_address(), &keeper)?;
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &keeper, &fee);
+    }
+    write_record(&env, &key, &(token, balance - fee));
+    extend_warranty_entries(
+        &env,
+        &warranty,
+        WARRANTY_TTL_EXTEND_TO.min(env.storage().max_ttl()),
+    );
+    Ok(ttl_status(&env, warranty_id))
+}
+
+pub(crate) fn keeper_bump_ttl(env: Env, keeper: Address) -> Result<bool, WarrantyError> {
+    require_feature(&env, "keeper_rewards")?;
+
+    require_keeper(&env, &keeper)?;
+
+    let max_ttl = env.storage().max_ttl();
+    env.storage().instance().extend_ttl(max_ttl, max_ttl);
+
+    let sequence = env.ledger().sequence();
+    let last_bump: Option<u32> = env.storage().instance().get(&KeeperKey::LastTtlBump);
+    if last_bump.is_some_and(|last| sequence < last + KEEPER_BUMP_INTERVAL_LEDGERS) {
+        return Ok(false);
+    }
+    env.storage()
+        .instance()
+        .set(&KeeperKey::LastTtlBump, &sequence);
+    credit_keeper(&env, &keeper, KeeperTask::TtlBump, 1);
+    Ok(true)
+}
+
+pub(crate) fn keeper_expire_batch(
+    env: Env,
+    keeper: Address,
+    from_id: u64,
+    to_id: u64,
+    limit: u32,
+) -> Result<u32, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "keeper_rewards")?;
+
+    require_keeper(&env, &keeper)?;
+
+    let expired = expire_range(&env, from_id, to_id, limit)?;
+    credit_keeper(&env, &keeper, KeeperTask::ExpirySweep, expired);
+    Ok(expired)
+}
+
+pub(crate) fn get_keeper_earnings(env: Env, keeper: Address) -> i128 {
+    read_record(&env, &KeeperKey::Earnings(keeper)).unwrap_or(0)
+}
+
+fn reward_token(env: &Env) -> Result<Address, WarrantyError> {
+    env.storage()
+        .instance()
+        .get(&KeeperKey::RewardToken)
+        .ok_or(WarrantyError::NotInitialized)
+}
+
+pub(crate) fn fund_keeper_pool(
+    env: Env,
+    funder: Address,
+    amount: i128,
+) -> Result<i128, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "keeper_rewards")?;
+
+    funder.require_auth();
+    if amount <= 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let token = reward_token(&env)?;
+
+    token::Client::new(&env, &token).transfer(&funder, env.current_contract_address(), &amount);
+    let balance = keeper_pool(&env) + amount;
+    env.storage().instance().set(&KeeperKey::Pool, &balance);
+    Ok(balance)
+}
+
+pub(crate) fn get_keeper_pool(env: Env) -> i128 {
+    keeper_pool(&env)
+}
+
+pub(crate) fn claim_keeper_rewards(env: Env, keeper: Address) -> Result<i128, WarrantyError> {
+    require_not_paused(&env)?;
+
+    keeper.require_auth();
+
+    let earnings: i128 = read_record(&env, &KeeperKey::Earnings(keeper.clone())).unwrap_or(0);
+    if earnings == 0 {
+        return Ok(0);
+    }
+    let token = reward_token(&env)?;
+    let pool = keeper_pool(&env);
+    if earnings > pool {
+        return Err(WarrantyError::InsufficientFunds);
+    }
+
+    check_compliance(&env, &env.current_contract_address(), &keeper)?;
+    remove_record(&env, &KeeperKey::Earnings(keeper.clone()));
+    env.storage()
+        .instance()
+        .set(&KeeperKey::Pool, &(pool - earnings));
+    token::Client::new(&env, &token).transfer(&env.current_contract_address(), &keeper, &earnings);
+    Ok(earnings)
+}
+
+pub(crate) fn compact_owner_index(
+    env: Env,
+    owner: Address,
+    cursor: Option<Cursor>,
+) -> Option<Cursor> {
+    owner.require_auth();
+
+    compact_index(&env, &owner, cursor).0
+}
+
+pub(crate) fn keeper_compact_owner_index(
+    env: Env,
+    keeper: Address,
+    owner: Address,
+    cursor: Option<Cursor>,
+) -> Result<Option<Cursor>, WarrantyError> {
+    require_feature(&env, "keeper_rewards")?;
+
+    require_keeper(&env, &keeper)?;
+
+    let (next, archived) = compact_index(&env, &owner, cursor);
+    credit_keeper(&env, &keeper, KeeperTask::IndexCompaction, archived);
+    Ok(next)
+}
+
+/// Archive the ended warranties in the next batch of an owner's hot index,
+/// returning where to resume and how many were archived.
+fn compact_index(env: &Env, owner: &Address, cursor: Option<Cursor>) -> (Option<Cursor>, u32) {
+    let owner_key = DataKey::OwnerWarranties(owner.clone());
+    let owner_warranties = read_index(env, &owner_key);
+
+    let archive_key = DataKey::ArchivedOwnerWarranties(owner.clone());
+    let mut archived = read_index(env, &archive_key);
+
+    let batch = page_ids(&owner_warranties, cursor, read_limits(env).max_batch_size);
+    let mut remaining = owner_warranties;
+    let mut moved = 0;
+    for warranty_id in batch.ids.iter() {
+        let terminal = load_warranty(env, warranty_id).is_some_and(|warranty| {
+            matches!(
+                warranty.status,
+                WarrantyStatus::Expired
+                    | WarrantyStatus::Revoked
+                    | WarrantyStatus::Voided
+                    | WarrantyStatus::Cancelled
+                    | WarrantyStatus::Replaced
+            )
+        });
+        if terminal {
+            remaining = without_id(env, &remaining, warranty_id);
+            insert_sorted(&mut archived, warranty_id);
+            moved += 1;
+        }
+    }
+
+    write_index(env, &owner_key, &remaining);
+    write_index(env, &archive_key, &archived);
+    (batch.next.cursor(), moved)
+}
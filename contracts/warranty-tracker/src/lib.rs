@@ -1,349 +1,6153 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, String, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
+};
+
+mod attestation;
+mod catalog;
+mod claims;
+mod consortium;
+mod counterfeit;
+mod devices;
+mod disputes;
+mod errors;
+mod events;
+mod feedback;
+mod fraud;
+mod keepers;
+mod nft;
+mod queries;
+mod roles;
+mod settings;
+mod storage;
+mod transfers;
+mod vouchers;
+mod warranty;
+
+pub use claims::*;
+pub use errors::*;
+pub use events::*;
+pub use nft::*;
+pub use roles::*;
+pub use storage::*;
+pub use warranty::*;
+
+/// Maximum number of hook contracts notified after each state change
+const MAX_HOOKS: u32 = 5;
+
+/// Read limits applied until the admin configures their own
+const DEFAULT_MAX_PAGE_SIZE: u32 = 100;
+const DEFAULT_MAX_BATCH_SIZE: u32 = 50;
+
+/// How far `purchase_date` may run ahead of ledger time by default, to absorb
+/// point-of-sale clocks that are ahead of the ledger
+const DEFAULT_PURCHASE_DATE_SKEW_SECS: u64 = 3600;
+
+/// How long after registration typos can be corrected by default
+const DEFAULT_EDIT_WINDOW_SECS: u64 = 86400;
+
+/// How long after an incident a claim can be filed by default
+const DEFAULT_CLAIM_WINDOW_SECS: u64 = 30 * 86400;
+
+/// How long after its rejection a claim can be disputed
+const DISPUTE_WINDOW_SECS: u64 = 30 * 86400;
+
+/// Largest dispute quorum the admin can set
+const MAX_DISPUTE_QUORUM: u32 = 15;
+
+/// Length of a month when reading the ages on depreciation curves
+const SECS_PER_MONTH: u64 = 30 * 24 * 60 * 60;
+
+/// Reporters whose dismissed reports outnumber confirmed ones by this much
+/// can no longer file counterfeit reports
+const MAX_NET_DISMISSED_REPORTS: u32 = 3;
+
+/// Counterfeit reports one address may file per day
+const MAX_DAILY_REPORTS: u32 = 5;
+
+/// Set on IDs derived by `register_warranty_deterministic`, keeping them apart
+/// from sequentially assigned IDs
+const DETERMINISTIC_ID_FLAG: u64 = 1 << 63;
+
+/// Registration quotas start over at each day boundary of ledger time, and
+/// their temporary counters are kept for about a day of ledgers
+const QUOTA_DAY_SECS: u64 = 86400;
+const QUOTA_TTL_LEDGERS: u32 = 17_280;
+
+/// Bumped whenever existing entry points change signature or behaviour;
+/// new capabilities are announced through `SUPPORTED_FEATURES` instead
+const INTERFACE_VERSION: u32 = 40;
+
+/// Registrations listed per bucket of the global warranty ID index
+const ID_BUCKET_SIZE: u64 = 256;
+
+/// Category whose serial numbers must be valid VINs
+const VEHICLE_CATEGORY: &str = "Vehicle";
+
+/// Persistent warranty entries are topped back up to about 180 days of TTL
+/// once fewer than about 30 days remain
+const WARRANTY_TTL_THRESHOLD: u32 = 518_400;
+const WARRANTY_TTL_EXTEND_TO: u32 = 3_110_400;
+
+/// How long an extended warranty quote can be purchased after it was issued
+const QUOTE_VALIDITY_SECS: u64 = 3600;
+
+/// How long a transfer offer waits for the recipient to accept, and the
+/// ledgers its temporary entry is kept for (about a week)
+const TRANSFER_OFFER_SECS: u64 = 7 * 86400;
+const TRANSFER_OFFER_TTL_LEDGERS: u32 = 120_960;
+
+/// Ledgers that must pass between two paid TTL bumps (about a day)
+const KEEPER_BUMP_INTERVAL_LEDGERS: u32 = 17_280;
+
+/// How long an owner waits between two pieces of feedback
+const FEEDBACK_INTERVAL_SECS: u64 = 86400;
+
+/// Highest score a feedback question accepts; the lowest is 1
+const MAX_FEEDBACK_SCORE: u32 = 5;
+
+/// Most metadata entries a warranty can carry, and the longest value
+const MAX_METADATA_ENTRIES: u32 = 16;
+const MAX_METADATA_VALUE_LEN: u32 = 256;
+
+/// Most co-owners a warranty can be shared with
+const MAX_CO_OWNERS: u32 = 10;
+
+/// Warranty ID recorded in the outbox and audit log for changes that are
+/// not tied to one warranty, such as claim pool funding
+const NO_WARRANTY_ID: u64 = 0;
+
+/// Most messages a claim's negotiation trail can hold
+const MAX_CLAIM_MESSAGES: u32 = 50;
+
+/// Most installments a claim's payment plan can be split into
+const MAX_PLAN_INSTALLMENTS: u32 = 60;
+
+/// Most members, and most arbitrators on its panel, a consortium can have
+const MAX_CONSORTIUM_SIZE: u32 = 50;
+
+/// Windows claim velocity is counted over
+const SHORT_VELOCITY_WINDOW_SECS: u64 = 30 * 86400;
+const LONG_VELOCITY_WINDOW_SECS: u64 = 90 * 86400;
+
+/// Most recent filings kept per serial number or owner for velocity counts
+const MAX_TRACKED_FILINGS: u32 = 100;
+
+/// Liabilities, in basis points of collateral, past which a manufacturer is
+/// under-collateralized unless the admin sets another threshold
+const DEFAULT_SOLVENCY_THRESHOLD_BPS: u32 = 10_000;
+
+/// Most warranties that can be linked under one parent warranty
+const MAX_LINKED_WARRANTIES: u32 = 50;
+
+/// Most part warranties that can be registered under one warranty
+const MAX_PART_WARRANTIES: u32 = 50;
+
+/// Most part warranties one service record can list
+const MAX_SERVICE_PARTS: u32 = 10;
+
+/// Most service records a warranty's history can hold
+const MAX_SERVICE_RECORDS: u32 = 50;
+
+/// Most compliance attestations a warranty's trail can hold
+const MAX_COMPLIANCE_ATTESTATIONS: u32 = 50;
+
+/// Largest council that can govern a deployment
+const MAX_COUNCIL_SIZE: u32 = 20;
+
+/// How long a council proposal can collect approvals
+const PROPOSAL_LIFETIME_SECS: u64 = 7 * 86400;
+
+/// Name reported by `describe` unless the deployment configures its own
+const CONTRACT_NAME: &str = "Warranty Tracker";
+
+/// Collection name and symbol reported to NFT wallets
+const TOKEN_NAME: &str = "Warranty";
+const TOKEN_SYMBOL: &str = "WRNTY";
+
+/// Longest base URI `token_uri` appends token IDs to
+const MAX_BASE_URI_LEN: u32 = 200;
+
+/// Optional capabilities clients can probe with `supports`
+///
+/// Positions double as bit numbers in `DeploymentConfig::features` and,
+/// from 64 on, `DeploymentConfig::extra_features`, so new features are
+/// appended rather than inserted in order.
+const SUPPORTED_FEATURES: [&str; 66] = [
+    "amendments",
+    "analytics_events",
+    "audit_log",
+    "batch_recalls",
+    "certificates",
+    "compliance_trail",
+    "cooling_off",
+    "correlation_ids",
+    "counterfeit_reports",
+    "coverage_terms",
+    "depreciation_curves",
+    "describe",
+    "deterministic_ids",
+    "device_binding",
+    "expiry_sweep",
+    "handovers",
+    "hooks",
+    "index_compaction",
+    "inheritance",
+    "insurance_policies",
+    "keeper_rewards",
+    "localized_names",
+    "merges",
+    "namespaced_ids",
+    "outbox",
+    "paged_reads",
+    "preregistration",
+    "private_visibility",
+    "statutory_minimums",
+    "storage_stats",
+    "tombstones",
+    "transfer_allowlist",
+    "vouchers",
+    "warranty_refs",
+    "part_warranties",
+    "category_validation",
+    "usage_limits",
+    "retailer_reports",
+    "campaigns",
+    "customer_badges",
+    "policy_docs",
+    "extended_warranty_sales",
+    "uniqueness_rules",
+    "manufacturer_registry",
+    "deferred_activation",
+    "warranty_links",
+    "service_history",
+    "council",
+    "claim_payouts",
+    "feedback",
+    "agents",
+    "terms_templates",
+    "metadata",
+    "attestation",
+    "arbitration",
+    "claim_codes",
+    "co_ownership",
+    "underwriting",
+    "product_catalog",
+    "reversion",
+    "coverage_extensions",
+    "rent_pool",
+    "claims_escrow",
+    "claim_velocity",
+    "consortium",
+    "chargebacks",
+];
+
+// Feature flags are stored as two u64 bitmasks; past 128 features
+// `DeploymentConfig` needs a third word
+const _: () = assert!(SUPPORTED_FEATURES.len() <= 128);
 
 #[contract]
 pub struct WarrantyTracker;
 
+/// Everything an explorer needs to render the contract page
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct WarrantyData {
-    pub id: u64,
-    pub owner: Address,
-    pub product_name: String,
-    pub serial_number: String,
-    pub manufacturer: String,
-    pub purchase_date: u64,
-    pub expiration_date: u64,
-    pub status: WarrantyStatus,
-    pub created_at: u64,
-}
-
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum WarrantyStatus {
-    Active,
-    Expired,
-    Revoked,
-}
-
-#[contracttype]
-pub enum DataKey {
-    WarrantyData,
-    WarrantyIds,
-    OwnerWarranties(Address),
-    WarrantyCount,
+pub struct ContractDescription {
+    pub name: String,
+    pub default_issuer: Option<Address>,
+    /// Features enabled for this deployment
+    pub features: Vec<Symbol>,
+    pub interface_version: u32,
+    pub admin: Option<Address>,
+    pub read_limits: ReadLimits,
+    pub purchase_date_skew_secs: u64,
+    pub cooling_off_secs: u64,
+    pub edit_window_secs: u64,
+    pub claim_window_secs: u64,
+    pub stats: StorageStats,
+    /// Sequence number of the latest outbox entry, 0 before any state change
+    pub latest_outbox_seq: u64,
 }
 
 #[contractimpl]
 impl WarrantyTracker {
-    /// Register a new warranty
+    /// Set the admin, and the white-label configuration if any, when the
+    /// contract is deployed
+    ///
+    /// Running at deployment means nobody can claim the admin role of a
+    /// deployed but not yet initialized contract. Without a configuration,
+    /// the deployment uses the stock name and enables every feature. A
+    /// configuration cannot be changed afterwards, so deployments built
+    /// from the same WASM keep the policy they were launched with.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `admin`: The admin address
+    /// - `config`: Name, default namespace issuer and enabled features
+    pub fn __constructor(env: Env, admin: Address, config: Option<DeploymentConfig>) {
+        roles::__constructor(env, admin, config)
+    }
+
+    /// Get the white-label configuration of this deployment
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `owner`: The address that owns this warranty
-    /// - `product_name`: Name of the product
-    /// - `serial_number`: Serial number of the product
-    /// - `manufacturer`: Manufacturer name
-    /// - `purchase_date`: Purchase date as Unix timestamp
-    /// - `expiration_date`: Warranty expiration date as Unix timestamp
     ///
     /// # Returns
-    /// The warranty ID
-    pub fn register_warranty(
-        env: Env,
-        owner: Address,
-        product_name: String,
-        serial_number: String,
-        manufacturer: String,
-        purchase_date: u64,
-        expiration_date: u64,
-    ) -> u64 {
-        owner.require_auth();
-
-        if expiration_date <= purchase_date {
-            panic!("expiration_date must be after purchase_date");
-        }
-
-        let current_time = env.ledger().timestamp();
-        if purchase_date > current_time {
-            panic!("purchase_date cannot be in the future");
-        }
-
-        let warranty_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyCount)
-            .unwrap_or(0);
-
-        let warranty_id = warranty_count + 1;
-
-        let status = if expiration_date < current_time {
-            WarrantyStatus::Expired
-        } else {
-            WarrantyStatus::Active
-        };
-
-        let warranty = WarrantyData {
-            id: warranty_id,
-            owner: owner.clone(),
-            product_name,
-            serial_number,
-            manufacturer,
-            purchase_date,
-            expiration_date,
-            status,
-            created_at: current_time,
-        };
-
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .unwrap_or(Map::new(&env));
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
-
-        let mut warranty_ids: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyIds)
-            .unwrap_or(Vec::new(&env));
-        warranty_ids.push_back(warranty_id);
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyIds, &warranty_ids);
-
-        let owner_key = DataKey::OwnerWarranties(owner.clone());
-        let mut owner_warranties: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&owner_key)
-            .unwrap_or(Vec::new(&env));
-        owner_warranties.push_back(warranty_id);
-        env.storage().instance().set(&owner_key, &owner_warranties);
-
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyCount, &warranty_id);
-
-        warranty_id
+    /// The configuration, or the stock one if none was given at deployment
+    pub fn get_deployment_config(env: Env) -> DeploymentConfig {
+        roles::get_deployment_config(env)
     }
 
-    /// Get warranty details by ID
+    /// Get the admin address
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to query
     ///
     /// # Returns
-    /// The warranty details or None if not found
-    pub fn get_warranty(env: Env, warranty_id: u64) -> Option<WarrantyData> {
-        let warranty_map: Map<u64, WarrantyData> =
-            env.storage().instance().get(&DataKey::WarrantyData)?;
-        warranty_map.get(warranty_id)
+    /// The admin
+    pub fn get_admin(env: Env) -> Option<Address> {
+        roles::get_admin(env)
     }
 
-    /// Update warranty status (can expire warranties or revoke them)
+    /// Hand the admin role to another address (admin only, or the council once enabled)
+    ///
+    /// The new admin signs too, so the role cannot go to an address nobody
+    /// controls.
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to update
-    /// - `status`: The new status
-    pub fn update_status(env: Env, warranty_id: u64, status: WarrantyStatus) {
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
+    /// - `new_admin`: The new admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), WarrantyError> {
+        roles::set_admin(env, new_admin)
+    }
 
-        let mut warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
+    /// Replace the contract code, keeping its storage (admin only, or the council once enabled)
+    ///
+    /// The new WASM must already be uploaded to the network. Warranties and
+    /// settings survive the upgrade; run `migrate_storage` afterwards if the
+    /// new code changes the storage layout.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `new_wasm_hash`: Hash of the uploaded WASM
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), WarrantyError> {
+        roles::upgrade(env, new_wasm_hash)
+    }
 
-        warranty.owner.require_auth();
+    /// Hand the admin's most sensitive powers to a council (admin only)
+    ///
+    /// Upgrades, admin handovers, manufacturer verification, namespace
+    /// issuers, the compliance hook, read limits and every setting listed
+    /// in `ConfigChange` then take `threshold` member approvals collected
+    /// with `propose` and `approve_proposal`, and the admin can no longer
+    /// change them alone. Freezes, counterfeit rulings and storage
+    /// maintenance stay with the admin.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `members`: Council member addresses
+    /// - `threshold`: Approvals a proposal needs, between 1 and the number of members
+    pub fn enable_council(
+        env: Env,
+        members: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), WarrantyError> {
+        roles::enable_council(env, members, threshold)
+    }
 
-        warranty.status = status;
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
+    /// Get the council governing the deployment
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The council or None while the admin governs alone
+    pub fn get_council(env: Env) -> Option<Council> {
+        roles::get_council(env)
     }
 
-    /// Transfer warranty ownership to another address
+    /// Propose a council action (council members only)
+    ///
+    /// The proposer's approval is counted, so with a threshold of 1 the
+    /// action is carried out right away.
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to transfer
-    /// - `new_owner`: The new owner address
-    pub fn transfer_ownership(env: Env, warranty_id: u64, new_owner: Address) {
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
-
-        let mut warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
-
-        warranty.owner.require_auth();
-
-        if warranty.status != WarrantyStatus::Active {
-            panic!("cannot transfer non-active warranty");
-        }
-
-        let old_owner = warranty.owner.clone();
-        warranty.owner = new_owner.clone();
-
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
-
-        let old_owner_key = DataKey::OwnerWarranties(old_owner.clone());
-        let old_owner_warranties: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&old_owner_key)
-            .unwrap_or(Vec::new(&env));
-
-        let mut new_old_list = Vec::new(&env);
-        for i in 0..old_owner_warranties.len() {
-            if old_owner_warranties.get(i).unwrap() != warranty_id {
-                new_old_list.push_back(old_owner_warranties.get(i).unwrap());
-            }
-        }
-        env.storage().instance().set(&old_owner_key, &new_old_list);
-
-        let new_owner_key = DataKey::OwnerWarranties(new_owner.clone());
-        let mut new_owner_warranties: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&new_owner_key)
-            .unwrap_or(Vec::new(&env));
-        new_owner_warranties.push_back(warranty_id);
-        env.storage()
-            .instance()
-            .set(&new_owner_key, &new_owner_warranties);
+    /// - `proposer`: The council member proposing
+    /// - `action`: The change to make
+    ///
+    /// # Returns
+    /// The proposal ID
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        action: CouncilAction,
+    ) -> Result<u64, WarrantyError> {
+        roles::propose(env, proposer, action)
     }
 
-    /// Revoke a warranty (only owner can revoke)
+    /// Approve a council proposal, carrying it out once enough members agree (council members only)
+    ///
+    /// Only approvals of current members count, so members removed by a
+    /// `SetCouncil` action no longer carry pending proposals.
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to revoke
-    pub fn revoke_warranty(env: Env, warranty_id: u64) {
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
+    /// - `member`: The approving council member
+    /// - `proposal_id`: The proposal ID
+    ///
+    /// # Returns
+    /// True if this approval carried the proposal out
+    pub fn approve_proposal(
+        env: Env,
+        member: Address,
+        proposal_id: u64,
+    ) -> Result<bool, WarrantyError> {
+        roles::approve_proposal(env, member, proposal_id)
+    }
 
-        let mut warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
+    /// Get a council proposal
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `proposal_id`: The proposal ID
+    ///
+    /// # Returns
+    /// The proposal or None if it does not exist
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Option<Proposal> {
+        roles::get_proposal(env, proposal_id)
+    }
 
-        warranty.owner.require_auth();
+    /// Get the version of the contract interface
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The interface version, bumped on breaking changes
+    pub fn get_interface_version(_env: Env) -> u32 {
+        settings::get_interface_version(_env)
+    }
 
-        warranty.status = WarrantyStatus::Revoked;
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
+    /// Check whether this deployment supports an optional capability
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `feature`: Capability name, e.g. `outbox` or `deterministic_ids`
+    ///
+    /// # Returns
+    /// True if the capability's entry points are available
+    pub fn supports(env: Env, feature: Symbol) -> bool {
+        settings::supports(env, feature)
     }
 
-    /// Set warranty status to Active (only owner can set)
+    /// Register a hook contract notified after warranty state changes (admin only, or the council once enabled)
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to set to active
-    pub fn set_to_active(env: Env, warranty_id: u64) {
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
+    /// - `hook`: A contract implementing `WarrantyHookInterface`
+    pub fn add_hook(env: Env, hook: Address) -> Result<(), WarrantyError> {
+        settings::add_hook(env, hook)
+    }
+
+    /// Set the contract screening transfers and payments (admin only, or the council once enabled)
+    ///
+    /// Every change of ownership, extended warranty purchase, keeper payout
+    /// and claim settlement asks the hook's `check_allowed(from, to)` and
+    /// fails with `TransferDenied` when it refuses.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `hook`: A contract implementing `ComplianceInterface`, or None to stop screening
+    pub fn set_compliance_hook(env: Env, hook: Option<Address>) -> Result<(), WarrantyError> {
+        settings::set_compliance_hook(env, hook)
+    }
 
-        let mut warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
+    /// Get the contract screening transfers and payments
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The compliance hook or None if screening is off
+    pub fn get_compliance_hook(env: Env) -> Option<Address> {
+        settings::get_compliance_hook(env)
+    }
+
+    /// Unregister a hook contract (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `hook`: The hook contract to remove
+    pub fn remove_hook(env: Env, hook: Address) -> Result<(), WarrantyError> {
+        settings::remove_hook(env, hook)
+    }
 
-        warranty.owner.require_auth();
+    /// Get the registered hook contracts
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Vector of hook contract addresses
+    pub fn get_hooks(env: Env) -> Vec<Address> {
+        settings::get_hooks(env)
+    }
 
-        warranty.status = WarrantyStatus::Active;
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
+    /// Set the read limits (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `limits`: The new limits
+    pub fn set_read_limits(env: Env, limits: ReadLimits) -> Result<(), WarrantyError> {
+        settings::set_read_limits(env, limits)
     }
 
-    /// Set warranty status to Expired (only owner can set)
+    /// Get the read limits in force
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to set to expired
-    pub fn set_to_expired(env: Env, warranty_id: u64) {
-        let mut warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
+    ///
+    /// # Returns
+    /// The configured limits, or the defaults if none were set
+    pub fn get_read_limits(env: Env) -> ReadLimits {
+        settings::get_read_limits(env)
+    }
+
+    /// Cap the number of warranties the deployment registers, e.g. for a
+    /// budget-constrained pilot (admin only, or the council once enabled)
+    ///
+    /// Registrations beyond the cap fail with `CapacityReached`. The cap
+    /// counts every registration, including warranties since deleted.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `max_warranties`: The new cap, at least the number already
+    ///   registered, or None to lift it
+    pub fn set_capacity(env: Env, max_warranties: Option<u64>) -> Result<(), WarrantyError> {
+        settings::set_capacity(env, max_warranties)
+    }
 
-        let mut warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
+    /// Get the registration cap and how much of it is used, so frontends can
+    /// warn before it is reached
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The cap, if any, and the number of warranties registered
+    pub fn get_capacity(env: Env) -> Capacity {
+        settings::get_capacity(env)
+    }
 
-        warranty.owner.require_auth();
+    /// Cap how many warranties are registered per day, per owner and for the
+    /// whole deployment (admin only, or the council once enabled)
+    ///
+    /// Registrations beyond either cap fail with `RateLimited` until the
+    /// next day of ledger time. Warranties pre-issued for claim codes only
+    /// count against the deployment's cap.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `limits`: The daily caps, None for no cap
+    pub fn set_registration_limits(
+        env: Env,
+        limits: RegistrationLimits,
+    ) -> Result<(), WarrantyError> {
+        settings::set_registration_limits(env, limits)
+    }
 
-        warranty.status = WarrantyStatus::Expired;
-        warranty_map.set(warranty_id, warranty.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::WarrantyData, &warranty_map);
+    /// Get the daily registration caps in force
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The caps, both None if none were set
+    pub fn get_registration_limits(env: Env) -> RegistrationLimits {
+        settings::get_registration_limits(env)
     }
 
-    /// Get all warranty IDs for a specific owner
+    /// Get how many more warranties an owner can receive today, so frontends
+    /// can show the limits before a registration fails
     ///
     /// # Arguments
     /// - `env`: The environment
     /// - `owner`: The owner address
     ///
     /// # Returns
-    /// Vector of warranty IDs owned by the address
-    pub fn get_warranties_by_owner(env: Env, owner: Address) -> Vec<u64> {
-        let owner_key = DataKey::OwnerWarranties(owner);
-        env.storage()
-            .instance()
-            .get(&owner_key)
-            .unwrap_or(Vec::new(&env))
+    /// What is left of the owner's and the deployment's caps, and when they
+    /// start over
+    pub fn get_remaining_quota(env: Env, owner: Address) -> RemainingQuota {
+        settings::get_remaining_quota(env, owner)
     }
 
-    /// Get total number of registered warranties
+    /// Stop every state-changing entry point outside administration (admin only, or the council once enabled)
+    ///
+    /// A circuit breaker for bugs or fraud waves. Reads keep working, and so
+    /// do admin and council calls, so a fix can be rolled out before
+    /// `unpause`. Blocked calls fail with `Paused`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    pub fn pause(env: Env) -> Result<(), WarrantyError> {
+        settings::pause(env)
+    }
+
+    /// Let state-changing entry points run again (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    pub fn unpause(env: Env) -> Result<(), WarrantyError> {
+        settings::unpause(env)
+    }
+
+    /// Check whether the admin has paused the contract
     ///
     /// # Arguments
     /// - `env`: The environment
     ///
     /// # Returns
-    /// Total warranty count
-    pub fn get_warranty_count(env: Env) -> u64 {
-        env.storage()
-            .instance()
-            .get(&DataKey::WarrantyCount)
-            .unwrap_or(0)
+    /// True while state-changing entry points are stopped
+    pub fn is_paused(env: Env) -> bool {
+        settings::is_paused(env)
     }
 
-    /// Check if a warranty is expired based on current time
+    /// Lock a disputed warranty against transfers and claims (admin only)
+    ///
+    /// Transfers, handovers, inheritance, filing claims and deciding them
+    /// fail with `Frozen` until the warranty is unfrozen.
     ///
     /// # Arguments
     /// - `env`: The environment
-    /// - `warranty_id`: The warranty ID to check
+    /// - `warranty_id`: The warranty under investigation
+    pub fn freeze_warranty(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        settings::freeze_warranty(env, warranty_id)
+    }
+
+    /// Lift the lock placed by `freeze_warranty` (admin only)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn unfreeze_warranty(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        settings::unfreeze_warranty(env, warranty_id)
+    }
+
+    /// Check whether a warranty is frozen
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
     ///
     /// # Returns
-    /// true if warranty is expired
-    pub fn is_warranty_expired(env: Env, warranty_id: u64) -> bool {
-        let warranty_map: Map<u64, WarrantyData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::WarrantyData)
-            .expect("warranty storage not initialized");
+    /// True while transfers and claims of the warranty are locked
+    pub fn is_frozen(env: Env, warranty_id: u64) -> bool {
+        settings::is_frozen(env, warranty_id)
+    }
+
+    /// Set how far `purchase_date` may be ahead of ledger time (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `skew_secs`: Allowed clock skew in seconds
+    pub fn set_purchase_date_skew(env: Env, skew_secs: u64) -> Result<(), WarrantyError> {
+        settings::set_purchase_date_skew(env, skew_secs)
+    }
+
+    /// Get how far `purchase_date` may be ahead of ledger time
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Allowed clock skew in seconds
+    pub fn get_purchase_date_skew(env: Env) -> u64 {
+        settings::get_purchase_date_skew(env)
+    }
 
-        let warranty: WarrantyData = warranty_map.get(warranty_id).expect("warranty not found");
+    /// Limit how long after purchase a manufacturer's products can be registered (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name the policy applies to
+    /// - `max_age_secs`: Maximum seconds between purchase and registration, or
+    ///   None to lift the limit
+    pub fn set_max_purchase_age(
+        env: Env,
+        manufacturer: String,
+        max_age_secs: Option<u64>,
+    ) -> Result<(), WarrantyError> {
+        settings::set_max_purchase_age(env, manufacturer, max_age_secs)
+    }
+
+    /// Get the registration window of a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// Maximum seconds between purchase and registration, or None if unlimited
+    pub fn get_max_purchase_age(env: Env, manufacturer: String) -> Option<u64> {
+        settings::get_max_purchase_age(env, manufacturer)
+    }
+
+    /// Choose which registrations of a manufacturer count as duplicates (admin only, or the council once enabled)
+    ///
+    /// Registering a warranty that a live warranty already covers under the
+    /// rule fails with `AlreadyExists`. Manufacturers without a rule follow
+    /// `Serial`. Under `SerialAndProduct`, warranties registered before the
+    /// rule was set do not count.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name the rule applies to
+    /// - `rule`: The uniqueness rule
+    pub fn set_uniqueness_rule(
+        env: Env,
+        manufacturer: String,
+        rule: UniquenessRule,
+    ) -> Result<(), WarrantyError> {
+        settings::set_uniqueness_rule(env, manufacturer, rule)
+    }
+
+    /// Get the uniqueness rule of a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The rule, `Serial` unless another one was set
+    pub fn get_uniqueness_rule(env: Env, manufacturer: String) -> UniquenessRule {
+        settings::get_uniqueness_rule(env, manufacturer)
+    }
 
-        let current_time = env.ledger().timestamp();
-        warranty.expiration_date < current_time
+    /// Set how a manufacturer's products in a category lose value (admin only, or the council once enabled)
+    ///
+    /// Each point is `(age_months, retained_bps)`: from that age on, the
+    /// product keeps `retained_bps` of its value until the next point. Products
+    /// younger than the first point keep their full value. Claims can be
+    /// approved and paid for at most their requested amount depreciated to
+    /// the product's age on the incident date.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name the curve applies to
+    /// - `category`: Product category the curve applies to
+    /// - `curve`: Points sorted by age, or empty to remove the curve
+    pub fn set_depreciation_curve(
+        env: Env,
+        manufacturer: String,
+        category: String,
+        curve: Vec<(u32, u32)>,
+    ) -> Result<(), WarrantyError> {
+        settings::set_depreciation_curve(env, manufacturer, category, curve)
+    }
+
+    /// Get the depreciation curve of a manufacturer's category
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `category`: Product category
+    ///
+    /// # Returns
+    /// The curve points, empty if none is set
+    pub fn get_depreciation_curve(
+        env: Env,
+        manufacturer: String,
+        category: String,
+    ) -> Vec<(u32, u32)> {
+        settings::get_depreciation_curve(env, manufacturer, category)
+    }
+
+    /// Choose how much the lifecycle events carry (admin only, or the council once enabled)
+    ///
+    /// `Full` publishes `WarrantyRegistered`, `WarrantyTransferred` and
+    /// `WarrantyStatusChanged` with owners, statuses and product details.
+    /// `Minimal` publishes a `WarrantyActivity` with only the action and
+    /// warranty ID instead, leaving indexers to read the rest from storage.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `verbosity`: The verbosity for subsequent events
+    pub fn set_event_verbosity(env: Env, verbosity: EventVerbosity) -> Result<(), WarrantyError> {
+        settings::set_event_verbosity(env, verbosity)
+    }
+
+    /// Get the lifecycle event verbosity
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The configured verbosity, `Full` by default
+    pub fn get_event_verbosity(env: Env) -> EventVerbosity {
+        settings::get_event_verbosity(env)
+    }
+
+    /// Turn anonymized registration analytics events on or off (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `enabled`: Whether registrations publish `RegistrationAnalytics`
+    pub fn set_analytics_enabled(env: Env, enabled: bool) -> Result<(), WarrantyError> {
+        settings::set_analytics_enabled(env, enabled)
+    }
+
+    /// Check whether registrations publish analytics events
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// True if the admin enabled analytics
+    pub fn get_analytics_enabled(env: Env) -> bool {
+        settings::get_analytics_enabled(env)
+    }
+
+    /// Set the legal minimum coverage for products sold in a region (admin only, or the council once enabled)
+    ///
+    /// Registrations tagged with the region are extended to at least
+    /// `purchase_date + min_duration_secs`. A minimum for a specific category
+    /// takes precedence over the region-wide one.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `region`: Region code, e.g. `EU`
+    /// - `category`: Product category, or None for the region-wide minimum
+    /// - `min_duration_secs`: Minimum coverage in seconds, or None to remove it
+    pub fn set_statutory_minimum(
+        env: Env,
+        region: String,
+        category: Option<String>,
+        min_duration_secs: Option<u64>,
+    ) -> Result<(), WarrantyError> {
+        settings::set_statutory_minimum(env, region, category, min_duration_secs)
+    }
+
+    /// Get the legal minimum coverage that applies to a region and category
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `region`: Region code
+    /// - `category`: Product category, or None for the region-wide minimum
+    ///
+    /// # Returns
+    /// Minimum coverage in seconds, or None if the law sets none
+    pub fn get_statutory_minimum(
+        env: Env,
+        region: String,
+        category: Option<String>,
+    ) -> Option<u64> {
+        settings::get_statutory_minimum(env, region, category)
+    }
+
+    /// Set the window after registration during which a warranty can be deleted (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cooling_off_secs`: Window length in seconds, 0 to disable deletion
+    pub fn set_cooling_off_secs(env: Env, cooling_off_secs: u64) -> Result<(), WarrantyError> {
+        settings::set_cooling_off_secs(env, cooling_off_secs)
+    }
+
+    /// Get the window after registration during which a warranty can be deleted
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Window length in seconds, 0 if deletion is disabled
+    pub fn get_cooling_off_secs(env: Env) -> u64 {
+        settings::get_cooling_off_secs(env)
+    }
+
+    /// Set the window after registration during which typos can be corrected (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `edit_window_secs`: Window length in seconds, 0 to disable amendments
+    pub fn set_edit_window_secs(env: Env, edit_window_secs: u64) -> Result<(), WarrantyError> {
+        settings::set_edit_window_secs(env, edit_window_secs)
+    }
+
+    /// Get the window after registration during which typos can be corrected
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Window length in seconds
+    pub fn get_edit_window_secs(env: Env) -> u64 {
+        settings::get_edit_window_secs(env)
+    }
+
+    /// Set how long after an incident a claim can be filed (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_window_secs`: Window length in seconds, 0 for no deadline
+    pub fn set_claim_window_secs(env: Env, claim_window_secs: u64) -> Result<(), WarrantyError> {
+        settings::set_claim_window_secs(env, claim_window_secs)
+    }
+
+    /// Get how long after an incident a claim can be filed
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Window length in seconds, 0 if claims have no deadline
+    pub fn get_claim_window_secs(env: Env) -> u64 {
+        settings::get_claim_window_secs(env)
+    }
+
+    /// Set how long after changing hands a warranty accepts no claims (admin only, or the council once enabled)
+    ///
+    /// Changes of ownership made before the cooldown was set count too.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cooldown_secs`: Cooldown length in seconds, 0 to disable it
+    pub fn set_transfer_claim_cooldown_secs(
+        env: Env,
+        cooldown_secs: u64,
+    ) -> Result<(), WarrantyError> {
+        settings::set_transfer_claim_cooldown_secs(env, cooldown_secs)
+    }
+
+    /// Get how long after changing hands a warranty accepts no claims
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Cooldown length in seconds, 0 if there is none
+    pub fn get_transfer_claim_cooldown_secs(env: Env) -> u64 {
+        settings::get_transfer_claim_cooldown_secs(env)
+    }
+
+    /// Set or clear the price oracle quoting claim settlement rates (admin only, or the council once enabled)
+    ///
+    /// While one is set, payouts on warranties that name a currency record
+    /// the oracle's rate into the payout token on the claim.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `oracle`: Contract implementing `PriceOracleInterface`, or None to stop quoting
+    pub fn set_price_oracle(env: Env, oracle: Option<Address>) -> Result<(), WarrantyError> {
+        settings::set_price_oracle(env, oracle)
+    }
+
+    /// Get the price oracle quoting claim settlement rates
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The oracle's address, or None if settlements record no rate
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        settings::get_price_oracle(env)
+    }
+
+    /// Set the ratio of approved-claim liabilities to collateral past which a manufacturer is under-collateralized (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `threshold_bps`: Liabilities in basis points of claim pool and escrow balances; 10000 by default
+    pub fn set_solvency_threshold(env: Env, threshold_bps: u32) -> Result<(), WarrantyError> {
+        settings::set_solvency_threshold(env, threshold_bps)
+    }
+
+    /// Get the ratio of approved-claim liabilities to collateral past which a manufacturer is under-collateralized
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The threshold in basis points
+    pub fn get_solvency_threshold(env: Env) -> u32 {
+        settings::get_solvency_threshold(env)
+    }
+
+    /// Cap how many claims one claimant can file against a manufacturer's
+    /// warranties within a rolling window (admin only, or the council once enabled)
+    ///
+    /// Claims count against the owner of the warranty they are filed on,
+    /// whoever files them. Filing past the cap fails with `RateLimited`
+    /// until the oldest counted claim leaves the window.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name the cap applies to
+    /// - `limit`: The cap, or None to lift it
+    pub fn set_claim_limit(
+        env: Env,
+        manufacturer: String,
+        limit: Option<ClaimLimit>,
+    ) -> Result<(), WarrantyError> {
+        settings::set_claim_limit(env, manufacturer, limit)
+    }
+
+    /// Get the claim cap of a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The cap, or None if claims are uncapped
+    pub fn get_claim_limit(env: Env, manufacturer: String) -> Option<ClaimLimit> {
+        settings::get_claim_limit(env, manufacturer)
+    }
+
+    /// Get how many more claims an address can file against a manufacturer's
+    /// warranties, so frontends can show the cap before a claim fails
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claimant`: The claimant's address
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// What is left of the cap and when the next counted claim drops out
+    pub fn get_claim_quota(env: Env, claimant: Address, manufacturer: String) -> ClaimQuota {
+        settings::get_claim_quota(env, claimant, manufacturer)
+    }
+
+    /// Register a new warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The address that owns this warranty
+    /// - `input`: Product details, dates and optional extras
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn register_warranty(
+        env: Env,
+        owner: Address,
+        input: WarrantyInput,
+    ) -> Result<u64, WarrantyError> {
+        warranty::register_warranty(env, owner, input)
+    }
+
+    /// Register a warranty under an ID derived from the product itself
+    ///
+    /// The ID is a hash of `(manufacturer, serial_number)`, so the same
+    /// physical product always maps to the same ID, across re-registrations
+    /// and migrations. Derived IDs have the top bit set and never clash with
+    /// sequential ones.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The address that owns this warranty
+    /// - `input`: Product details, dates and optional extras
+    ///
+    /// # Returns
+    /// The derived warranty ID
+    pub fn register_warranty_deterministic(
+        env: Env,
+        owner: Address,
+        input: WarrantyInput,
+    ) -> Result<u64, WarrantyError> {
+        warranty::register_warranty_deterministic(env, owner, input)
+    }
+
+    /// Get the ID a product registers under with `register_warranty_deterministic`
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `serial_number`: Serial number of the product
+    ///
+    /// # Returns
+    /// The derived warranty ID, whether or not it is registered yet
+    pub fn get_deterministic_id(env: Env, manufacturer: String, serial_number: String) -> u64 {
+        warranty::get_deterministic_id(env, manufacturer, serial_number)
+    }
+
+    /// Register a warranty for a replaced part, linked to the product's warranty
+    /// (authorized service centers or the product's manufacturer only)
+    ///
+    /// The part warranty is an ordinary warranty owned by the parent's owner,
+    /// with the part number as product name and the parent's manufacturer.
+    /// A warranty can carry at most `MAX_PART_WARRANTIES` parts; more fail
+    /// with `LimitExceeded`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `parent_id`: Warranty of the product the part was fitted to
+    /// - `technician`: The service center or manufacturer that fitted the part
+    /// - `part`: Part number, serial, coverage flag and length
+    ///
+    /// # Returns
+    /// The warranty ID of the part
+    pub fn register_part_warranty(
+        env: Env,
+        parent_id: u64,
+        technician: Address,
+        part: PartInfo,
+    ) -> Result<u64, WarrantyError> {
+        warranty::register_part_warranty(env, parent_id, technician, part)
+    }
+
+    /// Get the part warranties registered under a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `parent_id`: The product's warranty ID
+    ///
+    /// # Returns
+    /// Part warranty IDs in registration order
+    pub fn get_part_warranties(env: Env, parent_id: u64) -> Vec<u64> {
+        warranty::get_part_warranties(env, parent_id)
+    }
+
+    /// Get the link from a part warranty to the warranty it belongs to
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The part warranty ID
+    ///
+    /// # Returns
+    /// The link or None if the warranty is not a part warranty
+    pub fn get_part_record(env: Env, warranty_id: u64) -> Option<PartRecord> {
+        warranty::get_part_record(env, warranty_id)
+    }
+
+    /// Link a warranty to one it depends on, e.g. an accessory to its device (only owner can link)
+    ///
+    /// Both warranties must belong to the same owner. The link policy is
+    /// evaluated whenever the child is read or a claim is filed against it,
+    /// so later changes to the parent take effect without touching the child.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `parent_id`: The warranty depended on
+    /// - `child_id`: The dependent warranty; it can have only one parent
+    /// - `link_type`: What the parent's state means for the child
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn link_warranties(
+        env: Env,
+        parent_id: u64,
+        child_id: u64,
+        link_type: LinkType,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::link_warranties(env, parent_id, child_id, link_type, correlation_id)
+    }
+
+    /// Remove a warranty's link to its parent (only owner can unlink)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `child_id`: The dependent warranty
+    pub fn unlink_warranty(env: Env, child_id: u64) -> Result<(), WarrantyError> {
+        warranty::unlink_warranty(env, child_id)
+    }
+
+    /// Get the link from a warranty to the warranty it depends on
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `child_id`: The dependent warranty
+    ///
+    /// # Returns
+    /// The link or None if the warranty is not linked
+    pub fn get_warranty_link(env: Env, child_id: u64) -> Option<WarrantyLink> {
+        warranty::get_warranty_link(env, child_id)
+    }
+
+    /// Get the warranties linked under a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `parent_id`: The warranty depended on
+    ///
+    /// # Returns
+    /// Dependent warranty IDs in link order, at most `MAX_LINKED_WARRANTIES`
+    pub fn get_linked_warranties(env: Env, parent_id: u64) -> Vec<u64> {
+        warranty::get_linked_warranties(env, parent_id)
+    }
+
+    /// Look up a deterministically registered warranty by product
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `serial_number`: Serial number of the product
+    ///
+    /// # Returns
+    /// The warranty details or None if the product was not registered
+    /// deterministically
+    pub fn get_warranty_by_product(
+        env: Env,
+        manufacturer: String,
+        serial_number: String,
+    ) -> Option<WarrantyData> {
+        queries::get_warranty_by_product(env, manufacturer, serial_number)
+    }
+
+    /// Look up a warranty by its product's serial number, e.g. when a repair
+    /// shop scans a unit
+    ///
+    /// For manufacturers whose serial numbers are not unique, the most
+    /// recently registered warranty is returned.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `serial_number`: Serial number of the product
+    ///
+    /// # Returns
+    /// The warranty details or None if no live warranty has that serial
+    pub fn get_warranty_by_serial(
+        env: Env,
+        manufacturer: String,
+        serial_number: String,
+    ) -> Option<WarrantyData> {
+        queries::get_warranty_by_serial(env, manufacturer, serial_number)
+    }
+
+    /// Check whether a warranty covers its product right now, for calls
+    /// from other contracts
+    ///
+    /// Never fails: unknown or removed warranties come back as not valid,
+    /// so callers can branch on the result.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// Whether the warranty is valid, and its owner, expiration and manufacturer
+    pub fn verify(env: Env, warranty_id: u64) -> VerificationResult {
+        queries::verify(env, warranty_id)
+    }
+
+    /// Check whether a product's warranty covers it right now, for calls
+    /// from other contracts
+    ///
+    /// Looks the warranty up as `get_warranty_by_serial` does and never
+    /// fails, like `verify`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `serial_number`: Serial number of the product
+    ///
+    /// # Returns
+    /// Whether the warranty is valid, and its owner, expiration and manufacturer
+    pub fn verify_by_serial(
+        env: Env,
+        manufacturer: String,
+        serial_number: String,
+    ) -> VerificationResult {
+        queries::verify_by_serial(env, manufacturer, serial_number)
+    }
+
+    /// Hand a manufacturer's local ID space to an issuer (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name the namespace is for
+    /// - `issuer`: The address assigning local IDs, or None to revoke
+    pub fn set_namespace_issuer(
+        env: Env,
+        manufacturer: String,
+        issuer: Option<Address>,
+    ) -> Result<(), WarrantyError> {
+        roles::set_namespace_issuer(env, manufacturer, issuer)
+    }
+
+    /// Get the issuer controlling a manufacturer's local ID space
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The issuer, falling back to the deployment's default issuer, or None
+    pub fn get_namespace_issuer(env: Env, manufacturer: String) -> Option<Address> {
+        roles::get_namespace_issuer(env, manufacturer)
+    }
+
+    /// Verify a manufacturer's identity (admin only, or the council once enabled)
+    ///
+    /// Each address and each name can be registered once.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The manufacturer's address
+    /// - `name`: Manufacturer name written into the warranties it issues
+    pub fn register_manufacturer(
+        env: Env,
+        address: Address,
+        name: String,
+    ) -> Result<(), WarrantyError> {
+        roles::register_manufacturer(env, address, name)
+    }
+
+    /// Get a registered manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The manufacturer's address
+    ///
+    /// # Returns
+    /// The registry entry or None if the address is not registered
+    pub fn get_manufacturer(env: Env, address: Address) -> Option<Manufacturer> {
+        roles::get_manufacturer(env, address)
+    }
+
+    /// Remove a manufacturer from the registry (admin only, or the council once enabled)
+    ///
+    /// Warranties it already issued stay verified; it can issue no more.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The manufacturer's address
+    pub fn remove_manufacturer(env: Env, address: Address) -> Result<(), WarrantyError> {
+        roles::remove_manufacturer(env, address)
+    }
+
+    /// Hand a registered manufacturer over to a new address (admin and the old address sign)
+    ///
+    /// The registry entry, the name and the namespace issuer role (if the old
+    /// address held it) move to `new`, and `new` answers for the warranties
+    /// the old address issued from now on. Those warranties, and everything
+    /// recorded about them, stay attributed to the old address, which can
+    /// issue no more.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `old`: The registered manufacturer's current address
+    /// - `new`: Address taking over; must be neither registered nor retired
+    pub fn supersede_manufacturer(
+        env: Env,
+        old: Address,
+        new: Address,
+    ) -> Result<(), WarrantyError> {
+        roles::supersede_manufacturer(env, old, new)
+    }
+
+    /// Get the address currently acting for a manufacturer address
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: A manufacturer address, current or superseded
+    ///
+    /// # Returns
+    /// The end of the chain of supersessions starting at `address`, or
+    /// `address` itself if it was never superseded
+    pub fn get_current_manufacturer(env: Env, address: Address) -> Address {
+        roles::get_current_manufacturer(env, address)
+    }
+
+    /// Recall products by serial number (registered manufacturer only)
+    ///
+    /// Live warranties under the manufacturer's registered name whose serial
+    /// number is listed move to `Recalled`, unless they are revoked or
+    /// already ended for good. Serial numbers without such a warranty are
+    /// skipped.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer's address
+    /// - `serial_numbers`: Serial numbers of the recalled units, at most the
+    ///   maximum page size
+    ///
+    /// # Returns
+    /// The number of warranties recalled
+    pub fn recall_products(
+        env: Env,
+        manufacturer: Address,
+        serial_numbers: Vec<String>,
+    ) -> Result<u32, WarrantyError> {
+        warranty::recall_products(env, manufacturer, serial_numbers)
+    }
+
+    /// Void a warranty as its issuing manufacturer
+    ///
+    /// Unlike `revoke_warranty`, which the owner uses, this is how the
+    /// registered manufacturer that issued the warranty (or the address that
+    /// superseded it) declares it invalid. Warranties that already ended for
+    /// good cannot be voided.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `reason`: Why the warranty is voided, kept for `get_recall_info`
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn void_warranty(
+        env: Env,
+        warranty_id: u64,
+        reason: String,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::void_warranty(env, warranty_id, reason, correlation_id)
+    }
+
+    /// Get why and by whom a warranty was last recalled or voided
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The recall details or None if the manufacturer never pulled it
+    pub fn get_recall_info(env: Env, warranty_id: u64) -> Option<RecallInfo> {
+        warranty::get_recall_info(env, warranty_id)
+    }
+
+    /// Schedule the revocation of a warranty as its issuing manufacturer
+    ///
+    /// Meant for warranties granted before payment clears: unless the
+    /// issuer cancels it in time, anyone can execute the revocation from
+    /// `revoke_at` on. A warranty has at most one scheduled revocation.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `revoke_at`: Timestamp from which the revocation can be executed
+    /// - `reason`: Why the warranty is to be revoked
+    pub fn schedule_revocation(
+        env: Env,
+        warranty_id: u64,
+        revoke_at: u64,
+        reason: String,
+    ) -> Result<(), WarrantyError> {
+        warranty::schedule_revocation(env, warranty_id, revoke_at, reason)
+    }
+
+    /// Cancel a scheduled revocation (only the manufacturer that scheduled
+    /// it, or the address that superseded it, can cancel)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn cancel_revocation(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        warranty::cancel_revocation(env, warranty_id)
+    }
+
+    /// Get the revocation scheduled for a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The scheduled revocation or None if none is pending
+    pub fn get_scheduled_revocation(env: Env, warranty_id: u64) -> Option<ScheduledRevocation> {
+        warranty::get_scheduled_revocation(env, warranty_id)
+    }
+
+    /// Revoke a warranty whose scheduled revocation is due (anyone can execute)
+    ///
+    /// The manufacturer that scheduled the revocation is named in the
+    /// warranty's history. A warranty that was revoked in the meantime
+    /// just has the schedule cleared.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn execute_revocation(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::execute_revocation(env, warranty_id, correlation_id)
+    }
+
+    /// Issue a warranty as a registered manufacturer (manufacturer and owner sign)
+    ///
+    /// The warranty carries the manufacturer's registered name, whatever
+    /// `input.manufacturer` says, and records the manufacturer's address in
+    /// `verified_manufacturer`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer's address
+    /// - `owner`: Owner of the warranty
+    /// - `input`: Product details and registration options
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn manufacturer_issue_warranty(
+        env: Env,
+        manufacturer: Address,
+        owner: Address,
+        input: WarrantyInput,
+    ) -> Result<u64, WarrantyError> {
+        warranty::manufacturer_issue_warranty(env, manufacturer, owner, input)
+    }
+
+    /// Let a subsidiary issue and revoke warranties for one region in the
+    /// manufacturer's name (registered manufacturer only)
+    ///
+    /// Adding a delegate that already exists moves it to the new region.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer's address
+    /// - `delegate`: The subsidiary's address
+    /// - `region`: Region code whose warranties the delegate handles
+    pub fn add_issuer_delegate(
+        env: Env,
+        manufacturer: Address,
+        delegate: Address,
+        region: String,
+    ) -> Result<(), WarrantyError> {
+        roles::add_issuer_delegate(env, manufacturer, delegate, region)
+    }
+
+    /// Withdraw a subsidiary's delegation (registered manufacturer only)
+    ///
+    /// Warranties the delegate already issued are kept.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer's address
+    /// - `delegate`: The subsidiary's address
+    pub fn remove_issuer_delegate(
+        env: Env,
+        manufacturer: Address,
+        delegate: Address,
+    ) -> Result<(), WarrantyError> {
+        roles::remove_issuer_delegate(env, manufacturer, delegate)
+    }
+
+    /// Get a subsidiary's delegation from a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The manufacturer's address
+    /// - `delegate`: The subsidiary's address
+    ///
+    /// # Returns
+    /// The delegation or None if the manufacturer has not delegated to it
+    pub fn get_issuer_delegate(
+        env: Env,
+        manufacturer: Address,
+        delegate: Address,
+    ) -> Option<IssuerDelegate> {
+        roles::get_issuer_delegate(env, manufacturer, delegate)
+    }
+
+    /// Issue a warranty in a manufacturer's name as its delegate (delegate
+    /// and owner sign)
+    ///
+    /// Works as `manufacturer_issue_warranty`, with the warranty placed in
+    /// the delegate's region. The delegate is recorded for
+    /// `get_issuing_delegate`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `delegate`: The subsidiary's address
+    /// - `manufacturer`: The registered manufacturer it acts for
+    /// - `owner`: Owner of the warranty
+    /// - `input`: Product details and registration options; a region, if
+    ///   given, must be the delegate's
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn delegate_issue_warranty(
+        env: Env,
+        delegate: Address,
+        manufacturer: Address,
+        owner: Address,
+        input: WarrantyInput,
+    ) -> Result<u64, WarrantyError> {
+        warranty::delegate_issue_warranty(env, delegate, manufacturer, owner, input)
+    }
+
+    /// Revoke a warranty in a manufacturer's name as its delegate
+    ///
+    /// The warranty must have been issued by the manufacturer (or the
+    /// address that superseded it) and lie in the delegate's region. The
+    /// delegate is recorded as the actor in the warranty's history.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `delegate`: The subsidiary's address
+    /// - `warranty_id`: The warranty ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn delegate_revoke_warranty(
+        env: Env,
+        delegate: Address,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::delegate_revoke_warranty(env, delegate, warranty_id, correlation_id)
+    }
+
+    /// Get the delegate that issued a warranty in its manufacturer's name
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The delegate's address, or None if no delegate issued it
+    pub fn get_issuing_delegate(env: Env, warranty_id: u64) -> Option<Address> {
+        roles::get_issuing_delegate(env, warranty_id)
+    }
+
+    /// List a product model in the catalog (registered manufacturers only)
+    ///
+    /// Registrations that name the model with `WarrantyInput::with_model`
+    /// take its name, the manufacturer's registered name and the default
+    /// coverage from here, so warranties can be grouped by model. Models
+    /// cannot be changed once listed.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer's address
+    /// - `model_id`: Model number, unique across the catalog
+    /// - `name`: Product name written into the warranties
+    /// - `category`: Product category, if any
+    /// - `default_warranty_duration`: Coverage length, in seconds from purchase
+    pub fn add_product(
+        env: Env,
+        manufacturer: Address,
+        model_id: String,
+        name: String,
+        category: Option<String>,
+        default_warranty_duration: u64,
+    ) -> Result<(), WarrantyError> {
+        catalog::add_product(
+            env,
+            manufacturer,
+            model_id,
+            name,
+            category,
+            default_warranty_duration,
+        )
+    }
+
+    /// Get a product model from the catalog
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `model_id`: The model number
+    ///
+    /// # Returns
+    /// The product or None if the model is not listed
+    pub fn get_product(env: Env, model_id: String) -> Option<Product> {
+        catalog::get_product(env, model_id)
+    }
+
+    /// Give a warranty an ID in its manufacturer's namespace (namespace issuer only)
+    ///
+    /// Local IDs are chosen by the issuer, e.g. from its ERP, and resolve
+    /// to the global warranty ID through `get_warranty_ns`. A warranty has
+    /// at most one local ID and each local ID points at one warranty.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer
+    /// - `warranty_id`: The warranty ID
+    /// - `local_id`: ID within the manufacturer's namespace
+    pub fn assign_local_id(
+        env: Env,
+        issuer: Address,
+        warranty_id: u64,
+        local_id: u64,
+    ) -> Result<(), WarrantyError> {
+        warranty::assign_local_id(env, issuer, warranty_id, local_id)
+    }
+
+    /// Get the local ID of a warranty in its manufacturer's namespace
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The local ID or None if none was assigned
+    pub fn get_local_id(env: Env, warranty_id: u64) -> Option<u64> {
+        warranty::get_local_id(env, warranty_id)
+    }
+
+    /// Look up a warranty by its ID in a manufacturer's namespace
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `local_id`: ID within the manufacturer's namespace
+    ///
+    /// # Returns
+    /// The warranty details or None if the local ID is unassigned
+    pub fn get_warranty_ns(env: Env, manufacturer: String, local_id: u64) -> Option<WarrantyData> {
+        queries::get_warranty_ns(env, manufacturer, local_id)
+    }
+
+    /// Seed unclaimed warranties at manufacture time (issuer signs)
+    ///
+    /// The issuer must be the registered manufacturer named `manufacturer`
+    /// or that name's namespace issuer.
+    /// Entries are keyed by issuer and serial hash; ones already waiting for
+    /// activation are skipped so an interrupted upload can be resent. At most
+    /// `max_batch_size` entries are accepted per call.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The address uploading the batch
+    /// - `manufacturer`: Manufacturer name recorded on activated warranties
+    /// - `entries`: The units to pre-register
+    ///
+    /// # Returns
+    /// Number of new pre-registrations stored
+    pub fn preregister_batch(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        entries: Vec<PreRegEntry>,
+    ) -> Result<u32, WarrantyError> {
+        vouchers::preregister_batch(env, issuer, manufacturer, entries)
+    }
+
+    /// Get a pre-registration that has not been activated yet
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The address that uploaded the unit
+    /// - `serial_hash`: SHA-256 of the serial number
+    ///
+    /// # Returns
+    /// The pre-registration or None if unknown or already activated
+    pub fn get_preregistration(
+        env: Env,
+        issuer: Address,
+        serial_hash: BytesN<32>,
+    ) -> Option<PreRegistration> {
+        vouchers::get_preregistration(env, issuer, serial_hash)
+    }
+
+    /// Turn a pre-registered unit into a warranty owned by its buyer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The buyer, who becomes owner and registrant
+    /// - `issuer`: The address that pre-registered the unit
+    /// - `serial_number`: Serial number printed on the unit
+    /// - `purchase_date`: Purchase date as Unix timestamp
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn activate_preregistered(
+        env: Env,
+        owner: Address,
+        issuer: Address,
+        serial_number: String,
+        purchase_date: u64,
+    ) -> Result<u64, WarrantyError> {
+        vouchers::activate_preregistered(env, owner, issuer, serial_number, purchase_date)
+    }
+
+    /// Mint prepaid extension vouchers (manufacturer's namespace issuer only)
+    ///
+    /// The issuer hands out the codes and uploads only their SHA-256
+    /// hashes, so the codes never appear on the ledger before redemption.
+    /// Hashes that were already minted are skipped. At most
+    /// `max_batch_size` hashes are accepted per call.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer whose warranties the vouchers extend
+    /// - `code_hashes`: SHA-256 of each voucher code
+    /// - `duration_secs`: Coverage added by each voucher
+    /// - `valid_until`: Last timestamp the vouchers can be redeemed at
+    ///
+    /// # Returns
+    /// The hashes of the newly minted vouchers
+    pub fn mint_voucher(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        code_hashes: Vec<BytesN<32>>,
+        duration_secs: u64,
+        valid_until: u64,
+    ) -> Result<Vec<BytesN<32>>, WarrantyError> {
+        vouchers::mint_voucher(
+            env,
+            issuer,
+            manufacturer,
+            code_hashes,
+            duration_secs,
+            valid_until,
+        )
+    }
+
+    /// Get a voucher by the hash of its code
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `code_hash`: SHA-256 of the voucher code
+    ///
+    /// # Returns
+    /// The voucher or None if it was never minted
+    pub fn get_voucher(env: Env, code_hash: BytesN<32>) -> Option<Voucher> {
+        vouchers::get_voucher(env, code_hash)
+    }
+
+    /// Extend an active warranty with a prepaid voucher (only owner can redeem)
+    ///
+    /// Whether the warranty is active is judged from its expiration date
+    /// and usage, not only its stored status.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty to extend
+    /// - `code`: The voucher code whose hash was minted
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn redeem_voucher(
+        env: Env,
+        warranty_id: u64,
+        code: Bytes,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        vouchers::redeem_voucher(env, warranty_id, code, correlation_id)
+    }
+
+    /// Push back the expiration date of a warranty (issuing manufacturer only)
+    ///
+    /// This is the one way an Expired warranty comes back into force on its
+    /// own dates: once the new date is in the future it is Active again,
+    /// unless its usage limit or link policy still ends its coverage. Other
+    /// statuses cannot be extended.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to extend
+    /// - `manufacturer`: The warranty's manufacturer
+    /// - `expiration_date`: New expiration date, later than both the current
+    ///   one and now
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn extend_warranty(
+        env: Env,
+        warranty_id: u64,
+        manufacturer: Address,
+        expiration_date: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::extend_warranty(
+            env,
+            warranty_id,
+            manufacturer,
+            expiration_date,
+            correlation_id,
+        )
+    }
+
+    /// Register a warranty at the factory for a buyer to claim later
+    /// (manufacturer's namespace issuer only)
+    ///
+    /// The warranty is held by the contract, with no owner, until someone
+    /// presents the claim code to `claim_warranty`, e.g. by scanning a QR
+    /// code shipped with the product. Only the hash of the code is stored.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of the warranty's manufacturer
+    /// - `input`: Product details, dates and optional extras
+    /// - `code_hash`: SHA-256 of the claim code
+    ///
+    /// # Returns
+    /// The claim-code hash the warranty is filed under
+    pub fn pre_issue_warranty(
+        env: Env,
+        issuer: Address,
+        input: WarrantyInput,
+        code_hash: BytesN<32>,
+    ) -> Result<BytesN<32>, WarrantyError> {
+        vouchers::pre_issue_warranty(env, issuer, input, code_hash)
+    }
+
+    /// Get the pre-issued warranty a claim code still unlocks
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `code_hash`: SHA-256 of the claim code
+    ///
+    /// # Returns
+    /// The warranty ID or None if the code is unknown or already claimed
+    pub fn get_pre_issued(env: Env, code_hash: BytesN<32>) -> Option<u64> {
+        vouchers::get_pre_issued(env, code_hash)
+    }
+
+    /// Become the owner of a pre-issued warranty by presenting its claim code
+    ///
+    /// The code works once. The warranty moves to `owner` like a transfer
+    /// would, so it shows up in the owner's index and transfer history.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `code_preimage`: The claim code whose hash was pre-issued
+    /// - `owner`: The buyer claiming the warranty
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn claim_warranty(
+        env: Env,
+        code_preimage: Bytes,
+        owner: Address,
+    ) -> Result<u64, WarrantyError> {
+        vouchers::claim_warranty(env, code_preimage, owner)
+    }
+
+    /// Start a promotional campaign (manufacturer's namespace issuer only)
+    ///
+    /// Registrations for `manufacturer` made between `start` and `end`
+    /// get `extra_months` of bonus coverage and record the campaign ID.
+    /// When several campaigns match, the one with the largest bonus wins.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer whose registrations get the bonus
+    /// - `extra_months`: Bonus coverage, in 30-day months
+    /// - `start`: First timestamp of the campaign
+    /// - `end`: Last timestamp of the campaign
+    /// - `category_filter`: Product category the campaign is limited to, if any
+    ///
+    /// # Returns
+    /// The campaign ID
+    pub fn create_campaign(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        extra_months: u32,
+        start: u64,
+        end: u64,
+        category_filter: Option<String>,
+    ) -> Result<u64, WarrantyError> {
+        catalog::create_campaign(
+            env,
+            issuer,
+            manufacturer,
+            extra_months,
+            start,
+            end,
+            category_filter,
+        )
+    }
+
+    /// Get a promotional campaign by ID
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `campaign_id`: The campaign ID
+    ///
+    /// # Returns
+    /// The campaign or None if not found
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Option<Campaign> {
+        catalog::get_campaign(env, campaign_id)
+    }
+
+    /// Get how many registrations a campaign picked up
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `campaign_id`: The campaign ID
+    ///
+    /// # Returns
+    /// The campaign's statistics, all zero if it matched nothing yet
+    pub fn get_campaign_stats(env: Env, campaign_id: u64) -> CampaignStats {
+        catalog::get_campaign_stats(env, campaign_id)
+    }
+
+    /// Publish reusable warranty terms (manufacturer's namespace issuer only)
+    ///
+    /// Registrations for the manufacturer can reference the template by ID
+    /// through `WarrantyInput::with_terms`. Templates cannot be changed once
+    /// published, so warranties keep the terms they were sold under.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer whose registrations can use the template
+    /// - `terms`: Coverage type, claim and payout caps, transferability and deductible
+    ///
+    /// # Returns
+    /// The new template's ID
+    pub fn create_terms_template(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        terms: WarrantyTerms,
+    ) -> Result<u64, WarrantyError> {
+        catalog::create_terms_template(env, issuer, manufacturer, terms)
+    }
+
+    /// Get a terms template
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `terms_id`: The template ID
+    ///
+    /// # Returns
+    /// The template if it exists
+    pub fn get_terms_template(env: Env, terms_id: u64) -> Option<TermsTemplate> {
+        catalog::get_terms_template(env, terms_id)
+    }
+
+    /// Get the terms a warranty was registered under
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The template's terms, or None if the warranty was registered without one
+    pub fn get_warranty_terms(
+        env: Env,
+        warranty_id: u64,
+    ) -> Result<Option<WarrantyTerms>, WarrantyError> {
+        catalog::get_warranty_terms(env, warranty_id)
+    }
+
+    /// Switch Verified Customer badges on or off (manufacturer's namespace issuer only)
+    ///
+    /// While switched on, an owner's first registration with the
+    /// manufacturer mints a non-transferable badge that other contracts
+    /// can check with `has_badge`. Badges already issued are kept when
+    /// badges are switched off.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer whose registrations earn the badge
+    /// - `enabled`: Whether new registrations earn the badge
+    pub fn set_badges_enabled(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        enabled: bool,
+    ) -> Result<(), WarrantyError> {
+        catalog::set_badges_enabled(env, issuer, manufacturer, enabled)
+    }
+
+    /// Check whether an address holds a manufacturer's Verified Customer badge
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The address to check
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// True if the address earned the badge
+    pub fn has_badge(env: Env, owner: Address, manufacturer: String) -> bool {
+        catalog::has_badge(env, owner, manufacturer)
+    }
+
+    /// Get an address's Verified Customer badge for a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The badge holder
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The badge or None if the address has not earned it
+    pub fn get_badge(env: Env, owner: Address, manufacturer: String) -> Option<Badge> {
+        catalog::get_badge(env, owner, manufacturer)
+    }
+
+    /// Publish a new version of a manufacturer's terms (manufacturer's namespace issuer only)
+    ///
+    /// Warranties registered once `effective_date` is reached are stamped
+    /// with the version, so claims can be judged against the terms that
+    /// applied. Effective dates may lie in the future but must not go back
+    /// before the previous version's.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer the document applies to
+    /// - `hash`: SHA-256 of the document
+    /// - `uri`: Where the document can be fetched
+    /// - `effective_date`: Timestamp the document takes effect
+    ///
+    /// # Returns
+    /// The version number of the published document
+    pub fn publish_policy_doc(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        hash: BytesN<32>,
+        uri: String,
+        effective_date: u64,
+    ) -> Result<u32, WarrantyError> {
+        catalog::publish_policy_doc(env, issuer, manufacturer, hash, uri, effective_date)
+    }
+
+    /// Get one version of a manufacturer's policy document
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `version`: Version stamped on a warranty, starting at 1
+    ///
+    /// # Returns
+    /// The document or None if that version was never published
+    pub fn get_policy_doc(env: Env, manufacturer: String, version: u32) -> Option<PolicyDoc> {
+        catalog::get_policy_doc(env, manufacturer, version)
+    }
+
+    /// Get the policy document new registrations are currently stamped with
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The newest document already in effect, or None
+    pub fn get_current_policy(env: Env, manufacturer: String) -> Option<PolicyDoc> {
+        catalog::get_current_policy(env, manufacturer)
+    }
+
+    /// Set extended warranty prices for a product category (manufacturer's namespace issuer only)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `issuer`: The namespace issuer of `manufacturer`, who receives payments
+    /// - `manufacturer`: Manufacturer the prices apply to
+    /// - `category`: Product category the prices apply to
+    /// - `token`: Token the prices are denominated in
+    /// - `prices`: Price per coverage length in 30-day months; empty to stop selling
+    pub fn set_pricing(
+        env: Env,
+        issuer: Address,
+        manufacturer: String,
+        category: String,
+        token: Address,
+        prices: Map<u32, i128>,
+    ) -> Result<(), WarrantyError> {
+        catalog::set_pricing(env, issuer, manufacturer, category, token, prices)
+    }
+
+    /// Get the extended warranty prices for a product category
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `category`: Product category
+    ///
+    /// # Returns
+    /// The pricing table or None if the category is not for sale
+    pub fn get_pricing(env: Env, manufacturer: String, category: String) -> Option<PricingTable> {
+        catalog::get_pricing(env, manufacturer, category)
+    }
+
+    /// Quote an extended warranty from the current pricing table
+    ///
+    /// The quote can be purchased for `QUOTE_VALIDITY_SECS` and keeps its
+    /// price even if the table changes in the meantime.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer of the product
+    /// - `category`: Product category
+    /// - `duration_months`: Coverage length listed in the pricing table
+    ///
+    /// # Returns
+    /// The quote, including the ID to purchase it with
+    pub fn quote_extended_warranty(
+        env: Env,
+        manufacturer: String,
+        category: String,
+        duration_months: u32,
+    ) -> Result<Quote, WarrantyError> {
+        catalog::quote_extended_warranty(env, manufacturer, category, duration_months)
+    }
+
+    /// Get an outstanding extended warranty quote
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `quote_id`: The quote ID
+    ///
+    /// # Returns
+    /// The quote or None if it was purchased or has lapsed from storage
+    pub fn get_quote(env: Env, quote_id: u64) -> Option<Quote> {
+        catalog::get_quote(env, quote_id)
+    }
+
+    /// Pay for a quote and register the extended warranty (buyer signs)
+    ///
+    /// The price is transferred from `owner` to the issuer that set the
+    /// pricing. Coverage starts now and runs for the quoted number of months.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `quote_id`: A quote from `quote_extended_warranty`
+    /// - `owner`: The buyer, who pays and becomes owner
+    /// - `serial_number`: Serial number of the covered product
+    ///
+    /// # Returns
+    /// The warranty ID
+    pub fn purchase_extended_warranty(
+        env: Env,
+        quote_id: u64,
+        owner: Address,
+        serial_number: String,
+    ) -> Result<u64, WarrantyError> {
+        catalog::purchase_extended_warranty(env, quote_id, owner, serial_number)
+    }
+
+    /// Get pre-registration and activation counts for a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name used in `preregister_batch`
+    ///
+    /// # Returns
+    /// The manufacturer's activation statistics
+    pub fn get_activation_stats(env: Env, manufacturer: String) -> ActivationStats {
+        catalog::get_activation_stats(env, manufacturer)
+    }
+
+    /// Get a retailer's registration totals per 30-day bucket
+    ///
+    /// Buckets are registration timestamps divided by 30 days, so bucket
+    /// `n` covers `[n * 2592000, (n + 1) * 2592000)`. Buckets without
+    /// registrations are returned with zero totals, so stores can reconcile
+    /// every period against their POS records. At most `max_batch_size`
+    /// buckets are returned per call.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `retailer`: The retailer that co-signed the registrations
+    /// - `from_bucket`: First bucket of the report
+    /// - `to_bucket`: Last bucket of the report, inclusive
+    ///
+    /// # Returns
+    /// One entry per bucket in the range
+    pub fn get_retailer_report(
+        env: Env,
+        retailer: Address,
+        from_bucket: u64,
+        to_bucket: u64,
+    ) -> Result<Vec<RetailerBucket>, WarrantyError> {
+        catalog::get_retailer_report(env, retailer, from_bucket, to_bucket)
+    }
+
+    /// Get warranty details by ID
+    ///
+    /// Private warranties come back with only their ID and status, and with
+    /// the contract's address as owner and registrant; use
+    /// `get_warranty_details` to read them in full. A linked warranty
+    /// whose parent ended its coverage is reported as Expired.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to query
+    ///
+    /// # Returns
+    /// The warranty details or None if not found
+    pub fn get_warranty(env: Env, warranty_id: u64) -> Option<WarrantyData> {
+        queries::get_warranty(env, warranty_id)
+    }
+
+    /// Get every field of a warranty, including private ones
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to query
+    /// - `viewer`: The address reading the record; must be the owner, a
+    ///   co-owner, the registrant or hold an unexpired grant to see a private
+    ///   warranty in full
+    ///
+    /// # Returns
+    /// The warranty details or None if not found
+    pub fn get_warranty_details(
+        env: Env,
+        warranty_id: u64,
+        viewer: Address,
+    ) -> Option<WarrantyData> {
+        queries::get_warranty_details(env, warranty_id, viewer)
+    }
+
+    /// Hide a warranty's details from other readers, or publish them again
+    /// (only owner can change)
+    ///
+    /// Contract storage stays readable on the ledger itself; this controls
+    /// what the contract's getters return.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `private`: Whether the warranty is private
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn set_visibility(
+        env: Env,
+        warranty_id: u64,
+        private: bool,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::set_visibility(env, warranty_id, private, correlation_id)
+    }
+
+    /// Let another address read a private warranty in full until a deadline
+    /// (only owner can grant)
+    ///
+    /// Grants end when the warranty changes hands.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `viewer`: The address allowed to read the record, e.g. a repair shop
+    /// - `expires_at`: Unix timestamp at which the grant lapses
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn grant_viewer(
+        env: Env,
+        warranty_id: u64,
+        viewer: Address,
+        expires_at: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::grant_viewer(env, warranty_id, viewer, expires_at, correlation_id)
+    }
+
+    /// Withdraw a viewer's read access (only owner can revoke)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `viewer`: The address losing access
+    pub fn revoke_viewer(env: Env, warranty_id: u64, viewer: Address) -> Result<(), WarrantyError> {
+        warranty::revoke_viewer(env, warranty_id, viewer)
+    }
+
+    /// Get the read grants on a warranty and when each lapses
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// Map of viewer address to grant expiry
+    pub fn get_viewer_grants(env: Env, warranty_id: u64) -> Map<Address, u64> {
+        queries::get_viewer_grants(env, warranty_id)
+    }
+
+    /// Share a warranty with a co-owner, e.g. another member of the household
+    /// (only owner can add)
+    ///
+    /// Co-owners can file claims and read the warranty in full. Transfers
+    /// and status changes stay with the owner, and all co-owners are dropped
+    /// when the warranty changes hands.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `co_owner`: The address to share the warranty with
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn add_co_owner(
+        env: Env,
+        warranty_id: u64,
+        co_owner: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::add_co_owner(env, warranty_id, co_owner, correlation_id)
+    }
+
+    /// Stop sharing a warranty with a co-owner (only owner can remove)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `co_owner`: The co-owner to drop
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn remove_co_owner(
+        env: Env,
+        warranty_id: u64,
+        co_owner: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::remove_co_owner(env, warranty_id, co_owner, correlation_id)
+    }
+
+    /// Add or replace a translated product name (only registrant can set)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `lang`: Language code, e.g. `de` or `pt_BR`
+    /// - `name`: The product name in that language
+    pub fn set_localized_name(
+        env: Env,
+        warranty_id: u64,
+        lang: Symbol,
+        name: String,
+    ) -> Result<(), WarrantyError> {
+        warranty::set_localized_name(env, warranty_id, lang, name)
+    }
+
+    /// Get the product name in a language, falling back to the primary name
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `lang`: Language code, e.g. `de` or `pt_BR`
+    ///
+    /// # Returns
+    /// The localized name if one was set, otherwise the primary product name
+    pub fn get_product_name(
+        env: Env,
+        warranty_id: u64,
+        lang: Symbol,
+    ) -> Result<String, WarrantyError> {
+        queries::get_product_name(env, warranty_id, lang)
+    }
+
+    /// Get a warranty, distinguishing removed records from unknown IDs
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to query
+    ///
+    /// # Returns
+    /// The live record, the tombstone left by its removal, or `NotFound` if
+    /// the ID was never issued
+    pub fn get_warranty_record(env: Env, warranty_id: u64) -> WarrantyRecord {
+        queries::get_warranty_record(env, warranty_id)
+    }
+
+    /// Get the canonical reference to a warranty of this deployment
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The reference or None if the warranty doesn't exist
+    pub fn get_warranty_ref(env: Env, warranty_id: u64) -> Option<WarrantyRef> {
+        queries::get_warranty_ref(env, warranty_id)
+    }
+
+    /// Get the summary of a warranty of this deployment
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The summary or None if the warranty doesn't exist
+    pub fn get_warranty_summary(env: Env, warranty_id: u64) -> Option<WarrantySummary> {
+        queries::get_warranty_summary(env, warranty_id)
+    }
+
+    /// Resolve a reference to a warranty held by this or another deployment
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_ref`: The reference to resolve
+    ///
+    /// # Returns
+    /// The summary or None if the referenced warranty doesn't exist
+    pub fn resolve_warranty_ref(env: Env, warranty_ref: WarrantyRef) -> Option<WarrantySummary> {
+        queries::resolve_warranty_ref(env, warranty_ref)
+    }
+
+    /// List the entry points an address may currently call on a warranty
+    ///
+    /// Evaluates the same status, role, window and pending-handover checks
+    /// the entry points themselves apply. Transfers may still be refused by
+    /// the owner's allowlist, which depends on the destination.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `actor`: The address that would sign the call
+    ///
+    /// # Returns
+    /// Names of the callable entry points, empty if the warranty is unknown
+    pub fn get_allowed_actions(env: Env, warranty_id: u64, actor: Address) -> Vec<Symbol> {
+        queries::get_allowed_actions(env, warranty_id, actor)
+    }
+
+    /// Delete a warranty within the cooling-off window (only registrant can delete)
+    ///
+    /// Unlike revocation this removes the record and every index entry,
+    /// leaving a tombstone in its place.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to delete
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn delete_warranty(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::delete_warranty(env, warranty_id, correlation_id)
+    }
+
+    /// Merge a duplicate registration into another warranty (owner, confirmed
+    /// by the manufacturer's namespace issuer or the admin)
+    ///
+    /// Both records must share owner and manufacturer, their serial numbers
+    /// must be the same or one typo apart, neither may be frozen, and
+    /// neither may have a claim still open. The removed record's settled
+    /// claims move to the kept one, along with what they paid out. Its
+    /// attached policy and beneficiary carry over when the kept record has
+    /// none. The removed record is tombstoned with a pointer to the kept one.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keep_id`: The warranty that survives the merge
+    /// - `remove_id`: The duplicate to fold into `keep_id`
+    /// - `issuer`: The namespace issuer or admin confirming the merge
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn merge_warranties(
+        env: Env,
+        keep_id: u64,
+        remove_id: u64,
+        issuer: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::merge_warranties(env, keep_id, remove_id, issuer, correlation_id)
+    }
+
+    /// Correct registration details (only registrant, within the edit window and before any transfer or claim)
+    ///
+    /// The window closes for good on the first transfer or claim, even if
+    /// the warranty later returns to its registrant. Each changed field is
+    /// recorded in the warranty's history.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to amend
+    /// - `amendment`: The corrected fields
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn amend_warranty(
+        env: Env,
+        warranty_id: u64,
+        amendment: WarrantyAmendment,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::amend_warranty(env, warranty_id, amendment, correlation_id)
+    }
+
+    /// Transfer warranty ownership to another address
+    ///
+    /// The status is first recomputed from the expiration date and usage, so
+    /// a warranty that ran out cannot change hands while its stored status
+    /// still reads Active, and one whose coverage was extended is not held
+    /// back by a stale Expired.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to transfer
+    /// - `new_owner`: The new owner address
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn transfer_ownership(
+        env: Env,
+        warranty_id: u64,
+        new_owner: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::transfer_ownership(env, warranty_id, new_owner, correlation_id)
+    }
+
+    /// Transfer a warranty and attest the product's condition (only owner can transfer)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to transfer
+    /// - `new_owner`: The new owner address
+    /// - `condition`: Condition of the product as handed over
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn transfer_with_condition(
+        env: Env,
+        warranty_id: u64,
+        new_owner: Address,
+        condition: ConditionGrade,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::transfer_with_condition(env, warranty_id, new_owner, condition, correlation_id)
+    }
+
+    /// Offer a warranty to a new owner, who must accept it (only owner can offer)
+    ///
+    /// Unlike `transfer_ownership`, nothing moves until the recipient signs
+    /// `accept_transfer`, so a mistyped address cannot strand the warranty.
+    /// A new offer replaces any pending one, and an offer not accepted within
+    /// `TRANSFER_OFFER_SECS` lapses.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to transfer
+    /// - `new_owner`: The address that may accept the transfer
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn initiate_transfer(
+        env: Env,
+        warranty_id: u64,
+        new_owner: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::initiate_transfer(env, warranty_id, new_owner, correlation_id)
+    }
+
+    /// Accept a pending transfer, taking ownership of the warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID being received
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn accept_transfer(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::accept_transfer(env, warranty_id, correlation_id)
+    }
+
+    /// Withdraw a pending transfer offer (only owner can cancel)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn cancel_transfer(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::cancel_transfer(env, warranty_id, correlation_id)
+    }
+
+    /// Turn down a pending transfer offer (only the recipient can decline)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn decline_transfer(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::decline_transfer(env, warranty_id, correlation_id)
+    }
+
+    /// Get the transfer offer waiting for the recipient, if any
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The pending transfer or None if there is none or it has lapsed
+    pub fn get_pending_transfer(env: Env, warranty_id: u64) -> Option<PendingTransfer> {
+        transfers::get_pending_transfer(env, warranty_id)
+    }
+
+    /// Get every change of ownership of a warranty, oldest first
+    ///
+    /// Private warranties report no transfers, since each one names two
+    /// owners; their owner reads them with `export_owner_data`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The warranty's transfer history, including attested conditions
+    pub fn get_transfer_history(env: Env, warranty_id: u64) -> Vec<TransferRecord> {
+        transfers::get_transfer_history(env, warranty_id)
+    }
+
+    /// Get the recorded changes of a warranty, oldest first
+    ///
+    /// The history is append-only and covers registration, transfers,
+    /// status changes, changes of the expiration date and claims, each with
+    /// the address that made the change. It is kept after the warranty is
+    /// removed. Private warranties report no entries, since these name
+    /// their owners.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `start`: Index of the first entry to return, 0 for the oldest
+    /// - `limit`: Maximum number of entries to return
+    ///
+    /// # Returns
+    /// Up to `limit` entries from `start` on
+    pub fn get_warranty_history(
+        env: Env,
+        warranty_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<HistoryEntry>, WarrantyError> {
+        queries::get_warranty_history(env, warranty_id, start, limit)
+    }
+
+    /// Get the collection name NFT wallets show for warranties
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    pub fn name(env: Env) -> String {
+        nft::name(env)
+    }
+
+    /// Get the collection symbol NFT wallets show for warranties
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    pub fn symbol(env: Env) -> String {
+        nft::symbol(env)
+    }
+
+    /// Count the warranties an address holds, as an NFT balance
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The address to count for
+    ///
+    /// # Returns
+    /// The number of public warranties owned, archived ones included
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        nft::balance_of(env, owner)
+    }
+
+    /// Get the owner of a warranty by its token ID
+    ///
+    /// Token IDs are warranty IDs.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `token_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The current owner, or the contract's address for a private warranty
+    /// as in `get_warranty`; fails with `NotFound` for unknown IDs
+    pub fn owner_of(env: Env, token_id: u64) -> Result<Address, WarrantyError> {
+        nft::owner_of(env, token_id)
+    }
+
+    /// Get the metadata URI of a warranty token
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `token_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The base URI set with `set_token_base_uri` followed by the token ID,
+    /// or an empty string while no base URI is set
+    pub fn token_uri(env: Env, token_id: u64) -> Result<String, WarrantyError> {
+        nft::token_uri(env, token_id)
+    }
+
+    /// Set the prefix `token_uri` appends token IDs to (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `base_uri`: E.g. `https://warranties.example.com/token/`
+    pub fn set_token_base_uri(env: Env, base_uri: String) -> Result<(), WarrantyError> {
+        nft::set_token_base_uri(env, base_uri)
+    }
+
+    /// Transfer a warranty through the standard NFT interface (only owner can transfer)
+    ///
+    /// Behaves like `transfer_ownership`, so marketplaces and wallets go
+    /// through the same checks and keep the owner indexes in sync.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `from`: The current owner
+    /// - `to`: The new owner
+    /// - `token_id`: The warranty ID
+    pub fn transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u64,
+    ) -> Result<(), WarrantyError> {
+        nft::transfer(env, from, to, token_id)
+    }
+
+    /// Prove control of a warranty to an off-chain verifier (only owner can prove)
+    ///
+    /// The verifier hands out a random `challenge` and watches for an
+    /// `OwnershipProved` event carrying it, which only the current owner's
+    /// signature can produce.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `challenge`: Nonce issued by the verifier
+    pub fn prove_ownership(
+        env: Env,
+        warranty_id: u64,
+        challenge: BytesN<32>,
+    ) -> Result<(), WarrantyError> {
+        attestation::prove_ownership(env, warranty_id, challenge)
+    }
+
+    /// Anchor the hash of the purchase receipt or invoice (owner or manufacturer, once)
+    ///
+    /// The hash lands in `receipt_hash` and cannot be replaced afterwards,
+    /// including when it was supplied at registration, so disputes can be
+    /// settled against it with `verify_proof`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `attester`: The owner, or the manufacturer that issued the warranty
+    /// - `proof_hash`: SHA-256 of the document
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn attach_proof(
+        env: Env,
+        warranty_id: u64,
+        attester: Address,
+        proof_hash: BytesN<32>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        attestation::attach_proof(env, warranty_id, attester, proof_hash, correlation_id)
+    }
+
+    /// Check a document against the anchored proof of purchase
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `document`: The receipt or invoice as presented in the dispute
+    ///
+    /// # Returns
+    /// True if the document's SHA-256 matches the anchored hash, false if
+    /// it differs or no proof was anchored
+    pub fn verify_proof(
+        env: Env,
+        warranty_id: u64,
+        document: Bytes,
+    ) -> Result<bool, WarrantyError> {
+        attestation::verify_proof(env, warranty_id, document)
+    }
+
+    /// Allow or stop a retailer attesting purchases of a manufacturer's
+    /// products (admin or the manufacturer's namespace issuer only)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manager`: The admin, or the namespace issuer of `manufacturer`
+    /// - `manufacturer`: Manufacturer name the retailer sells for
+    /// - `retailer`: The retailer's address
+    /// - `authorized`: Whether the retailer may call `attest_purchase`
+    pub fn set_retailer_authorized(
+        env: Env,
+        manager: Address,
+        manufacturer: String,
+        retailer: Address,
+        authorized: bool,
+    ) -> Result<(), WarrantyError> {
+        roles::set_retailer_authorized(env, manager, manufacturer, retailer, authorized)
+    }
+
+    /// Check whether a retailer may attest purchases of a manufacturer's products
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `retailer`: The retailer's address
+    ///
+    /// # Returns
+    /// True if the retailer is on the manufacturer's allowlist
+    pub fn is_authorized_retailer(env: Env, manufacturer: String, retailer: Address) -> bool {
+        roles::is_authorized_retailer(env, manufacturer, retailer)
+    }
+
+    /// Confirm that a warranty's product was bought from an authorized retailer
+    ///
+    /// The attestation is stored on the warranty and stays with it when it
+    /// changes hands. Terms templates with `requires_attestation` refuse
+    /// claims until it is given.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `retailer`: A retailer on the allowlist of the warranty's manufacturer
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn attest_purchase(
+        env: Env,
+        warranty_id: u64,
+        retailer: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        attestation::attest_purchase(env, warranty_id, retailer, correlation_id)
+    }
+
+    /// Attach display metadata to a warranty (owner or manufacturer only)
+    ///
+    /// Dapps use entries such as `image`, `manual` or `spec` to show product
+    /// photos and link documents. An empty value removes the entry. A
+    /// warranty holds at most `MAX_METADATA_ENTRIES` entries of up to
+    /// `MAX_METADATA_VALUE_LEN` bytes each.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `author`: The owner, or the manufacturer that issued the warranty
+    /// - `key`: Name of the entry
+    /// - `value`: URI or short text to store
+    /// - `correlation_id`: Optional caller reference echoed into the outbox and audit log
+    pub fn attach_metadata(
+        env: Env,
+        warranty_id: u64,
+        author: Address,
+        key: Symbol,
+        value: String,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::attach_metadata(env, warranty_id, author, key, value, correlation_id)
+    }
+
+    /// Get the display metadata of a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The metadata entries, empty for private warranties
+    pub fn get_metadata(env: Env, warranty_id: u64) -> Result<Map<Symbol, String>, WarrantyError> {
+        queries::get_metadata(env, warranty_id)
+    }
+
+    /// Bind a warranty to the ed25519 key held by the covered device (only owner can bind)
+    ///
+    /// Replaces any previously bound key.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `public_key`: The device's ed25519 public key
+    pub fn bind_device_key(
+        env: Env,
+        warranty_id: u64,
+        public_key: BytesN<32>,
+    ) -> Result<(), WarrantyError> {
+        devices::bind_device_key(env, warranty_id, public_key)
+    }
+
+    /// Remove the device key bound to a warranty (only owner can unbind)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn unbind_device_key(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        devices::unbind_device_key(env, warranty_id)
+    }
+
+    /// Get the device key bound to a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The device's public key or None if no device is bound
+    pub fn get_device_key(env: Env, warranty_id: u64) -> Option<BytesN<32>> {
+        devices::get_device_key(env, warranty_id)
+    }
+
+    /// Log an event signed by the warranty's bound device
+    ///
+    /// The device signs the XDR encoding of
+    /// `(contract address, warranty_id, event_code, nonce)`. Nonces must
+    /// increase with every event, so a signed message cannot be replayed.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `event_code`: Device-defined event or fault code
+    /// - `nonce`: Greater than the nonce of the previous event
+    /// - `signature`: ed25519 signature by the bound device key
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn log_device_event(
+        env: Env,
+        warranty_id: u64,
+        event_code: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        devices::log_device_event(
+            env,
+            warranty_id,
+            event_code,
+            nonce,
+            signature,
+            correlation_id,
+        )
+    }
+
+    /// Get the events logged by a warranty's device, oldest first
+    ///
+    /// Each event is stored in its own entry, so the log can grow for the
+    /// life of the device; read it a page at a time.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `start`: Index of the first event to return, 0 for the oldest
+    /// - `limit`: Maximum number of events to return, at most `max_page_size`
+    ///
+    /// # Returns
+    /// Up to `limit` events from `start` on
+    pub fn get_device_events(
+        env: Env,
+        warranty_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<DeviceEvent>, WarrantyError> {
+        devices::get_device_events(env, warranty_id, start, limit)
+    }
+
+    /// Report a usage reading such as an odometer value (only owner can report)
+    ///
+    /// Readings may not go backwards. Once a reading passes the usage limit
+    /// an active warranty becomes Expired, whatever its expiration date.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: A warranty registered with a usage limit
+    /// - `reading`: Total usage so far, in the warranty's usage unit
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn report_usage(
+        env: Env,
+        warranty_id: u64,
+        reading: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        devices::report_usage(env, warranty_id, reading, correlation_id)
+    }
+
+    /// Report a usage reading signed by the warranty's bound device
+    ///
+    /// The device signs the XDR encoding of
+    /// `(contract address, warranty_id, reading, nonce)` and shares its nonce
+    /// sequence with `log_device_event`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: A warranty registered with a usage limit
+    /// - `reading`: Total usage so far, in the warranty's usage unit
+    /// - `nonce`: Greater than the nonce of the previous device message
+    /// - `signature`: ed25519 signature by the bound device key
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn report_device_usage(
+        env: Env,
+        warranty_id: u64,
+        reading: u64,
+        nonce: u64,
+        signature: BytesN<64>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        devices::report_device_usage(env, warranty_id, reading, nonce, signature, correlation_id)
+    }
+
+    /// Draft a claim from a fault reported by the warranty's bound device
+    ///
+    /// The device signs the XDR encoding of
+    /// `(contract address, "fault", warranty_id, fault_code, nonce)` and
+    /// shares its nonce sequence with `log_device_event`. The claim is
+    /// stored as a `Draft` describing the fault code, with the report's
+    /// time as incident date, and only counts as filed once the owner
+    /// confirms it with `confirm_claim_draft`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `fault_code`: Device-defined fault code
+    /// - `nonce`: Greater than the nonce of the previous device message
+    /// - `signature`: ed25519 signature by the bound device key
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The draft's claim ID and what the call changed
+    pub fn device_report_fault(
+        env: Env,
+        warranty_id: u64,
+        fault_code: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<FiledClaim, WarrantyError> {
+        devices::device_report_fault(
+            env,
+            warranty_id,
+            fault_code,
+            nonce,
+            signature,
+            correlation_id,
+        )
+    }
+
+    /// File a claim the warranty's device drafted (only owner can confirm)
+    ///
+    /// The draft is filed as `file_claim` would file it, with the same
+    /// checks, and names the current owner as claimant.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The drafted claim
+    /// - `requested_amount`: What the owner asks to be paid, if anything
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The claim ID and what the call changed
+    pub fn confirm_claim_draft(
+        env: Env,
+        claim_id: u64,
+        requested_amount: Option<i128>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<FiledClaim, WarrantyError> {
+        claims::confirm_claim_draft(env, claim_id, requested_amount, correlation_id)
+    }
+
+    /// Throw away a claim the warranty's device drafted (only owner can discard)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The drafted claim
+    pub fn discard_claim_draft(env: Env, claim_id: u64) -> Result<(), WarrantyError> {
+        claims::discard_claim_draft(env, claim_id)
+    }
+
+    /// Allow or stop a repair shop logging service work (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `center`: The service center address
+    /// - `authorized`: Whether the center may log service records
+    pub fn set_service_center(
+        env: Env,
+        center: Address,
+        authorized: bool,
+    ) -> Result<(), WarrantyError> {
+        roles::set_service_center(env, center, authorized)
+    }
+
+    /// Check whether an address is an authorized service center
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `center`: The address to check
+    ///
+    /// # Returns
+    /// True if the admin authorized the center
+    pub fn is_service_center(env: Env, center: Address) -> bool {
+        roles::is_service_center(env, center)
+    }
+
+    /// Log repair or maintenance work on a warranty (service centers and the manufacturer only)
+    ///
+    /// The manufacturer is the namespace issuer of the warranty's
+    /// manufacturer, or the registered manufacturer that issued it. A
+    /// warranty holds at most `MAX_SERVICE_RECORDS` records.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `technician`: The service center or manufacturer logging the work
+    /// - `description`: What was done
+    /// - `date`: When the work was done; not before purchase or in the future
+    /// - `parts`: Part warranties registered under this warranty for the
+    ///   components fitted, at most `MAX_SERVICE_PARTS`
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn add_service_record(
+        env: Env,
+        warranty_id: u64,
+        technician: Address,
+        description: String,
+        date: u64,
+        parts: Vec<u64>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::add_service_record(
+            env,
+            warranty_id,
+            technician,
+            description,
+            date,
+            parts,
+            correlation_id,
+        )
+    }
+
+    /// Get the service work logged on a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// Service records in chronological order of the work
+    pub fn get_service_history(env: Env, warranty_id: u64) -> Vec<ServiceRecord> {
+        claims::get_service_history(env, warranty_id)
+    }
+
+    /// Allow or stop an address attesting storage and transport conditions (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `provider`: The logistics provider address
+    /// - `authorized`: Whether the provider may attest
+    pub fn set_logistics_provider(
+        env: Env,
+        provider: Address,
+        authorized: bool,
+    ) -> Result<(), WarrantyError> {
+        roles::set_logistics_provider(env, provider, authorized)
+    }
+
+    /// Check whether an address may attest storage and transport conditions
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `provider`: The address to check
+    ///
+    /// # Returns
+    /// True if the admin authorized the provider
+    pub fn is_logistics_provider(env: Env, provider: Address) -> bool {
+        roles::is_logistics_provider(env, provider)
+    }
+
+    /// Append a compliance attestation to a warranty's trail (authorized providers only)
+    ///
+    /// A trail holds at most `MAX_COMPLIANCE_ATTESTATIONS` attestations.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `provider`: The attesting logistics provider
+    /// - `warranty_id`: The warranty ID
+    /// - `compliant`: Whether conditions stayed within requirements
+    /// - `evidence_hash`: Hash of the supporting sensor log or report
+    pub fn attest_compliance(
+        env: Env,
+        provider: Address,
+        warranty_id: u64,
+        compliant: bool,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), WarrantyError> {
+        attestation::attest_compliance(env, provider, warranty_id, compliant, evidence_hash)
+    }
+
+    /// Get a warranty's compliance attestations, oldest first
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The compliance trail
+    pub fn get_compliance_trail(env: Env, warranty_id: u64) -> Vec<ComplianceAttestation> {
+        attestation::get_compliance_trail(env, warranty_id)
+    }
+
+    /// Check that a warranty's compliance trail has no breaches
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// True if no attestation reported non-compliance
+    pub fn has_clean_compliance(env: Env, warranty_id: u64) -> bool {
+        attestation::has_clean_compliance(env, warranty_id)
+    }
+
+    /// Set the deductible, payout caps and exclusions of a warranty (admin
+    /// or the manufacturer's namespace issuer only)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `caller`: The admin or the manufacturer's namespace issuer
+    /// - `warranty_id`: The warranty ID
+    /// - `terms`: The coverage terms
+    pub fn set_coverage_terms(
+        env: Env,
+        caller: Address,
+        warranty_id: u64,
+        terms: CoverageTerms,
+    ) -> Result<(), WarrantyError> {
+        claims::set_coverage_terms(env, caller, warranty_id, terms)
+    }
+
+    /// Get the deductible and payout cap of a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The coverage terms or None if none were set
+    pub fn get_coverage_terms(env: Env, warranty_id: u64) -> Option<CoverageTerms> {
+        claims::get_coverage_terms(env, warranty_id)
+    }
+
+    /// Check whether a warranty covers a category of claim
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `category`: The claim category
+    ///
+    /// # Returns
+    /// False if the coverage terms exclude the category, true otherwise
+    pub fn covers_claim_category(env: Env, warranty_id: u64, category: ClaimCategory) -> bool {
+        claims::covers_claim_category(env, warranty_id, category)
+    }
+
+    /// Estimate what a claim on a warranty would pay out
+    ///
+    /// The claim is capped at `max_payout` (or the purchase price when no cap
+    /// is set), reduced for depreciation, then by the deductible. Warranties
+    /// without coverage terms of their own use those of their terms template.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `claim_amount`: Amount being claimed
+    ///
+    /// # Returns
+    /// The gross, deductible, depreciation and net amounts
+    pub fn estimate_payout(
+        env: Env,
+        warranty_id: u64,
+        claim_amount: i128,
+    ) -> Result<PayoutEstimate, WarrantyError> {
+        claims::estimate_payout(env, warranty_id, claim_amount)
+    }
+
+    /// Offer a warranty to a buyer as part of a second-hand sale (only owner can offer)
+    ///
+    /// Unlike `transfer_ownership`, the warranty only moves once the buyer
+    /// accepts, and the sale is kept in the warranty's handover history.
+    /// A new offer replaces any pending one.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID being sold
+    /// - `buyer`: The address that may accept the handover
+    /// - `sale_price_hash`: Hash of the agreed sale price
+    pub fn initiate_handover(
+        env: Env,
+        warranty_id: u64,
+        buyer: Address,
+        sale_price_hash: BytesN<32>,
+    ) -> Result<(), WarrantyError> {
+        transfers::initiate_handover(env, warranty_id, buyer, sale_price_hash)
+    }
+
+    /// Accept a pending handover, taking ownership of the warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID being bought
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn accept_handover(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::accept_handover(env, warranty_id, correlation_id)
+    }
+
+    /// Withdraw a pending handover offer (only owner can cancel)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn cancel_handover(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        transfers::cancel_handover(env, warranty_id)
+    }
+
+    /// Get the handover offer waiting for the buyer, if any
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The pending handover or None
+    pub fn get_pending_handover(env: Env, warranty_id: u64) -> Option<Handover> {
+        transfers::get_pending_handover(env, warranty_id)
+    }
+
+    /// Get the completed handovers of a warranty, oldest first
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The warranty's resale history
+    pub fn get_handovers(env: Env, warranty_id: u64) -> Vec<Handover> {
+        transfers::get_handovers(env, warranty_id)
+    }
+
+    /// Approve an agent to manage a warranty, e.g. the retailer that sold it
+    /// (only owner can approve)
+    ///
+    /// The agent can transfer the warranty, file claims and update its status
+    /// through the `agent_` entry points. There is one agent per warranty at
+    /// a time, and the approval ends when the warranty changes hands.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `agent`: The agent, or None to withdraw the approval
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn approve_agent(
+        env: Env,
+        warranty_id: u64,
+        agent: Option<Address>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        roles::approve_agent(env, warranty_id, agent, correlation_id)
+    }
+
+    /// Get the agent approved for a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The agent or None if there is none
+    pub fn get_approved(env: Env, warranty_id: u64) -> Option<Address> {
+        roles::get_approved(env, warranty_id)
+    }
+
+    /// Let an operator manage all of an owner's warranties, e.g. an insurer
+    /// (owner signs)
+    ///
+    /// Unlike agent approvals, operators keep their role for warranties the
+    /// owner acquires later.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner
+    /// - `operator`: The operator
+    /// - `approved`: Whether the operator may act for the owner
+    pub fn set_operator(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), WarrantyError> {
+        roles::set_operator(env, owner, operator, approved)
+    }
+
+    /// Check whether an address operates all of an owner's warranties
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner
+    /// - `operator`: The address to check
+    ///
+    /// # Returns
+    /// True if the owner made the address an operator
+    pub fn is_operator(env: Env, owner: Address, operator: Address) -> bool {
+        roles::is_operator(env, owner, operator)
+    }
+
+    /// Transfer a warranty on the owner's behalf (approved agent or operator only)
+    ///
+    /// Works as `transfer_ownership` does; the agent approval ends with the
+    /// transfer.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `agent`: The approved agent or operator
+    /// - `warranty_id`: The warranty ID to transfer
+    /// - `new_owner`: The new owner address
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn agent_transfer_ownership(
+        env: Env,
+        agent: Address,
+        warranty_id: u64,
+        new_owner: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::agent_transfer_ownership(env, agent, warranty_id, new_owner, correlation_id)
+    }
+
+    /// File a claim on the owner's behalf (approved agent or operator only)
+    ///
+    /// Works as `file_claim` does; the owner is recorded as claimant.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `agent`: The approved agent or operator
+    /// - `warranty_id`: The warranty the claim is about
+    /// - `category`: What kind of problem it is
+    /// - `description`: What went wrong
+    /// - `incident_date`: When the problem occurred
+    /// - `requested_amount`: What the claimant asks to be paid, if anything
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The claim ID and a summary of what changed
+    #[allow(clippy::too_many_arguments)]
+    pub fn agent_file_claim(
+        env: Env,
+        agent: Address,
+        warranty_id: u64,
+        category: ClaimCategory,
+        description: String,
+        incident_date: u64,
+        requested_amount: Option<i128>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<FiledClaim, WarrantyError> {
+        claims::agent_file_claim(
+            env,
+            agent,
+            warranty_id,
+            category,
+            description,
+            incident_date,
+            requested_amount,
+            correlation_id,
+        )
+    }
+
+    /// Update a warranty's status on the owner's behalf (approved agent or operator only)
+    ///
+    /// Only the transitions open to the owner are allowed: expiring,
+    /// revoking, cancelling and setting an unrevoked warranty back to Active.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `agent`: The approved agent or operator
+    /// - `warranty_id`: The warranty ID to update
+    /// - `status`: The new status
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn agent_update_status(
+        env: Env,
+        agent: Address,
+        warranty_id: u64,
+        status: WarrantyStatus,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::agent_update_status(env, agent, warranty_id, status, correlation_id)
+    }
+
+    /// Restrict which addresses an owner's warranties can be transferred to
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner setting the policy
+    /// - `allowlist`: Allowed destinations; an empty list lifts the restriction
+    pub fn set_transfer_allowlist(
+        env: Env,
+        owner: Address,
+        allowlist: Vec<Address>,
+    ) -> Result<(), WarrantyError> {
+        transfers::set_transfer_allowlist(env, owner, allowlist)
+    }
+
+    /// Get the transfer allowlist of an owner
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    ///
+    /// # Returns
+    /// Allowed destinations, empty if transfers are unrestricted
+    pub fn get_transfer_allowlist(env: Env, owner: Address) -> Vec<Address> {
+        transfers::get_transfer_allowlist(env, owner)
+    }
+
+    /// Name a beneficiary who can take over a warranty if the owner goes inactive
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `beneficiary`: The address that inherits the warranty
+    /// - `inactivity_secs`: Seconds without owner activity before it can be claimed
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn set_beneficiary(
+        env: Env,
+        warranty_id: u64,
+        beneficiary: Address,
+        inactivity_secs: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::set_beneficiary(
+            env,
+            warranty_id,
+            beneficiary,
+            inactivity_secs,
+            correlation_id,
+        )
+    }
+
+    /// Remove the beneficiary of a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn remove_beneficiary(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        transfers::remove_beneficiary(env, warranty_id)
+    }
+
+    /// Get the beneficiary of a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The beneficiary or None if not set
+    pub fn get_beneficiary(env: Env, warranty_id: u64) -> Option<Beneficiary> {
+        transfers::get_beneficiary(env, warranty_id)
+    }
+
+    /// Get the timestamp of an address's last authenticated action
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    ///
+    /// # Returns
+    /// The timestamp or None if the address has never acted
+    pub fn get_last_activity(env: Env, owner: Address) -> Option<u64> {
+        transfers::get_last_activity(env, owner)
+    }
+
+    /// Take ownership of a warranty whose owner has been inactive past the window
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to inherit
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn claim_inherited(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::claim_inherited(env, warranty_id, correlation_id)
+    }
+
+    /// Schedule a warranty to return to `revert_to` once a lease ends (only
+    /// owner can schedule)
+    ///
+    /// The reversion stays in place when the warranty changes hands, and
+    /// only the owner who scheduled it can replace it.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `revert_to`: The address that gets the warranty back
+    /// - `revert_after`: Timestamp from which the reversion can be executed
+    pub fn set_reversion(
+        env: Env,
+        warranty_id: u64,
+        revert_to: Address,
+        revert_after: u64,
+    ) -> Result<(), WarrantyError> {
+        transfers::set_reversion(env, warranty_id, revert_to, revert_after)
+    }
+
+    /// Get the reversion scheduled for a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The reversion or None if none is scheduled
+    pub fn get_reversion(env: Env, warranty_id: u64) -> Option<Reversion> {
+        transfers::get_reversion(env, warranty_id)
+    }
+
+    /// Return a warranty to the address its reversion names (anyone can execute)
+    ///
+    /// Ownership moves as in a transfer, whatever the warranty's status, and
+    /// a `WarrantyReverted` event is published. A warranty already back with
+    /// that address just has its reversion cleared.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn execute_reversion(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        transfers::execute_reversion(env, warranty_id, correlation_id)
+    }
+
+    /// Attach an external insurance policy to a warranty (only owner can attach)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `insurer`: The insurer contract implementing `InsurerInterface`
+    /// - `policy_id`: The policy ID within the insurer contract
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn attach_policy(
+        env: Env,
+        warranty_id: u64,
+        insurer: Address,
+        policy_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::attach_policy(env, warranty_id, insurer, policy_id, correlation_id)
+    }
+
+    /// Detach the insurance policy from a warranty (only owner can detach)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    pub fn detach_policy(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+        claims::detach_policy(env, warranty_id)
+    }
+
+    /// Get the insurance policy attached to a warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The attached policy or None if there is none
+    pub fn get_attached_policy(env: Env, warranty_id: u64) -> Option<AttachedPolicy> {
+        claims::get_attached_policy(env, warranty_id)
+    }
+
+    /// Let an insurer underwrite extended coverage (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The underwriter's address
+    pub fn register_underwriter(env: Env, address: Address) -> Result<(), WarrantyError> {
+        roles::register_underwriter(env, address)
+    }
+
+    /// Stop an insurer underwriting new coverage (admin only, or the council once enabled)
+    ///
+    /// Coverage it already underwrote stays in force.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The underwriter's address
+    pub fn remove_underwriter(env: Env, address: Address) -> Result<(), WarrantyError> {
+        roles::remove_underwriter(env, address)
+    }
+
+    /// Check whether an address is a registered underwriter
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `address`: The address to check
+    ///
+    /// # Returns
+    /// True if the admin registered the underwriter
+    pub fn is_underwriter(env: Env, address: Address) -> bool {
+        roles::is_underwriter(env, address)
+    }
+
+    /// Sell extended coverage on an active warranty (underwriter and owner sign)
+    ///
+    /// The owner pays `premium` of `premium_token` to the underwriter. The
+    /// coverage runs from the end of the manufacturer's term until
+    /// `covered_until`, stays with the warranty when it changes hands, and
+    /// cannot be replaced.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `underwriter`: A registered underwriter
+    /// - `warranty_id`: The warranty ID
+    /// - `coverage_terms`: Deductible, payout cap and exclusions of the coverage
+    /// - `covered_until`: When the coverage ends; must be after the warranty's expiration
+    /// - `premium_token`: Token the premium is paid in
+    /// - `premium`: Amount the owner pays the underwriter
+    pub fn underwrite(
+        env: Env,
+        underwriter: Address,
+        warranty_id: u64,
+        coverage_terms: CoverageTerms,
+        covered_until: u64,
+        premium_token: Address,
+        premium: i128,
+    ) -> Result<(), WarrantyError> {
+        claims::underwrite(
+            env,
+            underwriter,
+            warranty_id,
+            coverage_terms,
+            covered_until,
+            premium_token,
+            premium,
+        )
+    }
+
+    /// Get the extended coverage underwritten on a warranty
+    ///
+    /// Claims on a warranty whose manufacturer term has lapsed belong with
+    /// the underwriter while `covered_until` has not passed.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The underwriting or None if none was sold
+    pub fn get_underwriting(env: Env, warranty_id: u64) -> Option<Underwriting> {
+        claims::get_underwriting(env, warranty_id)
+    }
+
+    /// File a claim against a warranty (only owner can file)
+    ///
+    /// The warranty's status is first recomputed from its expiration date
+    /// and usage, and must then be Active. The incident must lie between
+    /// the purchase and now, and no further back than the claim window.
+    /// A requested amount must be positive and caps what approval may pay.
+    /// Categories the coverage terms exclude fail with `NotCovered`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty the claim is about
+    /// - `category`: What kind of problem it is
+    /// - `description`: What went wrong
+    /// - `incident_date`: When the problem occurred
+    /// - `requested_amount`: What the claimant asks to be paid, if anything
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The claim ID and a summary of what changed
+    pub fn file_claim(
+        env: Env,
+        warranty_id: u64,
+        category: ClaimCategory,
+        description: String,
+        incident_date: u64,
+        requested_amount: Option<i128>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<FiledClaim, WarrantyError> {
+        claims::file_claim(
+            env,
+            warranty_id,
+            category,
+            description,
+            incident_date,
+            requested_amount,
+            correlation_id,
+        )
+    }
+
+    /// File a claim against a jointly held warranty (co-owners only)
+    ///
+    /// Works as `file_claim` does; the owner is recorded as claimant.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `co_owner`: A co-owner of the warranty
+    /// - `warranty_id`: The warranty the claim is about
+    /// - `category`: What kind of problem it is
+    /// - `description`: What went wrong
+    /// - `incident_date`: When the problem occurred
+    /// - `requested_amount`: What the claimant asks to be paid, if anything
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The claim ID and a summary of what changed
+    #[allow(clippy::too_many_arguments)]
+    pub fn co_owner_file_claim(
+        env: Env,
+        co_owner: Address,
+        warranty_id: u64,
+        category: ClaimCategory,
+        description: String,
+        incident_date: u64,
+        requested_amount: Option<i128>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<FiledClaim, WarrantyError> {
+        claims::co_owner_file_claim(
+            env,
+            co_owner,
+            warranty_id,
+            category,
+            description,
+            incident_date,
+            requested_amount,
+            correlation_id,
+        )
+    }
+
+    /// Get every claim filed against a warranty, oldest first
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The warranty's claims
+    pub fn get_claims(env: Env, warranty_id: u64) -> Result<Vec<ClaimData>, WarrantyError> {
+        claims::get_claims(env, warranty_id)
+    }
+
+    /// Set funds aside for claim payouts (manufacturer signs)
+    ///
+    /// The amount is transferred from the manufacturer to the contract and
+    /// added to the manufacturer's pool for that token.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer or namespace issuer funding the pool
+    /// - `token`: Token contract the payouts are made in
+    /// - `amount`: Amount to add
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn fund_claim_pool(
+        env: Env,
+        manufacturer: Address,
+        token: Address,
+        amount: i128,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::fund_claim_pool(env, manufacturer, token, amount, correlation_id)
+    }
+
+    /// Take unused funds back out of a claim pool (manufacturer signs)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The pool's manufacturer
+    /// - `token`: Token contract of the pool
+    /// - `amount`: Amount to withdraw
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn withdraw_claim_pool(
+        env: Env,
+        manufacturer: Address,
+        token: Address,
+        amount: i128,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::withdraw_claim_pool(env, manufacturer, token, amount, correlation_id)
+    }
+
+    /// Get what a manufacturer's claim pool holds
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The pool's manufacturer
+    /// - `token`: Token contract of the pool
+    ///
+    /// # Returns
+    /// The pool balance, 0 if it was never funded
+    pub fn get_pool_balance(env: Env, manufacturer: Address, token: Address) -> i128 {
+        claims::get_pool_balance(env, manufacturer, token)
+    }
+
+    /// Commit funds to settling a manufacturer's approved claims (manufacturer signs)
+    ///
+    /// Escrowed funds are kept apart from the claim pool and only leave
+    /// through `settle_claim`, so claimants can rely on them.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The registered manufacturer or namespace issuer funding the escrow
+    /// - `token`: Token contract the settlements are made in
+    /// - `amount`: Amount to add
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn fund_claims_escrow(
+        env: Env,
+        manufacturer: Address,
+        token: Address,
+        amount: i128,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::fund_claims_escrow(env, manufacturer, token, amount, correlation_id)
+    }
+
+    /// Get what a manufacturer holds in escrow for settling approved claims
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The escrow's manufacturer
+    /// - `token`: Token contract of the escrow
+    ///
+    /// # Returns
+    /// The escrow balance, 0 if it was never funded
+    pub fn get_escrow_balance(env: Env, manufacturer: Address, token: Address) -> i128 {
+        claims::get_escrow_balance(env, manufacturer, token)
+    }
+
+    /// Let settlements refill a manufacturer's escrow from its token allowance (manufacturer signs)
+    ///
+    /// The manufacturer approves the contract as a spender on the token.
+    /// When a settlement would leave the escrow below `floor`, `amount` is
+    /// pulled from the allowance first; if the pull fails the settlement
+    /// uses what is escrowed.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The escrow's manufacturer
+    /// - `token`: Token contract of the escrow
+    /// - `floor`: Balance a settlement must leave without a refill
+    /// - `amount`: Amount pulled per refill, 0 to stop refilling
+    pub fn set_auto_topup(
+        env: Env,
+        manufacturer: Address,
+        token: Address,
+        floor: i128,
+        amount: i128,
+    ) -> Result<(), WarrantyError> {
+        claims::set_auto_topup(env, manufacturer, token, floor, amount)
+    }
+
+    /// Get how a manufacturer's escrow is refilled from its token allowance
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The escrow's manufacturer
+    /// - `token`: Token contract of the escrow
+    ///
+    /// # Returns
+    /// The floor and refill amount, or None if the escrow is not refilled
+    pub fn get_auto_topup(env: Env, manufacturer: Address, token: Address) -> Option<AutoTopUp> {
+        claims::get_auto_topup(env, manufacturer, token)
+    }
+
+    /// Settle an approved claim out of escrow (the paying manufacturer signs)
+    ///
+    /// Pays the warranty's current owner what was asked for on the claim and
+    /// not paid yet, and resolves the claim. Claims on a payment plan are
+    /// settled by their installments instead.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    /// - `token`: Token contract of the escrow to pay from
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn settle_claim(
+        env: Env,
+        claim_id: u64,
+        token: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::settle_claim(env, claim_id, token, correlation_id)
+    }
+
+    /// Hold claims filed at a high velocity for review (admin only, or the council once enabled)
+    ///
+    /// A claim is held when, as it is filed, its warranty's serial number or
+    /// owner already has `max_claims_30d` claims in the last 30 days or
+    /// `max_claims_90d` in the last 90. A held claim cannot be approved
+    /// until a reviewer clears it.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `thresholds`: The claim counts, or None to stop holding claims
+    pub fn set_velocity_thresholds(
+        env: Env,
+        thresholds: Option<VelocityThresholds>,
+    ) -> Result<(), WarrantyError> {
+        fraud::set_velocity_thresholds(env, thresholds)
+    }
+
+    /// Get the claim counts at which new claims are held for review
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The thresholds, or None if no claims are held
+    pub fn get_velocity_thresholds(env: Env) -> Option<VelocityThresholds> {
+        fraud::get_velocity_thresholds(env)
+    }
+
+    /// Get the recent claim velocity of a warranty's serial number and owner
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// Claims filed in the last 30 and 90 days, and whether the next claim
+    /// on the warranty would be held for review
+    pub fn get_fraud_signals(env: Env, warranty_id: u64) -> Result<FraudSignals, WarrantyError> {
+        fraud::get_fraud_signals(env, warranty_id)
+    }
+
+    /// Check whether a claim is held for review
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    ///
+    /// # Returns
+    /// True if the claim cannot be approved until a reviewer clears it
+    pub fn is_held_for_review(env: Env, claim_id: u64) -> bool {
+        fraud::is_held_for_review(env, claim_id)
+    }
+
+    /// Clear a claim's review hold so it can be approved (anyone who may approve the claim)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `reviewer`: Address clearing the hold
+    /// - `claim_id`: The claim ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn clear_claim_review(
+        env: Env,
+        reviewer: Address,
+        claim_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        fraud::clear_claim_review(env, reviewer, claim_id, correlation_id)
+    }
+
+    /// Reverse the settlement of a claim proven fraudulent (an arbitrator who may rule on the claim)
+    ///
+    /// Only a `Resolved` claim that paid out can be charged back. The claim
+    /// moves to `ChargedBack` and the payout is recorded as owed to the
+    /// paying manufacturer, to be returned with `repay_chargeback`. Other
+    /// balances the contract holds for the claimant are left alone. The
+    /// claimant's later claims are held for review.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator recording the chargeback
+    /// - `claim_id`: The claim ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn chargeback(
+        env: Env,
+        arbitrator: Address,
+        claim_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        fraud::chargeback(env, arbitrator, claim_id, correlation_id)
+    }
+
+    /// Repay part or all of a charged-back payout into the paying manufacturer's claim pool (anyone; `from` signs)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `from`: The address the repayment is taken from
+    /// - `claim_id`: The charged-back claim ID
+    /// - `amount`: Amount to repay, up to what is still outstanding
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn repay_chargeback(
+        env: Env,
+        from: Address,
+        claim_id: u64,
+        amount: i128,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        fraud::repay_chargeback(env, from, claim_id, amount, correlation_id)
+    }
+
+    /// Get the chargeback recorded against a claim
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    ///
+    /// # Returns
+    /// What was paid and recovered, or None if the claim was not charged back
+    pub fn get_chargeback(env: Env, claim_id: u64) -> Option<Chargeback> {
+        fraud::get_chargeback(env, claim_id)
+    }
+
+    /// Create a consortium for manufacturers to share an arbitration panel, escrow and claim cap (admin only; operator signs)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `operator`: Address that manages the members, panel and claim cap
+    /// - `name`: Name of the consortium
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// The consortium ID
+    pub fn create_consortium(
+        env: Env,
+        operator: Address,
+        name: String,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<u64, WarrantyError> {
+        consortium::create_consortium(env, operator, name, correlation_id)
+    }
+
+    /// Get a consortium
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    ///
+    /// # Returns
+    /// The consortium with its members and panel, or None if it does not exist
+    pub fn get_consortium(env: Env, consortium_id: u64) -> Option<Consortium> {
+        consortium::get_consortium(env, consortium_id)
+    }
+
+    /// Get the consortium a manufacturer belongs to
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `member`: The manufacturer address
+    ///
+    /// # Returns
+    /// The consortium ID, or None if it belongs to none
+    pub fn get_member_consortium(env: Env, member: Address) -> Option<u64> {
+        consortium::get_member_consortium(env, member)
+    }
+
+    /// Add a manufacturer to a consortium (operator and member sign)
+    ///
+    /// A manufacturer belongs to at most one consortium.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    /// - `member`: Manufacturer address that pays the claims the consortium backs
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn add_consortium_member(
+        env: Env,
+        consortium_id: u64,
+        member: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        consortium::add_consortium_member(env, consortium_id, member, correlation_id)
+    }
+
+    /// Remove a manufacturer from a consortium (the operator or the member itself)
+    ///
+    /// What the member paid into the shared escrow stays there until it
+    /// takes it back with `withdraw_consortium_share`, and its account keeps
+    /// the record.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `caller`: The operator or the member
+    /// - `consortium_id`: The consortium ID
+    /// - `member`: The member to remove
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn remove_consortium_member(
+        env: Env,
+        caller: Address,
+        consortium_id: u64,
+        member: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        consortium::remove_consortium_member(env, caller, consortium_id, member, correlation_id)
+    }
+
+    /// Add an arbitrator to or remove one from a consortium's panel (operator and every member sign)
+    ///
+    /// While the panel has anyone on it, only its arbitrators vote on
+    /// disputes over claims the consortium's members pay.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    /// - `arbitrator`: The arbitrator
+    /// - `authorized`: Whether the arbitrator sits on the panel
+    pub fn set_consortium_arbitrator(
+        env: Env,
+        consortium_id: u64,
+        arbitrator: Address,
+        authorized: bool,
+    ) -> Result<(), WarrantyError> {
+        consortium::set_consortium_arbitrator(env, consortium_id, arbitrator, authorized)
+    }
+
+    /// Cap how many claims one owner files across all of a consortium's members (operator signs)
+    ///
+    /// Applies on top of each manufacturer's own cap. Filing past it fails
+    /// with `RateLimited` until the oldest counted claim leaves the window.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    /// - `limit`: The cap, or None to lift it
+    pub fn set_consortium_claim_limit(
+        env: Env,
+        consortium_id: u64,
+        limit: Option<ClaimLimit>,
+    ) -> Result<(), WarrantyError> {
+        consortium::set_consortium_claim_limit(env, consortium_id, limit)
+    }
+
+    /// Get the cap on claims one owner files across a consortium's members
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    ///
+    /// # Returns
+    /// The cap, or None if there is none
+    pub fn get_consortium_claim_limit(env: Env, consortium_id: u64) -> Option<ClaimLimit> {
+        consortium::get_consortium_claim_limit(env, consortium_id)
+    }
+
+    /// Pay into the shared escrow of the member's consortium (member signs)
+    ///
+    /// Settlements on a member's claims draw on the shared escrow when the
+    /// member's own escrow cannot cover them.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `member`: The paying member
+    /// - `token`: Token contract to pay in
+    /// - `amount`: Amount to pay in
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn fund_consortium_escrow(
+        env: Env,
+        member: Address,
+        token: Address,
+        amount: i128,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        consortium::fund_consortium_escrow(env, member, token, amount, correlation_id)
+    }
+
+    /// Take back a former member's unused share of a consortium's shared escrow (former member only)
+    ///
+    /// Pays out what the member paid in and neither drew nor took back
+    /// already, as far as the shared escrow still holds it. Members must
+    /// leave the consortium first.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `member`: The former member
+    /// - `consortium_id`: The consortium it left
+    /// - `token`: Token contract of the escrow
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn withdraw_consortium_share(
+        env: Env,
+        member: Address,
+        consortium_id: u64,
+        token: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        consortium::withdraw_consortium_share(env, member, consortium_id, token, correlation_id)
+    }
+
+    /// Get the balance of a consortium's shared escrow
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    /// - `token`: Token contract of the escrow
+    ///
+    /// # Returns
+    /// The balance, 0 if it was never funded
+    pub fn get_consortium_escrow(env: Env, consortium_id: u64, token: Address) -> i128 {
+        consortium::get_consortium_escrow(env, consortium_id, token)
+    }
+
+    /// Get what a member paid into and drew from its consortium's shared escrow
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `consortium_id`: The consortium ID
+    /// - `member`: The member
+    /// - `token`: Token contract of the escrow
+    ///
+    /// # Returns
+    /// The member's contributions and draws in `token`, with what its
+    /// approved claims still ask for
+    pub fn get_member_account(
+        env: Env,
+        consortium_id: u64,
+        member: Address,
+        token: Address,
+    ) -> MemberAccount {
+        consortium::get_member_account(env, consortium_id, member, token)
+    }
+
+    /// Get the total paid out on a warranty's approved claims
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The amount paid so far, which counts against the warranty's payout
+    /// limit
+    pub fn get_paid_out(env: Env, warranty_id: u64) -> i128 {
+        claims::get_paid_out(env, warranty_id)
+    }
+
+    /// Approve a filed claim (admin or the warranty's manufacturer only)
+    ///
+    /// The insurer of an attached policy is notified of the approval. The
+    /// approved amount may be less than the claimant's requested amount,
+    /// depreciated to the product's age on the incident date, but not more,
+    /// and together with the warranty's earlier payouts it cannot
+    /// exceed the payout cap (or the purchase price) less the deductible.
+    /// Both amounts stay on the claim. With a payout, up to the approved
+    /// amount is paid to the warranty's current owner out of the claim pool
+    /// of the manufacturer that issued the warranty, or of its namespace
+    /// issuer for self-registered warranties; only the admin or the pool's
+    /// owner may attach one. Whatever is left unpaid is owed until the claim
+    /// is settled.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `approver`: The admin or the warranty's manufacturer deciding the claim
+    /// - `claim_id`: The claim ID
+    /// - `approved_amount`: Amount approved, or None for the payout, else the
+    ///   depreciated amount asked for
+    /// - `payout`: Token and amount to pay out, or None to approve without paying
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn approve_claim(
+        env: Env,
+        approver: Address,
+        claim_id: u64,
+        approved_amount: Option<i128>,
+        payout: Option<ClaimPayout>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::approve_claim(
+            env,
+            approver,
+            claim_id,
+            approved_amount,
+            payout,
+            correlation_id,
+        )
+    }
+
+    /// Post a message to a claim's thread (claimant, claim reviewers or the
+    /// dispute's arbitrator only)
+    ///
+    /// Only the body's hash and location go on-chain, so the negotiation
+    /// trail can be checked against the off-chain copies in a dispute.
+    /// Messages can be posted whatever the claim's status. Once the claim is
+    /// disputed, any registered arbitrator can post until one rules, and
+    /// then only the arbitrator that ruled.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `author`: The claimant, the admin, the warranty's manufacturer or the
+    ///   dispute's arbitrator
+    /// - `claim_id`: The claim ID
+    /// - `body_hash`: SHA-256 of the message body
+    /// - `uri`: Where the body can be fetched
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn post_claim_message(
+        env: Env,
+        author: Address,
+        claim_id: u64,
+        body_hash: BytesN<32>,
+        uri: String,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::post_claim_message(env, author, claim_id, body_hash, uri, correlation_id)
+    }
+
+    /// Get the message thread of a claim, oldest first
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    ///
+    /// # Returns
+    /// The claim's messages
+    pub fn get_claim_messages(env: Env, claim_id: u64) -> Vec<ClaimMessage> {
+        claims::get_claim_messages(env, claim_id)
+    }
+
+    /// Reject a filed claim (admin or the warranty's manufacturer only)
+    ///
+    /// The reason is stored on the claim and published with the rejection.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `approver`: The admin or the warranty's manufacturer deciding the claim
+    /// - `claim_id`: The claim ID
+    /// - `reason`: Why the claim is rejected
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn reject_claim(
+        env: Env,
+        approver: Address,
+        claim_id: u64,
+        reason: DenialReason,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::reject_claim(env, approver, claim_id, reason, correlation_id)
+    }
+
+    /// Mark an approved claim as settled (admin or the warranty's manufacturer only)
+    ///
+    /// The compliance hook, if any, must allow the settlement from the
+    /// approver to the claimant.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `approver`: The admin or the warranty's manufacturer settling the claim
+    /// - `claim_id`: The claim ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn resolve_claim(
+        env: Env,
+        approver: Address,
+        claim_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::resolve_claim(env, approver, claim_id, correlation_id)
+    }
+
+    /// Settle an approved claim in installments out of a claim pool (the pool's owner signs)
+    ///
+    /// The pool is the one that pays the claim's payouts. Installments
+    /// fall due every `interval` seconds from now, and together, with what
+    /// the claim and the warranty were already paid, must stay within the
+    /// limits a single payout has. The claim can then no longer be resolved
+    /// directly: it moves to `Resolved` when the final installment is paid.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The approved claim
+    /// - `token`: Token the installments are paid in
+    /// - `installment_amount`: Amount of each installment
+    /// - `count`: Number of installments, up to `MAX_PLAN_INSTALLMENTS`
+    /// - `interval`: Seconds between installment deadlines
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn create_payment_plan(
+        env: Env,
+        claim_id: u64,
+        token: Address,
+        installment_amount: i128,
+        count: u32,
+        interval: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::create_payment_plan(
+            env,
+            claim_id,
+            token,
+            installment_amount,
+            count,
+            interval,
+            correlation_id,
+        )
+    }
+
+    /// Pay the next installment of a claim's payment plan (the payer signs)
+    ///
+    /// The installment goes from the payer's claim pool to the warranty's
+    /// current owner, and fails with `InsufficientFunds` if the pool cannot
+    /// cover it. A late installment is still accepted, but the plan records
+    /// the missed deadline in `defaulted_at`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim on the plan
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn pay_installment(
+        env: Env,
+        claim_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        claims::pay_installment(env, claim_id, correlation_id)
+    }
+
+    /// Record a payment plan's default once an installment deadline has passed unpaid
+    ///
+    /// Anyone may run this, e.g. the claimant or a monitoring service.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim on the plan
+    ///
+    /// # Returns
+    /// True if the plan is in default
+    pub fn check_payment_plan(env: Env, claim_id: u64) -> Result<bool, WarrantyError> {
+        claims::check_payment_plan(env, claim_id)
+    }
+
+    /// Get the payment plan settling a claim
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    ///
+    /// # Returns
+    /// The plan and its progress, or None if the claim has none
+    pub fn get_payment_plan(env: Env, claim_id: u64) -> Option<PaymentPlan> {
+        claims::get_payment_plan(env, claim_id)
+    }
+
+    /// Allow or stop an address ruling on disputed claims (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator's address
+    /// - `authorized`: Whether the arbitrator may vote on disputes
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn set_arbitrator(
+        env: Env,
+        arbitrator: Address,
+        authorized: bool,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        disputes::set_arbitrator(env, arbitrator, authorized, correlation_id)
+    }
+
+    /// Check whether an address is a registered arbitrator
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The address to check
+    ///
+    /// # Returns
+    /// True if the admin registered the arbitrator
+    pub fn is_arbitrator(env: Env, arbitrator: Address) -> bool {
+        disputes::is_arbitrator(env, arbitrator)
+    }
+
+    /// Set how many arbitrators a dispute's ruling is put to (admin only, or the council once enabled)
+    ///
+    /// An outcome carries once more than half of `quorum` arbitrators
+    /// voted for it. Disputes keep the quorum in force when they were
+    /// raised.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `quorum`: Panel size per dispute, from 1 to `MAX_DISPUTE_QUORUM`
+    pub fn set_dispute_quorum(env: Env, quorum: u32) -> Result<(), WarrantyError> {
+        disputes::set_dispute_quorum(env, quorum)
+    }
+
+    /// Get how many arbitrators new disputes are put to
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The dispute quorum, 1 unless the admin set one
+    pub fn get_dispute_quorum(env: Env) -> u32 {
+        disputes::get_dispute_quorum(env)
+    }
+
+    /// Set or clear the fee for raising a dispute (admin only, or the council once enabled)
+    ///
+    /// Disputes keep the fee in force when they were raised.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `fee`: Token and amount charged per dispute, or None to charge nothing
+    pub fn set_dispute_fee(env: Env, fee: Option<DisputeFee>) -> Result<(), WarrantyError> {
+        disputes::set_dispute_fee(env, fee)
+    }
+
+    /// Get the fee for raising a dispute
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The dispute fee, or None if disputes are free
+    pub fn get_dispute_fee(env: Env) -> Option<DisputeFee> {
+        disputes::get_dispute_fee(env)
+    }
+
+    /// Get the dispute fees an arbitrator has earned and not yet withdrawn
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator
+    /// - `token`: Token the fees were paid in
+    ///
+    /// # Returns
+    /// Amount owed to the arbitrator
+    pub fn get_arbitrator_fees(env: Env, arbitrator: Address, token: Address) -> i128 {
+        disputes::get_arbitrator_fees(env, arbitrator, token)
+    }
+
+    /// Get the dispute fees collected in a token and not yet withdrawn
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `token`: The fee token
+    ///
+    /// # Returns
+    /// Balance of the dispute fee pool
+    pub fn get_dispute_fee_pool(env: Env, token: Address) -> i128 {
+        disputes::get_dispute_fee_pool(env, token)
+    }
+
+    /// Pay out an arbitrator's earned dispute fees (only the arbitrator can withdraw)
+    ///
+    /// Arbitrators removed from the panel can still withdraw what they
+    /// earned.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator
+    /// - `token`: Token the fees were paid in
+    ///
+    /// # Returns
+    /// Amount transferred to the arbitrator
+    pub fn withdraw_arbitrator_fees(
+        env: Env,
+        arbitrator: Address,
+        token: Address,
+    ) -> Result<i128, WarrantyError> {
+        disputes::withdraw_arbitrator_fees(env, arbitrator, token)
+    }
+
+    /// Escalate a rejected claim to arbitration (current owner only)
+    ///
+    /// The claim moves to `Disputed` until an arbitrator rules on it. A
+    /// claim can be disputed once, within 30 days of its rejection. If a
+    /// dispute fee is set, the owner pays it into the contract; it is
+    /// returned if the dispute is upheld, when the manufacturer's claim
+    /// pool pays the arbitrators instead.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The rejected claim
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn dispute_claim(
+        env: Env,
+        claim_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        disputes::dispute_claim(env, claim_id, correlation_id)
+    }
+
+    /// Rule on a disputed claim (registered arbitrators only)
+    ///
+    /// Upholding the dispute approves the claim and, with a payout, pays
+    /// the warranty's owner out of the manufacturer's claim pool as
+    /// `approve_claim` would. Otherwise the rejection stands for good.
+    /// Casts the arbitrator's vote as `vote_on_dispute` does, so with a
+    /// quorum above 1 the ruling waits for a majority.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator ruling on the dispute
+    /// - `claim_id`: The disputed claim
+    /// - `uphold`: True to side with the owner, false to confirm the rejection
+    /// - `payout`: Token and amount to pay out when upheld, or None
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn rule_on_dispute(
+        env: Env,
+        arbitrator: Address,
+        claim_id: u64,
+        uphold: bool,
+        payout: Option<ClaimPayout>,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        disputes::rule_on_dispute(env, arbitrator, claim_id, uphold, payout, correlation_id)
+    }
+
+    /// Get the dispute raised over a claim
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The claim ID
+    ///
+    /// # Returns
+    /// The dispute and its ruling, or None if the claim was never disputed
+    pub fn get_dispute(env: Env, claim_id: u64) -> Option<ClaimDispute> {
+        disputes::get_dispute(env, claim_id)
+    }
+
+    /// Vote on the outcome of a disputed claim (registered arbitrators only)
+    ///
+    /// Each arbitrator votes once per dispute. When more than half of the
+    /// dispute's quorum voted for the same outcome, that outcome is carried
+    /// out as `rule_on_dispute` describes, with the deciding voter as the
+    /// ruling arbitrator. Upholding votes count together only if they name
+    /// the same payout. The dispute's fee, paid by the losing party, is
+    /// split evenly among the arbitrators that voted for the ruling, the
+    /// deciding voter taking any remainder.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The voting arbitrator
+    /// - `claim_id`: The disputed claim
+    /// - `outcome`: The ruling the arbitrator votes for
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// A summary of what changed
+    pub fn vote_on_dispute(
+        env: Env,
+        arbitrator: Address,
+        claim_id: u64,
+        outcome: DisputeOutcome,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        disputes::vote_on_dispute(env, arbitrator, claim_id, outcome, correlation_id)
+    }
+
+    /// Get the outcome an arbitrator voted for on a dispute
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `claim_id`: The disputed claim
+    /// - `arbitrator`: The arbitrator
+    ///
+    /// # Returns
+    /// The arbitrator's vote, or None if they have not voted
+    pub fn get_dispute_vote(
+        env: Env,
+        claim_id: u64,
+        arbitrator: Address,
+    ) -> Option<DisputeOutcome> {
+        disputes::get_dispute_vote(env, claim_id, arbitrator)
+    }
+
+    /// Settle which warranty keeps a contested serial number (registered
+    /// arbitrators only)
+    ///
+    /// The conflict is looked up under the winner's manufacturer. The
+    /// winner takes the serial index entry, and every other claimant that
+    /// can still be revoked is, with the arbitrator named in its history.
+    /// The conflict record is kept with the ruling.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `arbitrator`: The arbitrator ruling on the conflict
+    /// - `serial_number`: The contested serial number
+    /// - `winner_id`: The warranty that keeps the serial number
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn resolve_serial_conflict(
+        env: Env,
+        arbitrator: Address,
+        serial_number: String,
+        winner_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        disputes::resolve_serial_conflict(env, arbitrator, serial_number, winner_id, correlation_id)
+    }
+
+    /// Get the warranties found claiming a manufacturer's serial number
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `serial_number`: The serial number
+    ///
+    /// # Returns
+    /// The conflict and its ruling, or None if no conflict was found
+    pub fn get_serial_conflict(
+        env: Env,
+        manufacturer: String,
+        serial_number: String,
+    ) -> Option<SerialConflict> {
+        disputes::get_serial_conflict(env, manufacturer, serial_number)
+    }
+
+    /// Rate a manufacturer after a settled claim or an expired warranty (owner signs)
+    ///
+    /// For a claim, the claimant signs once it is `Resolved`. For a warranty,
+    /// the current owner signs once it has expired. Each subject can be
+    /// rated once, and an owner can submit feedback at most once every
+    /// `FEEDBACK_INTERVAL_SECS`. The scores are added to the manufacturer's
+    /// rating and published in a `FeedbackSubmitted` event.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `subject`: The claim or warranty the feedback is about
+    /// - `scores`: Scores from 1 to `MAX_FEEDBACK_SCORE`
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn submit_feedback(
+        env: Env,
+        subject: FeedbackSubject,
+        scores: FeedbackScores,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        feedback::submit_feedback(env, subject, scores, correlation_id)
+    }
+
+    /// Get the feedback totals of a manufacturer
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    ///
+    /// # Returns
+    /// The number of responses and the sum of each score; divide by
+    /// `responses` for the averages
+    pub fn get_manufacturer_rating(env: Env, manufacturer: String) -> ManufacturerRating {
+        feedback::get_manufacturer_rating(env, manufacturer)
+    }
+
+    /// Revoke a warranty (only owner can revoke)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to revoke
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn revoke_warranty(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::revoke_warranty(env, warranty_id, correlation_id)
+    }
+
+    /// Set warranty status to Active (only owner can set)
+    ///
+    /// An expired, revoked or suspended warranty cannot be set back to
+    /// Active by its owner; that takes `reactivate_warranty`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to set to active
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn set_to_active(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::set_to_active(env, warranty_id, correlation_id)
+    }
+
+    /// Start the coverage of a warranty registered with deferred activation
+    /// (only owner can activate, once)
+    ///
+    /// Coverage runs for the duration given at registration, counted from
+    /// now, so stock that sits on a shelf does not lose coverage time.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to activate
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn activate(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::activate(env, warranty_id, correlation_id)
+    }
+
+    /// Set warranty status to Expired (only owner can set)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to set to expired
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn set_to_expired(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::set_to_expired(env, warranty_id, correlation_id)
+    }
+
+    /// End a warranty early, e.g. because the product was returned (only
+    /// owner can cancel)
+    ///
+    /// Cancelled is final.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to cancel
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn cancel_warranty(
+        env: Env,
+        warranty_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::cancel_warranty(env, warranty_id, correlation_id)
+    }
+
+    /// Put a revoked, expired or suspended warranty back in force (issuing
+    /// manufacturer only)
+    ///
+    /// The manufacturer is the registered manufacturer that issued the
+    /// warranty or the namespace issuer of its manufacturer name.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to reactivate
+    /// - `manufacturer`: The warranty's manufacturer
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn reactivate_warranty(
+        env: Env,
+        warranty_id: u64,
+        manufacturer: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::reactivate_warranty(env, warranty_id, manufacturer, correlation_id)
+    }
+
+    /// Take a warranty out of force for now, e.g. while a subscription is
+    /// unpaid (issuing manufacturer only)
+    ///
+    /// `reactivate_warranty` puts it back in force.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to suspend
+    /// - `manufacturer`: The warranty's manufacturer
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn suspend_warranty(
+        env: Env,
+        warranty_id: u64,
+        manufacturer: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::suspend_warranty(env, warranty_id, manufacturer, correlation_id)
+    }
+
+    /// Mark a warranty as superseded by the warranty of a replacement
+    /// product (issuing manufacturer only)
+    ///
+    /// Replaced is final.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to mark
+    /// - `manufacturer`: The warranty's manufacturer
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn mark_replaced(
+        env: Env,
+        warranty_id: u64,
+        manufacturer: Address,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        warranty::mark_replaced(env, warranty_id, manufacturer, correlation_id)
+    }
+
+    /// Export a warranty as an XDR-encoded certificate
+    ///
+    /// Private warranties can only be exported by their owner.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to export
+    ///
+    /// # Returns
+    /// The XDR-encoded `WarrantyCertificate`
+    pub fn export_certificate(env: Env, warranty_id: u64) -> Result<Bytes, WarrantyError> {
+        attestation::export_certificate(env, warranty_id)
+    }
+
+    /// Check a certificate against the current warranty state
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `certificate`: Bytes produced by `export_certificate`
+    ///
+    /// # Returns
+    /// true if the certificate was issued by this contract and the warranty
+    /// has not changed since; bytes that are not valid XDR fail the call
+    pub fn verify_certificate(env: Env, certificate: Bytes) -> bool {
+        attestation::verify_certificate(env, certificate)
+    }
+
+    /// Report a warranty as a suspected counterfeit
+    ///
+    /// Each reporter may file `MAX_DAILY_REPORTS` reports per day.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `reporter`: The reporting address
+    /// - `warranty_id`: The suspected warranty ID
+    /// - `evidence_hash`: Hash of the off-chain evidence
+    ///
+    /// # Returns
+    /// The report ID
+    pub fn report_counterfeit(
+        env: Env,
+        reporter: Address,
+        warranty_id: u64,
+        evidence_hash: BytesN<32>,
+    ) -> Result<u64, WarrantyError> {
+        counterfeit::report_counterfeit(env, reporter, warranty_id, evidence_hash)
+    }
+
+    /// Get a counterfeit report by ID
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `report_id`: The report ID
+    ///
+    /// # Returns
+    /// The report or None if not found
+    pub fn get_counterfeit_report(env: Env, report_id: u64) -> Option<CounterfeitReport> {
+        counterfeit::get_counterfeit_report(env, report_id)
+    }
+
+    /// Get a page of the IDs of reports awaiting review, oldest first
+    ///
+    /// `limit` report IDs are examined per page, so a page can hold fewer
+    /// IDs than `limit`, or none, while `next` is still set.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of reports to examine
+    ///
+    /// # Returns
+    /// The page of pending report IDs and the cursor for the next page, if
+    /// any
+    pub fn get_pending_reports(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<WarrantyIdPage, WarrantyError> {
+        counterfeit::get_pending_reports(env, cursor, limit)
+    }
+
+    /// Confirm a counterfeit report, revoking the warranty (admin only)
+    ///
+    /// The revocation follows the usual status rules, so a warranty that
+    /// was already voided, cancelled, replaced or recalled cannot be
+    /// revoked this way.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `report_id`: The report ID
+    /// - `correlation_id`: Caller reference echoed into the outbox and audit log
+    ///
+    /// # Returns
+    /// What the call changed
+    pub fn confirm_counterfeit(
+        env: Env,
+        report_id: u64,
+        correlation_id: Option<BytesN<16>>,
+    ) -> Result<MutationReceipt, WarrantyError> {
+        counterfeit::confirm_counterfeit(env, report_id, correlation_id)
+    }
+
+    /// Dismiss a counterfeit report (admin only)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `report_id`: The report ID
+    pub fn dismiss_counterfeit(env: Env, report_id: u64) -> Result<(), WarrantyError> {
+        counterfeit::dismiss_counterfeit(env, report_id)
+    }
+
+    /// Get the track record of a counterfeit reporter
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `reporter`: The reporter address
+    ///
+    /// # Returns
+    /// Counts of confirmed and dismissed reports
+    pub fn get_reporter_reputation(env: Env, reporter: Address) -> ReporterReputation {
+        counterfeit::get_reporter_reputation(env, reporter)
+    }
+
+    /// Reconcile an owner's index with the warranty records in an ID range (admin only)
+    ///
+    /// Covers the owner's active, archived and co-owned warranty indexes.
+    /// The range is bounded by `max_batch_size`; rebuild a large deployment by
+    /// walking consecutive ranges.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner whose index is rebuilt
+    /// - `from_id`: First warranty ID of the range
+    /// - `to_id`: Last warranty ID of the range (inclusive)
+    ///
+    /// # Returns
+    /// Number of index entries added or removed
+    pub fn rebuild_owner_index(
+        env: Env,
+        owner: Address,
+        from_id: u64,
+        to_id: u64,
+    ) -> Result<u32, WarrantyError> {
+        keepers::rebuild_owner_index(env, owner, from_id, to_id)
+    }
+
+    /// Point missing or stale serial index entries at the warranty records
+    /// in an ID range (admin only)
+    ///
+    /// An entry is repaired when it is missing or names a warranty that no
+    /// longer holds the serial number; entries held by a live warranty are
+    /// left alone. The range is bounded by `max_batch_size`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `from_id`: First warranty ID of the range
+    /// - `to_id`: Last warranty ID of the range (inclusive)
+    ///
+    /// # Returns
+    /// Number of warranties whose serial entries were rewritten
+    pub fn rebuild_serial_index(env: Env, from_id: u64, to_id: u64) -> Result<u32, WarrantyError> {
+        keepers::rebuild_serial_index(env, from_id, to_id)
+    }
+
+    /// Move warranties stored in the old single-map layout to per-warranty
+    /// persistent entries (admin only)
+    ///
+    /// Contracts deployed before the storage redesign keep every warranty
+    /// in one instance-storage map. Until they are migrated those records
+    /// are still read from the map, and any write moves a record out of it.
+    /// Each call moves at most `limit` records, plus the owner and global
+    /// ID indexes listing them; repeat until it returns 0.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `limit`: Maximum number of records to move, up to `max_batch_size`
+    ///
+    /// # Returns
+    /// Number of records still in the old layout
+    pub fn migrate_storage(env: Env, limit: u32) -> Result<u32, WarrantyError> {
+        keepers::migrate_storage(env, limit)
+    }
+
+    /// Mark a single Active warranty as Expired once its coverage has run out
+    ///
+    /// Anyone may run this, e.g. a wallet before showing the warranty. It
+    /// stores the status that reads, claims and transfers already derive
+    /// from the expiration date, usage and link policy.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// True if the warranty was flipped, false if it was not due
+    pub fn expire_warranty(env: Env, warranty_id: u64) -> Result<bool, WarrantyError> {
+        keepers::expire_warranty(env, warranty_id)
+    }
+
+    /// Mark Active warranties past their expiration date as Expired
+    ///
+    /// Warranties over their usage limit or ended by their parent's link
+    /// policy are expired as well. Anyone may run this. The ID range is bounded by `max_batch_size` and at
+    /// most `limit` warranties are flipped per call, so a keeper can sweep the
+    /// whole deployment with predictable costs.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `from_id`: First warranty ID of the range
+    /// - `to_id`: Last warranty ID of the range (inclusive)
+    /// - `limit`: Maximum number of warranties to expire
+    ///
+    /// # Returns
+    /// Number of warranties marked as expired
+    pub fn expire_batch(
+        env: Env,
+        from_id: u64,
+        to_id: u64,
+        limit: u32,
+    ) -> Result<u32, WarrantyError> {
+        keepers::expire_batch(env, from_id, to_id, limit)
+    }
+
+    /// Mark due Active warranties among one page of the global ID index as
+    /// Expired
+    ///
+    /// Works as `expire_batch` over the IDs `get_warranty_ids_page` returns
+    /// for the same cursor and limit, so deterministic IDs are swept too. A
+    /// keeper pages through the deployment by feeding `next` back until it
+    /// is None. Anyone may run this and one `WarrantyExpired` event is
+    /// published per flipped warranty.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous sweep, or None to start
+    /// - `limit`: Number of IDs to scan, at most `max_batch_size`
+    ///
+    /// # Returns
+    /// Number of warranties marked as expired and the cursor to resume from
+    pub fn sweep_expired(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<SweepPage, WarrantyError> {
+        keepers::sweep_expired(env, cursor, limit)
+    }
+
+    /// Register or remove a keeper (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper address
+    /// - `active`: Whether the keeper earns fees for its tasks
+    pub fn set_keeper(env: Env, keeper: Address, active: bool) -> Result<(), WarrantyError> {
+        roles::set_keeper(env, keeper, active)
+    }
+
+    /// Check whether an address is a registered keeper
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The address to check
+    ///
+    /// # Returns
+    /// True if the admin registered the keeper
+    pub fn is_keeper(env: Env, keeper: Address) -> bool {
+        roles::is_keeper(env, keeper)
+    }
+
+    /// Set the token keeper rewards are paid in (admin only, or the council once enabled)
+    ///
+    /// Rewards are paid out of the keeper pool funded with
+    /// `fund_keeper_pool`, never out of claim pools. The token cannot be
+    /// changed while the keeper pool holds funds, failing with `InvalidInput`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `token`: The reward token contract
+    pub fn set_keeper_reward_token(env: Env, token: Address) -> Result<(), WarrantyError> {
+        keepers::set_keeper_reward_token(env, token)
+    }
+
+    /// Set the fee earned per unit of a keeper task (admin only, or the council once enabled)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `task`: The keeper task
+    /// - `fee`: Reward token amount per bump or per expired warranty
+    pub fn set_keeper_fee(env: Env, task: KeeperTask, fee: i128) -> Result<(), WarrantyError> {
+        keepers::set_keeper_fee(env, task, fee)
+    }
+
+    /// Get the fee earned per unit of a keeper task
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `task`: The keeper task
+    ///
+    /// # Returns
+    /// The fee, 0 if none is set
+    pub fn get_keeper_fee(env: Env, task: KeeperTask) -> i128 {
+        keepers::get_keeper_fee(env, task)
+    }
+
+    /// Keep a warranty on the ledger for longer (anyone can pay for it)
+    ///
+    /// Extends the warranty record, its owner's index and any scheduled
+    /// reversion so they stay live for at least `ledgers` more ledgers.
+    /// Reads and writes top entries up on their own, but a warranty nobody
+    /// touches for months needs this to outlast long coverage periods.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    /// - `ledgers`: Minimum remaining TTL, up to the network's maximum
+    ///
+    /// # Returns
+    /// The warranty's TTL status after the extension
+    pub fn extend_warranty_ttl(
+        env: Env,
+        warranty_id: u64,
+        ledgers: u32,
+    ) -> Result<TtlStatus, WarrantyError> {
+        keepers::extend_warranty_ttl(env, warranty_id, ledgers)
+    }
+
+    /// Get how long a warranty is known to stay on the ledger
+    ///
+    /// Wallets can warn owners when `expiring_soon` is set and call
+    /// `extend_warranty_ttl` before the record is archived.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The ledger the record stays live until at least
+    pub fn get_ttl_status(env: Env, warranty_id: u64) -> Result<TtlStatus, WarrantyError> {
+        keepers::get_ttl_status(env, warranty_id)
+    }
+
+    /// Set or clear the rent registrations pay for long coverage (admin only, or the council once enabled)
+    ///
+    /// Warranties registered before the terms changed keep the rent they
+    /// paid, in the token they paid it in.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `terms`: Rent token, free coverage length, daily rent and keeper fee, or None to charge no rent
+    pub fn set_rent_terms(env: Env, terms: Option<RentTerms>) -> Result<(), WarrantyError> {
+        keepers::set_rent_terms(env, terms)
+    }
+
+    /// Get the rent registrations pay for long coverage
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The rent terms, or None if registrations pay no rent
+    pub fn get_rent_terms(env: Env) -> Option<RentTerms> {
+        keepers::get_rent_terms(env)
+    }
+
+    /// Get the rent held for extending a warranty's TTL
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// Rent left, 0 if the warranty has none
+    pub fn get_rent_balance(env: Env, warranty_id: u64) -> i128 {
+        keepers::get_rent_balance(env, warranty_id)
+    }
+
+    /// Add to the rent held for extending a warranty's TTL
+    ///
+    /// Anyone can top up, in the token the warranty's rent was paid in, or
+    /// the current rent token if it has none yet.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `from`: Address paying the rent; must sign
+    /// - `warranty_id`: The warranty ID
+    /// - `amount`: Amount to add
+    ///
+    /// # Returns
+    /// The warranty's new rent balance
+    pub fn top_up_rent(
+        env: Env,
+        from: Address,
+        warranty_id: u64,
+        amount: i128,
+    ) -> Result<i128, WarrantyError> {
+        keepers::top_up_rent(env, from, warranty_id, amount)
+    }
+
+    /// Extend a warranty's TTL out of its rent (registered keepers only)
+    ///
+    /// Only records running low on TTL are extended, and the keeper is paid
+    /// the rent terms' bump fee from the warranty's rent. Records that are
+    /// not running low are left alone and cost nothing.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper
+    /// - `warranty_id`: The warranty ID
+    ///
+    /// # Returns
+    /// The ledger the record stays live until at least
+    pub fn keeper_extend_warranty_ttl(
+        env: Env,
+        keeper: Address,
+        warranty_id: u64,
+    ) -> Result<TtlStatus, WarrantyError> {
+        keepers::keeper_extend_warranty_ttl(env, keeper, warranty_id)
+    }
+
+    /// Extend the contract instance TTL to the maximum (registered keepers only)
+    ///
+    /// The bump is paid at most once every `KEEPER_BUMP_INTERVAL_LEDGERS`
+    /// ledgers; earlier bumps still extend the TTL but earn nothing.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper doing the bump
+    ///
+    /// # Returns
+    /// True if the bump earned a fee
+    pub fn keeper_bump_ttl(env: Env, keeper: Address) -> Result<bool, WarrantyError> {
+        keepers::keeper_bump_ttl(env, keeper)
+    }
+
+    /// Run `expire_batch` as a registered keeper, earning the sweep fee per
+    /// warranty expired
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper running the sweep
+    /// - `from_id`: First warranty ID of the range
+    /// - `to_id`: Last warranty ID of the range (inclusive)
+    /// - `limit`: Maximum number of warranties to expire
+    ///
+    /// # Returns
+    /// Number of warranties marked as expired
+    pub fn keeper_expire_batch(
+        env: Env,
+        keeper: Address,
+        from_id: u64,
+        to_id: u64,
+        limit: u32,
+    ) -> Result<u32, WarrantyError> {
+        keepers::keeper_expire_batch(env, keeper, from_id, to_id, limit)
+    }
+
+    /// Get the rewards a keeper has earned but not claimed
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper address
+    ///
+    /// # Returns
+    /// Unclaimed reward token amount
+    pub fn get_keeper_earnings(env: Env, keeper: Address) -> i128 {
+        keepers::get_keeper_earnings(env, keeper)
+    }
+
+    /// Pay out a keeper's unclaimed rewards (only the keeper can claim)
+    ///
+    /// Keepers removed from the registry can still claim what they earned.
+    /// The payout is debited from the keeper pool and fails with
+    /// `InsufficientFunds` if the pool holds less than the earnings.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper address
+    ///
+    /// # Returns
+    /// Amount transferred to the keeper
+    pub fn claim_keeper_rewards(env: Env, keeper: Address) -> Result<i128, WarrantyError> {
+        keepers::claim_keeper_rewards(env, keeper)
+    }
+
+    /// Add reward tokens to the pool keeper rewards are paid from (funder signs)
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `funder`: The address the tokens are transferred from
+    /// - `amount`: Reward token amount to add
+    ///
+    /// # Returns
+    /// The keeper pool balance after funding
+    pub fn fund_keeper_pool(
+        env: Env,
+        funder: Address,
+        amount: i128,
+    ) -> Result<i128, WarrantyError> {
+        keepers::fund_keeper_pool(env, funder, amount)
+    }
+
+    /// Get the reward tokens left for keeper payouts
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The keeper pool balance
+    pub fn get_keeper_pool(env: Env) -> i128 {
+        keepers::get_keeper_pool(env)
+    }
+
+    /// Move an owner's ended warranties out of their hot index (owner signs,
+    /// or a keeper through `keeper_compact_owner_index`)
+    ///
+    /// Expired, revoked, voided, cancelled and replaced warranties are
+    /// archived; pending, suspended and recalled ones can still come back
+    /// into force and stay. Each call examines at most `max_batch_size`
+    /// entries of the hot index after `cursor`. Reactivated warranties
+    /// return to the hot index.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner whose index is compacted
+    /// - `cursor`: Where to resume, or None to start from the beginning
+    ///
+    /// # Returns
+    /// Cursor for the next batch, or None once the whole index was examined
+    pub fn compact_owner_index(env: Env, owner: Address, cursor: Option<Cursor>) -> Option<Cursor> {
+        keepers::compact_owner_index(env, owner, cursor)
+    }
+
+    /// Run `compact_owner_index` as a registered keeper, earning the
+    /// compaction fee per warranty archived
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `keeper`: The keeper running the compaction
+    /// - `owner`: The owner whose index is compacted
+    /// - `cursor`: Where to resume, or None to start from the beginning
+    ///
+    /// # Returns
+    /// Cursor for the next batch, or None once the whole index was examined
+    pub fn keeper_compact_owner_index(
+        env: Env,
+        keeper: Address,
+        owner: Address,
+        cursor: Option<Cursor>,
+    ) -> Result<Option<Cursor>, WarrantyError> {
+        keepers::keeper_compact_owner_index(env, keeper, owner, cursor)
+    }
+
+    /// Get the warranty IDs moved out of an owner's hot index by compaction
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    ///
+    /// # Returns
+    /// Archived warranty IDs in ascending order
+    pub fn get_archived_warranties(env: Env, owner: Address) -> Vec<u64> {
+        queries::get_archived_warranties(env, owner)
+    }
+
+    /// Export a page of everything recorded about an owner's warranties (owner signs)
+    ///
+    /// Covers archived warranties too, in ascending ID order, with their
+    /// claims, transfers and service records, so the owner can take the
+    /// complete history to another app or deployment. Private warranties are
+    /// exported in full.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of warranties to export
+    ///
+    /// # Returns
+    /// The page of warranties and the cursor for the next page, if any
+    pub fn export_owner_data(
+        env: Env,
+        owner: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<OwnerExport, WarrantyError> {
+        queries::export_owner_data(env, owner, cursor, limit)
+    }
+
+    /// Get all warranty IDs for a specific owner, including the ones it
+    /// co-owns, in ascending order
+    ///
+    /// Private warranties are left out unless `viewer` may see them, as in
+    /// `get_warranty_details`; a viewer must sign.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    /// - `viewer`: The caller, or None to list public warranties only
+    ///
+    /// # Returns
+    /// Vector of warranty IDs owned or co-owned by the address
+    pub fn get_warranties_by_owner(env: Env, owner: Address, viewer: Option<Address>) -> Vec<u64> {
+        queries::get_warranties_by_owner(env, owner, viewer)
+    }
+
+    /// Get a page of warranty IDs for a specific owner, including the ones
+    /// it co-owns, in ascending order
+    ///
+    /// Private warranties are left out, so a page can hold fewer than
+    /// `limit` IDs while more remain.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of IDs to return
+    ///
+    /// # Returns
+    /// The page of IDs and the cursor for the next page, if any
+    pub fn get_warranties_by_owner_page(
+        env: Env,
+        owner: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<WarrantyIdPage, WarrantyError> {
+        queries::get_warranties_by_owner_page(env, owner, cursor, limit)
+    }
+
+    /// Get a page of the warranties registered for a production batch, e.g.
+    /// to notify owners of a recall
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Manufacturer name
+    /// - `batch_number`: Batch or lot number
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of IDs to return
+    ///
+    /// # Returns
+    /// The page of IDs and the cursor for the next page, if any
+    pub fn get_warranties_in_batch(
+        env: Env,
+        manufacturer: String,
+        batch_number: String,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<WarrantyIdPage, WarrantyError> {
+        queries::get_warranties_in_batch(env, manufacturer, batch_number, cursor, limit)
+    }
+
+    /// Get a page of the warranties registered for a catalog model
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `model_id`: The model number
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of IDs to return
+    ///
+    /// # Returns
+    /// The page of IDs and the cursor for the next page, if any
+    pub fn get_warranties_by_model(
+        env: Env,
+        model_id: String,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<WarrantyIdPage, WarrantyError> {
+        queries::get_warranties_by_model(env, model_id, cursor, limit)
+    }
+
+    /// Get a page of all registered warranty IDs
+    ///
+    /// The global index is kept in buckets of registrations, each in
+    /// ascending order, so sequentially assigned IDs come back ascending.
+    /// A page can hold fewer IDs than `limit` while `next` is still set.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of IDs to return
+    ///
+    /// # Returns
+    /// The page of IDs and the cursor for the next page, if any
+    pub fn get_warranty_ids_page(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<WarrantyIdPage, WarrantyError> {
+        queries::get_warranty_ids_page(env, cursor, limit)
+    }
+
+    /// Get a page of all registered warranties as full records, in the order
+    /// of `get_warranty_ids_page`
+    ///
+    /// `limit` IDs are examined per page. With a status filter, a page can
+    /// therefore hold fewer records, or none, while `next` is still set.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of warranties to examine
+    /// - `status`: Only return warranties in this status, or None for all
+    ///
+    /// # Returns
+    /// The page of records and the cursor for the next page, if any
+    pub fn get_warranties_page(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+        status: Option<WarrantyStatus>,
+    ) -> Result<WarrantyPage, WarrantyError> {
+        queries::get_warranties_page(env, cursor, limit, status)
+    }
+
+    /// Get the warranties in an ID range as full records, for off-chain
+    /// indexers
+    ///
+    /// IDs without a stored record are skipped, and private warranties are
+    /// returned as `get_warranty` shows them.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `start_id`: First warranty ID of the range
+    /// - `end_id`: Last warranty ID of the range (inclusive), at most
+    ///   `max_batch_size` IDs after `start_id`
+    ///
+    /// # Returns
+    /// The stored warranties in the range, in ascending ID order
+    pub fn export_range(
+        env: Env,
+        start_id: u64,
+        end_id: u64,
+    ) -> Result<Vec<WarrantyData>, WarrantyError> {
+        queries::export_range(env, start_id, end_id)
+    }
+
+    /// Get a page of an owner's warranties as full records, in ascending ID
+    /// order
+    ///
+    /// Filtered pages work as in `get_warranties_page`. Private warranties
+    /// are left out, as from `get_warranties_by_owner`.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `owner`: The owner address
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    /// - `limit`: Maximum number of warranties to examine
+    /// - `status`: Only return warranties in this status, or None for all
+    ///
+    /// # Returns
+    /// The page of records and the cursor for the next page, if any
+    pub fn get_owner_warranties_detailed(
+        env: Env,
+        owner: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+        status: Option<WarrantyStatus>,
+    ) -> Result<WarrantyPage, WarrantyError> {
+        queries::get_owner_warranties_detailed(env, owner, cursor, limit, status)
+    }
+
+    /// Read state changes from the outbox, oldest first
+    ///
+    /// Every change that notifies hooks is also appended here, for
+    /// integrators who poll instead of ingesting events.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    ///   at the oldest retained entry
+    /// - `limit`: Maximum number of entries to return
+    ///
+    /// # Returns
+    /// The page of entries and the cursor for the next page, if any
+    pub fn read_outbox(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<OutboxPage, WarrantyError> {
+        events::read_outbox(env, cursor, limit)
+    }
+
+    /// Read the audit log, oldest first
+    ///
+    /// The audit log holds every state change since deployment under
+    /// gapless sequence numbers, minus the oldest entries the admin pruned
+    /// once they were archived off-chain. Entries name the contract's address
+    /// as owner if the warranty was private when they were written or is now.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `cursor`: Cursor returned by the previous page, or None to start
+    ///   at the first entry
+    /// - `limit`: Maximum number of entries to return
+    ///
+    /// # Returns
+    /// The page of entries and the cursor for the next page, if any
+    pub fn read_audit_log(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<AuditPage, WarrantyError> {
+        events::read_audit_log(env, cursor, limit)
+    }
+
+    /// Drop the oldest outbox entries (admin only)
+    ///
+    /// At most `max_batch_size` entries are removed per call.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `up_to_seq`: Last sequence number to drop (inclusive)
+    ///
+    /// # Returns
+    /// Number of entries removed
+    pub fn prune_outbox(env: Env, up_to_seq: u64) -> Result<u32, WarrantyError> {
+        events::prune_outbox(env, up_to_seq)
+    }
+
+    /// Drop the oldest audit log entries once they are archived off-chain
+    /// (admin only)
+    ///
+    /// At most `max_batch_size` entries are removed per call. Later entries
+    /// keep their sequence numbers.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `up_to_seq`: Last sequence number to drop (inclusive)
+    ///
+    /// # Returns
+    /// Number of entries removed
+    pub fn prune_audit_log(env: Env, up_to_seq: u64) -> Result<u32, WarrantyError> {
+        events::prune_audit_log(env, up_to_seq)
+    }
+
+    /// Get total number of registered warranties
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Total warranty count
+    pub fn get_warranty_count(env: Env) -> u64 {
+        queries::get_warranty_count(env)
+    }
+
+    /// Get entry counts per storage family
+    ///
+    /// Owner indexes mirror the live warranty records, so their size follows
+    /// `live_warranties`. Claim counts start from the claims saved after
+    /// they were introduced.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Current storage statistics
+    pub fn get_storage_stats(env: Env) -> StorageStats {
+        queries::get_storage_stats(env)
+    }
+
+    /// Get a manufacturer's outstanding warranty liability
+    ///
+    /// The figures are kept up to date as warranties change status and
+    /// claims are filed, decided and paid, so finance teams need not scan
+    /// the records. Like `get_stats`, a lapsed warranty counts as Active
+    /// until its expiry is stored.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: The manufacturer name
+    ///
+    /// # Returns
+    /// Active warranties, open claims and the amounts pending settlement
+    pub fn get_open_obligations(env: Env, manufacturer: String) -> Obligations {
+        queries::get_open_obligations(env, manufacturer)
+    }
+
+    /// Get how well a manufacturer's claim pools and escrow cover its approved claims
+    ///
+    /// Liabilities are what approved claims it pays still ask for beyond
+    /// what was paid out. Collateral, including the shared escrow of its
+    /// consortium, is kept apart per token, and the best-funded token is
+    /// what counts against the liabilities. A `SolvencyWarning` event is
+    /// published when they first pass the solvency threshold.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `manufacturer`: Address paying the claims, a registered manufacturer or namespace issuer
+    ///
+    /// # Returns
+    /// Liabilities, collateral and their ratio against the threshold
+    pub fn get_solvency(env: Env, manufacturer: Address) -> SolvencyStatus {
+        queries::get_solvency(env, manufacturer)
+    }
+
+    /// Get warranty counts per status
+    ///
+    /// The counters are updated whenever a warranty is registered, changes
+    /// status or is removed, so dashboards need not scan the records.
+    /// Statuses only derived on read, such as a lapsed warranty nobody has
+    /// expired yet, are counted once they are stored.
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// The total, the Active, Expired and Revoked counts, and every non-zero
+    /// count by status
+    pub fn get_stats(env: Env) -> WarrantyStats {
+        queries::get_stats(env)
+    }
+
+    /// Summarize the contract for block explorers
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    ///
+    /// # Returns
+    /// Name, admin, configuration, counts and latest outbox sequence number
+    pub fn describe(env: Env) -> ContractDescription {
+        settings::describe(env)
+    }
+
+    /// Check if a warranty is expired by date, by usage or through its link
+    /// to a parent warranty
+    ///
+    /// # Arguments
+    /// - `env`: The environment
+    /// - `warranty_id`: The warranty ID to check
+    ///
+    /// # Returns
+    /// true if warranty is expired
+    pub fn is_warranty_expired(env: Env, warranty_id: u64) -> Result<bool, WarrantyError> {
+        queries::is_warranty_expired(env, warranty_id)
     }
 }
 
 mod test;
+mod test_auth;
+mod test_events;
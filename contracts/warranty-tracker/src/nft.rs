@@ -0,0 +1,173 @@
+//! Non-fungible token view of warranties for NFT-aware wallets and marketplaces.
+use soroban_sdk::{contractevent, contracttype, symbol_short, Address, Env, String};
+
+use super::*;
+
+#[contracttype]
+pub enum NftKey {
+    /// Prefix the token ID is appended to in `token_uri`
+    BaseUri,
+}
+
+/// Standard NFT event for a change of ownership, published alongside
+/// `WarrantyTransferred`
+#[contractevent(topics = ["transfer"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u64,
+}
+
+/// Standard NFT event for a newly registered warranty
+#[contractevent(topics = ["mint"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub token_id: u64,
+}
+
+/// Standard NFT event for a removed warranty
+#[contractevent(topics = ["burn"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub token_id: u64,
+}
+
+pub(crate) fn token_name(env: &Env) -> String {
+    String::from_str(env, TOKEN_NAME)
+}
+
+pub(crate) fn token_symbol(env: &Env) -> String {
+    String::from_str(env, TOKEN_SYMBOL)
+}
+
+/// Number of public warranties `owner` holds, archived ones included.
+pub(crate) fn token_balance(env: &Env, owner: &Address) -> u32 {
+    visible_ids(
+        env,
+        read_index(env, &DataKey::OwnerWarranties(owner.clone())),
+        None,
+    )
+    .len()
+        + visible_ids(
+            env,
+            read_index(env, &DataKey::ArchivedOwnerWarranties(owner.clone())),
+            None,
+        )
+        .len()
+}
+
+/// The configured base URI followed by the decimal token ID, or an empty
+/// string while no base URI is set.
+pub(crate) fn build_token_uri(env: &Env, token_id: u64) -> String {
+    let Some(base_uri) = env.storage().instance().get::<_, String>(&NftKey::BaseUri) else {
+        return String::from_str(env, "");
+    };
+
+    let mut digits = [0u8; 20];
+    let mut start = digits.len();
+    let mut rest = token_id;
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+
+    let base_len = base_uri.len() as usize;
+    let len = base_len + digits.len() - start;
+    let mut buffer = [0u8; MAX_BASE_URI_LEN as usize + 20];
+    base_uri.copy_into_slice(&mut buffer[..base_len]);
+    buffer[base_len..len].copy_from_slice(&digits[start..]);
+    String::from_bytes(env, &buffer[..len])
+}
+
+pub(crate) fn publish_mint(env: &Env, to: &Address, token_id: u64) {
+    Mint {
+        to: to.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+/// Publish a change of owner, unless the warranty is private.
+pub(crate) fn publish_nft_transfer(env: &Env, from: &Address, to: &Address, token_id: u64) {
+    if is_private(env, token_id) {
+        return;
+    }
+    Transfer {
+        from: from.clone(),
+        to: to.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+/// Publish the removal of `warranty`, unless it was private.
+pub(crate) fn publish_burn(env: &Env, warranty: &WarrantyData) {
+    if warranty.private {
+        return;
+    }
+    Burn {
+        from: warranty.owner.clone(),
+        token_id: warranty.id,
+    }
+    .publish(env);
+}
+
+pub(crate) fn name(env: Env) -> String {
+    token_name(&env)
+}
+
+pub(crate) fn symbol(env: Env) -> String {
+    token_symbol(&env)
+}
+
+pub(crate) fn balance_of(env: Env, owner: Address) -> u32 {
+    token_balance(&env, &owner)
+}
+
+pub(crate) fn owner_of(env: Env, token_id: u64) -> Result<Address, WarrantyError> {
+    Ok(public_view(&env, require_warranty(&env, token_id)?).owner)
+}
+
+pub(crate) fn token_uri(env: Env, token_id: u64) -> Result<String, WarrantyError> {
+    require_warranty(&env, token_id)?;
+    Ok(build_token_uri(&env, token_id))
+}
+
+pub(crate) fn set_token_base_uri(env: Env, base_uri: String) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetTokenBaseUri(base_uri))
+}
+
+pub(crate) fn transfer(
+    env: Env,
+    from: Address,
+    to: Address,
+    token_id: u64,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    from.require_auth();
+    let mut warranty = require_warranty(&env, token_id)?;
+    if warranty.owner != from {
+        return Err(WarrantyError::Unauthorized);
+    }
+    record_activity(&env, &from);
+
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &to)?;
+    change_owner(&env, &from, warranty, &to, None)?;
+
+    notify_change(&env, symbol_short!("transfer"), token_id, None);
+    Ok(())
+}
@@ -0,0 +1,515 @@
+//! Read-only lookups, paginated listings and exports of warranty records.
+use soroban_sdk::{Address, Env, Map, String, Symbol, Vec};
+
+use super::*;
+
+pub(crate) fn get_warranty_by_product(
+    env: Env,
+    manufacturer: String,
+    serial_number: String,
+) -> Option<WarrantyData> {
+    let warranty_id = derive_warranty_id(&env, &manufacturer, &serial_number);
+    load_warranty(&env, warranty_id)
+        .filter(|warranty| {
+            warranty.manufacturer == manufacturer && warranty.serial_number == serial_number
+        })
+        .map(|warranty| public_view(&env, warranty))
+}
+
+pub(crate) fn get_warranty_by_serial(
+    env: Env,
+    manufacturer: String,
+    serial_number: String,
+) -> Option<WarrantyData> {
+    find_by_serial(&env, &manufacturer, &serial_number).map(|warranty| public_view(&env, warranty))
+}
+
+pub(crate) fn verify(env: Env, warranty_id: u64) -> VerificationResult {
+    verification(&env, load_warranty(&env, warranty_id))
+}
+
+pub(crate) fn verify_by_serial(
+    env: Env,
+    manufacturer: String,
+    serial_number: String,
+) -> VerificationResult {
+    verification(&env, find_by_serial(&env, &manufacturer, &serial_number))
+}
+
+pub(crate) fn get_warranty_ns(
+    env: Env,
+    manufacturer: String,
+    local_id: u64,
+) -> Option<WarrantyData> {
+    let warranty_id: u64 = read_record(
+        &env,
+        &NamespaceKey::Warranty(manufacturer.clone(), local_id),
+    )?;
+    load_warranty(&env, warranty_id)
+        .filter(|warranty| warranty.manufacturer == manufacturer)
+        .map(|warranty| public_view(&env, warranty))
+}
+
+pub(crate) fn get_warranty(env: Env, warranty_id: u64) -> Option<WarrantyData> {
+    load_warranty(&env, warranty_id)
+        .map(|warranty| public_view(&env, with_link_policy(&env, warranty)))
+}
+
+pub(crate) fn get_warranty_details(
+    env: Env,
+    warranty_id: u64,
+    viewer: Address,
+) -> Option<WarrantyData> {
+    viewer.require_auth();
+
+    let warranty = with_link_policy(&env, load_warranty(&env, warranty_id)?);
+    if can_view(&env, &viewer, &warranty) {
+        return Some(warranty);
+    }
+    Some(public_view(&env, warranty))
+}
+
+pub(crate) fn get_viewer_grants(env: Env, warranty_id: u64) -> Map<Address, u64> {
+    read_record(&env, &DataKey::ViewerGrants(warranty_id)).unwrap_or(Map::new(&env))
+}
+
+pub(crate) fn get_product_name(
+    env: Env,
+    warranty_id: u64,
+    lang: Symbol,
+) -> Result<String, WarrantyError> {
+    let warranty = get_warranty(env.clone(), warranty_id).ok_or(WarrantyError::NotFound)?;
+    Ok(warranty
+        .localized_names
+        .get(lang)
+        .unwrap_or(warranty.product_name))
+}
+
+pub(crate) fn get_warranty_record(env: Env, warranty_id: u64) -> WarrantyRecord {
+    if let Some(warranty) = get_warranty(env.clone(), warranty_id) {
+        return WarrantyRecord::Live(warranty);
+    }
+    match read_record(&env, &DataKey::Tombstone(warranty_id)) {
+        Some(tombstone) => WarrantyRecord::Removed(tombstone),
+        None => WarrantyRecord::NotFound,
+    }
+}
+
+pub(crate) fn get_warranty_ref(env: Env, warranty_id: u64) -> Option<WarrantyRef> {
+    load_warranty(&env, warranty_id)?;
+    Some(WarrantyRef::new(
+        env.current_contract_address(),
+        warranty_id,
+    ))
+}
+
+pub(crate) fn get_warranty_summary(env: Env, warranty_id: u64) -> Option<WarrantySummary> {
+    let warranty = get_warranty(env.clone(), warranty_id)?;
+    Some(WarrantySummary {
+        warranty_ref: WarrantyRef::new(env.current_contract_address(), warranty_id),
+        owner: warranty.owner,
+        product_name: warranty.product_name,
+        manufacturer: warranty.manufacturer,
+        status: warranty.status,
+        expiration_date: warranty.expiration_date,
+        transferable: warranty.transferable,
+        claims_open_at: claim_cooldown_until(&env, warranty_id),
+    })
+}
+
+pub(crate) fn resolve_warranty_ref(env: Env, warranty_ref: WarrantyRef) -> Option<WarrantySummary> {
+    if warranty_ref.contract == env.current_contract_address() {
+        return get_warranty_summary(env, warranty_ref.id);
+    }
+    warranty_ref.resolve(&env)
+}
+
+pub(crate) fn get_allowed_actions(env: Env, warranty_id: u64, actor: Address) -> Vec<Symbol> {
+    let mut actions = Vec::new(&env);
+    let Some(warranty) = load_warranty(&env, warranty_id) else {
+        return actions;
+    };
+    let now = env.ledger().timestamp();
+
+    if actor == warranty.owner {
+        if warranty.status == WarrantyStatus::Active && warranty.transferable {
+            actions.push_back(Symbol::new(&env, "transfer_ownership"));
+            actions.push_back(Symbol::new(&env, "initiate_transfer"));
+            actions.push_back(Symbol::new(&env, "initiate_handover"));
+        }
+        if pending_transfer(&env, warranty_id).is_some() {
+            actions.push_back(Symbol::new(&env, "cancel_transfer"));
+        }
+        if has_record(&env, &DataKey::PendingHandover(warranty_id)) {
+            actions.push_back(Symbol::new(&env, "cancel_handover"));
+        }
+        for (name, target) in [
+            ("set_to_active", WarrantyStatus::Active),
+            ("set_to_expired", WarrantyStatus::Expired),
+            ("revoke_warranty", WarrantyStatus::Revoked),
+            ("cancel_warranty", WarrantyStatus::Cancelled),
+        ] {
+            if status_transition_allowed(&warranty.status, &target, StatusAuthority::Owner) {
+                actions.push_back(Symbol::new(&env, name));
+            }
+        }
+        for name in [
+            "set_visibility",
+            "grant_viewer",
+            "set_beneficiary",
+            "attach_policy",
+            "add_co_owner",
+        ] {
+            actions.push_back(Symbol::new(&env, name));
+        }
+        if !warranty.co_owners.is_empty() {
+            actions.push_back(Symbol::new(&env, "remove_co_owner"));
+        }
+        if !get_viewer_grants(env.clone(), warranty_id).is_empty() {
+            actions.push_back(Symbol::new(&env, "revoke_viewer"));
+        }
+        if has_record(&env, &DataKey::Beneficiary(warranty_id)) {
+            actions.push_back(Symbol::new(&env, "remove_beneficiary"));
+        }
+        if has_record(&env, &DataKey::AttachedPolicy(warranty_id)) {
+            actions.push_back(Symbol::new(&env, "detach_policy"));
+        }
+        if warranty.owner == warranty.registrant
+            && warranty.claims_filed == 0
+            && !has_record(&env, &AmendKey::Locked(warranty_id))
+            && now
+                <= warranty
+                    .created_at
+                    .saturating_add(settings::get_edit_window_secs(env.clone()))
+        {
+            actions.push_back(Symbol::new(&env, "amend_warranty"));
+        }
+    }
+
+    if is_warranty_manufacturer(&env, &actor, &warranty) {
+        for (name, target) in [
+            ("reactivate_warranty", WarrantyStatus::Active),
+            ("suspend_warranty", WarrantyStatus::Suspended),
+            ("mark_replaced", WarrantyStatus::Replaced),
+        ] {
+            if status_transition_allowed(&warranty.status, &target, StatusAuthority::Manufacturer) {
+                actions.push_back(Symbol::new(&env, name));
+            }
+        }
+    }
+
+    if actor == warranty.registrant {
+        let cooling_off_secs = settings::get_cooling_off_secs(env.clone());
+        if cooling_off_secs > 0 && now <= warranty.created_at.saturating_add(cooling_off_secs) {
+            actions.push_back(Symbol::new(&env, "delete_warranty"));
+        }
+    }
+
+    if actor != warranty.owner
+        && (roles::get_approved(env.clone(), warranty_id).as_ref() == Some(&actor)
+            || roles::is_operator(env.clone(), warranty.owner.clone(), actor.clone()))
+    {
+        if warranty.status == WarrantyStatus::Active && warranty.transferable {
+            actions.push_back(Symbol::new(&env, "agent_transfer_ownership"));
+        }
+        actions.push_back(Symbol::new(&env, "agent_update_status"));
+    }
+
+    if let Some(pending) = transfers::get_pending_transfer(env.clone(), warranty_id) {
+        if pending.to == actor {
+            actions.push_back(Symbol::new(&env, "decline_transfer"));
+            if pending.from == warranty.owner
+                && warranty.status == WarrantyStatus::Active
+                && warranty.transferable
+            {
+                actions.push_back(Symbol::new(&env, "accept_transfer"));
+            }
+        }
+    }
+
+    if let Some(handover) = transfers::get_pending_handover(env.clone(), warranty_id) {
+        if handover.buyer == actor
+            && handover.seller == warranty.owner
+            && warranty.status == WarrantyStatus::Active
+            && warranty.transferable
+        {
+            actions.push_back(Symbol::new(&env, "accept_handover"));
+        }
+    }
+
+    if let Some(beneficiary) = transfers::get_beneficiary(env.clone(), warranty_id) {
+        let last_activity = transfers::get_last_activity(env.clone(), warranty.owner.clone())
+            .unwrap_or(warranty.created_at);
+        if beneficiary.beneficiary == actor
+            && now >= last_activity.saturating_add(beneficiary.inactivity_secs)
+        {
+            actions.push_back(Symbol::new(&env, "claim_inherited"));
+        }
+    }
+
+    actions
+}
+
+pub(crate) fn get_warranty_history(
+    env: Env,
+    warranty_id: u64,
+    start: u32,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, WarrantyError> {
+    check_page_size(&env, limit)?;
+    if is_private(&env, warranty_id) {
+        return Ok(Vec::new(&env));
+    }
+
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&HistoryKey::Count(warranty_id))
+        .unwrap_or(0);
+    let mut entries = Vec::new(&env);
+    for index in start..count.min(start.saturating_add(limit)) {
+        if let Some(entry) = env
+            .storage()
+            .persistent()
+            .get(&HistoryKey::Entry(warranty_id, index))
+        {
+            entries.push_back(entry);
+        }
+    }
+    Ok(entries)
+}
+
+pub(crate) fn get_metadata(
+    env: Env,
+    warranty_id: u64,
+) -> Result<Map<Symbol, String>, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+    if warranty.private {
+        return Ok(Map::new(&env));
+    }
+    Ok(env
+        .storage()
+        .persistent()
+        .get(&MetadataKey::Entries(warranty_id))
+        .unwrap_or(Map::new(&env)))
+}
+
+pub(crate) fn get_archived_warranties(env: Env, owner: Address) -> Vec<u64> {
+    read_index(&env, &DataKey::ArchivedOwnerWarranties(owner))
+}
+
+pub(crate) fn export_owner_data(
+    env: Env,
+    owner: Address,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<OwnerExport, WarrantyError> {
+    owner.require_auth();
+    check_page_size(&env, limit)?;
+
+    let mut warranty_ids = read_index(&env, &DataKey::OwnerWarranties(owner.clone()));
+    for warranty_id in read_index(&env, &DataKey::ArchivedOwnerWarranties(owner)).iter() {
+        insert_sorted(&mut warranty_ids, warranty_id);
+    }
+
+    let page = page_ids(&warranty_ids, cursor, limit);
+    let mut warranties = Vec::new(&env);
+    for warranty_id in page.ids.iter() {
+        let Some(warranty) = load_warranty(&env, warranty_id) else {
+            continue;
+        };
+        warranties.push_back(WarrantyExport {
+            warranty,
+            claims: claims::get_claims(env.clone(), warranty_id)?,
+            transfers: read_record(&env, &DataKey::TransferHistory(warranty_id))
+                .unwrap_or(Vec::new(&env)),
+            service_records: claims::get_service_history(env.clone(), warranty_id),
+        });
+    }
+    Ok(OwnerExport {
+        warranties,
+        next: page.next,
+    })
+}
+
+pub(crate) fn get_warranties_by_owner(
+    env: Env,
+    owner: Address,
+    viewer: Option<Address>,
+) -> Vec<u64> {
+    if let Some(viewer) = &viewer {
+        viewer.require_auth();
+    }
+    visible_ids(&env, held_warranties(&env, &owner), viewer.as_ref())
+}
+
+pub(crate) fn get_warranties_by_owner_page(
+    env: Env,
+    owner: Address,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<WarrantyIdPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let owner_warranties = held_warranties(&env, &owner);
+    let page = page_ids(&owner_warranties, cursor, limit);
+    Ok(WarrantyIdPage {
+        ids: visible_ids(&env, page.ids, None),
+        next: page.next,
+    })
+}
+
+pub(crate) fn get_warranties_in_batch(
+    env: Env,
+    manufacturer: String,
+    batch_number: String,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<WarrantyIdPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let batch = read_index(&env, &DataKey::BatchWarranties(manufacturer, batch_number));
+    Ok(page_ids(&batch, cursor, limit))
+}
+
+pub(crate) fn get_warranties_by_model(
+    env: Env,
+    model_id: String,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<WarrantyIdPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let model = read_index(&env, &DataKey::ModelWarranties(model_id));
+    Ok(page_ids(&model, cursor, limit))
+}
+
+pub(crate) fn get_warranty_ids_page(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+) -> Result<WarrantyIdPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    Ok(id_index_page(&env, cursor, limit))
+}
+
+pub(crate) fn get_warranties_page(
+    env: Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+    status: Option<WarrantyStatus>,
+) -> Result<WarrantyPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    Ok(page_records(
+        &env,
+        id_index_page(&env, cursor, limit),
+        status,
+    ))
+}
+
+pub(crate) fn export_range(
+    env: Env,
+    start_id: u64,
+    end_id: u64,
+) -> Result<Vec<WarrantyData>, WarrantyError> {
+    check_batch_range(&env, start_id, end_id)?;
+
+    let mut warranties = Vec::new(&env);
+    for warranty_id in start_id..=end_id {
+        if let Some(warranty) = get_warranty(env.clone(), warranty_id) {
+            warranties.push_back(warranty);
+        }
+    }
+    Ok(warranties)
+}
+
+pub(crate) fn get_owner_warranties_detailed(
+    env: Env,
+    owner: Address,
+    cursor: Option<Cursor>,
+    limit: u32,
+    status: Option<WarrantyStatus>,
+) -> Result<WarrantyPage, WarrantyError> {
+    check_page_size(&env, limit)?;
+
+    let owner_warranties = read_index(&env, &DataKey::OwnerWarranties(owner));
+    let page = page_ids(&owner_warranties, cursor, limit);
+    Ok(page_records(
+        &env,
+        WarrantyIdPage {
+            ids: visible_ids(&env, page.ids, None),
+            next: page.next,
+        },
+        status,
+    ))
+}
+
+pub(crate) fn get_warranty_count(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::WarrantyCount)
+        .unwrap_or(0)
+}
+
+pub(crate) fn get_storage_stats(env: Env) -> StorageStats {
+    let storage = env.storage().instance();
+    let registered: u64 = storage.get(&DataKey::WarrantyCount).unwrap_or(0);
+    // Every stored record is listed in the global ID index
+    let id_index_entries = id_index_len(&env);
+    let live_warranties = id_index_entries;
+    let hooks = storage
+        .get::<_, Vec<Address>>(&DataKey::Hooks)
+        .map(|hooks| hooks.len())
+        .unwrap_or(0);
+    let outbox_head: u64 = storage.get(&DataKey::OutboxHead).unwrap_or(1);
+    let outbox_tail: u64 = storage.get(&DataKey::OutboxTail).unwrap_or(0);
+
+    StorageStats {
+        registered,
+        live_warranties,
+        removed_warranties: registered.saturating_sub(live_warranties as u64),
+        id_index_entries,
+        counterfeit_reports: report_count(&env),
+        pending_reports: pending_report_count(&env),
+        hooks,
+        outbox_entries: (outbox_tail + 1).saturating_sub(outbox_head),
+        stored_claims: storage.get(&ClaimKey::Stored).unwrap_or(0),
+        open_claims: storage.get(&ClaimKey::OpenCount).unwrap_or(0),
+    }
+}
+
+pub(crate) fn get_stats(env: Env) -> WarrantyStats {
+    warranty_stats(&env)
+}
+
+pub(crate) fn get_open_obligations(env: Env, manufacturer: String) -> Obligations {
+    Obligations {
+        active_warranties: read_record(
+            &env,
+            &ObligationKey::ActiveWarranties(manufacturer.clone()),
+        )
+        .unwrap_or(0),
+        open_claims: read_record(&env, &ObligationKey::OpenClaims(manufacturer.clone()))
+            .unwrap_or(0),
+        pending_settlement_amounts: read_record(
+            &env,
+            &ObligationKey::PendingSettlement(manufacturer),
+        )
+        .unwrap_or(0),
+    }
+}
+
+pub(crate) fn get_solvency(env: Env, manufacturer: Address) -> SolvencyStatus {
+    solvency(&env, &manufacturer)
+}
+
+pub(crate) fn is_warranty_expired(env: Env, warranty_id: u64) -> Result<bool, WarrantyError> {
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let current_time = env.ledger().timestamp();
+    Ok(warranty.expiration_date < current_time
+        || usage_exceeded(&warranty)
+        || ended_with_parent(&env, &warranty))
+}
@@ -0,0 +1,1094 @@
+//! Admin, council, keeper and manufacturer role checks, and compliance
+//! screening.
+use soroban_sdk::{
+    contractclient, contracttype, symbol_short, Address, BytesN, Env, IntoVal, String, Val, Vec,
+};
+
+use super::*;
+
+/// Maintenance jobs keepers are paid for
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeeperTask {
+    /// Extending the contract instance TTL, paid per bump
+    TtlBump,
+    /// Marking overdue warranties as expired, paid per warranty
+    ExpirySweep,
+    /// Moving ended warranties out of owner indexes, paid per warranty
+    /// archived
+    IndexCompaction,
+}
+
+/// Manufacturer whose identity the admin verified
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Manufacturer {
+    pub address: Address,
+    /// Name written into the warranties it issues
+    pub name: String,
+    pub registered_at: u64,
+}
+
+/// Subsidiary a manufacturer lets issue and revoke warranties in its name
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerDelegate {
+    /// Region whose warranties the delegate issues and revokes
+    pub region: String,
+    pub added_at: u64,
+}
+
+/// Why and when a manufacturer pulled a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecallInfo {
+    /// Manufacturer address that recalled or voided the warranty
+    pub manufacturer: Address,
+    /// Recalled or Voided
+    pub status: WarrantyStatus,
+    /// Set when the warranty was voided
+    pub reason: Option<String>,
+    pub recorded_at: u64,
+}
+
+/// Interface screening contracts implement to vet the parties of transfers
+/// and payments, e.g. against sanctions lists
+#[contractclient(name = "ComplianceClient")]
+pub trait ComplianceInterface {
+    /// Whether value may move from `from` to `to`
+    fn check_allowed(env: Env, from: Address, to: Address) -> bool;
+}
+
+/// Addresses that jointly take over the admin's most sensitive powers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Council {
+    pub members: Vec<Address>,
+    /// Approvals a proposal needs before it is carried out
+    pub threshold: u32,
+}
+
+/// Change a council proposal carries out once approved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CouncilAction {
+    Upgrade(BytesN<32>),
+    SetAdmin(Address),
+    RegisterManufacturer(Address, String),
+    RemoveManufacturer(Address),
+    SetNamespaceIssuer(String, Option<Address>),
+    SetComplianceHook(Option<Address>),
+    SetReadLimits(ReadLimits),
+    /// Change one of the admin-tunable settings
+    SetConfig(ConfigChange),
+    /// Replace the council; no members dissolves it and hands its powers
+    /// back to the admin
+    SetCouncil(Vec<Address>, u32),
+}
+
+/// Admin-tunable setting, changed by its setter or a `SetConfig` proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigChange {
+    AddHook(Address),
+    RemoveHook(Address),
+    Pause,
+    Unpause,
+    SetCapacity(Option<u64>),
+    SetRegistrationLimits(RegistrationLimits),
+    SetPurchaseDateSkew(u64),
+    SetMaxPurchaseAge(String, Option<u64>),
+    SetUniquenessRule(String, UniquenessRule),
+    /// Manufacturer, category and `(age_months, retained_bps)` points
+    SetDepreciationCurve(String, String, Vec<(u32, u32)>),
+    SetEventVerbosity(EventVerbosity),
+    SetAnalyticsEnabled(bool),
+    /// Region, optional category and minimum duration
+    SetStatutoryMinimum(String, Option<String>, Option<u64>),
+    SetCoolingOffSecs(u64),
+    SetEditWindowSecs(u64),
+    SetClaimWindowSecs(u64),
+    SetTransferClaimCooldownSecs(u64),
+    SetClaimLimit(String, ClaimLimit),
+    ClearClaimLimit(String),
+    SetKeeper(Address, bool),
+    SetKeeperFee(KeeperTask, i128),
+    SetKeeperRewardToken(Address),
+    SetServiceCenter(Address, bool),
+    SetLogisticsProvider(Address, bool),
+    SetUnderwriter(Address, bool),
+    SetArbitrator(Address, bool),
+    SetDisputeQuorum(u32),
+    SetDisputeFee(DisputeFee),
+    ClearDisputeFee,
+    SetPriceOracle(Option<Address>),
+    SetRentTerms(RentTerms),
+    SetSolvencyThresholdBps(u32),
+    SetVelocityThresholds(VelocityThresholds),
+    ClearVelocityThresholds,
+    ClearRentTerms,
+    SetTokenBaseUri(String),
+}
+
+/// A council action waiting for approvals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub proposer: Address,
+    pub action: CouncilAction,
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub executed: bool,
+}
+
+/// Load the admin and require its authorization.
+pub(crate) fn require_admin(env: &Env) -> Result<Address, WarrantyError> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(WarrantyError::NotInitialized)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+/// Require the admin's authorization for a change the council takes over
+/// once one is enabled; while it governs, only a proposal can make it.
+pub(crate) fn require_sole_admin(env: &Env) -> Result<Address, WarrantyError> {
+    if env.storage().instance().has(&CouncilKey::Council) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    require_admin(env)
+}
+
+/// Require a council member's authorization.
+pub(crate) fn require_council_member(
+    env: &Env,
+    member: &Address,
+) -> Result<Council, WarrantyError> {
+    member.require_auth();
+    env.storage()
+        .instance()
+        .get::<_, Council>(&CouncilKey::Council)
+        .filter(|council| council.members.contains(member))
+        .ok_or(WarrantyError::Unauthorized)
+}
+
+/// Reject a council whose threshold cannot be met or whose members repeat.
+pub(crate) fn check_council(members: &Vec<Address>, threshold: u32) -> Result<(), WarrantyError> {
+    if members.len() > MAX_COUNCIL_SIZE {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    if threshold == 0 || threshold > members.len() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    for (i, member) in members.iter().enumerate() {
+        if members.first_index_of(&member) != Some(i as u32) {
+            return Err(WarrantyError::InvalidInput);
+        }
+    }
+    Ok(())
+}
+
+/// Reject a proposal whose action could never be carried out, so it does
+/// not collect approvals in vain.
+pub(crate) fn check_action(action: &CouncilAction) -> Result<(), WarrantyError> {
+    match action {
+        CouncilAction::RegisterManufacturer(_, name) if name.is_empty() => {
+            Err(WarrantyError::InvalidInput)
+        }
+        CouncilAction::SetReadLimits(limits)
+            if limits.max_page_size == 0 || limits.max_batch_size == 0 =>
+        {
+            Err(WarrantyError::InvalidLimit)
+        }
+        CouncilAction::SetConfig(change) => check_config(change),
+        CouncilAction::SetCouncil(members, threshold) if !members.is_empty() => {
+            check_council(members, *threshold)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reject a setting that is invalid whatever the contract's state.
+pub(crate) fn check_config(change: &ConfigChange) -> Result<(), WarrantyError> {
+    match change {
+        ConfigChange::SetRegistrationLimits(limits)
+            if limits.per_owner_daily == Some(0) || limits.global_daily == Some(0) =>
+        {
+            Err(WarrantyError::InvalidLimit)
+        }
+        ConfigChange::SetDepreciationCurve(_, _, curve) => {
+            let mut previous: Option<(u32, u32)> = None;
+            for (age_months, retained_bps) in curve.iter() {
+                if retained_bps > 10_000 {
+                    return Err(WarrantyError::InvalidInput);
+                }
+                if let Some((prev_age, prev_bps)) = previous {
+                    if age_months <= prev_age || retained_bps > prev_bps {
+                        return Err(WarrantyError::InvalidInput);
+                    }
+                }
+                previous = Some((age_months, retained_bps));
+            }
+            Ok(())
+        }
+        ConfigChange::SetClaimLimit(_, limit)
+            if limit.max_claims == 0 || limit.period_secs == 0 =>
+        {
+            Err(WarrantyError::InvalidInput)
+        }
+        ConfigChange::SetKeeperFee(_, fee) if *fee < 0 => Err(WarrantyError::InvalidInput),
+        ConfigChange::SetDisputeQuorum(0) => Err(WarrantyError::InvalidInput),
+        ConfigChange::SetDisputeFee(fee) if fee.amount <= 0 => Err(WarrantyError::InvalidInput),
+        ConfigChange::SetSolvencyThresholdBps(0) => Err(WarrantyError::InvalidInput),
+        ConfigChange::SetVelocityThresholds(thresholds)
+            if thresholds.max_claims_30d == 0 || thresholds.max_claims_90d == 0 =>
+        {
+            Err(WarrantyError::InvalidInput)
+        }
+        ConfigChange::SetRentTerms(terms) if terms.daily_rent <= 0 || terms.bump_fee < 0 => {
+            Err(WarrantyError::InvalidInput)
+        }
+        ConfigChange::SetDisputeQuorum(quorum) if *quorum > MAX_DISPUTE_QUORUM => {
+            Err(WarrantyError::LimitExceeded)
+        }
+        ConfigChange::SetTokenBaseUri(base_uri) if base_uri.len() > MAX_BASE_URI_LEN => {
+            Err(WarrantyError::InvalidInput)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validate and store a setting; callers check who may change it.
+pub(crate) fn apply_config(env: &Env, change: ConfigChange) -> Result<(), WarrantyError> {
+    check_config(&change)?;
+    let storage = env.storage().instance();
+    match change {
+        ConfigChange::AddHook(hook) => {
+            let mut hooks: Vec<Address> = storage.get(&DataKey::Hooks).unwrap_or(Vec::new(env));
+            if hooks.contains(&hook) {
+                return Err(WarrantyError::AlreadyExists);
+            }
+            if hooks.len() >= MAX_HOOKS {
+                return Err(WarrantyError::LimitExceeded);
+            }
+            hooks.push_back(hook);
+            storage.set(&DataKey::Hooks, &hooks);
+        }
+        ConfigChange::RemoveHook(hook) => {
+            let mut hooks: Vec<Address> = storage.get(&DataKey::Hooks).unwrap_or(Vec::new(env));
+            let index = hooks.first_index_of(&hook).ok_or(WarrantyError::NotFound)?;
+            hooks.remove(index);
+            storage.set(&DataKey::Hooks, &hooks);
+        }
+        ConfigChange::Pause => storage.set(&PauseKey::Paused, &true),
+        ConfigChange::Unpause => storage.remove(&PauseKey::Paused),
+        ConfigChange::SetCapacity(Some(max)) => {
+            let registered: u64 = storage.get(&DataKey::WarrantyCount).unwrap_or(0);
+            if max < registered {
+                return Err(WarrantyError::InvalidInput);
+            }
+            storage.set(&DataKey::Capacity, &max);
+        }
+        ConfigChange::SetCapacity(None) => storage.remove(&DataKey::Capacity),
+        ConfigChange::SetRegistrationLimits(limits) => storage.set(&RateLimitKey::Limits, &limits),
+        ConfigChange::SetPurchaseDateSkew(skew_secs) => {
+            storage.set(&DataKey::PurchaseDateSkew, &skew_secs)
+        }
+        ConfigChange::SetMaxPurchaseAge(manufacturer, max_age_secs) => {
+            let key = DataKey::MaxPurchaseAge(manufacturer);
+            match max_age_secs {
+                Some(max_age_secs) => write_record(env, &key, &max_age_secs),
+                None => remove_record(env, &key),
+            }
+        }
+        ConfigChange::SetUniquenessRule(manufacturer, rule) => {
+            require_feature(env, "uniqueness_rules")?;
+            let key = UniquenessKey::Rule(manufacturer);
+            match rule {
+                UniquenessRule::Serial => remove_record(env, &key),
+                rule => write_record(env, &key, &rule),
+            }
+        }
+        ConfigChange::SetDepreciationCurve(manufacturer, category, curve) => {
+            let key = DataKey::DepreciationCurve(manufacturer, category);
+            if curve.is_empty() {
+                remove_record(env, &key);
+            } else {
+                write_record(env, &key, &curve);
+            }
+        }
+        ConfigChange::SetEventVerbosity(verbosity) => {
+            storage.set(&DataKey::EventVerbosity, &verbosity)
+        }
+        ConfigChange::SetAnalyticsEnabled(enabled) => {
+            storage.set(&DataKey::AnalyticsEnabled, &enabled)
+        }
+        ConfigChange::SetStatutoryMinimum(region, category, min_duration_secs) => {
+            let key = DataKey::StatutoryMinimum(region, category);
+            match min_duration_secs {
+                Some(min_duration_secs) => write_record(env, &key, &min_duration_secs),
+                None => remove_record(env, &key),
+            }
+        }
+        ConfigChange::SetCoolingOffSecs(secs) => storage.set(&DataKey::CoolingOffSecs, &secs),
+        ConfigChange::SetEditWindowSecs(secs) => storage.set(&DataKey::EditWindowSecs, &secs),
+        ConfigChange::SetClaimWindowSecs(secs) => storage.set(&ClaimKey::WindowSecs, &secs),
+        ConfigChange::SetTransferClaimCooldownSecs(secs) => {
+            storage.set(&ClaimKey::TransferCooldownSecs, &secs)
+        }
+        ConfigChange::SetClaimLimit(manufacturer, limit) => {
+            write_record(env, &ClaimKey::Limit(manufacturer), &limit)
+        }
+        ConfigChange::ClearClaimLimit(manufacturer) => {
+            remove_record(env, &ClaimKey::Limit(manufacturer))
+        }
+        ConfigChange::SetKeeper(keeper, active) => {
+            set_flag(env, &KeeperKey::Keeper(keeper), active)
+        }
+        ConfigChange::SetKeeperFee(task, fee) => storage.set(&KeeperKey::Fee(task), &fee),
+        ConfigChange::SetKeeperRewardToken(token) => {
+            if keeper_pool(env) > 0 {
+                return Err(WarrantyError::InvalidInput);
+            }
+            storage.set(&KeeperKey::RewardToken, &token);
+        }
+        ConfigChange::SetServiceCenter(center, authorized) => {
+            set_flag(env, &ServiceKey::Center(center), authorized)
+        }
+        ConfigChange::SetLogisticsProvider(provider, authorized) => {
+            set_flag(env, &DataKey::LogisticsProvider(provider), authorized)
+        }
+        ConfigChange::SetUnderwriter(address, active) => {
+            if active {
+                require_feature(env, "underwriting")?;
+            }
+            set_flag(env, &UnderwritingKey::Underwriter(address), active)
+        }
+        ConfigChange::SetArbitrator(arbitrator, authorized) => {
+            set_flag(env, &ArbitrationKey::Arbitrator(arbitrator), authorized)
+        }
+        ConfigChange::SetDisputeQuorum(1) => storage.remove(&ArbitrationKey::Quorum),
+        ConfigChange::SetDisputeQuorum(quorum) => storage.set(&ArbitrationKey::Quorum, &quorum),
+        ConfigChange::SetDisputeFee(fee) => storage.set(&ArbitrationKey::FeeTerms, &fee),
+        ConfigChange::ClearDisputeFee => storage.remove(&ArbitrationKey::FeeTerms),
+        ConfigChange::SetPriceOracle(Some(oracle)) => storage.set(&ClaimKey::PriceOracle, &oracle),
+        ConfigChange::SetPriceOracle(None) => storage.remove(&ClaimKey::PriceOracle),
+        ConfigChange::SetRentTerms(terms) => {
+            require_feature(env, "rent_pool")?;
+            storage.set(&TtlKey::RentTerms, &terms)
+        }
+        ConfigChange::ClearRentTerms => storage.remove(&TtlKey::RentTerms),
+        ConfigChange::SetVelocityThresholds(thresholds) => {
+            require_feature(env, "claim_velocity")?;
+            storage.set(&FraudKey::VelocityThresholds, &thresholds)
+        }
+        ConfigChange::ClearVelocityThresholds => storage.remove(&FraudKey::VelocityThresholds),
+        ConfigChange::SetSolvencyThresholdBps(DEFAULT_SOLVENCY_THRESHOLD_BPS) => {
+            storage.remove(&ObligationKey::SolvencyThreshold)
+        }
+        ConfigChange::SetSolvencyThresholdBps(threshold_bps) => {
+            storage.set(&ObligationKey::SolvencyThreshold, &threshold_bps)
+        }
+        ConfigChange::SetTokenBaseUri(base_uri) => storage.set(&NftKey::BaseUri, &base_uri),
+    }
+    Ok(())
+}
+
+/// Store or clear a persistent marker entry.
+fn set_flag<K: IntoVal<Env, Val>>(env: &Env, key: &K, set: bool) {
+    if set {
+        write_record(env, key, &true);
+    } else {
+        remove_record(env, key);
+    }
+}
+
+/// Record `member`'s approval of a proposal and carry it out once the
+/// current members' approvals reach the threshold.
+pub(crate) fn add_approval(
+    env: &Env,
+    council: &Council,
+    member: Address,
+    proposal_id: u64,
+) -> Result<bool, WarrantyError> {
+    let key = CouncilKey::Proposal(proposal_id);
+    let mut proposal: Proposal = read_record(env, &key).ok_or(WarrantyError::NotFound)?;
+    if proposal.executed || proposal.approvals.contains(&member) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    if env.ledger().timestamp() > proposal.expires_at {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    proposal.approvals.push_back(member);
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|approver| council.members.contains(approver))
+        .count() as u32;
+    proposal.executed = approvals >= council.threshold;
+    write_record(env, &key, &proposal);
+
+    if proposal.executed {
+        execute_action(env, proposal.action)?;
+    }
+    Ok(proposal.executed)
+}
+
+/// Carry out an approved council action.
+pub(crate) fn execute_action(env: &Env, action: CouncilAction) -> Result<(), WarrantyError> {
+    let storage = env.storage().instance();
+    match action {
+        CouncilAction::Upgrade(new_wasm_hash) => {
+            env.deployer().update_current_contract_wasm(new_wasm_hash);
+        }
+        CouncilAction::SetAdmin(new_admin) => storage.set(&DataKey::Admin, &new_admin),
+        CouncilAction::RegisterManufacturer(address, name) => {
+            require_feature(env, "manufacturer_registry")?;
+            store_manufacturer(env, address, name)?;
+        }
+        CouncilAction::RemoveManufacturer(address) => delete_manufacturer(env, &address)?,
+        CouncilAction::SetNamespaceIssuer(manufacturer, issuer) => {
+            let key = NamespaceKey::Issuer(manufacturer);
+            match issuer {
+                Some(issuer) => write_record(env, &key, &issuer),
+                None => remove_record(env, &key),
+            }
+        }
+        CouncilAction::SetComplianceHook(hook) => match hook {
+            Some(hook) => storage.set(&DataKey::ComplianceHook, &hook),
+            None => storage.remove(&DataKey::ComplianceHook),
+        },
+        CouncilAction::SetReadLimits(limits) => storage.set(&DataKey::ReadLimits, &limits),
+        CouncilAction::SetConfig(change) => {
+            let arbitrator = matches!(change, ConfigChange::SetArbitrator(..));
+            apply_config(env, change)?;
+            if arbitrator {
+                notify_change(env, symbol_short!("arbiter"), NO_WARRANTY_ID, None);
+            }
+        }
+        CouncilAction::SetCouncil(members, threshold) => {
+            if members.is_empty() {
+                storage.remove(&CouncilKey::Council);
+            } else {
+                storage.set(&CouncilKey::Council, &Council { members, threshold });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Add a manufacturer to the registry, failing if its address or name is
+/// already taken or the address was retired by a supersession.
+pub(crate) fn store_manufacturer(
+    env: &Env,
+    address: Address,
+    name: String,
+) -> Result<(), WarrantyError> {
+    if name.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let key = ManufacturerKey::Manufacturer(address.clone());
+    let name_key = ManufacturerKey::Name(name.clone());
+    if has_record(env, &key)
+        || has_record(env, &name_key)
+        || has_record(env, &ManufacturerKey::SupersededBy(address.clone()))
+    {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    write_record(env, &name_key, &address);
+    write_record(
+        env,
+        &key,
+        &Manufacturer {
+            address,
+            name,
+            registered_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Remove a manufacturer from the registry, failing if it is not in it.
+pub(crate) fn delete_manufacturer(env: &Env, address: &Address) -> Result<(), WarrantyError> {
+    let manufacturer: Manufacturer =
+        read_record(env, &ManufacturerKey::Manufacturer(address.clone()))
+            .ok_or(WarrantyError::NotFound)?;
+    remove_record(env, &ManufacturerKey::Manufacturer(address.clone()));
+    remove_record(env, &ManufacturerKey::Name(manufacturer.name));
+    Ok(())
+}
+
+/// Follow the chain of supersessions from a manufacturer address to the
+/// address currently acting for it.
+pub(crate) fn current_manufacturer(env: &Env, address: &Address) -> Address {
+    let mut current = address.clone();
+    while let Some(next) =
+        read_record::<_, Address>(env, &ManufacturerKey::SupersededBy(current.clone()))
+    {
+        current = next;
+    }
+    current
+}
+
+/// Whether `address` speaks for the manufacturer of `warranty`: the
+/// registered manufacturer that issued it (or the address that superseded
+/// it), or the namespace issuer of its manufacturer name.
+pub(crate) fn is_warranty_manufacturer(
+    env: &Env,
+    address: &Address,
+    warranty: &WarrantyData,
+) -> bool {
+    warranty
+        .verified_manufacturer
+        .as_ref()
+        .is_some_and(|issuer| current_manufacturer(env, issuer) == *address)
+        || get_namespace_issuer(env.clone(), warranty.manufacturer.clone()) == Some(address.clone())
+}
+
+/// Whether `address` may act for the manufacturer name `manufacturer`: the
+/// registered manufacturer of that name, or its namespace issuer.
+pub(crate) fn speaks_for_manufacturer(env: &Env, address: &Address, manufacturer: &String) -> bool {
+    read_record::<_, Manufacturer>(env, &ManufacturerKey::Manufacturer(address.clone()))
+        .is_some_and(|registered| registered.name == *manufacturer)
+        || get_namespace_issuer(env.clone(), manufacturer.clone()) == Some(address.clone())
+}
+
+/// Whether `address` may manage the retailers allowed to attest purchases of
+/// `manufacturer`'s products: the admin or the manufacturer's namespace issuer.
+pub(crate) fn is_retailer_manager(env: &Env, address: &Address, manufacturer: &String) -> bool {
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    admin.as_ref() == Some(address)
+        || get_namespace_issuer(env.clone(), manufacturer.clone()) == Some(address.clone())
+}
+
+/// Move a warranty to `status` on its manufacturer's behalf; backs
+/// `reactivate_warranty`, `suspend_warranty` and `mark_replaced`.
+pub(crate) fn set_status_as_manufacturer(
+    env: Env,
+    warranty_id: u64,
+    manufacturer: Address,
+    status: WarrantyStatus,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    manufacturer.require_auth();
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    if !is_warranty_manufacturer(&env, &manufacturer, &warranty) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    apply_status(
+        &env,
+        &manufacturer,
+        warranty,
+        status,
+        StatusAuthority::Manufacturer,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+/// Move `warranty` to the status in `info` on its manufacturer's behalf,
+/// keeping `info` for `get_recall_info`.
+pub(crate) fn pull_warranty(env: &Env, mut warranty: WarrantyData, info: RecallInfo) {
+    let previous = warranty.status.clone();
+    warranty.status = info.status.clone();
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(env, &warranty);
+
+    env.storage()
+        .persistent()
+        .set(&ManufacturerKey::Recall(warranty.id), &info);
+
+    let action = if info.status == WarrantyStatus::Recalled {
+        symbol_short!("recall")
+    } else {
+        symbol_short!("void")
+    };
+    record_status_change(env, &warranty, &info.manufacturer, action, previous.clone());
+    publish_status_change(env, &warranty, previous);
+    publish_recalled(env, warranty.id, &info);
+}
+
+/// Require the authorization of an address acting for the owner of
+/// `warranty`: the owner itself, the agent approved for the warranty, or an
+/// operator of the owner.
+pub(crate) fn require_agent(
+    env: &Env,
+    agent: &Address,
+    warranty: &WarrantyData,
+) -> Result<(), WarrantyError> {
+    agent.require_auth();
+    let approved: Option<Address> = read_record(env, &AgentKey::Approved(warranty.id));
+    if *agent != warranty.owner
+        && approved.as_ref() != Some(agent)
+        && !is_operator(env.clone(), warranty.owner.clone(), agent.clone())
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Require a registered keeper's authorization.
+pub(crate) fn require_keeper(env: &Env, keeper: &Address) -> Result<(), WarrantyError> {
+    keeper.require_auth();
+    if !has_record(env, &KeeperKey::Keeper(keeper.clone())) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Add the fee for `units` of a task to a keeper's unclaimed earnings.
+pub(crate) fn credit_keeper(env: &Env, keeper: &Address, task: KeeperTask, units: u32) {
+    let fee: i128 = env
+        .storage()
+        .instance()
+        .get(&KeeperKey::Fee(task))
+        .unwrap_or(0);
+    if fee == 0 || units == 0 {
+        return;
+    }
+    let key = KeeperKey::Earnings(keeper.clone());
+    let earnings: i128 = read_record(env, &key).unwrap_or(0);
+    write_record(env, &key, &(earnings + fee * units as i128));
+}
+
+/// Reward tokens funded for keepers and not yet paid out.
+pub(crate) fn keeper_pool(env: &Env) -> i128 {
+    env.storage().instance().get(&KeeperKey::Pool).unwrap_or(0)
+}
+
+/// Require a registered manufacturer's authorization and return its entry.
+pub(crate) fn require_manufacturer(
+    env: &Env,
+    address: &Address,
+) -> Result<Manufacturer, WarrantyError> {
+    address.require_auth();
+    read_record(env, &ManufacturerKey::Manufacturer(address.clone()))
+        .ok_or(WarrantyError::Unauthorized)
+}
+
+/// Require a delegate's authorization and return the registered
+/// manufacturer it acts for, along with the delegation. Fails with
+/// `Unauthorized` unless `manufacturer` delegated to it.
+pub(crate) fn require_delegate(
+    env: &Env,
+    delegate: &Address,
+    manufacturer: &Address,
+) -> Result<(Manufacturer, IssuerDelegate), WarrantyError> {
+    delegate.require_auth();
+    let registered: Manufacturer =
+        read_record(env, &ManufacturerKey::Manufacturer(manufacturer.clone()))
+            .ok_or(WarrantyError::Unauthorized)?;
+    let delegation: IssuerDelegate = read_record(
+        env,
+        &DelegateKey::Delegate(manufacturer.clone(), delegate.clone()),
+    )
+    .ok_or(WarrantyError::Unauthorized)?;
+    Ok((registered, delegation))
+}
+
+/// Ask the configured compliance hook whether value may move from `from` to
+/// `to`, failing with `TransferDenied` if it refuses or cannot answer.
+/// Without a hook everything is allowed.
+pub(crate) fn check_compliance(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+) -> Result<(), WarrantyError> {
+    let Some(hook) = env
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::ComplianceHook)
+    else {
+        return Ok(());
+    };
+    match ComplianceClient::new(env, &hook).try_check_allowed(from, to) {
+        Ok(Ok(true)) => Ok(()),
+        _ => Err(WarrantyError::TransferDenied),
+    }
+}
+
+pub(crate) fn __constructor(env: Env, admin: Address, config: Option<DeploymentConfig>) {
+    env.storage().instance().set(&DataKey::Admin, &admin);
+    if let Some(config) = config {
+        env.storage()
+            .instance()
+            .set(&DataKey::DeploymentConfig, &config);
+    }
+}
+
+pub(crate) fn get_deployment_config(env: Env) -> DeploymentConfig {
+    deployment_config(&env)
+}
+
+pub(crate) fn get_admin(env: Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub(crate) fn set_admin(env: Env, new_admin: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    new_admin.require_auth();
+
+    env.storage().instance().set(&DataKey::Admin, &new_admin);
+    Ok(())
+}
+
+pub(crate) fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+
+    env.deployer().update_current_contract_wasm(new_wasm_hash);
+    Ok(())
+}
+
+pub(crate) fn enable_council(
+    env: Env,
+    members: Vec<Address>,
+    threshold: u32,
+) -> Result<(), WarrantyError> {
+    require_feature(&env, "council")?;
+    require_sole_admin(&env)?;
+
+    check_council(&members, threshold)?;
+    env.storage()
+        .instance()
+        .set(&CouncilKey::Council, &Council { members, threshold });
+    Ok(())
+}
+
+pub(crate) fn get_council(env: Env) -> Option<Council> {
+    env.storage().instance().get(&CouncilKey::Council)
+}
+
+pub(crate) fn propose(
+    env: Env,
+    proposer: Address,
+    action: CouncilAction,
+) -> Result<u64, WarrantyError> {
+    let council = require_council_member(&env, &proposer)?;
+    check_action(&action)?;
+
+    let proposal_id: u64 = env
+        .storage()
+        .instance()
+        .get(&CouncilKey::Count)
+        .unwrap_or(0)
+        + 1;
+    let now = env.ledger().timestamp();
+    let proposal = Proposal {
+        proposal_id,
+        proposer: proposer.clone(),
+        action,
+        approvals: Vec::new(&env),
+        created_at: now,
+        expires_at: now + PROPOSAL_LIFETIME_SECS,
+        executed: false,
+    };
+    write_record(&env, &CouncilKey::Proposal(proposal_id), &proposal);
+    env.storage()
+        .instance()
+        .set(&CouncilKey::Count, &proposal_id);
+
+    add_approval(&env, &council, proposer, proposal_id)?;
+    Ok(proposal_id)
+}
+
+pub(crate) fn approve_proposal(
+    env: Env,
+    member: Address,
+    proposal_id: u64,
+) -> Result<bool, WarrantyError> {
+    let council = require_council_member(&env, &member)?;
+
+    add_approval(&env, &council, member, proposal_id)
+}
+
+pub(crate) fn get_proposal(env: Env, proposal_id: u64) -> Option<Proposal> {
+    read_record(&env, &CouncilKey::Proposal(proposal_id))
+}
+
+pub(crate) fn set_namespace_issuer(
+    env: Env,
+    manufacturer: String,
+    issuer: Option<Address>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+
+    let key = NamespaceKey::Issuer(manufacturer);
+    match issuer {
+        Some(issuer) => write_record(&env, &key, &issuer),
+        None => remove_record(&env, &key),
+    }
+    Ok(())
+}
+
+pub(crate) fn get_namespace_issuer(env: Env, manufacturer: String) -> Option<Address> {
+    read_record(&env, &NamespaceKey::Issuer(manufacturer))
+        .or(deployment_config(&env).default_issuer)
+}
+
+pub(crate) fn register_manufacturer(
+    env: Env,
+    address: Address,
+    name: String,
+) -> Result<(), WarrantyError> {
+    require_feature(&env, "manufacturer_registry")?;
+    require_sole_admin(&env)?;
+
+    store_manufacturer(&env, address, name)?;
+    Ok(())
+}
+
+pub(crate) fn get_manufacturer(env: Env, address: Address) -> Option<Manufacturer> {
+    read_record(&env, &ManufacturerKey::Manufacturer(address))
+}
+
+pub(crate) fn remove_manufacturer(env: Env, address: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+
+    delete_manufacturer(&env, &address)?;
+    Ok(())
+}
+
+pub(crate) fn supersede_manufacturer(
+    env: Env,
+    old: Address,
+    new: Address,
+) -> Result<(), WarrantyError> {
+    require_feature(&env, "manufacturer_registry")?;
+    require_admin(&env)?;
+    old.require_auth();
+
+    let manufacturer: Manufacturer = read_record(&env, &ManufacturerKey::Manufacturer(old.clone()))
+        .ok_or(WarrantyError::NotFound)?;
+    if has_record(&env, &ManufacturerKey::Manufacturer(new.clone()))
+        || has_record(&env, &ManufacturerKey::SupersededBy(new.clone()))
+    {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    let issuer_key = NamespaceKey::Issuer(manufacturer.name.clone());
+    if read_record::<_, Address>(&env, &issuer_key) == Some(old.clone()) {
+        write_record(&env, &issuer_key, &new);
+    }
+    remove_record(&env, &ManufacturerKey::Manufacturer(old.clone()));
+    write_record(
+        &env,
+        &ManufacturerKey::Name(manufacturer.name.clone()),
+        &new,
+    );
+    write_record(
+        &env,
+        &ManufacturerKey::Manufacturer(new.clone()),
+        &Manufacturer {
+            address: new.clone(),
+            name: manufacturer.name,
+            registered_at: env.ledger().timestamp(),
+        },
+    );
+    write_record(&env, &ManufacturerKey::SupersededBy(old), &new);
+    Ok(())
+}
+
+pub(crate) fn get_current_manufacturer(env: Env, address: Address) -> Address {
+    current_manufacturer(&env, &address)
+}
+
+pub(crate) fn add_issuer_delegate(
+    env: Env,
+    manufacturer: Address,
+    delegate: Address,
+    region: String,
+) -> Result<(), WarrantyError> {
+    require_feature(&env, "manufacturer_registry")?;
+    require_manufacturer(&env, &manufacturer)?;
+    if region.is_empty() || delegate == manufacturer {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    write_record(
+        &env,
+        &DelegateKey::Delegate(manufacturer, delegate),
+        &IssuerDelegate {
+            region,
+            added_at: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn remove_issuer_delegate(
+    env: Env,
+    manufacturer: Address,
+    delegate: Address,
+) -> Result<(), WarrantyError> {
+    require_manufacturer(&env, &manufacturer)?;
+
+    let key = DelegateKey::Delegate(manufacturer, delegate);
+    if !has_record(&env, &key) {
+        return Err(WarrantyError::NotFound);
+    }
+    remove_record(&env, &key);
+    Ok(())
+}
+
+pub(crate) fn get_issuer_delegate(
+    env: Env,
+    manufacturer: Address,
+    delegate: Address,
+) -> Option<IssuerDelegate> {
+    read_record(&env, &DelegateKey::Delegate(manufacturer, delegate))
+}
+
+pub(crate) fn get_issuing_delegate(env: Env, warranty_id: u64) -> Option<Address> {
+    read_record(&env, &DelegateKey::IssuedBy(warranty_id))
+}
+
+pub(crate) fn set_retailer_authorized(
+    env: Env,
+    manager: Address,
+    manufacturer: String,
+    retailer: Address,
+    authorized: bool,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "attestation")?;
+
+    manager.require_auth();
+    if !is_retailer_manager(&env, &manager, &manufacturer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let key = RetailerKey::Authorized(manufacturer, retailer);
+    if authorized {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+    Ok(())
+}
+
+pub(crate) fn is_authorized_retailer(env: Env, manufacturer: String, retailer: Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&RetailerKey::Authorized(manufacturer, retailer))
+}
+
+pub(crate) fn set_service_center(
+    env: Env,
+    center: Address,
+    authorized: bool,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetServiceCenter(center, authorized))
+}
+
+pub(crate) fn is_service_center(env: Env, center: Address) -> bool {
+    has_record(&env, &ServiceKey::Center(center))
+}
+
+pub(crate) fn set_logistics_provider(
+    env: Env,
+    provider: Address,
+    authorized: bool,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        ConfigChange::SetLogisticsProvider(provider, authorized),
+    )
+}
+
+pub(crate) fn is_logistics_provider(env: Env, provider: Address) -> bool {
+    has_record(&env, &DataKey::LogisticsProvider(provider))
+}
+
+pub(crate) fn approve_agent(
+    env: Env,
+    warranty_id: u64,
+    agent: Option<Address>,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "agents")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    let key = AgentKey::Approved(warranty_id);
+    match agent {
+        Some(agent) => write_record(&env, &key, &agent),
+        None => remove_record(&env, &key),
+    }
+
+    notify_change(&env, symbol_short!("agent"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_approved(env: Env, warranty_id: u64) -> Option<Address> {
+    read_record(&env, &AgentKey::Approved(warranty_id))
+}
+
+pub(crate) fn set_operator(
+    env: Env,
+    owner: Address,
+    operator: Address,
+    approved: bool,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "agents")?;
+    owner.require_auth();
+    record_activity(&env, &owner);
+
+    let key = AgentKey::Operator(owner, operator);
+    if approved {
+        write_record(&env, &key, &true);
+    } else {
+        remove_record(&env, &key);
+    }
+    Ok(())
+}
+
+pub(crate) fn is_operator(env: Env, owner: Address, operator: Address) -> bool {
+    has_record(&env, &AgentKey::Operator(owner, operator))
+}
+
+pub(crate) fn register_underwriter(env: Env, address: Address) -> Result<(), WarrantyError> {
+    require_feature(&env, "underwriting")?;
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetUnderwriter(address, true))
+}
+
+pub(crate) fn remove_underwriter(env: Env, address: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetUnderwriter(address, false))
+}
+
+pub(crate) fn is_underwriter(env: Env, address: Address) -> bool {
+    has_record(&env, &UnderwritingKey::Underwriter(address))
+}
+
+pub(crate) fn set_keeper(env: Env, keeper: Address, active: bool) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetKeeper(keeper, active))
+}
+
+pub(crate) fn is_keeper(env: Env, keeper: Address) -> bool {
+    has_record(&env, &KeeperKey::Keeper(keeper))
+}
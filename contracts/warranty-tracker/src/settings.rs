@@ -0,0 +1,356 @@
+//! Feature probes, hooks, read and registration limits, pausing, freezes
+//! and the admin-tunable windows and policies.
+use soroban_sdk::{Address, Env, String, Symbol, Vec};
+
+use super::*;
+
+pub(crate) fn get_interface_version(_env: Env) -> u32 {
+    INTERFACE_VERSION
+}
+
+pub(crate) fn supports(env: Env, feature: Symbol) -> bool {
+    SUPPORTED_FEATURES
+        .iter()
+        .any(|name| Symbol::new(&env, name) == feature && feature_enabled(&env, name))
+}
+
+pub(crate) fn add_hook(env: Env, hook: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::AddHook(hook))
+}
+
+pub(crate) fn set_compliance_hook(env: Env, hook: Option<Address>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+
+    match hook {
+        Some(hook) => env
+            .storage()
+            .instance()
+            .set(&DataKey::ComplianceHook, &hook),
+        None => env.storage().instance().remove(&DataKey::ComplianceHook),
+    }
+    Ok(())
+}
+
+pub(crate) fn get_compliance_hook(env: Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ComplianceHook)
+}
+
+pub(crate) fn remove_hook(env: Env, hook: Address) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::RemoveHook(hook))
+}
+
+pub(crate) fn get_hooks(env: Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Hooks)
+        .unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn set_read_limits(env: Env, limits: ReadLimits) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+
+    if limits.max_page_size == 0 || limits.max_batch_size == 0 {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    env.storage().instance().set(&DataKey::ReadLimits, &limits);
+    Ok(())
+}
+
+pub(crate) fn get_read_limits(env: Env) -> ReadLimits {
+    read_limits(&env)
+}
+
+pub(crate) fn set_capacity(env: Env, max_warranties: Option<u64>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetCapacity(max_warranties))
+}
+
+pub(crate) fn get_capacity(env: Env) -> Capacity {
+    Capacity {
+        max_warranties: env.storage().instance().get(&DataKey::Capacity),
+        registered: queries::get_warranty_count(env),
+    }
+}
+
+pub(crate) fn set_registration_limits(
+    env: Env,
+    limits: RegistrationLimits,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetRegistrationLimits(limits))
+}
+
+pub(crate) fn get_registration_limits(env: Env) -> RegistrationLimits {
+    registration_limits(&env)
+}
+
+pub(crate) fn get_remaining_quota(env: Env, owner: Address) -> RemainingQuota {
+    remaining_quota(&env, &owner)
+}
+
+pub(crate) fn pause(env: Env) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::Pause)
+}
+
+pub(crate) fn unpause(env: Env) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::Unpause)
+}
+
+pub(crate) fn is_paused(env: Env) -> bool {
+    env.storage().instance().has(&PauseKey::Paused)
+}
+
+pub(crate) fn freeze_warranty(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_admin(&env)?;
+    require_warranty(&env, warranty_id)?;
+    write_record(&env, &PauseKey::Frozen(warranty_id), &true);
+    Ok(())
+}
+
+pub(crate) fn unfreeze_warranty(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_admin(&env)?;
+    remove_record(&env, &PauseKey::Frozen(warranty_id));
+    Ok(())
+}
+
+pub(crate) fn is_frozen(env: Env, warranty_id: u64) -> bool {
+    has_record(&env, &PauseKey::Frozen(warranty_id))
+}
+
+pub(crate) fn set_purchase_date_skew(env: Env, skew_secs: u64) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetPurchaseDateSkew(skew_secs))
+}
+
+pub(crate) fn get_purchase_date_skew(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PurchaseDateSkew)
+        .unwrap_or(DEFAULT_PURCHASE_DATE_SKEW_SECS)
+}
+
+pub(crate) fn set_max_purchase_age(
+    env: Env,
+    manufacturer: String,
+    max_age_secs: Option<u64>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        ConfigChange::SetMaxPurchaseAge(manufacturer, max_age_secs),
+    )
+}
+
+pub(crate) fn get_max_purchase_age(env: Env, manufacturer: String) -> Option<u64> {
+    read_record(&env, &DataKey::MaxPurchaseAge(manufacturer))
+}
+
+pub(crate) fn set_uniqueness_rule(
+    env: Env,
+    manufacturer: String,
+    rule: UniquenessRule,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetUniquenessRule(manufacturer, rule))
+}
+
+pub(crate) fn get_uniqueness_rule(env: Env, manufacturer: String) -> UniquenessRule {
+    read_record(&env, &UniquenessKey::Rule(manufacturer)).unwrap_or(UniquenessRule::Serial)
+}
+
+pub(crate) fn set_depreciation_curve(
+    env: Env,
+    manufacturer: String,
+    category: String,
+    curve: Vec<(u32, u32)>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        ConfigChange::SetDepreciationCurve(manufacturer, category, curve),
+    )
+}
+
+pub(crate) fn get_depreciation_curve(
+    env: Env,
+    manufacturer: String,
+    category: String,
+) -> Vec<(u32, u32)> {
+    read_record(&env, &DataKey::DepreciationCurve(manufacturer, category)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn set_event_verbosity(
+    env: Env,
+    verbosity: EventVerbosity,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetEventVerbosity(verbosity))
+}
+
+pub(crate) fn get_event_verbosity(env: Env) -> EventVerbosity {
+    event_verbosity(&env)
+}
+
+pub(crate) fn set_analytics_enabled(env: Env, enabled: bool) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetAnalyticsEnabled(enabled))
+}
+
+pub(crate) fn get_analytics_enabled(env: Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::AnalyticsEnabled)
+        .unwrap_or(false)
+}
+
+pub(crate) fn set_statutory_minimum(
+    env: Env,
+    region: String,
+    category: Option<String>,
+    min_duration_secs: Option<u64>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        ConfigChange::SetStatutoryMinimum(region, category, min_duration_secs),
+    )
+}
+
+pub(crate) fn get_statutory_minimum(
+    env: Env,
+    region: String,
+    category: Option<String>,
+) -> Option<u64> {
+    statutory_minimum(&env, region, category)
+}
+
+pub(crate) fn set_cooling_off_secs(env: Env, cooling_off_secs: u64) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetCoolingOffSecs(cooling_off_secs))
+}
+
+pub(crate) fn get_cooling_off_secs(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CoolingOffSecs)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_edit_window_secs(env: Env, edit_window_secs: u64) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetEditWindowSecs(edit_window_secs))
+}
+
+pub(crate) fn get_edit_window_secs(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::EditWindowSecs)
+        .unwrap_or(DEFAULT_EDIT_WINDOW_SECS)
+}
+
+pub(crate) fn set_claim_window_secs(env: Env, claim_window_secs: u64) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetClaimWindowSecs(claim_window_secs))
+}
+
+pub(crate) fn get_claim_window_secs(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&ClaimKey::WindowSecs)
+        .unwrap_or(DEFAULT_CLAIM_WINDOW_SECS)
+}
+
+pub(crate) fn set_transfer_claim_cooldown_secs(
+    env: Env,
+    cooldown_secs: u64,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        ConfigChange::SetTransferClaimCooldownSecs(cooldown_secs),
+    )
+}
+
+pub(crate) fn get_transfer_claim_cooldown_secs(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&ClaimKey::TransferCooldownSecs)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_price_oracle(env: Env, oracle: Option<Address>) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetPriceOracle(oracle))
+}
+
+pub(crate) fn get_price_oracle(env: Env) -> Option<Address> {
+    env.storage().instance().get(&ClaimKey::PriceOracle)
+}
+
+pub(crate) fn set_solvency_threshold(env: Env, threshold_bps: u32) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(&env, ConfigChange::SetSolvencyThresholdBps(threshold_bps))
+}
+
+pub(crate) fn get_solvency_threshold(env: Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ObligationKey::SolvencyThreshold)
+        .unwrap_or(DEFAULT_SOLVENCY_THRESHOLD_BPS)
+}
+
+pub(crate) fn set_claim_limit(
+    env: Env,
+    manufacturer: String,
+    limit: Option<ClaimLimit>,
+) -> Result<(), WarrantyError> {
+    require_sole_admin(&env)?;
+    apply_config(
+        &env,
+        match limit {
+            Some(limit) => ConfigChange::SetClaimLimit(manufacturer, limit),
+            None => ConfigChange::ClearClaimLimit(manufacturer),
+        },
+    )
+}
+
+pub(crate) fn get_claim_limit(env: Env, manufacturer: String) -> Option<ClaimLimit> {
+    read_record(&env, &ClaimKey::Limit(manufacturer))
+}
+
+pub(crate) fn get_claim_quota(env: Env, claimant: Address, manufacturer: String) -> ClaimQuota {
+    claim_quota(&env, &claimant, &manufacturer)
+}
+
+pub(crate) fn describe(env: Env) -> ContractDescription {
+    let config = deployment_config(&env);
+    let mut features = Vec::new(&env);
+    for name in SUPPORTED_FEATURES.iter() {
+        if feature_enabled(&env, name) {
+            features.push_back(Symbol::new(&env, name));
+        }
+    }
+
+    ContractDescription {
+        name: config.name,
+        default_issuer: config.default_issuer,
+        features,
+        interface_version: INTERFACE_VERSION,
+        admin: roles::get_admin(env.clone()),
+        read_limits: read_limits(&env),
+        purchase_date_skew_secs: get_purchase_date_skew(env.clone()),
+        cooling_off_secs: get_cooling_off_secs(env.clone()),
+        edit_window_secs: get_edit_window_secs(env.clone()),
+        claim_window_secs: get_claim_window_secs(env.clone()),
+        stats: queries::get_storage_stats(env.clone()),
+        latest_outbox_seq: env
+            .storage()
+            .instance()
+            .get(&DataKey::OutboxTail)
+            .unwrap_or(0),
+    }
+}
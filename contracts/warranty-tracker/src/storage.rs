@@ -0,0 +1,1524 @@
+//! Storage keys, record access and index maintenance.
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Map, String, TryFromVal, Val, Vec};
+
+use super::*;
+
+/// Upper bounds on read sizes so oversized requests fail cleanly instead of
+/// exhausting the call budget
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReadLimits {
+    /// Maximum `limit` accepted by paginated queries
+    pub max_page_size: u32,
+    /// Maximum number of items accepted by batch entry points
+    pub max_batch_size: u32,
+}
+
+/// How many warranties a deployment may register, and how many it has
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Capacity {
+    /// Cap on registrations, or None for no cap
+    pub max_warranties: Option<u64>,
+    pub registered: u64,
+}
+
+/// Daily registration caps that keep spam from bloating storage and using
+/// up IDs
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistrationLimits {
+    /// Registrations one owner may receive per day, or None for no cap
+    pub per_owner_daily: Option<u32>,
+    /// Registrations the deployment accepts per day, or None for no cap
+    pub global_daily: Option<u32>,
+}
+
+/// What is left of today's registration caps
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemainingQuota {
+    /// Registrations the owner may still receive today, or None if uncapped
+    pub owner: Option<u32>,
+    /// Registrations the deployment still accepts today, or None if uncapped
+    pub global: Option<u32>,
+    /// When the counts start over
+    pub resets_at: u64,
+}
+
+/// White-label settings fixed when the contract is deployed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeploymentConfig {
+    /// Name reported by `describe`
+    pub name: String,
+    /// Namespace issuer for manufacturers without one of their own
+    pub default_issuer: Option<Address>,
+    /// Bit `i` enables the `i`-th entry of `SUPPORTED_FEATURES` in this build
+    pub features: u64,
+    /// Bit `i` enables the `(64 + i)`-th entry of `SUPPORTED_FEATURES`, for
+    /// features past the first word
+    pub extra_features: u64,
+}
+
+/// Position in a paginated listing.
+///
+/// Pages resume after `last_id` rather than at a numeric offset, so ids
+/// inserted into or removed from the underlying index between calls do not
+/// cause entries to be skipped or returned twice. `bucket` identifies the
+/// index shard the cursor points into. Only the global ID index is split
+/// into shards; for the other indexes it is always `0`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cursor {
+    pub last_id: u64,
+    pub bucket: u32,
+}
+
+/// Where a paginated listing continues
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NextPage {
+    /// The listing has no entries left
+    Done,
+    /// Pass the cursor back to fetch the next page
+    At(Cursor),
+}
+
+impl NextPage {
+    /// The cursor to resume from, or None once the listing is done
+    pub fn cursor(&self) -> Option<Cursor> {
+        match self {
+            NextPage::Done => None,
+            NextPage::At(cursor) => Some(cursor.clone()),
+        }
+    }
+}
+
+impl From<Option<Cursor>> for NextPage {
+    fn from(cursor: Option<Cursor>) -> Self {
+        cursor.map_or(NextPage::Done, NextPage::At)
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyIdPage {
+    pub ids: Vec<u64>,
+    pub next: NextPage,
+}
+
+/// Outcome of one page of an expiry sweep over the global ID index
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepPage {
+    pub expired: u32,
+    pub next: NextPage,
+}
+
+/// Page of full warranty records, as the public sees them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyPage {
+    pub warranties: Vec<WarrantyData>,
+    pub next: NextPage,
+}
+
+/// Everything recorded about one of an owner's warranties
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyExport {
+    pub warranty: WarrantyData,
+    /// Claims filed against the warranty, oldest first
+    pub claims: Vec<ClaimData>,
+    /// Changes of owner, oldest first
+    pub transfers: Vec<TransferRecord>,
+    /// Service records, ordered by service date
+    pub service_records: Vec<ServiceRecord>,
+}
+
+/// Page of an owner's data export
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerExport {
+    pub warranties: Vec<WarrantyExport>,
+    pub next: NextPage,
+}
+
+/// How long a warranty record is known to stay on the ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlStatus {
+    /// Ledger the record stays live until at least; reads may have pushed
+    /// the actual TTL further
+    pub live_until_ledger: u32,
+    pub current_ledger: u32,
+    /// Fewer than `WARRANTY_TTL_THRESHOLD` ledgers are known to remain
+    pub expiring_soon: bool,
+}
+
+/// Rent charged at registration for warranties that outlast what a record
+/// can cheaply be kept alive for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RentTerms {
+    pub token: Address,
+    /// Coverage length registrations get without paying rent
+    pub free_secs: u64,
+    /// Rent per started day of coverage beyond `free_secs`
+    pub daily_rent: i128,
+    /// Paid to a keeper from a warranty's rent each time it extends the
+    /// record's TTL
+    pub bump_fee: i128,
+}
+
+/// Entry counts per storage family, for monitoring growth
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageStats {
+    /// Warranties ever registered
+    pub registered: u64,
+    /// Warranty records currently stored
+    pub live_warranties: u32,
+    /// Registrations whose record has since been removed
+    pub removed_warranties: u64,
+    /// Entries in the global warranty ID index
+    pub id_index_entries: u32,
+    pub counterfeit_reports: u64,
+    pub pending_reports: u32,
+    pub hooks: u32,
+    /// Outbox entries not yet pruned
+    pub outbox_entries: u64,
+    /// Claim records currently stored, drafts included
+    pub stored_claims: u64,
+    /// Stored claims filed, approved or disputed and not yet closed
+    pub open_claims: u32,
+}
+
+#[contracttype]
+pub enum KeeperKey {
+    Keeper(Address),
+    Fee(KeeperTask),
+    Earnings(Address),
+    RewardToken,
+    LastTtlBump,
+    /// Reward tokens set aside for keeper payouts, kept apart from the
+    /// claim pools the contract also holds
+    Pool,
+}
+
+#[contracttype]
+pub enum AuditKey {
+    Entry(u64),
+    /// Oldest entry not pruned yet
+    Head,
+    Tail,
+}
+
+#[contracttype]
+pub enum HistoryKey {
+    /// Entries of one warranty's history, numbered from 0
+    Entry(u64, u32),
+    Count(u64),
+}
+
+#[contracttype]
+pub enum DeviceLogKey {
+    /// Events logged by one warranty's device, numbered from 0; named
+    /// apart from `HistoryKey` so the two never share a storage key
+    Event(u64, u32),
+    EventCount(u64),
+}
+
+#[contracttype]
+pub enum NamespaceKey {
+    /// Issuer of a manufacturer's local IDs, in persistent storage
+    Issuer(String),
+    Warranty(String, u64),
+    LocalId(u64),
+}
+
+#[contracttype]
+pub enum PartKey {
+    /// Part warranties registered under a parent warranty, at most
+    /// `MAX_PART_WARRANTIES`
+    Parts(u64),
+    /// Link from a part warranty back to its parent
+    Part(u64),
+}
+
+#[contracttype]
+pub enum LinkKey {
+    /// Link from a dependent warranty to the warranty it depends on, in
+    /// persistent storage
+    Parent(u64),
+    /// Warranties linked under a parent warranty, at most
+    /// `MAX_LINKED_WARRANTIES`, in persistent storage
+    Children(u64),
+}
+
+#[contracttype]
+pub enum RetailerKey {
+    /// Registration totals per retailer and 30-day bucket
+    Bucket(Address, u64),
+    /// Retailers a manufacturer allows to attest purchases of its products
+    Authorized(String, Address),
+}
+
+#[contracttype]
+pub enum CampaignKey {
+    Campaign(u64),
+    Stats(u64),
+    Count,
+    /// Campaigns of a manufacturer that have not ended yet, in persistent
+    /// storage
+    Running(String),
+}
+
+#[contracttype]
+pub enum PauseKey {
+    /// Whether state-changing entry points are stopped
+    Paused,
+    /// Whether a warranty is locked against transfers and claims, in
+    /// persistent storage
+    Frozen(u64),
+}
+
+#[contracttype]
+pub enum MetadataKey {
+    /// Display metadata of a warranty, e.g. image and manual URIs
+    Entries(u64),
+}
+
+#[contracttype]
+pub enum TtlKey {
+    /// Ledger a warranty record was last extended to
+    LiveUntil(u64),
+    /// Rent registrations pay, in instance storage
+    RentTerms,
+    /// Token and amount of rent held for a warranty's TTL extensions
+    Rent(u64),
+}
+
+#[contracttype]
+pub enum TermsKey {
+    Template(u64),
+    Count,
+}
+
+#[contracttype]
+pub enum BadgeKey {
+    /// Whether a manufacturer hands out badges, in persistent storage
+    Enabled(String),
+    Badge(Address, String),
+}
+
+#[contracttype]
+pub enum PolicyKey {
+    /// Published policy documents of a manufacturer, oldest version first
+    Docs(String),
+}
+
+#[contracttype]
+pub enum SalesKey {
+    /// Extended warranty prices of a manufacturer for one product category
+    Pricing(String, String),
+    /// Quotes live in temporary storage and lapse on their own
+    Quote(u64),
+    QuoteCount,
+}
+
+#[contracttype]
+pub enum ClaimKey {
+    Claim(u64),
+    /// Claims filed against a warranty, oldest first
+    Claims(u64),
+    Count,
+    /// Message thread of a claim, oldest first
+    Messages(u64),
+    /// Funds a manufacturer set aside for payouts, per token
+    Pool(Address, Address),
+    /// How long after an incident a claim can be filed
+    WindowSecs,
+    /// Total paid out on a warranty's approved claims, in persistent storage
+    PaidOut(u64),
+    /// Rolling cap on claims per claimant for a manufacturer's warranties,
+    /// in persistent storage
+    Limit(String),
+    /// When a claimant filed the claims still counted against a
+    /// manufacturer's cap, oldest first, in persistent storage
+    Filings(Address, String),
+    /// How long after changing hands a warranty accepts no claims
+    TransferCooldownSecs,
+    /// When a warranty last changed hands, in persistent storage
+    TransferredAt(u64),
+    /// Installment schedule settling an approved claim, in persistent
+    /// storage
+    Plan(u64),
+    /// Contract quoting conversion rates for settlements in another token
+    PriceOracle,
+    /// Funds a manufacturer committed to settling approved claims, per
+    /// token; unlike its pool they cannot be withdrawn
+    Escrow(Address, Address),
+    /// How a manufacturer's escrow in a token is refilled from its
+    /// allowance, in persistent storage
+    AutoTopUp(Address, Address),
+    /// Token a claim's payouts were made in, in persistent storage
+    PaidIn(u64),
+    /// Number of claim records stored, counted apart from `Count`, which
+    /// shares its storage key with `CounterfeitKey::Count`
+    Stored,
+    /// Number of filed, approved and disputed claims across manufacturers
+    OpenCount,
+}
+
+/// Manufacturer consortiums, in persistent storage unless noted
+#[contracttype]
+pub enum ConsortiumKey {
+    Consortium(u64),
+    /// ID of the most recently created consortium, in instance storage
+    LastConsortiumId,
+    /// Consortium a manufacturer address belongs to
+    MemberOf(Address),
+    /// Balance of a consortium's shared escrow in a token
+    SharedEscrow(u64, Address),
+    /// What a member paid into and drew from the shared escrow in a token
+    Account(u64, Address, Address),
+    /// Cap on claims one owner files across all members' warranties
+    ClaimCap(u64),
+    /// When an owner's claims counted against the consortium's cap were filed
+    SharedFilings(u64, Address),
+    /// Tokens a consortium's shared escrow was funded in
+    EscrowTokens(u64),
+}
+
+/// Claim velocity and review holds, in persistent storage
+#[contracttype]
+pub enum FraudKey {
+    /// When claims were filed against a manufacturer's serial number in the
+    /// last 90 days, oldest first
+    SerialFilings(String, String),
+    /// When claims were filed on an owner's warranties in the last 90 days,
+    /// oldest first
+    OwnerFilings(Address),
+    /// Claim counts at which new claims are held for review, in instance
+    /// storage
+    VelocityThresholds,
+    /// Marks a claim that cannot be approved until a reviewer clears it
+    ReviewHold(u64),
+    /// Reversal of a settled claim found fraudulent
+    Chargeback(u64),
+    /// Number of chargebacks against an owner; their claims are held for
+    /// review while it is above 0
+    Chargebacks(Address),
+}
+
+#[contracttype]
+pub enum StatsKey {
+    /// Stored warranty records in a status, in persistent storage
+    Status(WarrantyStatus),
+}
+
+/// Per-manufacturer aggregates behind `get_open_obligations` and
+/// `get_solvency`, in persistent storage
+#[contracttype]
+pub enum ObligationKey {
+    /// Stored Active warranties under a manufacturer name
+    ActiveWarranties(String),
+    /// Filed, approved and disputed claims not yet resolved or rejected
+    OpenClaims(String),
+    /// Amounts asked for on open claims and not yet paid out
+    PendingSettlement(String),
+    /// Owed on approved claims an address pays and has not settled
+    Liabilities(Address),
+    /// Address an approved claim's unsettled amount is counted against
+    LiableParty(u64),
+    /// Tokens an address funded a claim pool or escrow in
+    CollateralTokens(Address),
+    /// Set while an address is under-collateralized and has been warned
+    Undercollateralized(Address),
+    /// Liabilities, in basis points of collateral, past which an address
+    /// is under-collateralized, in instance storage
+    SolvencyThreshold,
+}
+
+#[contracttype]
+pub enum RateLimitKey {
+    Limits,
+    /// Registrations an owner received on a day, in temporary storage
+    Owner(Address, u64),
+    /// Registrations the deployment accepted on a day, in temporary storage
+    Global(u64),
+    /// Counterfeit reports an address filed on a day, in temporary storage
+    Reports(Address, u64),
+}
+
+#[contracttype]
+pub enum CounterfeitKey {
+    /// One counterfeit report, in persistent storage
+    Report(u64),
+    /// Number of reports ever filed
+    Count,
+    /// Number of reports awaiting review
+    Pending,
+}
+
+#[contracttype]
+pub enum CatalogKey {
+    /// Product model listed by a registered manufacturer
+    Product(String),
+}
+
+#[contracttype]
+pub enum UnderwritingKey {
+    /// Insurers the admin registered to underwrite extended coverage
+    Underwriter(Address),
+    /// Extended coverage on a warranty, in persistent storage
+    Record(u64),
+}
+
+#[contracttype]
+pub enum ArbitrationKey {
+    /// Addresses the admin registered to rule on disputed claims
+    Arbitrator(Address),
+    /// Escalation of a rejected claim
+    Dispute(u64),
+    /// Arbitrators whose votes make up a majority, unset for 1
+    Quorum,
+    /// Outcome an arbitrator voted for on a dispute
+    Ballot(u64, Address),
+    /// Arbitrators that voted for one outcome of a dispute
+    Tally(u64, DisputeOutcome),
+    /// Fee charged for new disputes, unset for none
+    FeeTerms,
+    /// Dispute fees collected in a token and not yet withdrawn
+    FeePool(Address),
+    /// Dispute fees owed to an arbitrator in a token
+    FeesOwed(Address, Address),
+}
+
+#[contracttype]
+pub enum AgentKey {
+    /// Address the owner approved to manage one warranty, in persistent
+    /// storage
+    Approved(u64),
+    /// Whether an operator manages all of an owner's warranties
+    Operator(Address, Address),
+}
+
+#[contracttype]
+pub enum FeedbackKey {
+    /// When feedback on a claim or warranty was submitted
+    Given(FeedbackSubject),
+    /// When an owner last submitted feedback
+    Last(Address),
+    /// Feedback totals of a manufacturer, in persistent storage
+    Rating(String),
+}
+
+#[contracttype]
+pub enum ServiceKey {
+    /// Repair shop the admin authorized to log service work
+    Center(Address),
+    /// Service records of a warranty, ordered by service date, at most
+    /// `MAX_SERVICE_RECORDS`, in persistent storage
+    History(u64),
+}
+
+#[contracttype]
+pub enum CouncilKey {
+    Council,
+    Proposal(u64),
+    /// Number of proposals ever made
+    Count,
+}
+
+#[contracttype]
+pub enum ManufacturerKey {
+    Manufacturer(Address),
+    /// Address a manufacturer name is registered to
+    Name(String),
+    /// Address that took over from a superseded manufacturer address
+    SupersededBy(Address),
+    /// Why a manufacturer recalled or voided a warranty
+    Recall(u64),
+}
+
+#[contracttype]
+pub enum UniquenessKey {
+    /// Rule a manufacturer's registrations follow, in persistent storage
+    Rule(String),
+    /// Warranty holding a manufacturer's serial number for one product
+    SerialAndProduct(String, String, String),
+}
+
+#[contracttype]
+pub enum VoucherKey {
+    /// Keyed by the SHA-256 of the voucher code
+    Voucher(BytesN<32>),
+}
+
+#[contracttype]
+pub enum ClaimCodeKey {
+    /// Pre-issued warranty waiting for a buyer, keyed by the SHA-256 of its
+    /// claim code
+    Code(BytesN<32>),
+}
+
+#[contracttype]
+pub enum ReversionKey {
+    /// Scheduled return of a warranty to an earlier owner, in persistent
+    /// storage
+    Scheduled(u64),
+}
+
+#[contracttype]
+pub enum RevocationKey {
+    /// Revocation an issuer scheduled for a warranty, in persistent storage
+    Scheduled(u64),
+}
+
+#[contracttype]
+pub enum ConflictKey {
+    /// Warranties found claiming the same serial number of a manufacturer,
+    /// in persistent storage
+    Serial(String, String),
+}
+
+#[contracttype]
+pub enum AmendKey {
+    /// Set once a warranty first changed hands or had a claim filed, after
+    /// which its registration details can no longer be amended
+    Locked(u64),
+}
+
+#[contracttype]
+pub enum DelegateKey {
+    /// Delegation from a manufacturer to a subsidiary address, in persistent
+    /// storage
+    Delegate(Address, Address),
+    /// Delegate that issued a warranty in its manufacturer's name, kept
+    /// after the warranty is deleted
+    IssuedBy(u64),
+}
+
+#[contracttype]
+pub enum IdIndexKey {
+    /// Registrations ever added to the global ID index; the n-th goes into
+    /// bucket `n / ID_BUCKET_SIZE`
+    Slots,
+    /// IDs the global index currently lists
+    Len,
+    /// One bucket of the global ID index, ascending, in persistent storage
+    Bucket(u32),
+    /// Bucket a warranty ID is listed in
+    BucketOf(u64),
+}
+
+/// Warranty record as the first release stored it in the legacy instance
+/// map under `DataKey::WarrantyData`
+///
+/// The layout is frozen: records written by that release decode only into
+/// exactly these fields, so new fields go on `WarrantyData` instead.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyWarrantyDataV1 {
+    pub id: u64,
+    pub owner: Address,
+    pub product_name: String,
+    pub serial_number: String,
+    pub manufacturer: String,
+    pub purchase_date: u64,
+    pub expiration_date: u64,
+    pub status: WarrantyStatus,
+    pub created_at: u64,
+}
+
+impl LegacyWarrantyDataV1 {
+    /// Convert to the current record, with the defaults a warranty
+    /// registered without any of the later options gets. The ledger the
+    /// record was written in was not stored, so both ledger fields are 0.
+    pub(crate) fn upgrade(self, env: &Env) -> WarrantyData {
+        WarrantyData {
+            id: self.id,
+            owner: self.owner.clone(),
+            registrant: self.owner,
+            product_name: self.product_name,
+            serial_number: self.serial_number,
+            manufacturer: self.manufacturer,
+            purchase_date: self.purchase_date,
+            expiration_date: self.expiration_date,
+            status: self.status,
+            created_at: self.created_at,
+            created_ledger: 0,
+            last_modified_ledger: 0,
+            category: None,
+            price: None,
+            currency: None,
+            receipt_hash: None,
+            coverage: None,
+            transferable: true,
+            private: false,
+            localized_names: Map::new(env),
+            batch_number: None,
+            manufacture_date: None,
+            region: None,
+            usage_limit: None,
+            usage_unit: None,
+            usage_reading: None,
+            campaign_id: None,
+            policy_version: None,
+            verified_manufacturer: None,
+            activation_duration: None,
+            activated_at: None,
+            terms_id: None,
+            attestation: SaleAttestation::Unattested,
+            claims_filed: 0,
+            co_owners: Vec::new(env),
+            model_id: None,
+        }
+    }
+}
+
+#[contracttype]
+pub enum DataKey {
+    /// Legacy map of every warranty in instance storage, emptied by
+    /// `migrate_storage`
+    WarrantyData,
+    /// One warranty record, in persistent storage
+    Warranty(u64),
+    /// Legacy global ID index in a single entry, split into `IdIndexKey`
+    /// buckets by `migrate_storage`
+    WarrantyIds,
+    OwnerWarranties(Address),
+    WarrantyCount,
+    /// Cap on the number of warranties the deployment registers
+    Capacity,
+    TransferAllowlist(Address),
+    Beneficiary(u64),
+    LastActivity(Address),
+    AttachedPolicy(u64),
+    Admin,
+    Hooks,
+    ComplianceHook,
+    ReadLimits,
+    ReporterReputation(Address),
+    PurchaseDateSkew,
+    MaxPurchaseAge(String),
+    CoolingOffSecs,
+    EditWindowSecs,
+    Tombstone(u64),
+    ArchivedOwnerWarranties(Address),
+    OutboxEntry(u64),
+    OutboxHead,
+    OutboxTail,
+    PreRegistration(Address, BytesN<32>),
+    ActivationStats(String),
+    PendingHandover(u64),
+    PendingTransfer(u64),
+    Handovers(u64),
+    ViewerGrants(u64),
+    BatchWarranties(String, String),
+    TransferHistory(u64),
+    DeviceKey(u64),
+    DeviceNonce(u64),
+    LogisticsProvider(Address),
+    ComplianceTrail(u64),
+    CoverageTerms(u64),
+    DepreciationCurve(String, String),
+    AnalyticsEnabled,
+    StatutoryMinimum(String, Option<String>),
+    DeploymentConfig,
+    EventVerbosity,
+    /// Latest warranty registered for a manufacturer's serial number
+    SerialIndex(String, String),
+    /// Warranties an address holds as co-owner
+    CoOwnedWarranties(Address),
+    /// Warranties registered for a catalog model
+    ModelWarranties(String),
+}
+
+/// Top up the TTL of a persistent entry once it runs low.
+pub(crate) fn bump_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let extend_to = WARRANTY_TTL_EXTEND_TO.min(env.storage().max_ttl());
+    env.storage()
+        .persistent()
+        .extend_ttl(key, WARRANTY_TTL_THRESHOLD.min(extend_to), extend_to);
+}
+
+/// Give a persistent entry that was just written the full default TTL.
+///
+/// New entries start at the network minimum, which is above
+/// `WARRANTY_TTL_THRESHOLD`, so `bump_persistent` alone would leave them
+/// there.
+pub(crate) fn extend_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let extend_to = WARRANTY_TTL_EXTEND_TO.min(env.storage().max_ttl());
+    env.storage()
+        .persistent()
+        .extend_ttl(key, extend_to, extend_to);
+}
+
+/// Read a record kept per warranty or per address.
+///
+/// Such records live in persistent storage, one entry each, so they do not
+/// add to the size of the instance entry as warranties accumulate.
+pub(crate) fn read_record<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+    V::Error: core::fmt::Debug,
+{
+    let value = env.storage().persistent().get(key)?;
+    bump_persistent(env, key);
+    Some(value)
+}
+
+/// Whether a record kept per warranty or per address exists.
+pub(crate) fn has_record<K: IntoVal<Env, Val>>(env: &Env, key: &K) -> bool {
+    env.storage().persistent().has(key)
+}
+
+/// Write a record kept per warranty or per address to persistent storage.
+pub(crate) fn write_record<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    extend_persistent(env, key);
+}
+
+/// Delete a record kept per warranty or per address.
+pub(crate) fn remove_record<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage().persistent().remove(key);
+}
+
+/// The transfer offer waiting on `warranty_id`, or None once the recipient
+/// has let `TRANSFER_OFFER_SECS` pass.
+///
+/// Offers are temporary entries that lapse on their own.
+pub(crate) fn pending_transfer(env: &Env, warranty_id: u64) -> Option<PendingTransfer> {
+    let pending: PendingTransfer = env
+        .storage()
+        .temporary()
+        .get(&DataKey::PendingTransfer(warranty_id))?;
+    let deadline = pending.initiated_at.saturating_add(TRANSFER_OFFER_SECS);
+    (env.ledger().timestamp() <= deadline).then_some(pending)
+}
+
+/// Record a transfer offer, replacing any pending one.
+pub(crate) fn store_pending_transfer(env: &Env, warranty_id: u64, pending: &PendingTransfer) {
+    let key = DataKey::PendingTransfer(warranty_id);
+    let ttl = TRANSFER_OFFER_TTL_LEDGERS.min(env.storage().max_ttl());
+    env.storage().temporary().set(&key, pending);
+    env.storage().temporary().extend_ttl(&key, ttl, ttl);
+}
+
+pub(crate) fn remove_pending_transfer(env: &Env, warranty_id: u64) {
+    env.storage()
+        .temporary()
+        .remove(&DataKey::PendingTransfer(warranty_id));
+}
+
+/// Read a warranty record as stored, without applying visibility rules.
+///
+/// Records not yet moved out of the legacy instance map are read from there.
+pub(crate) fn load_warranty(env: &Env, warranty_id: u64) -> Option<WarrantyData> {
+    let key = DataKey::Warranty(warranty_id);
+    if let Some(warranty) = env.storage().persistent().get(&key) {
+        bump_persistent(env, &key);
+        return Some(warranty);
+    }
+    let legacy: Map<u64, LegacyWarrantyDataV1> =
+        env.storage().instance().get(&DataKey::WarrantyData)?;
+    legacy
+        .get(warranty_id)
+        .map(|warranty| warranty.upgrade(env))
+}
+
+/// Read a warranty record that must exist, failing with `NotFound`.
+pub(crate) fn require_warranty(env: &Env, warranty_id: u64) -> Result<WarrantyData, WarrantyError> {
+    load_warranty(env, warranty_id).ok_or(WarrantyError::NotFound)
+}
+
+/// Move one warranty record between the per-status counters behind
+/// `get_stats`, and in or out of its manufacturer's Active count; `None`
+/// stands for a record being added or removed.
+pub(crate) fn move_status_count(
+    env: &Env,
+    manufacturer: &String,
+    from: Option<WarrantyStatus>,
+    to: Option<WarrantyStatus>,
+) {
+    let was_active = from == Some(WarrantyStatus::Active);
+    let is_active = to == Some(WarrantyStatus::Active);
+    if was_active != is_active {
+        let key = ObligationKey::ActiveWarranties(manufacturer.clone());
+        let count: u64 = read_record(env, &key).unwrap_or(0);
+        let count = if is_active {
+            count.saturating_add(1)
+        } else {
+            count.saturating_sub(1)
+        };
+        write_record(env, &key, &count);
+    }
+    if let Some(from) = from {
+        let key = StatsKey::Status(from);
+        let count: u64 = read_record(env, &key).unwrap_or(0);
+        write_record(env, &key, &count.saturating_sub(1));
+    }
+    if let Some(to) = to {
+        let key = StatsKey::Status(to);
+        let count: u64 = read_record(env, &key).unwrap_or(0);
+        write_record(env, &key, &count.saturating_add(1));
+    }
+}
+
+/// Write a warranty record back under its ID.
+pub(crate) fn save_warranty(env: &Env, warranty: &WarrantyData) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Warranty(warranty.id), warranty);
+    extend_warranty_entries(
+        env,
+        warranty,
+        WARRANTY_TTL_EXTEND_TO.min(env.storage().max_ttl()),
+    );
+    drop_legacy_warranty(env, warranty.id);
+}
+
+/// Keep a warranty record, its owner index and any scheduled reversion or
+/// revocation live for at least `ledgers` more ledgers, noting how far the record now
+/// reaches.
+pub(crate) fn extend_warranty_entries(env: &Env, warranty: &WarrantyData, ledgers: u32) {
+    let storage = env.storage().persistent();
+    let live_key = TtlKey::LiveUntil(warranty.id);
+    let live_until = storage
+        .get(&live_key)
+        .unwrap_or(0)
+        .max(env.ledger().sequence().saturating_add(ledgers));
+    storage.set(&live_key, &live_until);
+    storage.extend_ttl(&live_key, ledgers, ledgers);
+
+    let key = DataKey::Warranty(warranty.id);
+    storage.extend_ttl(&key, ledgers, ledgers);
+    let index = DataKey::OwnerWarranties(warranty.owner.clone());
+    if storage.has(&index) {
+        storage.extend_ttl(&index, ledgers, ledgers);
+    }
+    let reversion = ReversionKey::Scheduled(warranty.id);
+    if storage.has(&reversion) {
+        storage.extend_ttl(&reversion, ledgers, ledgers);
+    }
+    let revocation = RevocationKey::Scheduled(warranty.id);
+    if storage.has(&revocation) {
+        storage.extend_ttl(&revocation, ledgers, ledgers);
+    }
+    let rent = TtlKey::Rent(warranty.id);
+    if storage.has(&rent) {
+        storage.extend_ttl(&rent, ledgers, ledgers);
+    }
+}
+
+/// How long a warranty record is known to stay live. Records saved before
+/// TTLs were tracked report the current ledger, so wallets prompt for an
+/// extension.
+pub(crate) fn ttl_status(env: &Env, warranty_id: u64) -> TtlStatus {
+    let current_ledger = env.ledger().sequence();
+    let live_until_ledger = env
+        .storage()
+        .persistent()
+        .get(&TtlKey::LiveUntil(warranty_id))
+        .unwrap_or(current_ledger);
+    TtlStatus {
+        live_until_ledger,
+        current_ledger,
+        expiring_soon: live_until_ledger.saturating_sub(current_ledger) < WARRANTY_TTL_THRESHOLD,
+    }
+}
+
+/// Delete a warranty record.
+pub(crate) fn delete_warranty_record(env: &Env, warranty_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Warranty(warranty_id));
+    env.storage()
+        .persistent()
+        .remove(&TtlKey::LiveUntil(warranty_id));
+    drop_legacy_warranty(env, warranty_id);
+}
+
+/// Remove a record from the legacy instance map, deleting the map once it
+/// is empty.
+///
+/// The per-status counters never included legacy records, so the record is
+/// counted under its stored status as it leaves the map.
+fn drop_legacy_warranty(env: &Env, warranty_id: u64) {
+    let Some(mut legacy) = env
+        .storage()
+        .instance()
+        .get::<_, Map<u64, LegacyWarrantyDataV1>>(&DataKey::WarrantyData)
+    else {
+        return;
+    };
+    let Some(warranty) = legacy.get(warranty_id) else {
+        return;
+    };
+    move_status_count(env, &warranty.manufacturer, None, Some(warranty.status));
+    legacy.remove(warranty_id);
+    if legacy.is_empty() {
+        env.storage().instance().remove(&DataKey::WarrantyData);
+    } else {
+        env.storage()
+            .instance()
+            .set(&DataKey::WarrantyData, &legacy);
+    }
+}
+
+/// Move up to `limit` records, and the indexes listing them, from the
+/// legacy instance map to persistent storage, counting each in the
+/// per-status counters. Returns how many records are still left in the
+/// legacy map.
+pub(crate) fn migrate_legacy_warranties(env: &Env, limit: u32) -> u32 {
+    let Some(mut legacy) = env
+        .storage()
+        .instance()
+        .get::<_, Map<u64, LegacyWarrantyDataV1>>(&DataKey::WarrantyData)
+    else {
+        return 0;
+    };
+
+    migrate_id_index(env);
+    let batch = legacy.values().slice(0..limit.min(legacy.len()));
+    for warranty in batch.iter() {
+        let warranty = warranty.upgrade(env);
+        migrate_index(env, &DataKey::OwnerWarranties(warranty.owner.clone()));
+        let key = DataKey::Warranty(warranty.id);
+        env.storage().persistent().set(&key, &warranty);
+        extend_persistent(env, &key);
+        move_status_count(
+            env,
+            &warranty.manufacturer,
+            None,
+            Some(warranty.status.clone()),
+        );
+        match serial_holder(
+            env,
+            &warranty.manufacturer,
+            &warranty.serial_number,
+            &warranty.product_name,
+            Some(warranty.id),
+        ) {
+            Some(holder_id) => open_serial_conflict(env, &warranty, holder_id),
+            None => record_serial(env, &warranty),
+        }
+        legacy.remove(warranty.id);
+    }
+
+    if legacy.is_empty() {
+        env.storage().instance().remove(&DataKey::WarrantyData);
+    } else {
+        env.storage()
+            .instance()
+            .set(&DataKey::WarrantyData, &legacy);
+    }
+    legacy.len()
+}
+
+/// Read a counterfeit report.
+pub(crate) fn load_report(env: &Env, report_id: u64) -> Option<CounterfeitReport> {
+    read_record(env, &CounterfeitKey::Report(report_id))
+}
+
+/// Write a counterfeit report.
+pub(crate) fn save_report(env: &Env, report: &CounterfeitReport) {
+    write_record(env, &CounterfeitKey::Report(report.id), report);
+}
+
+/// Number of counterfeit reports ever filed.
+pub(crate) fn report_count(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&CounterfeitKey::Count)
+        .unwrap_or(0)
+}
+
+/// Number of counterfeit reports awaiting review.
+pub(crate) fn pending_report_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&CounterfeitKey::Pending)
+        .unwrap_or(0)
+}
+
+/// Add a report to the pending count, `filed` for a new report and not for
+/// a resolved one.
+pub(crate) fn count_pending_report(env: &Env, filed: bool) {
+    let pending = pending_report_count(env);
+    let pending = if filed {
+        pending + 1
+    } else {
+        pending.saturating_sub(1)
+    };
+    env.storage()
+        .instance()
+        .set(&CounterfeitKey::Pending, &pending);
+}
+
+/// Slice one page out of the counterfeit reports, starting after the
+/// cursor, keeping those still awaiting review.
+///
+/// `limit` report IDs are examined per page, so a page can hold fewer IDs
+/// than `limit`, or none, while `next` is still set.
+pub(crate) fn pending_report_page(env: &Env, cursor: Option<Cursor>, limit: u32) -> WarrantyIdPage {
+    let count = report_count(env);
+    let start = cursor.map_or(1, |cursor| cursor.last_id.saturating_add(1));
+    let end = start.saturating_add(limit as u64 - 1).min(count);
+
+    let mut ids = Vec::new(env);
+    for report_id in start..=end {
+        if load_report(env, report_id).is_some_and(|report| report.status == ReportStatus::Pending)
+        {
+            ids.push_back(report_id);
+        }
+    }
+    let next = (end < count).then_some(Cursor {
+        last_id: end,
+        bucket: 0,
+    });
+
+    WarrantyIdPage {
+        ids,
+        next: next.into(),
+    }
+}
+
+/// Get the configured read limits, falling back to the defaults.
+pub(crate) fn read_limits(env: &Env) -> ReadLimits {
+    env.storage()
+        .instance()
+        .get(&DataKey::ReadLimits)
+        .unwrap_or(ReadLimits {
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+        })
+}
+
+pub(crate) fn registration_limits(env: &Env) -> RegistrationLimits {
+    env.storage()
+        .instance()
+        .get(&RateLimitKey::Limits)
+        .unwrap_or(RegistrationLimits {
+            per_owner_daily: None,
+            global_daily: None,
+        })
+}
+
+/// The ledger day registration quotas are counted in.
+pub(crate) fn quota_day(env: &Env) -> u64 {
+    env.ledger().timestamp() / QUOTA_DAY_SECS
+}
+
+fn quota_used(env: &Env, key: &RateLimitKey) -> u32 {
+    env.storage().temporary().get(key).unwrap_or(0)
+}
+
+fn use_quota(env: &Env, key: &RateLimitKey, cap: Option<u32>) -> Result<(), WarrantyError> {
+    let used = quota_used(env, key);
+    if cap.is_some_and(|cap| used >= cap) {
+        return Err(WarrantyError::RateLimited);
+    }
+    env.storage().temporary().set(key, &(used + 1));
+    env.storage()
+        .temporary()
+        .extend_ttl(key, QUOTA_TTL_LEDGERS, QUOTA_TTL_LEDGERS);
+    Ok(())
+}
+
+/// Count a registration for `owner` against today's caps, failing with
+/// `RateLimited` once one is used up. Warranties the contract holds
+/// itself, pending a claim code, only count against the global cap.
+pub(crate) fn use_registration_quota(env: &Env, owner: &Address) -> Result<(), WarrantyError> {
+    let limits = registration_limits(env);
+    let day = quota_day(env);
+    use_quota(env, &RateLimitKey::Global(day), limits.global_daily)?;
+    if *owner != env.current_contract_address() {
+        use_quota(
+            env,
+            &RateLimitKey::Owner(owner.clone(), day),
+            limits.per_owner_daily,
+        )?;
+    }
+    Ok(())
+}
+
+/// Count a counterfeit report filed by `reporter` against today's cap,
+/// failing with `RateLimited` once it is used up.
+pub(crate) fn use_report_quota(env: &Env, reporter: &Address) -> Result<(), WarrantyError> {
+    use_quota(
+        env,
+        &RateLimitKey::Reports(reporter.clone(), quota_day(env)),
+        Some(MAX_DAILY_REPORTS),
+    )
+}
+
+/// What `owner` and the deployment have left of today's caps.
+pub(crate) fn remaining_quota(env: &Env, owner: &Address) -> RemainingQuota {
+    let limits = registration_limits(env);
+    let day = quota_day(env);
+    let left = |cap: Option<u32>, key: RateLimitKey| {
+        cap.map(|cap| cap.saturating_sub(quota_used(env, &key)))
+    };
+    RemainingQuota {
+        owner: left(
+            limits.per_owner_daily,
+            RateLimitKey::Owner(owner.clone(), day),
+        ),
+        global: left(limits.global_daily, RateLimitKey::Global(day)),
+        resets_at: (day + 1) * QUOTA_DAY_SECS,
+    }
+}
+
+/// White-label settings, or the stock ones if none were given at deployment.
+pub(crate) fn deployment_config(env: &Env) -> DeploymentConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::DeploymentConfig)
+        .unwrap_or(DeploymentConfig {
+            name: String::from_str(env, CONTRACT_NAME),
+            default_issuer: None,
+            features: u64::MAX,
+            extra_features: u64::MAX,
+        })
+}
+
+/// Whether `feature` is built in and enabled for this deployment.
+pub(crate) fn feature_enabled(env: &Env, feature: &str) -> bool {
+    let Some(bit) = SUPPORTED_FEATURES.iter().position(|name| *name == feature) else {
+        return false;
+    };
+    let config = deployment_config(env);
+    let word = if bit < 64 {
+        config.features
+    } else {
+        config.extra_features
+    };
+    word & (1 << (bit % 64)) != 0
+}
+
+/// Stop state-changing entry points while the admin has paused the contract.
+pub(crate) fn require_not_paused(env: &Env) -> Result<(), WarrantyError> {
+    if env.storage().instance().has(&PauseKey::Paused) {
+        return Err(WarrantyError::Paused);
+    }
+    Ok(())
+}
+
+/// Stop transfers and claims of a warranty the admin has frozen.
+pub(crate) fn require_not_frozen(env: &Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    if has_record(env, &PauseKey::Frozen(warranty_id)) {
+        return Err(WarrantyError::Frozen);
+    }
+    Ok(())
+}
+
+/// Stop entry points of features the deployment switched off.
+pub(crate) fn require_feature(env: &Env, feature: &str) -> Result<(), WarrantyError> {
+    if !feature_enabled(env, feature) {
+        return Err(WarrantyError::FeatureDisabled);
+    }
+    Ok(())
+}
+
+/// Validate a requested page size against the read limits.
+pub(crate) fn check_page_size(env: &Env, limit: u32) -> Result<(), WarrantyError> {
+    if limit == 0 {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if limit > read_limits(env).max_page_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    Ok(())
+}
+
+/// Copy of an ID index with every occurrence of `id` removed.
+pub(crate) fn without_id(env: &Env, ids: &Vec<u64>, id: u64) -> Vec<u64> {
+    let mut remaining = Vec::new(env);
+    for existing in ids.iter() {
+        if existing != id {
+            remaining.push_back(existing);
+        }
+    }
+    remaining
+}
+
+/// Validate an inclusive ID range against the batch size limit.
+pub(crate) fn check_batch_range(env: &Env, from_id: u64, to_id: u64) -> Result<(), WarrantyError> {
+    if to_id < from_id {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if to_id - from_id >= read_limits(env).max_batch_size as u64 {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    Ok(())
+}
+
+/// Insert `id` into an ascending ID index, keeping it sorted.
+pub(crate) fn insert_sorted(ids: &mut Vec<u64>, id: u64) {
+    if let Err(pos) = ids.binary_search(id) {
+        ids.insert(pos, id);
+    }
+}
+
+/// Slice one page out of an ascending ID index, starting after the cursor.
+pub(crate) fn page_ids(ids: &Vec<u64>, cursor: Option<Cursor>, limit: u32) -> WarrantyIdPage {
+    let start = match cursor {
+        Some(cursor) => match ids.binary_search(cursor.last_id) {
+            Ok(pos) => pos + 1,
+            Err(pos) => pos,
+        },
+        None => 0,
+    };
+    let end = start.saturating_add(limit).min(ids.len());
+
+    let page = ids.slice(start..end);
+    let next = if end < ids.len() {
+        Some(Cursor {
+            last_id: ids.get_unchecked(end - 1),
+            bucket: 0,
+        })
+    } else {
+        None
+    };
+
+    WarrantyIdPage {
+        ids: page,
+        next: next.into(),
+    }
+}
+
+/// Read the ID index stored under `key`.
+///
+/// Owner indexes the first release kept in instance storage are read from
+/// there until `migrate_storage` moves them.
+pub(crate) fn read_index(env: &Env, key: &DataKey) -> Vec<u64> {
+    if let Some(ids) = env.storage().persistent().get(key) {
+        bump_persistent(env, key);
+        return ids;
+    }
+    match key {
+        DataKey::OwnerWarranties(_) => legacy_index(env, key).unwrap_or(Vec::new(env)),
+        _ => Vec::new(env),
+    }
+}
+
+/// Write the ID index stored under `key` to persistent storage, dropping
+/// the first release's copy of an owner index.
+pub(crate) fn write_index(env: &Env, key: &DataKey, ids: &Vec<u64>) {
+    env.storage().persistent().set(key, ids);
+    extend_persistent(env, key);
+    if matches!(key, DataKey::OwnerWarranties(_)) && env.storage().instance().has(key) {
+        env.storage().instance().remove(key);
+    }
+}
+
+/// An ID index as the first release kept it in instance storage, sorted.
+///
+/// Those indexes were appended to in the order warranties arrived, e.g. a
+/// transfer added an older ID at the end, so they are sorted on the way in.
+/// Everything written since keeps the index ascending.
+fn legacy_index(env: &Env, key: &DataKey) -> Option<Vec<u64>> {
+    let legacy: Vec<u64> = env.storage().instance().get(key)?;
+    let mut ids = Vec::new(env);
+    for id in legacy.iter() {
+        insert_sorted(&mut ids, id);
+    }
+    Some(ids)
+}
+
+/// Move an owner index to persistent storage, if it is still in the first
+/// release's layout.
+fn migrate_index(env: &Env, key: &DataKey) {
+    if let Some(ids) = legacy_index(env, key) {
+        write_index(env, key, &ids);
+    }
+}
+
+/// Add `id` to the ascending ID index stored under `key`.
+pub(crate) fn index_add(env: &Env, key: &DataKey, id: u64) {
+    let mut ids = read_index(env, key);
+    insert_sorted(&mut ids, id);
+    write_index(env, key, &ids);
+}
+
+/// Remove `id` from the ID index stored under `key`, returning whether it
+/// was listed there.
+pub(crate) fn index_remove(env: &Env, key: &DataKey, id: u64) -> bool {
+    let ids = read_index(env, key);
+    if !ids.contains(id) {
+        return false;
+    }
+    write_index(env, key, &without_id(env, &ids, id));
+    true
+}
+
+/// Move `id` from the ID index under `from` to the one under `to`.
+pub(crate) fn index_move(env: &Env, from: &DataKey, to: &DataKey, id: u64) {
+    index_remove(env, from, id);
+    index_add(env, to, id);
+}
+
+/// IDs of the warranties `owner` holds, alone or as co-owner, ascending.
+pub(crate) fn held_warranties(env: &Env, owner: &Address) -> Vec<u64> {
+    let mut warranty_ids = read_index(env, &DataKey::OwnerWarranties(owner.clone()));
+    for warranty_id in read_index(env, &DataKey::CoOwnedWarranties(owner.clone())).iter() {
+        insert_sorted(&mut warranty_ids, warranty_id);
+    }
+    warranty_ids
+}
+
+/// The first release's single-entry global ID index, while it is still in
+/// use.
+fn legacy_id_index(env: &Env) -> Option<Vec<u64>> {
+    legacy_index(env, &DataKey::WarrantyIds)
+}
+
+/// Split the legacy global ID index into buckets, if it is still in the
+/// old layout.
+fn migrate_id_index(env: &Env) {
+    let Some(ids) = legacy_id_index(env) else {
+        return;
+    };
+    env.storage().instance().remove(&DataKey::WarrantyIds);
+    for warranty_id in ids.iter() {
+        append_id(env, warranty_id);
+    }
+}
+
+fn append_id(env: &Env, warranty_id: u64) {
+    let storage = env.storage().instance();
+    let slot: u64 = storage.get(&IdIndexKey::Slots).unwrap_or(0);
+    let bucket = (slot / ID_BUCKET_SIZE) as u32;
+    let key = IdIndexKey::Bucket(bucket);
+    let mut ids = read_record(env, &key).unwrap_or(Vec::new(env));
+    insert_sorted(&mut ids, warranty_id);
+    write_record(env, &key, &ids);
+    write_record(env, &IdIndexKey::BucketOf(warranty_id), &bucket);
+    storage.set(&IdIndexKey::Slots, &(slot + 1));
+    storage.set(&IdIndexKey::Len, &(id_index_len(env) + 1));
+}
+
+/// List `warranty_id` in the global ID index.
+pub(crate) fn id_index_add(env: &Env, warranty_id: u64) {
+    migrate_id_index(env);
+    if !has_record(env, &IdIndexKey::BucketOf(warranty_id)) {
+        append_id(env, warranty_id);
+    }
+}
+
+/// Drop `warranty_id` from the global ID index.
+pub(crate) fn id_index_remove(env: &Env, warranty_id: u64) {
+    migrate_id_index(env);
+    let bucket_key = IdIndexKey::BucketOf(warranty_id);
+    let Some(bucket) = read_record::<_, u32>(env, &bucket_key) else {
+        return;
+    };
+    let key = IdIndexKey::Bucket(bucket);
+    let ids = read_record(env, &key).unwrap_or(Vec::new(env));
+    write_record(env, &key, &without_id(env, &ids, warranty_id));
+    remove_record(env, &bucket_key);
+    env.storage()
+        .instance()
+        .set(&IdIndexKey::Len, &id_index_len(env).saturating_sub(1));
+}
+
+/// Number of IDs the global index lists.
+pub(crate) fn id_index_len(env: &Env) -> u32 {
+    if let Some(ids) = legacy_id_index(env) {
+        return ids.len();
+    }
+    env.storage().instance().get(&IdIndexKey::Len).unwrap_or(0)
+}
+
+/// Slice one page out of the global ID index, starting after the cursor.
+///
+/// Buckets are read in order, each ascending, and at most `limit` of them
+/// per call, so a page can hold fewer IDs than `limit` while `next` is set.
+pub(crate) fn id_index_page(env: &Env, cursor: Option<Cursor>, limit: u32) -> WarrantyIdPage {
+    if let Some(ids) = legacy_id_index(env) {
+        return page_ids(&ids, cursor, limit);
+    }
+    let slots: u64 = env
+        .storage()
+        .instance()
+        .get(&IdIndexKey::Slots)
+        .unwrap_or(0);
+    let buckets = slots.div_ceil(ID_BUCKET_SIZE) as u32;
+    let (mut bucket, mut after) = match cursor {
+        Some(cursor) => (cursor.bucket, Some(cursor.last_id)),
+        None => (0, None),
+    };
+
+    let mut page = Vec::new(env);
+    let mut examined = 0;
+    while bucket < buckets && page.len() < limit && examined < limit {
+        let ids: Vec<u64> = read_record(env, &IdIndexKey::Bucket(bucket)).unwrap_or(Vec::new(env));
+        let start = match after {
+            Some(last_id) => match ids.binary_search(last_id) {
+                Ok(pos) => pos + 1,
+                Err(pos) => pos,
+            },
+            None => 0,
+        };
+        let end = start.saturating_add(limit - page.len()).min(ids.len());
+        for warranty_id in ids.slice(start..end).iter() {
+            page.push_back(warranty_id);
+        }
+        if end < ids.len() {
+            let next = Cursor {
+                last_id: ids.get_unchecked(end - 1),
+                bucket,
+            };
+            return WarrantyIdPage {
+                ids: page,
+                next: NextPage::At(next),
+            };
+        }
+        bucket += 1;
+        after = None;
+        examined += 1;
+    }
+
+    // IDs start at 1, so a `last_id` of 0 resumes at the start of a bucket
+    let next = (bucket < buckets).then_some(Cursor { last_id: 0, bucket });
+    WarrantyIdPage {
+        ids: page,
+        next: next.into(),
+    }
+}
+
+/// Move `warranty_id` from one owner's index to another's.
+pub(crate) fn move_owner_index(env: &Env, warranty_id: u64, from: &Address, to: &Address) {
+    index_remove(
+        env,
+        &DataKey::ArchivedOwnerWarranties(from.clone()),
+        warranty_id,
+    );
+    index_move(
+        env,
+        &DataKey::OwnerWarranties(from.clone()),
+        &DataKey::OwnerWarranties(to.clone()),
+        warranty_id,
+    );
+}
+
+/// Put a reactivated warranty back into its owner's hot index.
+pub(crate) fn unarchive_warranty(env: &Env, owner: &Address, warranty_id: u64) {
+    if index_remove(
+        env,
+        &DataKey::ArchivedOwnerWarranties(owner.clone()),
+        warranty_id,
+    ) {
+        index_add(env, &DataKey::OwnerWarranties(owner.clone()), warranty_id);
+    }
+}
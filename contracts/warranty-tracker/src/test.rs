@@ -1,17 +1,18 @@
 #![cfg(test)]
+extern crate std;
 
 use super::*;
+use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
-    testutils::Address as _, testutils::Ledger, testutils::LedgerInfo, Address, Env, String,
+    map, symbol_short, testutils::storage::Persistent as _, testutils::Address as _,
+    testutils::Ledger, testutils::LedgerInfo, token, vec, Address, Env, Error, IntoVal, String,
 };
 
-#[test]
-fn test_register_warranty() {
+/// Fresh environment with the ledger settings every test starts from
+fn test_env(timestamp: u64) -> Env {
     let env = Env::default();
-    let base_timestamp: u64 = 1704067200;
-    let current_time = base_timestamp + 86400; // 1 day after base
     env.ledger().set(LedgerInfo {
-        timestamp: current_time,
+        timestamp,
         protocol_version: 23,
         sequence_number: 0,
         network_id: [0; 32],
@@ -20,8 +21,24 @@ fn test_register_warranty() {
         min_persistent_entry_ttl: 86400 * 30, // 30 days
         min_temp_entry_ttl: 86400 * 7,        // 7 days
     });
+    env
+}
+
+/// Move the ledger clock forward, e.g. past an expiry or a cooldown
+fn advance_time(env: &Env, secs: u64) {
+    env.ledger().with_mut(|li| li.timestamp += secs);
+}
+
+#[test]
+fn test_register_warranty() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400; // 1 day after base
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -34,11 +51,13 @@ fn test_register_warranty() {
     env.mock_all_auths();
     let warranty_id = client.register_warranty(
         &owner,
-        &product_name,
-        &serial_number,
-        &manufacturer,
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            product_name.clone(),
+            serial_number.clone(),
+            manufacturer.clone(),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     assert_eq!(warranty_id, 1);
@@ -57,21 +76,14 @@ fn test_register_warranty() {
 
 #[test]
 fn test_get_warranties_by_owner() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner1 = Address::generate(&env);
@@ -83,58 +95,57 @@ fn test_get_warranties_by_owner() {
 
     let warranty1_id = client.register_warranty(
         &owner1,
-        &String::from_str(&env, "Product1"),
-        &String::from_str(&env, "SN1"),
-        &String::from_str(&env, "Manufacturer1"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product1"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer1"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     let warranty2_id = client.register_warranty(
         &owner1,
-        &String::from_str(&env, "Product2"),
-        &String::from_str(&env, "SN2"),
-        &String::from_str(&env, "Manufacturer2"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product2"),
+            String::from_str(&env, "SN2"),
+            String::from_str(&env, "Manufacturer2"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     let warranty3_id = client.register_warranty(
         &owner2,
-        &String::from_str(&env, "Product3"),
-        &String::from_str(&env, "SN3"),
-        &String::from_str(&env, "Manufacturer3"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product3"),
+            String::from_str(&env, "SN3"),
+            String::from_str(&env, "Manufacturer3"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
-    let owner1_warranties = client.get_warranties_by_owner(&owner1);
+    let owner1_warranties = client.get_warranties_by_owner(&owner1, &None);
     assert_eq!(owner1_warranties.len(), 2);
-    assert!(owner1_warranties.contains(&warranty1_id));
-    assert!(owner1_warranties.contains(&warranty2_id));
+    assert!(owner1_warranties.contains(warranty1_id));
+    assert!(owner1_warranties.contains(warranty2_id));
 
-    let owner2_warranties = client.get_warranties_by_owner(&owner2);
+    let owner2_warranties = client.get_warranties_by_owner(&owner2, &None);
     assert_eq!(owner2_warranties.len(), 1);
     assert_eq!(owner2_warranties.get(0).unwrap(), warranty3_id);
 }
 
 #[test]
 fn test_update_status() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -145,39 +156,151 @@ fn test_update_status() {
 
     let warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     let warranty = client.get_warranty(&warranty_id).unwrap();
     assert_eq!(warranty.status, WarrantyStatus::Active);
 
-    client.update_status(&warranty_id, &WarrantyStatus::Revoked);
+    client.revoke_warranty(&warranty_id, &None);
 
     let warranty = client.get_warranty(&warranty_id).unwrap();
     assert_eq!(warranty.status, WarrantyStatus::Revoked);
+
+    // Once revoked, only the manufacturer can move the warranty again
+    assert_eq!(
+        client.try_cancel_warranty(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    let returned_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN456"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    client.cancel_warranty(&returned_id, &None);
+
+    let warranty = client.get_warranty(&returned_id).unwrap();
+    assert_eq!(warranty.status, WarrantyStatus::Cancelled);
+}
+
+#[test]
+fn test_status_transitions() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(
+        &String::from_str(&env, "Manufacturer"),
+        &Some(maker.clone()),
+    );
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    // Owners can let a warranty lapse, but only its manufacturer revives it
+    client.set_to_expired(&warranty_id, &None);
+    assert_eq!(
+        client.try_set_to_active(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.reactivate_warranty(&warranty_id, &maker, &None);
+
+    // but a revoked warranty only comes back through its manufacturer
+    client.revoke_warranty(&warranty_id, &None);
+    assert_eq!(
+        client.try_set_to_active(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert!(client
+        .get_allowed_actions(&warranty_id, &maker)
+        .contains(Symbol::new(&env, "reactivate_warranty")));
+    assert_eq!(
+        client.try_reactivate_warranty(&warranty_id, &stranger, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.reactivate_warranty(&warranty_id, &maker, &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().status,
+        WarrantyStatus::Active
+    );
+
+    client.suspend_warranty(&warranty_id, &maker, &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().status,
+        WarrantyStatus::Suspended
+    );
+    assert_eq!(
+        client.try_set_to_active(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_mark_replaced(&warranty_id, &maker, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.reactivate_warranty(&warranty_id, &maker, &None);
+
+    // Replaced is final
+    client.mark_replaced(&warranty_id, &maker, &None);
+    assert_eq!(
+        client.try_set_to_active(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_revoke_warranty(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_reactivate_warranty(&warranty_id, &maker, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert!(!client
+        .get_allowed_actions(&warranty_id, &owner)
+        .contains(Symbol::new(&env, "set_to_active")));
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().status,
+        WarrantyStatus::Replaced
+    );
 }
 
 #[test]
 fn test_transfer_ownership() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner1 = Address::generate(&env);
@@ -189,47 +312,323 @@ fn test_transfer_ownership() {
 
     let warranty_id = client.register_warranty(
         &owner1,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
-    let owner1_warranties = client.get_warranties_by_owner(&owner1);
+    let owner1_warranties = client.get_warranties_by_owner(&owner1, &None);
     assert_eq!(owner1_warranties.len(), 1);
 
-    client.transfer_ownership(&warranty_id, &owner2);
+    client.transfer_ownership(&warranty_id, &owner2, &None);
 
     let warranty = client.get_warranty(&warranty_id).unwrap();
     assert_eq!(warranty.owner, owner2);
 
-    let owner1_warranties_after = client.get_warranties_by_owner(&owner1);
+    let owner1_warranties_after = client.get_warranties_by_owner(&owner1, &None);
     assert_eq!(owner1_warranties_after.len(), 0);
 
-    let owner2_warranties = client.get_warranties_by_owner(&owner2);
+    let owner2_warranties = client.get_warranties_by_owner(&owner2, &None);
     assert_eq!(owner2_warranties.len(), 1);
     assert_eq!(owner2_warranties.get(0).unwrap(), warranty_id);
 }
 
 #[test]
-#[should_panic(expected = "cannot transfer non-active warranty")]
-fn test_transfer_revoked_warranty() {
-    let env = Env::default();
+fn test_transfer_uses_effective_status() {
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let register = |serial: &str, expiration_date: u64| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                expiration_date,
+            ),
+        )
+    };
+    let lapsing = register("SN1", current_time + 1000);
+    let stale = register("SN2", current_time + 31536000);
+    client.set_to_expired(&stale, &None);
+
+    // Still stored as Active, but past its expiration date
+    advance_time(&env, 1001);
+    assert_eq!(
+        client.get_warranty(&lapsing).unwrap().status,
+        WarrantyStatus::Active
+    );
+    assert_eq!(
+        client.try_transfer_ownership(&lapsing, &buyer, &None),
+        Err(Ok(WarrantyError::NotActive))
+    );
+    assert_eq!(
+        client.try_file_claim(
+            &lapsing,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Cracked screen"),
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotActive))
+    );
+
+    // Expired warranties are never revived just because their dates allow it
+    assert_eq!(
+        client.try_transfer_ownership(&stale, &buyer, &None),
+        Err(Ok(WarrantyError::NotActive))
+    );
+    assert_eq!(
+        client.get_warranty(&stale).unwrap().status,
+        WarrantyStatus::Expired
+    );
+}
+
+#[test]
+fn test_extend_warranty() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            base_timestamp,
+            current_time + 1000,
+        ),
+    );
+    advance_time(&env, 1001);
+    let now = env.ledger().timestamp();
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &buyer, &None),
+        Err(Ok(WarrantyError::NotActive))
+    );
+
+    assert_eq!(
+        client.try_extend_warranty(&warranty_id, &owner, &(now + 86400), &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_extend_warranty(&warranty_id, &issuer, &now, &None),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    // Extending past now puts the warranty back in force
+    client.extend_warranty(&warranty_id, &issuer, &(now + 86400), &None);
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.status, WarrantyStatus::Active);
+    assert_eq!(warranty.expiration_date, now + 86400);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, buyer);
+
+    // Revoked warranties need reactivate_warranty instead
+    client.revoke_warranty(&warranty_id, &None);
+    assert_eq!(
+        client.try_extend_warranty(&warranty_id, &issuer, &(now + 2 * 86400), &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_two_step_transfer() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let typo = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    assert_eq!(
+        client.try_accept_transfer(&warranty_id, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    // Nothing moves until the recipient accepts
+    client.initiate_transfer(&warranty_id, &typo, &None);
+    assert_eq!(
+        client.get_pending_transfer(&warranty_id),
+        Some(PendingTransfer {
+            from: owner.clone(),
+            to: typo.clone(),
+            initiated_at: current_time,
+        })
+    );
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+    client.cancel_transfer(&warranty_id, &None);
+    assert_eq!(client.get_pending_transfer(&warranty_id), None);
+
+    client.initiate_transfer(&warranty_id, &recipient, &None);
+    let decline_ref = BytesN::from_array(&env, &[4; 16]);
+    assert_eq!(
+        client.decline_transfer(&warranty_id, &Some(decline_ref.clone())),
+        MutationReceipt {
+            warranty_ids: vec![&env, warranty_id],
+            changes: 1,
+            warranty_count: 1,
+        }
+    );
+    assert_eq!(client.get_pending_transfer(&warranty_id), None);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+
+    // Offers, withdrawals and refusals reach the outbox as well
+    let actions: std::vec::Vec<Symbol> = client
+        .read_outbox(&None, &10)
+        .entries
+        .iter()
+        .map(|entry| entry.event_type)
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            symbol_short!("register"),
+            symbol_short!("offer"),
+            symbol_short!("withdraw"),
+            symbol_short!("offer"),
+            symbol_short!("decline"),
+        ]
+    );
+    let log = client.read_audit_log(&None, &10).entries;
+    assert_eq!(log.last().unwrap().correlation_id, Some(decline_ref));
+
+    client.initiate_transfer(&warranty_id, &recipient, &None);
+    assert!(client
+        .get_allowed_actions(&warranty_id, &recipient)
+        .contains(Symbol::new(&env, "accept_transfer")));
+    client.accept_transfer(&warranty_id, &None);
+
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, recipient);
+    assert_eq!(client.get_pending_transfer(&warranty_id), None);
+    assert!(client.get_warranties_by_owner(&owner, &None).is_empty());
+    assert_eq!(
+        client.get_warranties_by_owner(&recipient, &None),
+        vec![&env, warranty_id]
+    );
+    assert_eq!(client.get_transfer_history(&warranty_id).len(), 1);
+
+    // Transferring by other means drops the offer
+    client.initiate_transfer(&warranty_id, &typo, &None);
+    client.transfer_ownership(&warranty_id, &owner, &None);
+    assert_eq!(client.get_pending_transfer(&warranty_id), None);
+
+    // Offers are temporary entries and lapse once the window has passed
+    client.initiate_transfer(&warranty_id, &recipient, &None);
+    env.as_contract(&contract_id, || {
+        let key = DataKey::PendingTransfer(warranty_id);
+        assert!(env.storage().temporary().has(&key));
+        assert!(!env.storage().instance().has(&key));
     });
+    advance_time(&env, TRANSFER_OFFER_SECS + 1);
+    assert_eq!(client.get_pending_transfer(&warranty_id), None);
+    assert_eq!(
+        client.try_accept_transfer(&warranty_id, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+}
+
+#[test]
+fn test_prove_ownership() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let challenge = BytesN::from_array(&env, &[42; 32]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    // Answering a challenge counts as owner activity
+    advance_time(&env, 3600);
+    client.prove_ownership(&warranty_id, &challenge);
+    assert_eq!(client.get_last_activity(&owner), Some(current_time + 3600));
+
+    assert_eq!(
+        client.try_prove_ownership(&99, &challenge),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")] // NotActive
+fn test_transfer_revoked_warranty() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner1 = Address::generate(&env);
@@ -241,35 +640,30 @@ fn test_transfer_revoked_warranty() {
 
     let warranty_id = client.register_warranty(
         &owner1,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
-    client.revoke_warranty(&warranty_id);
+    client.revoke_warranty(&warranty_id, &None);
 
-    client.transfer_ownership(&warranty_id, &owner2);
+    client.transfer_ownership(&warranty_id, &owner2, &None);
 }
 
 #[test]
 fn test_revoke_warranty() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -280,14 +674,16 @@ fn test_revoke_warranty() {
 
     let warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
-    client.revoke_warranty(&warranty_id);
+    client.revoke_warranty(&warranty_id, &None);
 
     let warranty = client.get_warranty(&warranty_id).unwrap();
     assert_eq!(warranty.status, WarrantyStatus::Revoked);
@@ -295,21 +691,14 @@ fn test_revoke_warranty() {
 
 #[test]
 fn test_is_warranty_expired() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 31536000; // 1 year after base
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -322,20 +711,24 @@ fn test_is_warranty_expired() {
 
     let expired_warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "OldProduct"),
-        &String::from_str(&env, "SN1"),
-        &String::from_str(&env, "Manufacturer"),
-        &expired_purchase,
-        &expired_expiration,
+        &WarrantyInput::new(
+            String::from_str(&env, "OldProduct"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            expired_purchase,
+            expired_expiration,
+        ),
     );
 
     let active_warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "NewProduct"),
-        &String::from_str(&env, "SN2"),
-        &String::from_str(&env, "Manufacturer"),
-        &active_purchase,
-        &active_expiration,
+        &WarrantyInput::new(
+            String::from_str(&env, "NewProduct"),
+            String::from_str(&env, "SN2"),
+            String::from_str(&env, "Manufacturer"),
+            active_purchase,
+            active_expiration,
+        ),
     );
 
     assert!(client.is_warranty_expired(&expired_warranty_id));
@@ -343,22 +736,15 @@ fn test_is_warranty_expired() {
 }
 
 #[test]
-#[should_panic(expected = "expiration_date must be after purchase_date")]
+#[should_panic(expected = "Error(Contract, #8)")] // InvalidDates
 fn test_register_warranty_invalid_dates() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
-    env.ledger().set(LedgerInfo {
-        timestamp: base_timestamp + 259200,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(base_timestamp + 259200);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -369,31 +755,26 @@ fn test_register_warranty_invalid_dates() {
 
     client.register_warranty(
         &owner,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 }
 
 #[test]
 fn test_register_expired_warranty() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 31536000; // 1 year after base
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -404,11 +785,13 @@ fn test_register_expired_warranty() {
 
     let warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "OldProduct"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "OldProduct"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     let warranty = client.get_warranty(&warranty_id).unwrap();
@@ -417,21 +800,14 @@ fn test_register_expired_warranty() {
 
 #[test]
 fn test_set_to_active() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -442,41 +818,39 @@ fn test_set_to_active() {
 
     let warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
-    // First revoke it
-    client.revoke_warranty(&warranty_id);
+    // First expire it
+    client.set_to_expired(&warranty_id, &None);
     let warranty = client.get_warranty(&warranty_id).unwrap();
-    assert_eq!(warranty.status, WarrantyStatus::Revoked);
+    assert_eq!(warranty.status, WarrantyStatus::Expired);
 
-    // Then set it back to active
-    client.set_to_active(&warranty_id);
+    // Its owner cannot set it back to active
+    assert_eq!(
+        client.try_set_to_active(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
     let warranty = client.get_warranty(&warranty_id).unwrap();
-    assert_eq!(warranty.status, WarrantyStatus::Active);
+    assert_eq!(warranty.status, WarrantyStatus::Expired);
 }
 
 #[test]
 fn test_set_to_expired() {
-    let env = Env::default();
     let base_timestamp: u64 = 1704067200;
     let current_time = base_timestamp + 86400;
-    env.ledger().set(LedgerInfo {
-        timestamp: current_time,
-        protocol_version: 23,
-        sequence_number: 0,
-        network_id: [0; 32],
-        base_reserve: 1000000,
-        max_entry_ttl: 86400 * 365 * 10,      // 10 years
-        min_persistent_entry_ttl: 86400 * 30, // 30 days
-        min_temp_entry_ttl: 86400 * 7,        // 7 days
-    });
+    let env = test_env(current_time);
 
-    let contract_id = env.register(WarrantyTracker, ());
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
     let client = WarrantyTrackerClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
@@ -487,11 +861,13 @@ fn test_set_to_expired() {
 
     let warranty_id = client.register_warranty(
         &owner,
-        &String::from_str(&env, "Product"),
-        &String::from_str(&env, "SN123"),
-        &String::from_str(&env, "Manufacturer"),
-        &purchase_date,
-        &expiration_date,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
     );
 
     // Initially active
@@ -499,7 +875,11065 @@ fn test_set_to_expired() {
     assert_eq!(warranty.status, WarrantyStatus::Active);
 
     // Set it to expired
-    client.set_to_expired(&warranty_id);
+    client.set_to_expired(&warranty_id, &None);
     let warranty = client.get_warranty(&warranty_id).unwrap();
     assert_eq!(warranty.status, WarrantyStatus::Expired);
 }
+
+#[test]
+fn test_get_warranties_by_owner_page() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    // Ids 1, 2 and 4 belong to owner1, id 3 to owner2
+    for (i, owner) in [&owner1, &owner1, &owner2, &owner1].into_iter().enumerate() {
+        client.register_warranty(
+            owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                purchase_date,
+                expiration_date,
+            ),
+        );
+    }
+
+    let first_page = client.get_warranties_by_owner_page(&owner1, &None, &2);
+    assert_eq!(first_page.ids, vec![&env, 1, 2]);
+    let cursor = first_page.next.cursor().unwrap();
+    assert_eq!(cursor.last_id, 2);
+
+    // Id 3 is inserted behind the cursor; the next page must neither skip
+    // nor repeat entries
+    client.transfer_ownership(&3, &owner1, &None);
+
+    let second_page = client.get_warranties_by_owner_page(&owner1, &Some(cursor), &2);
+    assert_eq!(second_page.ids, vec![&env, 3, 4]);
+    assert_eq!(second_page.next, NextPage::Done);
+
+    let all_ids = client.get_warranty_ids_page(&None, &10);
+    assert_eq!(all_ids.ids.len(), 4);
+    assert_eq!(all_ids.next, NextPage::Done);
+}
+
+#[test]
+fn test_detailed_warranty_pages() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    // Ids 1, 2 and 4 belong to owner1, id 3 to owner2; id 2 gets revoked
+    for (i, owner) in [&owner1, &owner1, &owner2, &owner1].into_iter().enumerate() {
+        client.register_warranty(
+            owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        );
+    }
+    client.revoke_warranty(&2, &None);
+
+    let first_page = client.get_warranties_page(&None, &3, &None);
+    assert_eq!(first_page.warranties.len(), 3);
+    assert_eq!(first_page.warranties.get(2).unwrap().owner, owner2);
+    let second_page = client.get_warranties_page(&first_page.next.cursor(), &3, &None);
+    assert_eq!(second_page.warranties.len(), 1);
+    assert_eq!(second_page.warranties.get(0).unwrap().id, 4);
+    assert_eq!(second_page.next, NextPage::Done);
+
+    // Filtered pages examine `limit` IDs, so they can come back short
+    let active = Some(WarrantyStatus::Active);
+    let first_page = client.get_owner_warranties_detailed(&owner1, &None, &2, &active);
+    assert_eq!(first_page.warranties.len(), 1);
+    assert_eq!(first_page.warranties.get(0).unwrap().id, 1);
+    let second_page =
+        client.get_owner_warranties_detailed(&owner1, &first_page.next.cursor(), &2, &active);
+    assert_eq!(second_page.warranties.len(), 1);
+    assert_eq!(second_page.warranties.get(0).unwrap().id, 4);
+    assert_eq!(second_page.next, NextPage::Done);
+
+    let revoked =
+        client.get_owner_warranties_detailed(&owner1, &None, &10, &Some(WarrantyStatus::Revoked));
+    assert_eq!(revoked.warranties.len(), 1);
+    assert_eq!(revoked.warranties.get(0).unwrap().id, 2);
+
+    assert_eq!(
+        client.try_get_warranties_page(&None, &0, &None),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+}
+
+#[test]
+fn test_id_index_buckets() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let registered = ID_BUCKET_SIZE + 2;
+    for i in 0..registered {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        );
+    }
+    client.delete_warranty(&2, &None);
+
+    // Pages run across the bucket boundary without gaps or repeats
+    let mut listed = std::vec::Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = client.get_warranty_ids_page(&cursor, &100);
+        listed.extend(page.ids.iter());
+        cursor = page.next.cursor();
+        if cursor.is_none() {
+            break;
+        }
+    }
+    let expected: std::vec::Vec<u64> = (1..=registered).filter(|id| *id != 2).collect();
+    assert_eq!(listed, expected);
+    assert_eq!(
+        client.get_storage_stats().id_index_entries as u64,
+        registered - 1
+    );
+
+    // No single entry holds the whole index
+    env.as_contract(&contract_id, || {
+        let last_bucket: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&IdIndexKey::Bucket(1))
+            .unwrap();
+        assert_eq!(last_bucket, vec![&env, registered - 1, registered]);
+    });
+}
+
+#[test]
+fn test_register_warranty_with_optional_fields() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let receipt_hash = BytesN::from_array(&env, &[7; 32]);
+
+    env.mock_all_auths();
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Dishwasher"),
+        String::from_str(&env, "DW-1"),
+        String::from_str(&env, "HomeCo"),
+        base_timestamp,
+        current_time + 31536000,
+    )
+    .with_category(String::from_str(&env, "appliances"))
+    .with_price(49_900)
+    .with_receipt_hash(receipt_hash.clone())
+    .with_coverage(String::from_str(&env, "parts and labour"))
+    .with_transferable(false);
+    let warranty_id = client.register_warranty(&owner, &input);
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(
+        warranty.category,
+        Some(String::from_str(&env, "appliances"))
+    );
+    assert_eq!(warranty.price, Some(49_900));
+    assert_eq!(warranty.receipt_hash, Some(receipt_hash));
+    assert_eq!(
+        warranty.coverage,
+        Some(String::from_str(&env, "parts and labour"))
+    );
+    assert!(!warranty.transferable);
+    assert!(client
+        .try_transfer_ownership(&warranty_id, &new_owner, &None)
+        .is_err());
+
+    // Extras default to unset and transferable
+    let plain_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Kettle"),
+            String::from_str(&env, "K-1"),
+            String::from_str(&env, "HomeCo"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    let plain = client.get_warranty(&plain_id).unwrap();
+    assert_eq!(plain.category, None);
+    assert_eq!(plain.price, None);
+    assert!(plain.transferable);
+    client.transfer_ownership(&plain_id, &new_owner, &None);
+    assert_eq!(client.get_warranty(&plain_id).unwrap().owner, new_owner);
+}
+
+#[test]
+fn test_purchase_proof() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let by_owner = register("SN1");
+    let by_issuer = register("SN2");
+
+    let invoice = Bytes::from_slice(&env, b"INVOICE 2024-0001 Product 499.00");
+    let forged = Bytes::from_slice(&env, b"INVOICE 2024-0001 Product 99.00");
+    let invoice_hash: BytesN<32> = env.crypto().sha256(&invoice).into();
+    assert!(!client.verify_proof(&by_owner, &invoice));
+
+    assert_eq!(
+        client.try_attach_proof(&by_owner, &stranger, &invoice_hash, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.attach_proof(&by_owner, &owner, &invoice_hash, &None);
+    client.attach_proof(&by_issuer, &issuer, &invoice_hash, &None);
+    assert_eq!(
+        client.try_attach_proof(&by_owner, &owner, &invoice_hash, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    assert!(client.verify_proof(&by_owner, &invoice));
+    assert!(client.verify_proof(&by_issuer, &invoice));
+    assert!(!client.verify_proof(&by_owner, &forged));
+}
+
+#[test]
+fn test_metadata() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+
+    let image = Symbol::new(&env, "image");
+    let manual = Symbol::new(&env, "manual");
+    let image_uri = String::from_str(&env, "ipfs://bafyimage");
+    let manual_uri = String::from_str(&env, "https://example.com/manual.pdf");
+    assert_eq!(
+        client.try_attach_metadata(&warranty_id, &stranger, &image, &image_uri, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.attach_metadata(&warranty_id, &owner, &image, &image_uri, &None);
+    client.attach_metadata(&warranty_id, &issuer, &manual, &manual_uri, &None);
+    let metadata = client.get_metadata(&warranty_id);
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(metadata.get(image.clone()), Some(image_uri));
+    assert_eq!(metadata.get(manual.clone()), Some(manual_uri));
+
+    // Values and entry counts are capped to bound fees
+    let oversized = String::from_bytes(&env, &[b'a'; MAX_METADATA_VALUE_LEN as usize + 1]);
+    assert_eq!(
+        client.try_attach_metadata(&warranty_id, &owner, &image, &oversized, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let value = String::from_str(&env, "x");
+    for n in 2..MAX_METADATA_ENTRIES {
+        let key = Symbol::new(&env, &std::format!("spec{n}"));
+        client.attach_metadata(&warranty_id, &owner, &key, &value, &None);
+    }
+    let extra = Symbol::new(&env, "extra");
+    assert_eq!(
+        client.try_attach_metadata(&warranty_id, &owner, &extra, &value, &None),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    // An empty value removes the entry and frees its slot
+    let empty = String::from_str(&env, "");
+    client.attach_metadata(&warranty_id, &owner, &manual, &empty, &None);
+    assert_eq!(client.get_metadata(&warranty_id).get(manual), None);
+    client.attach_metadata(&warranty_id, &owner, &extra, &value, &None);
+
+    // Private warranties do not show their metadata
+    client.set_visibility(&warranty_id, &true, &None);
+    assert!(client.get_metadata(&warranty_id).is_empty());
+}
+
+#[test]
+fn test_registration_analytics() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert!(!client.get_analytics_enabled());
+
+    client.set_analytics_enabled(&true);
+    assert!(client.get_analytics_enabled());
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Heater"),
+            String::from_str(&env, "H-1"),
+            String::from_str(&env, "HomeCo"),
+            base_timestamp,
+            current_time + 31536000,
+        )
+        .with_category(String::from_str(&env, "appliances"))
+        .with_region(String::from_str(&env, "EU")),
+    );
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().region,
+        Some(String::from_str(&env, "EU"))
+    );
+
+    // The region is part of the details private warranties hide
+    client.set_visibility(&warranty_id, &true, &None);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().region, None);
+
+    client.set_analytics_enabled(&false);
+    assert!(!client.get_analytics_enabled());
+}
+
+#[test]
+fn test_statutory_minimum() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let eu = String::from_str(&env, "EU");
+    let electronics = String::from_str(&env, "electronics");
+    let one_year = 31536000;
+
+    env.mock_all_auths();
+    client.set_statutory_minimum(&eu, &None, &Some(one_year));
+    client.set_statutory_minimum(&eu, &Some(electronics.clone()), &Some(2 * one_year));
+    assert_eq!(
+        client.get_statutory_minimum(&eu, &Some(String::from_str(&env, "toys"))),
+        Some(one_year)
+    );
+
+    let register = |serial: &str, category: &String, region: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                base_timestamp + 86400 * 180,
+            )
+            .with_category(category.clone())
+            .with_region(region.clone()),
+        )
+    };
+
+    // EU electronics get the category minimum
+    let laptop = register("SN1", &electronics, &eu);
+    assert_eq!(
+        client.get_warranty(&laptop).unwrap().expiration_date,
+        base_timestamp + 2 * one_year
+    );
+
+    // Other EU products get the region-wide minimum
+    let toy = register("SN2", &String::from_str(&env, "toys"), &eu);
+    assert_eq!(
+        client.get_warranty(&toy).unwrap().expiration_date,
+        base_timestamp + one_year
+    );
+
+    // Regions without a minimum keep the requested coverage
+    let us_laptop = register("SN3", &electronics, &String::from_str(&env, "US"));
+    assert_eq!(
+        client.get_warranty(&us_laptop).unwrap().expiration_date,
+        base_timestamp + 86400 * 180
+    );
+
+    client.set_statutory_minimum(&eu, &Some(electronics.clone()), &None);
+    assert_eq!(
+        client.get_statutory_minimum(&eu, &Some(electronics)),
+        Some(one_year)
+    );
+}
+
+#[test]
+fn test_handover() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price_hash = BytesN::from_array(&env, &[9; 32]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &seller,
+        &WarrantyInput::new(
+            String::from_str(&env, "Camera"),
+            String::from_str(&env, "CAM-1"),
+            String::from_str(&env, "Optix"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    assert!(client.try_accept_handover(&warranty_id, &None).is_err());
+
+    client.initiate_handover(&warranty_id, &buyer, &price_hash);
+    // Nothing moves until the buyer accepts
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, seller);
+    assert_eq!(
+        client.get_pending_handover(&warranty_id).unwrap().buyer,
+        buyer
+    );
+
+    advance_time(&env, 60);
+    client.accept_handover(&warranty_id, &None);
+
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, buyer);
+    assert_eq!(
+        client.get_warranties_by_owner(&buyer, &None),
+        vec![&env, warranty_id]
+    );
+    assert_eq!(client.get_pending_handover(&warranty_id), None);
+    assert_eq!(
+        client.get_handovers(&warranty_id),
+        vec![
+            &env,
+            Handover {
+                seller: seller.clone(),
+                buyer: buyer.clone(),
+                sale_price_hash: price_hash.clone(),
+                initiated_at: current_time,
+                completed_at: current_time + 60,
+            }
+        ]
+    );
+
+    // An offer is dropped once the seller transfers the warranty some other way
+    client.initiate_handover(&warranty_id, &seller, &price_hash);
+    client.transfer_ownership(&warranty_id, &Address::generate(&env), &None);
+    assert_eq!(client.get_pending_handover(&warranty_id), None);
+}
+
+#[test]
+fn test_private_visibility() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Hearing aid"),
+            String::from_str(&env, "HA-1"),
+            String::from_str(&env, "AudioMed"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    let full = client.get_warranty(&warranty_id).unwrap();
+
+    client.set_visibility(&warranty_id, &true, &None);
+
+    let public = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(public.id, warranty_id);
+    assert_eq!(public.status, WarrantyStatus::Active);
+    assert_eq!(public.product_name, String::from_str(&env, ""));
+    assert_eq!(public.serial_number, String::from_str(&env, ""));
+    assert_eq!(public.expiration_date, 0);
+
+    // Nothing public ties the warranty to its owner
+    assert_eq!(public.owner, contract_id);
+    assert_eq!(public.registrant, contract_id);
+    let verification = client.verify(&warranty_id);
+    assert!(verification.is_valid);
+    assert_eq!(verification.owner, None);
+    assert!(client.get_warranties_by_owner(&owner, &None).is_empty());
+    assert!(client
+        .get_warranties_by_owner_page(&owner, &None, &10)
+        .ids
+        .is_empty());
+    assert!(client
+        .get_owner_warranties_detailed(&owner, &None, &10, &None)
+        .warranties
+        .is_empty());
+
+    let owner_view = client.get_warranty_details(&warranty_id, &owner).unwrap();
+    assert_eq!(owner_view.product_name, full.product_name);
+    assert!(owner_view.private);
+    assert_eq!(
+        client.get_warranty_details(&warranty_id, &stranger),
+        Some(public.clone())
+    );
+
+    // Grants give temporary full access
+    client.grant_viewer(&warranty_id, &stranger, &(current_time + 3600), &None);
+    assert_eq!(
+        client
+            .get_warranty_details(&warranty_id, &stranger)
+            .unwrap()
+            .product_name,
+        full.product_name
+    );
+    advance_time(&env, 3600);
+    assert_eq!(
+        client.get_warranty_details(&warranty_id, &stranger),
+        Some(public.clone())
+    );
+    client.grant_viewer(&warranty_id, &stranger, &(current_time + 7200), &None);
+    client.revoke_viewer(&warranty_id, &stranger);
+    assert_eq!(client.get_viewer_grants(&warranty_id).len(), 0);
+    assert!(client
+        .try_grant_viewer(&warranty_id, &stranger, &(current_time + 3600), &None)
+        .is_err());
+
+    // Certificates of private warranties still verify against the full record
+    let certificate = client.export_certificate(&warranty_id);
+    assert!(client.verify_certificate(&certificate));
+
+    client.set_visibility(&warranty_id, &false, &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().product_name,
+        full.product_name
+    );
+}
+
+#[test]
+fn test_private_owner_reads() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Hearing aid"),
+            String::from_str(&env, "HA-1"),
+            String::from_str(&env, "AudioMed"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    client.set_visibility(&warranty_id, &true, &None);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+
+    // NFT and history reads name no owner
+    assert_eq!(client.owner_of(&warranty_id), contract_id);
+    assert_eq!(client.balance_of(&buyer), 0);
+    assert!(client.get_transfer_history(&warranty_id).is_empty());
+    assert!(client
+        .get_warranty_history(&warranty_id, &0, &10)
+        .is_empty());
+    let audit = client.read_audit_log(&None, &10).entries;
+    assert_eq!(audit.len(), 3);
+    for entry in audit.iter() {
+        assert_eq!(entry.owner, Some(contract_id.clone()));
+    }
+
+    // The owner and co-owners still list their private warranties
+    assert_eq!(
+        client.get_warranties_by_owner(&buyer, &Some(buyer.clone())),
+        vec![&env, warranty_id]
+    );
+    assert!(client
+        .get_warranties_by_owner(&buyer, &Some(stranger))
+        .is_empty());
+    assert!(client.get_warranties_by_owner(&buyer, &None).is_empty());
+    assert_eq!(
+        client
+            .export_owner_data(&buyer, &None, &10)
+            .warranties
+            .get(0)
+            .unwrap()
+            .transfers
+            .len(),
+        1
+    );
+
+    // Live reads are full again once public
+    client.set_visibility(&warranty_id, &false, &None);
+    assert_eq!(client.owner_of(&warranty_id), buyer);
+    assert_eq!(client.balance_of(&buyer), 1);
+    assert_eq!(
+        client
+            .get_transfer_history(&warranty_id)
+            .get(0)
+            .unwrap()
+            .from,
+        owner
+    );
+
+    // Audit entries written while private stay redacted
+    let audit = client.read_audit_log(&None, &10).entries;
+    assert_eq!(audit.get(0).unwrap().owner, Some(owner));
+    assert_eq!(audit.get(1).unwrap().owner, Some(contract_id));
+}
+
+#[test]
+fn test_get_allowed_actions() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    let allowed = |actor: &Address, name: &str| {
+        client
+            .get_allowed_actions(&warranty_id, actor)
+            .contains(Symbol::new(&env, name))
+    };
+
+    assert!(allowed(&owner, "transfer_ownership"));
+    assert!(allowed(&owner, "amend_warranty"));
+    assert!(allowed(&owner, "delete_warranty"));
+    assert!(!allowed(&owner, "cancel_handover"));
+    assert!(client.get_allowed_actions(&warranty_id, &buyer).is_empty());
+    assert!(client.get_allowed_actions(&99, &owner).is_empty());
+
+    client.initiate_handover(&warranty_id, &buyer, &BytesN::from_array(&env, &[0; 32]));
+    assert!(allowed(&owner, "cancel_handover"));
+    assert!(allowed(&buyer, "accept_handover"));
+
+    // Windows close and revocation blocks transfers
+    advance_time(&env, 86401);
+    client.revoke_warranty(&warranty_id, &None);
+    assert!(!allowed(&owner, "transfer_ownership"));
+    assert!(!allowed(&owner, "amend_warranty"));
+    assert!(!allowed(&owner, "delete_warranty"));
+    assert!(!allowed(&buyer, "accept_handover"));
+}
+
+#[test]
+fn test_localized_names() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let de = Symbol::new(&env, "de");
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Washing machine"),
+            String::from_str(&env, "WM-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    assert_eq!(
+        client.get_product_name(&warranty_id, &de),
+        String::from_str(&env, "Washing machine")
+    );
+
+    client.set_localized_name(&warranty_id, &de, &String::from_str(&env, "Waschmaschine"));
+
+    assert_eq!(
+        client.get_product_name(&warranty_id, &de),
+        String::from_str(&env, "Waschmaschine")
+    );
+    assert_eq!(
+        client.get_product_name(&warranty_id, &Symbol::new(&env, "fr")),
+        String::from_str(&env, "Washing machine")
+    );
+    assert_eq!(
+        client
+            .get_warranty(&warranty_id)
+            .unwrap()
+            .localized_names
+            .len(),
+        1
+    );
+    assert!(client
+        .try_set_localized_name(&warranty_id, &de, &String::from_str(&env, ""))
+        .is_err());
+}
+
+#[test]
+fn test_get_warranties_in_batch() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let lot = String::from_str(&env, "LOT-7");
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let register = |serial: &str, batch: Option<&str>| {
+        let mut input = WarrantyInput::new(
+            String::from_str(&env, "Heater"),
+            String::from_str(&env, serial),
+            manufacturer.clone(),
+            base_timestamp,
+            current_time + 31536000,
+        );
+        if let Some(batch) = batch {
+            input = input.with_batch(String::from_str(&env, batch), Some(base_timestamp - 86400));
+        }
+        client.register_warranty(&owner, &input)
+    };
+    let first = register("H-1", Some("LOT-7"));
+    register("H-2", Some("LOT-8"));
+    let third = register("H-3", Some("LOT-7"));
+    register("H-4", None);
+
+    let warranty = client.get_warranty(&first).unwrap();
+    assert_eq!(warranty.batch_number, Some(lot.clone()));
+    assert_eq!(warranty.manufacture_date, Some(base_timestamp - 86400));
+
+    let page = client.get_warranties_in_batch(&manufacturer, &lot, &None, &10);
+    assert_eq!(page.ids, vec![&env, first, third]);
+    assert_eq!(page.next, NextPage::Done);
+    assert_eq!(
+        client
+            .get_warranties_in_batch(&String::from_str(&env, "Other"), &lot, &None, &10)
+            .ids
+            .len(),
+        0
+    );
+
+    client.delete_warranty(&third, &None);
+    assert_eq!(
+        client
+            .get_warranties_in_batch(&manufacturer, &lot, &None, &10)
+            .ids,
+        vec![&env, first]
+    );
+}
+
+#[test]
+fn test_transfer_with_condition() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &first,
+        &WarrantyInput::new(
+            String::from_str(&env, "Bike"),
+            String::from_str(&env, "B-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    client.transfer_with_condition(&warranty_id, &second, &ConditionGrade::LikeNew, &None);
+    advance_time(&env, 100);
+    client.transfer_ownership(&warranty_id, &third, &None);
+
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, third);
+    assert_eq!(
+        client.get_transfer_history(&warranty_id),
+        vec![
+            &env,
+            TransferRecord {
+                from: first.clone(),
+                to: second.clone(),
+                condition: ConditionGrade::LikeNew,
+                transferred_at: current_time,
+            },
+            TransferRecord {
+                from: second.clone(),
+                to: third.clone(),
+                condition: ConditionGrade::Unreported,
+                transferred_at: current_time + 100,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_device_events() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let device = SigningKey::from_bytes(&[7; 32]);
+    let impostor = SigningKey::from_bytes(&[8; 32]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Fridge"),
+            String::from_str(&env, "FR-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    client.bind_device_key(
+        &warranty_id,
+        &BytesN::from_array(&env, &device.verifying_key().to_bytes()),
+    );
+
+    let sign = |key: &SigningKey, event_code: u32, nonce: u64| {
+        let message = (contract_id.clone(), warranty_id, event_code, nonce).to_xdr(&env);
+        let mut buf = std::vec![0u8; message.len() as usize];
+        message.copy_into_slice(&mut buf);
+        BytesN::from_array(&env, &key.sign(&buf).to_bytes())
+    };
+
+    client.log_device_event(&warranty_id, &42, &1, &sign(&device, 42, 1), &None);
+    assert_eq!(
+        client.get_device_events(&warranty_id, &0, &10),
+        vec![
+            &env,
+            DeviceEvent {
+                event_code: 42,
+                nonce: 1,
+                logged_at: current_time,
+            }
+        ]
+    );
+
+    // One entry per event, read a page at a time
+    advance_time(&env, 60);
+    client.log_device_event(&warranty_id, &7, &5, &sign(&device, 7, 5), &None);
+    let page = client.get_device_events(&warranty_id, &1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().event_code, 7);
+    assert_eq!(page.get(0).unwrap().logged_at, current_time + 60);
+    assert_eq!(client.get_device_events(&warranty_id, &0, &1).len(), 1);
+    assert!(client.get_device_events(&warranty_id, &2, &10).is_empty());
+    assert_eq!(
+        client.try_get_device_events(&warranty_id, &0, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+
+    // Replays, foreign keys and tampered codes are rejected
+    assert!(client
+        .try_log_device_event(&warranty_id, &42, &1, &sign(&device, 42, 1), &None)
+        .is_err());
+    assert!(client
+        .try_log_device_event(&warranty_id, &42, &6, &sign(&impostor, 42, 6), &None)
+        .is_err());
+    assert!(client
+        .try_log_device_event(&warranty_id, &43, &6, &sign(&device, 42, 6), &None)
+        .is_err());
+
+    client.unbind_device_key(&warranty_id);
+    assert_eq!(client.get_device_key(&warranty_id), None);
+    assert!(client
+        .try_log_device_event(&warranty_id, &42, &7, &sign(&device, 42, 7), &None)
+        .is_err());
+}
+
+#[test]
+fn test_device_fault_drafts() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let device = SigningKey::from_bytes(&[7; 32]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Fridge"),
+            String::from_str(&env, "FR-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    client.bind_device_key(
+        &warranty_id,
+        &BytesN::from_array(&env, &device.verifying_key().to_bytes()),
+    );
+
+    let sign = |fault_code: u32, nonce: u64| {
+        let message = (
+            contract_id.clone(),
+            symbol_short!("fault"),
+            warranty_id,
+            fault_code,
+            nonce,
+        )
+            .to_xdr(&env);
+        let mut buf = std::vec![0u8; message.len() as usize];
+        message.copy_into_slice(&mut buf);
+        BytesN::from_array(&env, &device.sign(&buf).to_bytes())
+    };
+
+    // A signed event log entry does not pass for a fault report
+    let event = (contract_id.clone(), warranty_id, 1042u32, 1u64).to_xdr(&env);
+    let mut buf = std::vec![0u8; event.len() as usize];
+    event.copy_into_slice(&mut buf);
+    let event_signature = BytesN::from_array(&env, &device.sign(&buf).to_bytes());
+    assert!(client
+        .try_device_report_fault(&warranty_id, &1042, &1, &event_signature, &None)
+        .is_err());
+
+    let find = |claim_id: u64| {
+        client
+            .get_claims(&warranty_id)
+            .iter()
+            .find(|claim| claim.claim_id == claim_id)
+    };
+    let draft = client
+        .device_report_fault(&warranty_id, &1042, &1, &sign(1042, 1), &None)
+        .claim_id;
+    let claim = find(draft).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Draft);
+    assert_eq!(
+        claim.description,
+        String::from_str(&env, "Device fault 1042")
+    );
+    assert_eq!(claim.incident_date, current_time);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().claims_filed, 0);
+    assert!(client
+        .try_device_report_fault(&warranty_id, &1042, &1, &sign(1042, 1), &None)
+        .is_err());
+
+    // Drafts cannot be decided before the owner confirms them
+    assert_eq!(
+        client.try_approve_claim(&admin, &draft, &None, &None, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    advance_time(&env, 3600);
+    let filed = client.confirm_claim_draft(&draft, &Some(250), &None);
+    assert_eq!(filed.claim_id, draft);
+    let claim = find(draft).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Filed);
+    assert_eq!(claim.requested_amount, Some(250));
+    assert_eq!(claim.incident_date, current_time);
+    assert_eq!(claim.filed_at, current_time + 3600);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().claims_filed, 1);
+    assert_eq!(client.get_claims(&warranty_id).len(), 1);
+    assert_eq!(
+        client.try_confirm_claim_draft(&draft, &None, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    // Unwanted drafts can be thrown away
+    let second = client
+        .device_report_fault(&warranty_id, &7, &2, &sign(7, 2), &None)
+        .claim_id;
+    assert_eq!(
+        find(second).unwrap().description,
+        String::from_str(&env, "Device fault 7")
+    );
+    assert_eq!(
+        client.try_discard_claim_draft(&draft),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.discard_claim_draft(&second);
+    assert_eq!(find(second), None);
+    assert_eq!(client.get_claims(&warranty_id), vec![&env, claim]);
+    let stats = client.get_storage_stats();
+    assert_eq!(stats.stored_claims, 1);
+    assert_eq!(stats.open_claims, 1);
+}
+
+#[test]
+fn test_compliance_attestations() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let carrier = Address::generate(&env);
+    let evidence = BytesN::from_array(&env, &[3; 32]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Insulin pump"),
+            String::from_str(&env, "IP-1"),
+            String::from_str(&env, "MedCo"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    assert!(client
+        .try_attest_compliance(&carrier, &warranty_id, &true, &evidence)
+        .is_err());
+
+    client.set_logistics_provider(&carrier, &true);
+    client.attest_compliance(&carrier, &warranty_id, &true, &evidence);
+    assert!(client.has_clean_compliance(&warranty_id));
+
+    client.attest_compliance(&carrier, &warranty_id, &false, &evidence);
+    assert!(!client.has_clean_compliance(&warranty_id));
+    assert_eq!(client.get_compliance_trail(&warranty_id).len(), 2);
+
+    for _ in 2..MAX_COMPLIANCE_ATTESTATIONS {
+        client.attest_compliance(&carrier, &warranty_id, &true, &evidence);
+    }
+    assert_eq!(
+        client.try_attest_compliance(&carrier, &warranty_id, &true, &evidence),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    client.set_logistics_provider(&carrier, &false);
+    assert!(!client.is_logistics_provider(&carrier));
+}
+
+#[test]
+fn test_estimate_payout() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "LT-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        )
+        .with_price(1_000),
+    );
+
+    assert!(client.covers_claim_category(&warranty_id, &ClaimCategory::Cosmetic));
+
+    // Without terms the purchase price caps the claim
+    assert_eq!(
+        client.estimate_payout(&warranty_id, &1_500),
+        PayoutEstimate {
+            gross: 1_000,
+            deductible: 0,
+            depreciation: 0,
+            net: 1_000,
+        }
+    );
+
+    client.set_coverage_terms(
+        &admin,
+        &warranty_id,
+        &CoverageTerms {
+            deductible: 100,
+            max_payout: Some(800),
+            excluded_categories: vec![&env, ClaimCategory::Cosmetic],
+            category_caps: Map::new(&env),
+        },
+    );
+    assert_eq!(
+        client.estimate_payout(&warranty_id, &900),
+        PayoutEstimate {
+            gross: 800,
+            deductible: 100,
+            depreciation: 0,
+            net: 700,
+        }
+    );
+    assert_eq!(client.estimate_payout(&warranty_id, &50).net, 0);
+    assert!(client.covers_claim_category(&warranty_id, &ClaimCategory::Defect));
+    assert!(!client.covers_claim_category(&warranty_id, &ClaimCategory::Cosmetic));
+    assert!(client.try_estimate_payout(&warranty_id, &-1).is_err());
+}
+
+#[test]
+fn test_claim_categories() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claims_escrow(&issuer, &token_address, &1_000, &None);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Phone"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let terms = |caps: Map<ClaimCategory, i128>| CoverageTerms {
+        deductible: 0,
+        max_payout: None,
+        excluded_categories: vec![&env, ClaimCategory::AccidentalDamage],
+        category_caps: caps,
+    };
+
+    // Only the issuing side sets coverage, and caps cannot be negative
+    assert_eq!(
+        client.try_set_coverage_terms(&owner, &warranty_id, &terms(Map::new(&env))),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_coverage_terms(
+            &issuer,
+            &warranty_id,
+            &terms(map![&env, (ClaimCategory::Defect, -1)])
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_coverage_terms(
+        &issuer,
+        &warranty_id,
+        &terms(map![&env, (ClaimCategory::Cosmetic, 100)]),
+    );
+
+    // Excluded categories are turned away at filing
+    let file = |category: ClaimCategory, requested: i128| {
+        client.try_file_claim(
+            &warranty_id,
+            &category,
+            &String::from_str(&env, "Cracked screen"),
+            &current_time,
+            &Some(requested),
+            &None,
+        )
+    };
+    assert_eq!(
+        file(ClaimCategory::AccidentalDamage, 300),
+        Err(Ok(WarrantyError::NotCovered))
+    );
+    let cosmetic = file(ClaimCategory::Cosmetic, 300)
+        .unwrap()
+        .unwrap()
+        .claim_id;
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.category, ClaimCategory::Cosmetic);
+
+    // The category cap bounds the approval
+    assert_eq!(
+        client.try_approve_claim(&issuer, &cosmetic, &None, &None, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&issuer, &cosmetic, &Some(100), &None, &None);
+    client.settle_claim(&cosmetic, &token_address, &None);
+    assert_eq!(
+        token::Client::new(&env, &token_address).balance(&owner),
+        100
+    );
+
+    // A cap lowered after approval still holds at settlement
+    let defect = file(ClaimCategory::Defect, 300).unwrap().unwrap().claim_id;
+    client.approve_claim(&issuer, &defect, &None, &None, &None);
+    client.set_coverage_terms(
+        &admin,
+        &warranty_id,
+        &terms(map![&env, (ClaimCategory::Defect, 200)]),
+    );
+    assert_eq!(
+        client.try_settle_claim(&defect, &token_address, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_depreciation_curve() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400 * 400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let electronics = String::from_str(&env, "electronics");
+
+    env.mock_all_auths();
+
+    // Purchased about 13 months ago
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "LT-1"),
+            manufacturer.clone(),
+            base_timestamp,
+            current_time + 31536000,
+        )
+        .with_category(electronics.clone())
+        .with_price(1_000),
+    );
+    assert_eq!(client.estimate_payout(&warranty_id, &1_000).depreciation, 0);
+
+    let curve = vec![&env, (6u32, 8_000u32), (12, 6_000), (24, 3_000)];
+    client.set_depreciation_curve(&manufacturer, &electronics, &curve);
+    assert_eq!(
+        client.get_depreciation_curve(&manufacturer, &electronics),
+        curve
+    );
+    assert_eq!(
+        client.estimate_payout(&warranty_id, &1_000),
+        PayoutEstimate {
+            gross: 1_000,
+            deductible: 0,
+            depreciation: 400,
+            net: 600,
+        }
+    );
+
+    // Curves are per manufacturer
+    assert!(client
+        .get_depreciation_curve(&String::from_str(&env, "Other"), &electronics)
+        .is_empty());
+
+    let unsorted = vec![&env, (12u32, 6_000u32), (6, 8_000)];
+    assert!(client
+        .try_set_depreciation_curve(&manufacturer, &electronics, &unsorted)
+        .is_err());
+    let too_high = vec![&env, (6u32, 12_000u32)];
+    assert!(client
+        .try_set_depreciation_curve(&manufacturer, &electronics, &too_high)
+        .is_err());
+
+    client.set_depreciation_curve(&manufacturer, &electronics, &vec![&env]);
+    assert_eq!(client.estimate_payout(&warranty_id, &1_000).depreciation, 0);
+}
+
+#[test]
+fn test_depreciation_applies_to_payouts() {
+    let base_timestamp: u64 = 1704067200;
+    // Two weeks before the end of a two-year term
+    let current_time = base_timestamp + 86400 * 715;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let electronics = String::from_str(&env, "electronics");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &2_000);
+    client.fund_claims_escrow(&issuer, &token_address, &2_000, &None);
+    client.set_depreciation_curve(
+        &manufacturer,
+        &electronics,
+        &vec![
+            &env,
+            (6u32, 8_000u32),
+            (12, 6_000),
+            (18, 4_000),
+            (24, 2_000),
+        ],
+    );
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "LT-1"),
+            manufacturer.clone(),
+            base_timestamp,
+            base_timestamp + 86400 * 730,
+        )
+        .with_category(electronics.clone())
+        .with_price(1_000),
+    );
+    let file = |incident_date: u64| {
+        client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Battery swollen"),
+                &incident_date,
+                &Some(500),
+                &None,
+            )
+            .claim_id
+    };
+
+    // Late in the term only 40% of the requested amount is covered
+    let late_id = file(current_time);
+    assert_eq!(
+        client.try_approve_claim(&issuer, &late_id, &Some(201), &None, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&issuer, &late_id, &None, &None, &None);
+    client.settle_claim(&late_id, &token_address, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.approved_amount, Some(200));
+    assert_eq!(claim.paid_amount, 200);
+    assert_eq!(token_client.balance(&owner), 200);
+
+    // The age on the incident date counts, not when the claim is decided
+    let pending_id = file(current_time);
+    advance_time(&env, 86400 * 8);
+    assert_eq!(client.estimate_payout(&warranty_id, &500).net, 100);
+    client.approve_claim(&issuer, &pending_id, &None, &None, &None);
+    client.settle_claim(&pending_id, &token_address, &None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(1).unwrap().paid_amount,
+        200
+    );
+    assert_eq!(token_client.balance(&owner), 400);
+
+    // Depreciating the largest possible request must not overflow
+    let huge_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen cracked"),
+            &env.ledger().timestamp(),
+            &Some(i128::MAX),
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_approve_claim(&issuer, &huge_id, &None, &None, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&issuer, &huge_id, &Some(100), &None, &None);
+}
+
+#[test]
+fn test_warranty_ref() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let expiration_date = current_time + 31536000;
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "LT-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            expiration_date,
+        ),
+    );
+
+    let warranty_ref = client.get_warranty_ref(&warranty_id).unwrap();
+    assert_eq!(
+        warranty_ref,
+        WarrantyRef::new(contract_id.clone(), warranty_id)
+    );
+    assert_eq!(client.get_warranty_ref(&999), None);
+
+    let summary = client.resolve_warranty_ref(&warranty_ref).unwrap();
+    assert_eq!(
+        summary,
+        WarrantySummary {
+            warranty_ref: warranty_ref.clone(),
+            owner: owner.clone(),
+            product_name: String::from_str(&env, "Laptop"),
+            manufacturer: String::from_str(&env, "Manufacturer"),
+            status: WarrantyStatus::Active,
+            expiration_date,
+            transferable: true,
+            claims_open_at: None,
+        }
+    );
+    assert_eq!(client.get_warranty_summary(&warranty_id), Some(summary));
+    assert_eq!(
+        client.resolve_warranty_ref(&WarrantyRef::new(contract_id, 999)),
+        None
+    );
+
+    client.set_visibility(&warranty_id, &true, &None);
+    let redacted = client.resolve_warranty_ref(&warranty_ref).unwrap();
+    assert_eq!(redacted.owner, client.address);
+    assert_eq!(redacted.product_name, String::from_str(&env, ""));
+}
+
+#[test]
+fn test_keeper_rewards() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400 * 400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&admin, &1_000);
+    client.set_keeper_reward_token(&token_address);
+    client.set_keeper_fee(&KeeperTask::TtlBump, &10);
+    client.set_keeper_fee(&KeeperTask::ExpirySweep, &3);
+    assert_eq!(client.get_keeper_fee(&KeeperTask::ExpirySweep), 3);
+
+    for i in 0..2 {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                current_time + 86400,
+            ),
+        );
+    }
+
+    env.ledger().set_timestamp(current_time + 86400 * 2);
+
+    // Only registered keepers earn fees
+    assert!(client.try_keeper_bump_ttl(&keeper).is_err());
+    client.set_keeper(&keeper, &true);
+    assert!(client.is_keeper(&keeper));
+
+    assert!(client.keeper_bump_ttl(&keeper));
+    // Bumping again right away extends the TTL but earns nothing
+    assert!(!client.keeper_bump_ttl(&keeper));
+    assert_eq!(client.get_keeper_earnings(&keeper), 10);
+
+    assert_eq!(client.keeper_expire_batch(&keeper, &1, &2, &10), 2);
+    assert_eq!(client.keeper_expire_batch(&keeper, &1, &2, &10), 0);
+    assert_eq!(client.get_keeper_earnings(&keeper), 16);
+
+    env.ledger().set_sequence_number(17_280);
+    assert!(client.keeper_bump_ttl(&keeper));
+
+    // Removed keepers keep what they earned, paid out of the keeper pool
+    client.set_keeper(&keeper, &false);
+    assert_eq!(
+        client.try_claim_keeper_rewards(&keeper),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+    assert_eq!(client.fund_keeper_pool(&admin, &100), 100);
+    assert_eq!(
+        client.try_set_keeper_reward_token(&token_address),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(client.claim_keeper_rewards(&keeper), 26);
+    assert_eq!(client.get_keeper_earnings(&keeper), 0);
+    assert_eq!(client.get_keeper_pool(), 74);
+    assert_eq!(client.claim_keeper_rewards(&keeper), 0);
+
+    let token = token::Client::new(&env, &token_address);
+    assert_eq!(token.balance(&keeper), 26);
+    assert_eq!(token.balance(&contract_id), 74);
+}
+
+#[test]
+fn test_keeper_rewards_leave_claim_pools_alone() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let maker = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&maker, &1_000);
+    client.fund_claim_pool(&maker, &token_address, &1_000, &None);
+
+    // The admin raises the fee and registers a keeper of its own
+    client.set_keeper_reward_token(&token_address);
+    client.set_keeper_fee(&KeeperTask::TtlBump, &1_000);
+    client.set_keeper(&keeper, &true);
+    assert!(client.keeper_bump_ttl(&keeper));
+    assert_eq!(client.get_keeper_earnings(&keeper), 1_000);
+
+    // The manufacturer's pool is not the keepers' to draw on
+    assert_eq!(client.get_keeper_pool(), 0);
+    assert_eq!(
+        client.try_claim_keeper_rewards(&keeper),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+    let token = token::Client::new(&env, &token_address);
+    assert_eq!(token.balance(&keeper), 0);
+    assert_eq!(token.balance(&contract_id), 1_000);
+    assert_eq!(client.get_pool_balance(&maker, &token_address), 1_000);
+}
+
+#[test]
+fn test_rent_pool() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+    // Short TTLs let the test reach the point where records need extending
+    env.ledger().with_mut(|li| {
+        li.max_entry_ttl = 600_000;
+        li.min_persistent_entry_ttl = 200_000;
+    });
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&owner, &1_000);
+    let terms = RentTerms {
+        token: token_address.clone(),
+        free_secs: 365 * 86400,
+        daily_rent: 2,
+        bump_fee: 5,
+    };
+    assert_eq!(
+        client.try_set_rent_terms(&Some(RentTerms {
+            daily_rent: 0,
+            ..terms.clone()
+        })),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_rent_terms(&Some(terms.clone()));
+    assert_eq!(client.get_rent_terms(), Some(terms));
+    client.set_keeper(&keeper, &true);
+
+    let register = |serial: &str, days: u64| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + days * 86400,
+            ),
+        )
+    };
+
+    // Coverage within the free length pays no rent
+    let short_id = register("SN1", 365);
+    assert_eq!(client.get_rent_balance(&short_id), 0);
+    assert_eq!(token_client.balance(&owner), 1_000);
+
+    // Longer coverage pays for each day beyond it
+    let long_id = register("SN2", 730);
+    assert_eq!(client.get_rent_balance(&long_id), 730);
+    let medium_id = register("SN3", 400);
+    assert_eq!(client.get_rent_balance(&medium_id), 70);
+    assert_eq!(token_client.balance(&owner), 200);
+
+    assert_eq!(
+        client.try_top_up_rent(&owner, &long_id, &0),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(client.top_up_rent(&owner, &long_id, &100), 830);
+    assert_eq!(client.top_up_rent(&owner, &short_id, &20), 20);
+
+    // Records with plenty of TTL left are not extended or charged
+    let status = client.keeper_extend_warranty_ttl(&keeper, &long_id);
+    assert!(!status.expiring_soon);
+    assert_eq!(client.get_rent_balance(&long_id), 830);
+
+    env.ledger().with_mut(|li| li.sequence_number = 100_000);
+    env.as_contract(&contract_id, || {
+        env.storage().instance().extend_ttl(599_999, 599_999);
+    });
+    assert!(client.get_ttl_status(&long_id).expiring_soon);
+
+    // Keepers extend running-low records out of their rent
+    let status = client.keeper_extend_warranty_ttl(&keeper, &long_id);
+    assert!(!status.expiring_soon);
+    assert_eq!(client.get_rent_balance(&long_id), 825);
+    assert_eq!(token_client.balance(&keeper), 5);
+
+    // Rent covers the warranty's coverage, not beyond
+    env.ledger().set_timestamp(current_time + 401 * 86400);
+    assert_eq!(
+        client.try_keeper_extend_warranty_ttl(&keeper, &medium_id),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    let unrented_id = register("SN4", 30);
+    assert_eq!(
+        client.try_keeper_extend_warranty_ttl(&keeper, &unrented_id),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+fn test_namespaced_ids() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let mut ids = std::vec::Vec::new();
+    for i in 0..2 {
+        ids.push(client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                manufacturer.clone(),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        ));
+    }
+
+    // Nobody controls the namespace yet
+    assert!(client.try_assign_local_id(&issuer, &ids[0], &4711).is_err());
+
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    env.as_contract(&contract_id, || {
+        let key = NamespaceKey::Issuer(manufacturer.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    assert_eq!(
+        client.get_namespace_issuer(&manufacturer),
+        Some(issuer.clone())
+    );
+    client.assign_local_id(&issuer, &ids[0], &4711);
+
+    assert_eq!(client.get_local_id(&ids[0]), Some(4711));
+    assert_eq!(
+        client.get_warranty_ns(&manufacturer, &4711).unwrap().id,
+        ids[0]
+    );
+    assert_eq!(
+        client.get_warranty_ns(&String::from_str(&env, "Other"), &4711),
+        None
+    );
+
+    // Local IDs and warranties pair up one to one
+    assert!(client.try_assign_local_id(&issuer, &ids[1], &4711).is_err());
+    assert!(client.try_assign_local_id(&issuer, &ids[0], &4712).is_err());
+
+    client.delete_warranty(&ids[0], &None);
+    assert_eq!(client.get_warranty_ns(&manufacturer, &4711), None);
+    client.assign_local_id(&issuer, &ids[1], &4711);
+    assert_eq!(
+        client.get_warranty_ns(&manufacturer, &4711).unwrap().id,
+        ids[1]
+    );
+}
+
+#[test]
+fn test_transfer_allowlist() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let subsidiary = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    client.set_transfer_allowlist(&owner, &vec![&env, subsidiary.clone()]);
+    assert_eq!(
+        client.get_transfer_allowlist(&owner),
+        vec![&env, subsidiary.clone()]
+    );
+
+    let result = client.try_transfer_ownership(&warranty_id, &outsider, &None);
+    assert_eq!(result, Err(Ok(WarrantyError::TransferNotAllowed)));
+
+    client.transfer_ownership(&warranty_id, &subsidiary, &None);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, subsidiary);
+
+    client.set_transfer_allowlist(&owner, &Vec::new(&env));
+    assert!(client.get_transfer_allowlist(&owner).is_empty());
+}
+
+#[test]
+fn test_claim_inherited() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+    let inactivity_secs: u64 = 86400 * 90;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Roof"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    client.set_beneficiary(&warranty_id, &heir, &inactivity_secs, &None);
+    assert_eq!(client.get_last_activity(&owner), Some(current_time));
+
+    // Owner is still considered active
+    assert!(client.try_claim_inherited(&warranty_id, &None).is_err());
+
+    advance_time(&env, inactivity_secs);
+    client.claim_inherited(&warranty_id, &None);
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.owner, heir);
+    assert_eq!(client.get_warranties_by_owner(&owner, &None).len(), 0);
+    assert_eq!(
+        client.get_warranties_by_owner(&heir, &None),
+        vec![&env, warranty_id]
+    );
+    assert_eq!(client.get_beneficiary(&warranty_id), None);
+}
+
+#[test]
+fn test_reversion() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let lessor = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let lease_end = current_time + 86400 * 180;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &lessor,
+        &WarrantyInput::new(
+            String::from_str(&env, "Excavator"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    assert_eq!(
+        client.try_set_reversion(&warranty_id, &lessor, &current_time),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+    assert_eq!(
+        client.try_execute_reversion(&warranty_id, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    // The reversion survives handing the equipment to the lessee
+    client.set_reversion(&warranty_id, &lessor, &lease_end);
+    client.transfer_ownership(&warranty_id, &lessee, &None);
+    let reversion = Reversion {
+        revert_to: lessor.clone(),
+        revert_after: lease_end,
+        set_by: lessor.clone(),
+    };
+    assert_eq!(client.get_reversion(&warranty_id), Some(reversion));
+    env.as_contract(&contract_id, || {
+        let key = ReversionKey::Scheduled(warranty_id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // The lessee cannot cancel it by scheduling their own
+    assert_eq!(
+        client.try_set_reversion(&warranty_id, &lessee, &(lease_end + 1)),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_execute_reversion(&warranty_id, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+
+    env.ledger().set_timestamp(lease_end);
+    client.execute_reversion(&warranty_id, &None);
+
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, lessor);
+    assert_eq!(client.get_warranties_by_owner(&lessee, &None).len(), 0);
+    assert_eq!(
+        client.get_warranties_by_owner(&lessor, &None),
+        vec![&env, warranty_id]
+    );
+    assert_eq!(client.get_reversion(&warranty_id), None);
+    assert_eq!(
+        client.try_execute_reversion(&warranty_id, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+fn test_ledger_sequence_anchoring() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+    env.ledger().set_sequence_number(100);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner1,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.created_ledger, 100);
+    assert_eq!(warranty.last_modified_ledger, 100);
+
+    env.ledger().set_sequence_number(105);
+    client.transfer_ownership(&warranty_id, &owner2, &None);
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.created_ledger, 100);
+    assert_eq!(warranty.last_modified_ledger, 105);
+
+    env.ledger().set_sequence_number(110);
+    client.revoke_warranty(&warranty_id, &None);
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.last_modified_ledger, 110);
+
+    // Every history entry names the ledger of its change
+    let ledgers: std::vec::Vec<u32> = client
+        .get_warranty_history(&warranty_id, &0, &10)
+        .iter()
+        .map(|entry| entry.ledger)
+        .collect();
+    assert_eq!(ledgers, std::vec![100, 105, 110]);
+}
+
+#[test]
+fn test_attach_policy() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let insurer = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    assert_eq!(client.get_attached_policy(&warranty_id), None);
+
+    client.attach_policy(&warranty_id, &insurer, &42, &None);
+    let policy = client.get_attached_policy(&warranty_id).unwrap();
+    assert_eq!(policy.insurer, insurer);
+    assert_eq!(policy.policy_id, 42);
+    assert_eq!(policy.attached_at, current_time);
+
+    client.detach_policy(&warranty_id);
+    assert_eq!(client.get_attached_policy(&warranty_id), None);
+}
+
+mod recording_hook {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Env, Symbol, Vec};
+
+    #[contract]
+    pub struct RecordingHook;
+
+    #[contractimpl]
+    impl RecordingHook {
+        pub fn on_warranty_event(env: Env, event_type: Symbol, warranty_id: u64) {
+            let mut seen = Self::seen(env.clone());
+            seen.push_back((event_type, warranty_id));
+            env.storage().instance().set(&symbol_short!("seen"), &seen);
+        }
+
+        pub fn seen(env: Env) -> Vec<(Symbol, u64)> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("seen"))
+                .unwrap_or(Vec::new(&env))
+        }
+    }
+}
+
+mod failing_hook {
+    use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+    #[contract]
+    pub struct FailingHook;
+
+    #[contractimpl]
+    impl FailingHook {
+        pub fn on_warranty_event(_env: Env, _event_type: Symbol, _warranty_id: u64) {
+            panic!("hook failure");
+        }
+    }
+}
+
+#[test]
+fn test_hooks_notified_with_failure_isolation() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let failing_id = env.register(failing_hook::FailingHook, ());
+    let recording_id = env.register(recording_hook::RecordingHook, ());
+    let recording = recording_hook::RecordingHookClient::new(&env, &recording_id);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    // The admin is set at deployment; nobody can run the constructor again
+    // to take the hook registry and upgrades over
+    assert_eq!(client.get_admin(), Some(admin.clone()));
+    let takeover = env.try_invoke_contract::<(), Error>(
+        &contract_id,
+        &Symbol::new(&env, "__constructor"),
+        vec![
+            &env,
+            owner1.into_val(&env),
+            None::<DeploymentConfig>.into_val(&env),
+        ],
+    );
+    assert!(takeover.is_err());
+    assert_eq!(client.get_admin(), Some(admin.clone()));
+
+    // The failing hook comes first and must not stop the recording one
+    client.add_hook(&failing_id);
+    client.add_hook(&recording_id);
+    assert_eq!(
+        client.get_hooks(),
+        vec![&env, failing_id.clone(), recording_id.clone()]
+    );
+
+    let warranty_id = client.register_warranty(
+        &owner1,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    client.transfer_ownership(&warranty_id, &owner2, &None);
+    client.revoke_warranty(&warranty_id, &None);
+
+    assert_eq!(
+        recording.seen(),
+        vec![
+            &env,
+            (symbol_short!("register"), warranty_id),
+            (symbol_short!("transfer"), warranty_id),
+            (symbol_short!("status"), warranty_id),
+        ]
+    );
+
+    client.remove_hook(&failing_id);
+    assert_eq!(client.get_hooks(), vec![&env, recording_id]);
+}
+
+#[test]
+fn test_read_outbox() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+    env.ledger().set_sequence_number(10);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let empty = client.read_outbox(&None, &10);
+    assert_eq!(empty.entries.len(), 0);
+    assert_eq!(empty.next, NextPage::Done);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    client.transfer_ownership(&warranty_id, &new_owner, &None);
+    client.revoke_warranty(&warranty_id, &None);
+
+    let first = client.read_outbox(&None, &2);
+    assert_eq!(
+        first.entries,
+        vec![
+            &env,
+            OutboxEntry {
+                seq: 1,
+                event_type: symbol_short!("register"),
+                warranty_id,
+                ledger: 10,
+                timestamp: current_time,
+                correlation_id: None,
+            },
+            OutboxEntry {
+                seq: 2,
+                event_type: symbol_short!("transfer"),
+                warranty_id,
+                ledger: 10,
+                timestamp: current_time,
+                correlation_id: None,
+            },
+        ]
+    );
+    let second = client.read_outbox(&first.next.cursor(), &2);
+    assert_eq!(second.entries.len(), 1);
+    assert_eq!(
+        second.entries.get_unchecked(0).event_type,
+        symbol_short!("status")
+    );
+    assert_eq!(second.next, NextPage::Done);
+
+    // Pruned entries are skipped by readers starting from the beginning
+    assert_eq!(client.prune_outbox(&2), 2);
+    assert_eq!(client.prune_outbox(&2), 0);
+    let remaining = client.read_outbox(&None, &10);
+    assert_eq!(remaining.entries.len(), 1);
+    assert_eq!(remaining.entries.get_unchecked(0).seq, 3);
+}
+
+#[test]
+fn test_read_audit_log() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+    env.ledger().set_sequence_number(10);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    client.transfer_ownership(&warranty_id, &new_owner, &None);
+    client.revoke_warranty(&warranty_id, &None);
+    client.delete_warranty(&warranty_id, &None);
+
+    // Pruning the outbox leaves the audit log intact
+    client.prune_outbox(&4);
+
+    let first = client.read_audit_log(&None, &3);
+    assert_eq!(
+        first.entries,
+        vec![
+            &env,
+            AuditEntry {
+                seq: 1,
+                warranty_id,
+                action: symbol_short!("register"),
+                owner: Some(owner.clone()),
+                status: AuditedStatus::Present(WarrantyStatus::Active),
+                ledger: 10,
+                timestamp: current_time,
+                correlation_id: None,
+            },
+            AuditEntry {
+                seq: 2,
+                warranty_id,
+                action: symbol_short!("transfer"),
+                owner: Some(new_owner.clone()),
+                status: AuditedStatus::Present(WarrantyStatus::Active),
+                ledger: 10,
+                timestamp: current_time,
+                correlation_id: None,
+            },
+            AuditEntry {
+                seq: 3,
+                warranty_id,
+                action: symbol_short!("status"),
+                owner: Some(new_owner.clone()),
+                status: AuditedStatus::Present(WarrantyStatus::Revoked),
+                ledger: 10,
+                timestamp: current_time,
+                correlation_id: None,
+            },
+        ]
+    );
+
+    let second = client.read_audit_log(&first.next.cursor(), &3);
+    assert_eq!(second.entries.len(), 1);
+    let removal = second.entries.get_unchecked(0);
+    assert_eq!(removal.seq, 4);
+    assert_eq!(removal.action, symbol_short!("delete"));
+    assert_eq!(removal.owner, None);
+    assert_eq!(second.next, NextPage::Done);
+
+    // Archived entries can be pruned from the front
+    assert_eq!(client.prune_audit_log(&2), 2);
+    assert_eq!(client.prune_audit_log(&2), 0);
+    let rest = client.read_audit_log(&None, &10).entries;
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest.get_unchecked(0).seq, 3);
+    assert_eq!(
+        client
+            .read_audit_log(&first.next.cursor(), &10)
+            .entries
+            .len(),
+        1
+    );
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().persistent().has(&AuditKey::Entry(1)));
+    });
+
+    assert_eq!(
+        client.try_read_audit_log(&None, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+}
+
+#[test]
+fn test_mutation_receipts() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let mut ids = std::vec::Vec::new();
+    for serial in ["SN1", "SN2"] {
+        ids.push(client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        ));
+    }
+
+    assert_eq!(
+        client.transfer_ownership(&ids[1], &new_owner, &None),
+        MutationReceipt {
+            warranty_ids: vec![&env, ids[1]],
+            changes: 1,
+            warranty_count: 2,
+        }
+    );
+    assert_eq!(
+        client.revoke_warranty(&ids[0], &None),
+        MutationReceipt {
+            warranty_ids: vec![&env, ids[0]],
+            changes: 1,
+            warranty_count: 2,
+        }
+    );
+
+    // Claim steps report the warranty they touched and echo the reference
+    let claim_ref = BytesN::from_array(&env, &[3; 16]);
+    let filed = client.file_claim(
+        &ids[1],
+        &ClaimCategory::Defect,
+        &String::from_str(&env, "Screen flickers"),
+        &current_time,
+        &None,
+        &Some(claim_ref.clone()),
+    );
+    let touched = MutationReceipt {
+        warranty_ids: vec![&env, ids[1]],
+        changes: 1,
+        warranty_count: 2,
+    };
+    assert_eq!(filed.receipt, touched);
+    assert_eq!(
+        client.approve_claim(&admin, &filed.claim_id, &None, &None, &None),
+        touched
+    );
+
+    let outbox = client.read_outbox(&None, &10).entries;
+    assert_eq!(outbox.len(), 6);
+    let filing = outbox.get(4).unwrap();
+    assert_eq!(filing.event_type, symbol_short!("claim"));
+    assert_eq!(filing.warranty_id, ids[1]);
+    assert_eq!(filing.correlation_id, Some(claim_ref));
+    assert_eq!(outbox.get(5).unwrap().event_type, symbol_short!("claim"));
+}
+
+#[test]
+fn test_correlation_ids() {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let registration_ref = BytesN::from_array(&env, &[1; 16]);
+    let transfer_ref = BytesN::from_array(&env, &[2; 16]);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            1704000000,
+            1767225600,
+        )
+        .with_correlation_id(registration_ref.clone()),
+    );
+    client.transfer_ownership(&warranty_id, &new_owner, &Some(transfer_ref.clone()));
+    client.revoke_warranty(&warranty_id, &None);
+
+    let outbox = client.read_outbox(&None, &10).entries;
+    assert_eq!(
+        outbox.get(0).unwrap().correlation_id,
+        Some(registration_ref.clone())
+    );
+    assert_eq!(
+        outbox.get(1).unwrap().correlation_id,
+        Some(transfer_ref.clone())
+    );
+    assert_eq!(outbox.get(2).unwrap().correlation_id, None);
+
+    let log = client.read_audit_log(&None, &10).entries;
+    assert_eq!(log.get(0).unwrap().correlation_id, Some(registration_ref));
+    assert_eq!(log.get(1).unwrap().correlation_id, Some(transfer_ref));
+    assert_eq!(log.get(2).unwrap().correlation_id, None);
+
+    // Settings changes carry references too, even when no warranty is involved
+    let visibility_ref = BytesN::from_array(&env, &[3; 16]);
+    let arbitrator_ref = BytesN::from_array(&env, &[4; 16]);
+    let receipt = client.set_visibility(&warranty_id, &true, &Some(visibility_ref.clone()));
+    assert_eq!(receipt.warranty_ids, vec![&env, warranty_id]);
+    let receipt = client.set_arbitrator(
+        &Address::generate(&env),
+        &true,
+        &Some(arbitrator_ref.clone()),
+    );
+    assert_eq!(receipt.changes, 1);
+    assert!(receipt.warranty_ids.is_empty());
+
+    let outbox = client.read_outbox(&None, &10).entries;
+    assert_eq!(outbox.get(3).unwrap().correlation_id, Some(visibility_ref));
+    let entry = outbox.get(4).unwrap();
+    assert_eq!(entry.event_type, symbol_short!("arbiter"));
+    assert_eq!(entry.warranty_id, 0);
+    assert_eq!(entry.correlation_id, Some(arbitrator_ref.clone()));
+    let entry = client.read_audit_log(&None, &10).entries.get(4).unwrap();
+    assert_eq!(entry.correlation_id, Some(arbitrator_ref));
+    assert_eq!(entry.owner, None);
+}
+
+/// Assert every ID index agrees with the stored warranty records. `owners`
+/// must include past owners so stale entries are caught.
+fn assert_indexes_match_records(env: &Env, contract_id: &Address, owners: &[&Address]) {
+    env.as_contract(contract_id, || {
+        let storage = env.storage().persistent();
+        let mut ids: Vec<u64> = Vec::new(env);
+        let mut cursor = None;
+        loop {
+            let page = id_index_page(env, cursor, 100);
+            for id in page.ids.iter() {
+                insert_sorted(&mut ids, id);
+            }
+            cursor = page.next.cursor();
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(id_index_len(env), ids.len());
+        let registered: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::WarrantyCount)
+            .unwrap_or(0);
+        let mut records: Map<u64, WarrantyData> = Map::new(env);
+        for id in (1..=registered).chain(ids.iter()) {
+            if let Some(warranty) = storage.get(&DataKey::Warranty(id)) {
+                records.set(id, warranty);
+            }
+        }
+        assert_eq!(ids, records.keys());
+
+        for owner in owners {
+            let hot: Vec<u64> = storage
+                .get(&DataKey::OwnerWarranties((*owner).clone()))
+                .unwrap_or(Vec::new(env));
+            let archived: Vec<u64> = storage
+                .get(&DataKey::ArchivedOwnerWarranties((*owner).clone()))
+                .unwrap_or(Vec::new(env));
+            let mut indexed: Vec<u64> = hot.clone();
+            for id in archived.iter() {
+                assert!(!hot.contains(id), "warranty {} listed twice", id);
+                indexed.push_back(id);
+            }
+            for id in indexed.iter() {
+                assert_eq!(records.get(id).map(|w| w.owner), Some((*owner).clone()));
+            }
+            for warranty in records.values() {
+                if warranty.owner == **owner {
+                    assert!(indexed.contains(warranty.id));
+                }
+            }
+        }
+
+        for warranty in records.values() {
+            if let Some(batch_number) = warranty.batch_number.clone() {
+                let batch: Vec<u64> = storage
+                    .get(&DataKey::BatchWarranties(
+                        warranty.manufacturer.clone(),
+                        batch_number.clone(),
+                    ))
+                    .unwrap_or(Vec::new(env));
+                for id in batch.iter() {
+                    let listed = records.get(id).unwrap();
+                    assert_eq!(listed.batch_number, Some(batch_number.clone()));
+                }
+                assert!(batch.contains(warranty.id));
+            }
+        }
+    });
+}
+
+#[test]
+fn test_indexes_match_records() {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let owners = [&owner, &buyer, &stranger];
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+    client.set_namespace_issuer(
+        &String::from_str(&env, "Manufacturer"),
+        &Some(maker.clone()),
+    );
+
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Heater"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                1704000000,
+                1767225600,
+            )
+            .with_batch(String::from_str(&env, "LOT-7"), None),
+        )
+    };
+    let first = register("H-1");
+    let second = register("H-2");
+    let third = register("H-3");
+    let duplicate = register("H-3b");
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    client.transfer_ownership(&first, &buyer, &None);
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    // Calls that fail part-way leave every index as it was
+    client.revoke_warranty(&second, &None);
+    assert!(client
+        .try_transfer_ownership(&second, &buyer, &None)
+        .is_err());
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    client.set_transfer_allowlist(&owner, &vec![&env, buyer.clone()]);
+    assert_eq!(
+        client.try_transfer_ownership(&third, &stranger, &None),
+        Err(Ok(WarrantyError::TransferNotAllowed))
+    );
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    assert!(client
+        .try_merge_warranties(&first, &third, &maker, &None)
+        .is_err());
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    client.merge_warranties(&third, &duplicate, &maker, &None);
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    client.set_to_expired(&third, &None);
+    client.compact_owner_index(&owner, &None);
+    client.reactivate_warranty(&third, &maker, &None);
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    client.delete_warranty(&second, &None);
+    assert_indexes_match_records(&env, &contract_id, &owners);
+
+    env.ledger().set_timestamp(1704067200 + 7200);
+    assert!(client.try_delete_warranty(&third, &None).is_err());
+    assert_indexes_match_records(&env, &contract_id, &owners);
+    assert_eq!(client.get_warranty_count(), 4);
+}
+
+/// Small deterministic generator so the replay test is reproducible per seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+
+    fn pick(&mut self, ids: &[u64]) -> u64 {
+        ids[self.next(ids.len() as u64) as usize]
+    }
+}
+
+#[test]
+fn test_audit_log_replay_rebuilds_state() {
+    for seed in [1u64, 7, 42, 2024] {
+        let env = Env::default();
+        env.ledger().set_timestamp(1704067200);
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+        let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+        let holders = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+
+        env.mock_all_auths();
+        client.set_cooling_off_secs(&3600);
+
+        let mut rng = Rng(seed);
+        let mut registered: std::vec::Vec<u64> = std::vec::Vec::new();
+        for step in 0..60 {
+            let now = env.ledger().timestamp();
+            let holder = &holders[rng.next(3) as usize];
+
+            let op = if registered.is_empty() {
+                0
+            } else {
+                rng.next(8)
+            };
+            match op {
+                0 => {
+                    let serial = std::format!("S-{}-{}", seed, step);
+                    registered.push(client.register_warranty(
+                        holder,
+                        &WarrantyInput::new(
+                            String::from_str(&env, "Product"),
+                            String::from_str(&env, &serial),
+                            String::from_str(&env, "Manufacturer"),
+                            now - 86400,
+                            now + 86400 * (1 + rng.next(30)),
+                        ),
+                    ));
+                }
+                1 => {
+                    let _ = client.try_transfer_ownership(&rng.pick(&registered), holder, &None);
+                }
+                2 => {
+                    let _ = client.try_revoke_warranty(&rng.pick(&registered), &None);
+                }
+                3 => {
+                    let _ = client.try_set_to_active(&rng.pick(&registered), &None);
+                }
+                4 => {
+                    let _ = client.try_set_to_expired(&rng.pick(&registered), &None);
+                }
+                5 => {
+                    let _ = client.try_delete_warranty(&rng.pick(&registered), &None);
+                }
+                6 => {
+                    let keep_id = rng.pick(&registered);
+                    let remove_id = rng.pick(&registered);
+                    let _ = client.try_merge_warranties(&keep_id, &remove_id, &admin, &None);
+                }
+                _ => {
+                    env.ledger().set_timestamp(now + 86400 * rng.next(10));
+                    let last_id = *registered.last().unwrap();
+                    let from_id = last_id.saturating_sub(49).max(registered[0]);
+                    let _ = client.try_expire_batch(&from_id, &last_id, &50);
+                }
+            }
+            env.ledger()
+                .set_timestamp(env.ledger().timestamp() + rng.next(900));
+        }
+
+        // Mirror the contract the way an indexer would, from the audit log alone
+        let mut mirror = std::collections::BTreeMap::new();
+        let mut cursor = None;
+        loop {
+            let page = client.read_audit_log(&cursor, &100);
+            for entry in page.entries.iter() {
+                match (entry.owner, entry.status) {
+                    (Some(owner), AuditedStatus::Present(status)) => {
+                        mirror.insert(entry.warranty_id, (owner, status));
+                    }
+                    _ => {
+                        mirror.remove(&entry.warranty_id);
+                    }
+                }
+            }
+            if page.next == NextPage::Done {
+                break;
+            }
+            cursor = page.next.cursor();
+        }
+
+        let ids = client.get_warranty_ids_page(&None, &100).ids;
+        assert_eq!(
+            ids.iter().collect::<std::vec::Vec<_>>(),
+            mirror.keys().copied().collect::<std::vec::Vec<_>>(),
+            "seed {}",
+            seed
+        );
+        for (id, (owner, status)) in mirror.iter() {
+            let warranty = client.get_warranty(id).unwrap();
+            assert_eq!(&warranty.owner, owner, "seed {}", seed);
+            assert_eq!(&warranty.status, status, "seed {}", seed);
+        }
+        for holder in holders.iter() {
+            let owned: std::vec::Vec<u64> = mirror
+                .iter()
+                .filter(|(_, (owner, _))| owner == holder)
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(
+                client
+                    .get_warranties_by_owner(holder, &None)
+                    .iter()
+                    .collect::<std::vec::Vec<_>>(),
+                owned,
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn test_vouchers() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+
+    let register = |manufacturer: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, "SN123"),
+                manufacturer.clone(),
+                current_time - 86400,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let warranty_id = register(&manufacturer);
+    let other_id = register(&String::from_str(&env, "Other"));
+
+    let code = |text: &str| Bytes::from_slice(&env, text.as_bytes());
+    let hash_of = |text: &str| -> BytesN<32> { env.crypto().sha256(&code(text)).into() };
+    let hashes = vec![&env, hash_of("GIFT-1"), hash_of("GIFT-2")];
+
+    // Only the manufacturer's namespace issuer can mint
+    assert_eq!(
+        client.try_mint_voucher(
+            &issuer,
+            &manufacturer,
+            &hashes,
+            &15552000,
+            &(current_time + 86400)
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    assert_eq!(
+        client.mint_voucher(
+            &issuer,
+            &manufacturer,
+            &hashes,
+            &15552000,
+            &(current_time + 86400)
+        ),
+        hashes
+    );
+    // Minting again skips the existing vouchers
+    assert_eq!(
+        client
+            .mint_voucher(
+                &issuer,
+                &manufacturer,
+                &hashes,
+                &15552000,
+                &(current_time + 86400)
+            )
+            .len(),
+        0
+    );
+
+    client.redeem_voucher(&warranty_id, &code("GIFT-1"), &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().expiration_date,
+        current_time + 31536000 + 15552000
+    );
+    assert_eq!(
+        client.get_voucher(&hash_of("GIFT-1")).unwrap().redeemed_by,
+        Some(warranty_id)
+    );
+
+    // Single use, manufacturer-bound and expirable
+    assert!(client
+        .try_redeem_voucher(&warranty_id, &code("GIFT-1"), &None)
+        .is_err());
+    assert!(client
+        .try_redeem_voucher(&other_id, &code("GIFT-2"), &None)
+        .is_err());
+    assert!(client
+        .try_redeem_voucher(&warranty_id, &code("NOT-MINTED"), &None)
+        .is_err());
+    env.ledger().set_timestamp(current_time + 86401);
+    assert!(client
+        .try_redeem_voucher(&warranty_id, &code("GIFT-2"), &None)
+        .is_err());
+    assert_eq!(
+        client.get_voucher(&hash_of("GIFT-2")).unwrap().redeemed_by,
+        None
+    );
+}
+
+#[test]
+fn test_claim_codes() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+
+    let code = Bytes::from_slice(&env, b"QR-0001");
+    let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        manufacturer.clone(),
+        current_time - 86400,
+        current_time + 31536000,
+    );
+
+    // Only the manufacturer's namespace issuer can pre-issue
+    assert_eq!(
+        client.try_pre_issue_warranty(&issuer, &input, &code_hash),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    assert_eq!(
+        client.pre_issue_warranty(&issuer, &input, &code_hash),
+        code_hash
+    );
+    let warranty_id = client.get_pre_issued(&code_hash).unwrap();
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.owner, contract_id);
+    assert_eq!(warranty.registrant, issuer);
+
+    // Each code is filed once
+    let mut second = input.clone();
+    second.serial_number = String::from_str(&env, "SN124");
+    assert_eq!(
+        client.try_pre_issue_warranty(&issuer, &second, &code_hash),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // A wrong code unlocks nothing
+    assert_eq!(
+        client.try_claim_warranty(&Bytes::from_slice(&env, b"QR-9999"), &buyer),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    assert_eq!(client.claim_warranty(&code, &buyer), warranty_id);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, buyer);
+    assert_eq!(
+        client.get_warranties_by_owner(&buyer, &None),
+        vec![&env, warranty_id]
+    );
+    assert!(client
+        .get_warranties_by_owner(&contract_id, &None)
+        .is_empty());
+    assert_eq!(client.get_pre_issued(&code_hash), None);
+
+    // and works once
+    assert_eq!(
+        client.try_claim_warranty(&code, &Address::generate(&env)),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+fn test_co_owners() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Dishwasher"),
+            String::from_str(&env, "DW-1"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    client.add_co_owner(&warranty_id, &partner, &None);
+    assert_eq!(
+        client.try_add_co_owner(&warranty_id, &partner, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_add_co_owner(&warranty_id, &owner, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.get_warranties_by_owner(&partner, &None),
+        vec![&env, warranty_id]
+    );
+
+    // Co-owners read a private record in full and file claims
+    client.set_visibility(&warranty_id, &true, &None);
+    let details = client.get_warranty_details(&warranty_id, &partner).unwrap();
+    assert_eq!(details.product_name, String::from_str(&env, "Dishwasher"));
+    assert_eq!(details.co_owners, vec![&env, partner.clone()]);
+    assert_eq!(
+        client.try_co_owner_file_claim(
+            &stranger,
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Leaks"),
+            &current_time,
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    let claim_id = client
+        .co_owner_file_claim(
+            &partner,
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Leaks"),
+            &current_time,
+            &None,
+            &None,
+        )
+        .claim_id;
+    let claims = client.get_claims(&warranty_id);
+    assert_eq!(claims.get(0).unwrap().claim_id, claim_id);
+    assert_eq!(claims.get(0).unwrap().claimant, owner);
+    client.set_visibility(&warranty_id, &false, &None);
+
+    client.remove_co_owner(&warranty_id, &partner, &None);
+    assert!(client.get_warranties_by_owner(&partner, &None).is_empty());
+    assert_eq!(
+        client.try_remove_co_owner(&warranty_id, &partner, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    // Co-ownership ends when the warranty changes hands
+    client.add_co_owner(&warranty_id, &partner, &None);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert!(client
+        .get_warranty(&warranty_id)
+        .unwrap()
+        .co_owners
+        .is_empty());
+    assert!(client.get_warranties_by_owner(&partner, &None).is_empty());
+    assert_eq!(
+        client.get_warranties_by_owner(&buyer, &None),
+        vec![&env, warranty_id]
+    );
+}
+
+#[test]
+fn test_white_label_config() {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Acme");
+
+    // Everything except vouchers and consortiums, which is past the first word
+    let bit = |feature: &str| {
+        SUPPORTED_FEATURES
+            .iter()
+            .position(|name| *name == feature)
+            .unwrap()
+    };
+    assert!(bit("consortium") >= 64);
+    let config = DeploymentConfig {
+        name: String::from_str(&env, "Acme Care"),
+        default_issuer: Some(issuer.clone()),
+        features: !(1 << bit("vouchers")),
+        extra_features: !(1 << (bit("consortium") - 64)),
+    };
+
+    let contract_id = env.register(WarrantyTracker, (&admin, Some(config.clone())));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_deployment_config(), config);
+
+    let description = client.describe();
+    assert_eq!(description.name, String::from_str(&env, "Acme Care"));
+    assert_eq!(description.default_issuer, Some(issuer.clone()));
+    assert_eq!(
+        description.features.len() as usize,
+        SUPPORTED_FEATURES.len() - 2
+    );
+    assert!(!description.features.contains(Symbol::new(&env, "vouchers")));
+
+    assert!(client.supports(&Symbol::new(&env, "outbox")));
+    assert!(client.supports(&Symbol::new(&env, "chargebacks")));
+    assert!(!client.supports(&Symbol::new(&env, "vouchers")));
+    assert!(!client.supports(&Symbol::new(&env, "consortium")));
+    assert_eq!(
+        client.try_create_consortium(&issuer, &String::from_str(&env, "Group"), &None),
+        Err(Ok(WarrantyError::FeatureDisabled))
+    );
+    assert_eq!(
+        client.try_mint_voucher(
+            &issuer,
+            &manufacturer,
+            &vec![&env, BytesN::from_array(&env, &[1; 32])],
+            &86400,
+            &1767225600,
+        ),
+        Err(Ok(WarrantyError::FeatureDisabled))
+    );
+
+    // Manufacturers without their own issuer fall back to the default one
+    assert_eq!(
+        client.get_namespace_issuer(&manufacturer),
+        Some(issuer.clone())
+    );
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer.clone(),
+            1704000000,
+            1767225600,
+        ),
+    );
+    client.assign_local_id(&issuer, &warranty_id, &17);
+    assert_eq!(client.get_local_id(&warranty_id), Some(17));
+}
+
+#[test]
+fn test_part_warranties() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let center = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_service_center(&center, &true);
+
+    let parent_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Washing Machine"),
+            String::from_str(&env, "WM-1"),
+            String::from_str(&env, "Manufacturer"),
+            current_time - 86400,
+            current_time + 31536000,
+        ),
+    );
+
+    let pump_part = PartInfo {
+        part_number: String::from_str(&env, "PUMP-200"),
+        serial_number: String::from_str(&env, "P-77"),
+        covered: true,
+        coverage_secs: 15552000,
+    };
+
+    // Only a service center or the manufacturer fits parts
+    assert_eq!(
+        client.try_register_part_warranty(&parent_id, &stranger, &pump_part),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    let pump = client.register_part_warranty(&parent_id, &center, &pump_part);
+    let door = client.register_part_warranty(
+        &parent_id,
+        &center,
+        &PartInfo {
+            part_number: String::from_str(&env, "SEAL-9"),
+            serial_number: String::from_str(&env, "S-3"),
+            covered: false,
+            coverage_secs: 7776000,
+        },
+    );
+
+    assert_eq!(
+        client.get_part_warranties(&parent_id),
+        vec![&env, pump, door]
+    );
+    assert_eq!(client.get_part_warranties(&pump).len(), 0);
+    assert_eq!(
+        client.get_part_record(&pump),
+        Some(PartRecord {
+            parent_id,
+            part_number: String::from_str(&env, "PUMP-200"),
+            covered: true,
+        })
+    );
+    assert_eq!(client.get_part_record(&parent_id), None);
+
+    // Service records list the part warranties of the components fitted
+    let repair = String::from_str(&env, "Replaced pump and door seal");
+    client.add_service_record(
+        &parent_id,
+        &center,
+        &repair,
+        &current_time,
+        &vec![&env, pump, door],
+        &None,
+    );
+    assert_eq!(
+        client.get_service_history(&parent_id).get(0).unwrap().parts,
+        vec![&env, pump, door]
+    );
+    assert_eq!(
+        client.try_add_service_record(
+            &parent_id,
+            &center,
+            &repair,
+            &current_time,
+            &vec![&env, parent_id],
+            &None
+        ),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    assert_eq!(
+        client.try_add_service_record(
+            &pump,
+            &center,
+            &repair,
+            &current_time,
+            &vec![&env, door],
+            &None
+        ),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    let mut too_many = vec![&env];
+    for _ in 0..11 {
+        too_many.push_back(pump);
+    }
+    assert_eq!(
+        client.try_add_service_record(
+            &parent_id,
+            &center,
+            &repair,
+            &current_time,
+            &too_many,
+            &None
+        ),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    let part = client.get_warranty(&door).unwrap();
+    assert_eq!(part.owner, owner);
+    assert_eq!(part.product_name, String::from_str(&env, "SEAL-9"));
+    assert_eq!(part.manufacturer, String::from_str(&env, "Manufacturer"));
+    assert_eq!(part.expiration_date, current_time + 7776000);
+
+    client.revoke_warranty(&parent_id, &None);
+    assert!(client
+        .try_register_part_warranty(
+            &parent_id,
+            &center,
+            &PartInfo {
+                part_number: String::from_str(&env, "BELT-1"),
+                serial_number: String::from_str(&env, "B-1"),
+                covered: false,
+                coverage_secs: 86400,
+            },
+        )
+        .is_err());
+
+    // Deployments without part warranties refuse them
+    let features = SUPPORTED_FEATURES
+        .iter()
+        .position(|name| *name == "part_warranties")
+        .map(|bit| !(1u64 << bit))
+        .unwrap();
+    let contract_id = env.register(
+        WarrantyTracker,
+        (
+            Address::generate(&env),
+            Some(DeploymentConfig {
+                name: String::from_str(&env, "Parts-free"),
+                default_issuer: None,
+                features,
+                extra_features: u64::MAX,
+            }),
+        ),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+    client.set_service_center(&center, &true);
+    let parent_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Washing Machine"),
+            String::from_str(&env, "WM-2"),
+            String::from_str(&env, "Manufacturer"),
+            current_time - 86400,
+            current_time + 31536000,
+        ),
+    );
+    assert_eq!(
+        client.try_register_part_warranty(&parent_id, &center, &pump_part),
+        Err(Ok(WarrantyError::FeatureDisabled))
+    );
+}
+
+#[test]
+fn test_warranty_links() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let one_year = 31536000;
+
+    env.mock_all_auths();
+
+    let register = |owner: &Address, product: &str, expiration_date: u64| {
+        client.register_warranty(
+            owner,
+            &WarrantyInput::new(
+                String::from_str(&env, product),
+                String::from_str(&env, product),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                expiration_date,
+            ),
+        )
+    };
+    let phone = register(&owner, "Phone", current_time + one_year);
+    let case = register(&owner, "Case", current_time + 2 * one_year);
+    let charger = register(&owner, "Charger", current_time + 2 * one_year);
+    let foreign = register(&stranger, "Tablet", current_time + 2 * one_year);
+
+    client.link_warranties(&phone, &case, &LinkType::ExpiresOnParentRevocation, &None);
+    client.link_warranties(&phone, &charger, &LinkType::ExpiresWithParent, &None);
+    assert_eq!(
+        client.get_warranty_link(&case),
+        Some(WarrantyLink {
+            parent_id: phone,
+            link_type: LinkType::ExpiresOnParentRevocation,
+            linked_at: current_time,
+        })
+    );
+    assert_eq!(
+        client.get_linked_warranties(&phone),
+        vec![&env, case, charger]
+    );
+
+    assert_eq!(
+        client.try_link_warranties(&phone, &case, &LinkType::Related, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_link_warranties(&case, &phone, &LinkType::Related, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_link_warranties(&phone, &foreign, &LinkType::Related, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // The phone's coverage runs out before the accessories' own
+    advance_time(&env, one_year + 1);
+    assert_eq!(
+        client.get_warranty(&charger).unwrap().status,
+        WarrantyStatus::Expired
+    );
+    assert!(client.is_warranty_expired(&charger));
+    assert_eq!(
+        client.try_file_claim(
+            &charger,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Frayed cable"),
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotActive))
+    );
+    assert_eq!(
+        client.get_warranty(&case).unwrap().status,
+        WarrantyStatus::Active
+    );
+
+    client.revoke_warranty(&phone, &None);
+    assert_eq!(
+        client.get_warranty(&case).unwrap().status,
+        WarrantyStatus::Expired
+    );
+    assert_eq!(
+        client.try_file_claim(
+            &case,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Cracked"),
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotActive))
+    );
+
+    client.unlink_warranty(&case);
+    assert_eq!(client.get_warranty_link(&case), None);
+    assert_eq!(client.get_linked_warranties(&phone), vec![&env, charger]);
+    assert_eq!(
+        client.get_warranty(&case).unwrap().status,
+        WarrantyStatus::Active
+    );
+}
+
+#[test]
+fn test_warranty_link_limit() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let register = |serial: std::string::String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Rack"),
+                String::from_str(&env, &serial),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let rack = register(std::string::String::from("RACK"));
+    for i in 0..MAX_LINKED_WARRANTIES {
+        let module = register(std::format!("MOD-{i}"));
+        client.link_warranties(&rack, &module, &LinkType::Related, &None);
+    }
+    let extra = register(std::string::String::from("MOD-EXTRA"));
+    assert_eq!(
+        client.try_link_warranties(&rack, &extra, &LinkType::Related, &None),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    assert_eq!(
+        client.get_linked_warranties(&rack).len(),
+        MAX_LINKED_WARRANTIES
+    );
+
+    // The link graph stays out of the instance entry
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&LinkKey::Children(rack)));
+        assert!(!env.storage().instance().has(&LinkKey::Children(rack)));
+    });
+}
+
+#[test]
+fn test_vehicle_vin_validation() {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let input = |serial: &str, category: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Car"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            1704000000,
+            1767225600,
+        )
+        .with_category(String::from_str(&env, category))
+    };
+
+    let warranty_id = client.register_warranty(&owner, &input("1M8GDM9AXKP042788", "Vehicle"));
+    client.register_warranty(&owner, &input("1HGCM82633A004352", "Vehicle"));
+
+    // Wrong check digit, wrong length and a letter VINs never use
+    for serial in ["1M8GDM9A1KP042788", "1M8GDM9AXKP04278", "1M8GDM9AXKP0427I8"] {
+        assert!(client
+            .try_register_warranty(&owner, &input(serial, "Vehicle"))
+            .is_err());
+    }
+    // Other categories keep free-form serial numbers
+    client.register_warranty(&owner, &input("1M8GDM9A1KP042788", "Electronics"));
+
+    let amendment = |serial: &str| WarrantyAmendment {
+        product_name: None,
+        serial_number: Some(String::from_str(&env, serial)),
+        purchase_date: None,
+        expiration_date: None,
+    };
+    assert!(client
+        .try_amend_warranty(&warranty_id, &amendment("1M8GDM9A1KP042788"), &None)
+        .is_err());
+    client.amend_warranty(&warranty_id, &amendment("11111111111111111"), &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().serial_number,
+        String::from_str(&env, "11111111111111111")
+    );
+}
+
+#[test]
+fn test_usage_limits() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let device = SigningKey::from_bytes(&[7; 32]);
+
+    env.mock_all_auths();
+
+    let register = |serial: &str, usage_limit: Option<u64>| {
+        let mut input = WarrantyInput::new(
+            String::from_str(&env, "Car"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            current_time - 86400,
+            current_time + 31536000,
+        );
+        if let Some(limit) = usage_limit {
+            input = input.with_usage_limit(limit, Symbol::new(&env, "km"));
+        }
+        client.register_warranty(&owner, &input)
+    };
+    let warranty_id = register("CAR-1", Some(100_000));
+    let untracked_id = register("CAR-2", None);
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.usage_limit, Some(100_000));
+    assert_eq!(warranty.usage_unit, Some(Symbol::new(&env, "km")));
+    assert_eq!(warranty.usage_reading, None);
+
+    client.report_usage(&warranty_id, &40_000, &None);
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().usage_reading,
+        Some(40_000)
+    );
+    assert!(!client.is_warranty_expired(&warranty_id));
+
+    // Odometers do not run backwards, and only usage-based warranties take readings
+    assert!(client
+        .try_report_usage(&warranty_id, &39_000, &None)
+        .is_err());
+    assert!(client.try_report_usage(&untracked_id, &1, &None).is_err());
+
+    client.bind_device_key(
+        &warranty_id,
+        &BytesN::from_array(&env, &device.verifying_key().to_bytes()),
+    );
+    let sign = |reading: u64, nonce: u64| {
+        let message = (contract_id.clone(), warranty_id, reading, nonce).to_xdr(&env);
+        let mut buf = std::vec![0u8; message.len() as usize];
+        message.copy_into_slice(&mut buf);
+        BytesN::from_array(&env, &device.sign(&buf).to_bytes())
+    };
+    assert!(client
+        .try_report_device_usage(&warranty_id, &100_001, &1, &sign(100_000, 1), &None)
+        .is_err());
+
+    // Passing the limit ends the warranty long before its expiration date
+    client.report_device_usage(&warranty_id, &100_001, &1, &sign(100_001, 1), &None);
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.usage_reading, Some(100_001));
+    assert_eq!(warranty.status, WarrantyStatus::Expired);
+    assert!(client.is_warranty_expired(&warranty_id));
+    assert!(!client.is_warranty_expired(&untracked_id));
+}
+
+#[test]
+fn test_retailer_report() {
+    let env = Env::default();
+    let month: u64 = 30 * 24 * 60 * 60;
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+    let bucket = current_time / month;
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let store = Address::generate(&env);
+    let other_store = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let register = |serial: &str, retailer: Option<&Address>, price: Option<i128>| {
+        let mut input = WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            env.ledger().timestamp(),
+            env.ledger().timestamp() + 31536000,
+        );
+        if let Some(retailer) = retailer {
+            input = input.with_retailer(retailer.clone());
+        }
+        if let Some(price) = price {
+            input = input.with_price(price);
+        }
+        client.register_warranty(&owner, &input);
+    };
+
+    register("SN-1", Some(&store), Some(1_000));
+    register("SN-2", Some(&store), None);
+    register("SN-3", Some(&other_store), Some(500));
+    register("SN-4", None, Some(700));
+
+    env.ledger().set_timestamp(current_time + month);
+    register("SN-5", Some(&store), Some(250));
+
+    let report = client.get_retailer_report(&store, &(bucket - 1), &(bucket + 1));
+    assert_eq!(
+        report,
+        vec![
+            &env,
+            RetailerBucket {
+                bucket: bucket - 1,
+                registrations: 0,
+                price_total: 0,
+            },
+            RetailerBucket {
+                bucket,
+                registrations: 2,
+                price_total: 1_000,
+            },
+            RetailerBucket {
+                bucket: bucket + 1,
+                registrations: 1,
+                price_total: 250,
+            },
+        ]
+    );
+
+    let report = client.get_retailer_report(&other_store, &bucket, &bucket);
+    assert_eq!(report.get(0).unwrap().registrations, 1);
+    assert_eq!(report.get(0).unwrap().price_total, 500);
+    env.as_contract(&contract_id, || {
+        let key = RetailerKey::Bucket(store.clone(), bucket);
+        assert!(env.storage().persistent().get_ttl(&key) > 0);
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // Negative prices would let a registration shrink the reported totals
+    assert_eq!(
+        client.try_register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Laptop"),
+                String::from_str(&env, "SN-6"),
+                String::from_str(&env, "Manufacturer"),
+                env.ledger().timestamp(),
+                env.ledger().timestamp() + 31536000,
+            )
+            .with_retailer(store.clone())
+            .with_price(-1),
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+
+    assert_eq!(
+        client.try_get_retailer_report(&store, &bucket, &(bucket - 1)),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    assert_eq!(
+        client.try_get_retailer_report(&store, &0, &bucket),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+}
+
+#[test]
+fn test_campaigns() {
+    let env = Env::default();
+    let month: u64 = 30 * 24 * 60 * 60;
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let tv = String::from_str(&env, "TV");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let spring = client.create_campaign(
+        &issuer,
+        &manufacturer,
+        &3,
+        &current_time,
+        &(current_time + month),
+        &None,
+    );
+    let tv_promo = client.create_campaign(
+        &issuer,
+        &manufacturer,
+        &6,
+        &current_time,
+        &(current_time + month),
+        &Some(tv.clone()),
+    );
+    assert_eq!(
+        client.get_campaign(&tv_promo).unwrap().category_filter,
+        Some(tv.clone())
+    );
+    assert_eq!(
+        client.try_create_campaign(
+            &owner,
+            &manufacturer,
+            &3,
+            &current_time,
+            &current_time,
+            &None
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let register = |serial: &str, manufacturer: &str, category: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, manufacturer),
+                current_time,
+                current_time + 12 * month,
+            )
+            .with_category(category.clone()),
+        )
+    };
+
+    let laptop = client
+        .get_warranty(&register(
+            "SN-1",
+            "Manufacturer",
+            &String::from_str(&env, "Laptop"),
+        ))
+        .unwrap();
+    assert_eq!(laptop.campaign_id, Some(spring));
+    assert_eq!(laptop.expiration_date, current_time + 15 * month);
+
+    // The larger bonus wins when both campaigns match
+    let television = client
+        .get_warranty(&register("SN-2", "Manufacturer", &tv))
+        .unwrap();
+    assert_eq!(television.campaign_id, Some(tv_promo));
+    assert_eq!(television.expiration_date, current_time + 18 * month);
+
+    let other = client
+        .get_warranty(&register("SN-3", "Other", &tv))
+        .unwrap();
+    assert_eq!(other.campaign_id, None);
+    assert_eq!(other.expiration_date, current_time + 12 * month);
+
+    env.ledger().set_timestamp(current_time + month + 1);
+    let late = client
+        .get_warranty(&register("SN-4", "Manufacturer", &tv))
+        .unwrap();
+    assert_eq!(late.campaign_id, None);
+
+    assert_eq!(
+        client.get_campaign_stats(&spring),
+        CampaignStats {
+            registrations: 1,
+            bonus_secs_granted: 3 * month,
+        }
+    );
+    assert_eq!(client.get_campaign_stats(&tv_promo).registrations, 1);
+    env.as_contract(&contract_id, || {
+        let key = CampaignKey::Stats(spring);
+        assert_eq!(
+            env.storage().persistent().get_ttl(&key),
+            WARRANTY_TTL_EXTEND_TO
+        );
+        assert!(!env.storage().instance().has(&key));
+    });
+}
+
+#[test]
+fn test_customer_badges() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let other = String::from_str(&env, "Other");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let register = |serial: &str, manufacturer: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+
+    // Badges are opt-in per manufacturer
+    register("SN-1", &manufacturer);
+    assert!(!client.has_badge(&owner, &manufacturer));
+
+    assert_eq!(
+        client.try_set_badges_enabled(&owner, &manufacturer, &true),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_badges_enabled(&issuer, &manufacturer, &true);
+
+    let first_id = register("SN-2", &manufacturer);
+    register("SN-3", &manufacturer);
+    register("SN-4", &other);
+    assert!(client.has_badge(&owner, &manufacturer));
+    assert!(!client.has_badge(&owner, &other));
+    assert_eq!(
+        client.get_badge(&owner, &manufacturer),
+        Some(Badge {
+            warranty_id: first_id,
+            issued_at: current_time,
+        })
+    );
+    env.as_contract(&contract_id, || {
+        let key = BadgeKey::Badge(owner.clone(), manufacturer.clone());
+        assert_eq!(
+            env.storage().persistent().get_ttl(&key),
+            WARRANTY_TTL_EXTEND_TO
+        );
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // The badge stays with the registering owner when the warranty is sold
+    client.transfer_ownership(&first_id, &buyer, &None);
+    assert!(client.has_badge(&owner, &manufacturer));
+    assert!(!client.has_badge(&buyer, &manufacturer));
+
+    client.set_badges_enabled(&issuer, &manufacturer, &false);
+    assert!(client.has_badge(&owner, &manufacturer));
+}
+
+#[test]
+fn test_migrate_storage() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    // Lay out the warranties the way the first release stored them
+    let owner_key = DataKey::OwnerWarranties(owner.clone());
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        let mut legacy: Map<u64, LegacyWarrantyDataV1> = Map::new(&env);
+        for (id, serial) in [(1u64, "SN-1"), (2, "SN-2"), (3, "SN-3")] {
+            legacy.set(
+                id,
+                LegacyWarrantyDataV1 {
+                    id,
+                    owner: owner.clone(),
+                    product_name: String::from_str(&env, "Product"),
+                    serial_number: String::from_str(&env, serial),
+                    manufacturer: String::from_str(&env, "Manufacturer"),
+                    purchase_date: current_time,
+                    expiration_date: current_time + 31536000,
+                    status: WarrantyStatus::Active,
+                    created_at: current_time,
+                },
+            );
+        }
+        instance.set(&DataKey::WarrantyData, &legacy);
+        // The owner index was appended to, so it need not be ascending
+        instance.set(&owner_key, &vec![&env, 3u64, 1, 2]);
+        instance.set(&DataKey::WarrantyIds, &vec![&env, 1u64, 2, 3]);
+        instance.set(&DataKey::WarrantyCount, &3u64);
+    });
+    assert_eq!(client.get_stats().total, 0);
+
+    // Legacy records stay readable, and writing one moves it out of the map
+    assert_eq!(
+        client.get_warranty(&2).unwrap().serial_number,
+        String::from_str(&env, "SN-2")
+    );
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 1, 2, 3]
+    );
+    let first = client.get_warranties_by_owner_page(&owner, &None, &2);
+    assert_eq!(first.ids, vec![&env, 1, 2]);
+    assert_eq!(
+        client
+            .get_warranties_by_owner_page(&owner, &first.next.cursor(), &2)
+            .ids,
+        vec![&env, 3]
+    );
+    client.transfer_ownership(&2, &buyer, &None);
+    assert_eq!(client.get_warranties_by_owner(&buyer, &None), vec![&env, 2]);
+    assert_eq!(
+        client.get_warranty_ids_page(&None, &10).ids,
+        vec![&env, 1, 2, 3]
+    );
+
+    assert_eq!(
+        client.try_migrate_storage(&0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    assert_eq!(client.get_stats().active, 1);
+    assert_eq!(client.migrate_storage(&1), 1);
+    assert_eq!(client.migrate_storage(&1), 0);
+    assert_eq!(client.migrate_storage(&1), 0);
+
+    // Every record is counted once, whichever way it left the legacy map
+    let stats = client.get_stats();
+    assert_eq!(stats.total, 3);
+    assert_eq!(stats.active, 3);
+
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().instance().has(&DataKey::WarrantyData));
+        assert!(!env.storage().instance().has(&DataKey::WarrantyIds));
+        assert!(!env.storage().persistent().has(&DataKey::WarrantyIds));
+        assert!(!env.storage().instance().has(&owner_key));
+        assert!(env.storage().persistent().has(&owner_key));
+        for id in 1..=3u64 {
+            assert!(env.storage().persistent().has(&DataKey::Warranty(id)));
+        }
+    });
+    assert_indexes_match_records(&env, &contract_id, &[&owner, &buyer]);
+
+    // Migrated records carry the legacy fields and defaults for the rest
+    let migrated = client.get_warranty(&1).unwrap();
+    assert_eq!(migrated.serial_number, String::from_str(&env, "SN-1"));
+    assert_eq!(migrated.registrant, owner);
+    assert!(migrated.transferable);
+    assert_eq!(migrated.attestation, SaleAttestation::Unattested);
+    assert_eq!(client.get_warranty(&2).unwrap().owner, buyer);
+    assert_eq!(client.get_storage_stats().live_warranties, 3);
+    assert_eq!(
+        client
+            .get_warranty_by_serial(
+                &String::from_str(&env, "Manufacturer"),
+                &String::from_str(&env, "SN-3")
+            )
+            .unwrap()
+            .id,
+        3
+    );
+}
+
+#[test]
+fn test_serial_conflicts() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let serial = String::from_str(&env, "SN-1");
+
+    env.mock_all_auths();
+
+    for serial in ["SN-1", "SN-2", "SN-3"] {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        );
+    }
+
+    // Live registrations still reject a taken serial number
+    assert_eq!(
+        client.try_register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                serial.clone(),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        ),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // Records imported into the legacy layout with the same serial number
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        let mut legacy: Map<u64, LegacyWarrantyDataV1> = Map::new(&env);
+        for id in [2u64, 3] {
+            let warranty: WarrantyData = persistent.get(&DataKey::Warranty(id)).unwrap();
+            persistent.remove(&DataKey::SerialIndex(
+                manufacturer.clone(),
+                warranty.serial_number.clone(),
+            ));
+            legacy.set(
+                id,
+                LegacyWarrantyDataV1 {
+                    id,
+                    owner: warranty.owner,
+                    product_name: warranty.product_name,
+                    serial_number: serial.clone(),
+                    manufacturer: warranty.manufacturer,
+                    purchase_date: warranty.purchase_date,
+                    expiration_date: warranty.expiration_date,
+                    status: warranty.status,
+                    created_at: warranty.created_at,
+                },
+            );
+            persistent.remove(&DataKey::Warranty(id));
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::WarrantyData, &legacy);
+    });
+    assert_eq!(client.migrate_storage(&10), 0);
+
+    let conflict = client.get_serial_conflict(&manufacturer, &serial).unwrap();
+    assert_eq!(conflict.warranty_ids, vec![&env, 1, 2, 3]);
+    assert_eq!(conflict.recorded_at, current_time);
+    assert_eq!(conflict.winner_id, None);
+    env.as_contract(&contract_id, || {
+        let key = ConflictKey::Serial(manufacturer.clone(), serial.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    assert_eq!(
+        client.get_serial_conflict(&manufacturer, &String::from_str(&env, "SN-2")),
+        None
+    );
+
+    assert_eq!(
+        client.try_resolve_serial_conflict(&arbitrator, &serial, &2, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_arbitrator(&arbitrator, &true, &None);
+    assert_eq!(
+        client.try_resolve_serial_conflict(&arbitrator, &String::from_str(&env, "SN-2"), &2, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    let receipt = client.resolve_serial_conflict(&arbitrator, &serial, &2, &None);
+    assert_eq!(receipt.warranty_ids, vec![&env, 1, 3]);
+    let status = |id: u64| client.get_warranty(&id).unwrap().status;
+    assert_eq!(status(1), WarrantyStatus::Revoked);
+    assert_eq!(status(2), WarrantyStatus::Active);
+    assert_eq!(status(3), WarrantyStatus::Revoked);
+    assert_eq!(
+        client
+            .get_warranty_by_serial(&manufacturer, &serial)
+            .unwrap()
+            .id,
+        2
+    );
+    let history = client.get_warranty_history(&1, &0, &10);
+    assert_eq!(history.last().unwrap().actor, arbitrator);
+
+    // The ruling is kept and stands
+    let conflict = client.get_serial_conflict(&manufacturer, &serial).unwrap();
+    assert_eq!(conflict.winner_id, Some(2));
+    assert_eq!(conflict.arbitrator, Some(arbitrator.clone()));
+    assert_eq!(conflict.resolved_at, Some(current_time));
+    assert_eq!(
+        client.try_resolve_serial_conflict(&arbitrator, &serial, &1, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_policy_docs() {
+    let env = Env::default();
+    let day: u64 = 86400;
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let register = |serial: &str| {
+        let now = env.ledger().timestamp();
+        let id = client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                now,
+                now + 31536000,
+            ),
+        );
+        client.get_warranty(&id).unwrap().policy_version
+    };
+
+    assert_eq!(register("SN-1"), None);
+
+    let terms_v1 = BytesN::from_array(&env, &[1; 32]);
+    let uri_v1 = String::from_str(&env, "ipfs://terms-v1");
+    assert_eq!(
+        client.publish_policy_doc(&issuer, &manufacturer, &terms_v1, &uri_v1, &current_time),
+        1
+    );
+    // Version 2 is published ahead of time and only applies from next week
+    assert_eq!(
+        client.publish_policy_doc(
+            &issuer,
+            &manufacturer,
+            &BytesN::from_array(&env, &[2; 32]),
+            &String::from_str(&env, "ipfs://terms-v2"),
+            &(current_time + 7 * day),
+        ),
+        2
+    );
+    assert!(client
+        .try_publish_policy_doc(
+            &issuer,
+            &manufacturer,
+            &BytesN::from_array(&env, &[3; 32]),
+            &String::from_str(&env, "ipfs://terms-v3"),
+            &current_time,
+        )
+        .is_err());
+    assert_eq!(
+        client.try_publish_policy_doc(&owner, &manufacturer, &terms_v1, &uri_v1, &current_time),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    assert_eq!(register("SN-2"), Some(1));
+    assert_eq!(client.get_current_policy(&manufacturer).unwrap().version, 1);
+
+    env.ledger().set_timestamp(current_time + 7 * day);
+    assert_eq!(register("SN-3"), Some(2));
+
+    let doc = client.get_policy_doc(&manufacturer, &1).unwrap();
+    assert_eq!(doc.hash, terms_v1);
+    assert_eq!(doc.uri, uri_v1);
+    assert_eq!(doc.effective_date, current_time);
+    assert_eq!(client.get_policy_doc(&manufacturer, &0), None);
+    assert_eq!(client.get_policy_doc(&manufacturer, &3), None);
+}
+
+#[test]
+fn test_typed_errors() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        String::from_str(&env, "Manufacturer"),
+        current_time,
+        current_time + 31536000,
+    );
+    let mut backwards = input.clone();
+    backwards.expiration_date = current_time - 1;
+    assert_eq!(
+        client.try_register_warranty(&owner, &backwards),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    assert_eq!(
+        client.try_transfer_ownership(&1, &buyer, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    let warranty_id = client.register_warranty(&owner, &input);
+    client.revoke_warranty(&warranty_id, &None);
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &buyer, &None),
+        Err(Ok(WarrantyError::NotActive))
+    );
+
+    env.ledger().set_timestamp(current_time + 3601);
+    assert_eq!(
+        client.try_delete_warranty(&warranty_id, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+}
+
+#[test]
+fn test_extended_warranty_sales() {
+    let env = Env::default();
+    let month: u64 = 30 * 24 * 60 * 60;
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let laptops = String::from_str(&env, "Laptop");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    let token = token::Client::new(&env, &token_address);
+
+    let mut prices = Map::new(&env);
+    prices.set(12u32, 300i128);
+    prices.set(24u32, 500i128);
+    assert_eq!(
+        client.try_set_pricing(&buyer, &manufacturer, &laptops, &token_address, &prices),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_pricing(&issuer, &manufacturer, &laptops, &token_address, &prices);
+
+    assert_eq!(
+        client.try_quote_extended_warranty(&manufacturer, &laptops, &36),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    let quote = client.quote_extended_warranty(&manufacturer, &laptops, &24);
+    assert_eq!(quote.price, 500);
+    assert_eq!(quote.token, token_address);
+    assert_eq!(quote.expires_at, current_time + 3600);
+
+    // A later price change does not affect the quote already issued
+    prices.set(24u32, 800i128);
+    client.set_pricing(&issuer, &manufacturer, &laptops, &token_address, &prices);
+
+    let serial = String::from_str(&env, "LT-1");
+    let warranty_id = client.purchase_extended_warranty(&quote.quote_id, &buyer, &serial);
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.owner, buyer);
+    assert_eq!(warranty.serial_number, serial);
+    assert_eq!(warranty.category, Some(laptops.clone()));
+    assert_eq!(warranty.expiration_date, current_time + 24 * month);
+    assert_eq!(token.balance(&buyer), 500);
+    assert_eq!(token.balance(&issuer), 500);
+
+    // Quotes are single-use and lapse after an hour
+    assert_eq!(
+        client.try_purchase_extended_warranty(&quote.quote_id, &buyer, &serial),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    let stale = client.quote_extended_warranty(&manufacturer, &laptops, &12);
+    env.ledger().set_timestamp(current_time + 3601);
+    assert_eq!(
+        client.try_purchase_extended_warranty(&stale.quote_id, &buyer, &serial),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    assert_eq!(token.balance(&buyer), 500);
+}
+
+#[test]
+fn test_underwriting() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let insurer = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&owner, &1_000);
+    let token = token::Client::new(&env, &token_address);
+
+    let expiration_date = current_time + 31536000;
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            current_time - 86400,
+            expiration_date,
+        ),
+    );
+    let terms = CoverageTerms {
+        deductible: 50,
+        max_payout: Some(800),
+        excluded_categories: Vec::new(&env),
+        category_caps: Map::new(&env),
+    };
+    let covered_until = expiration_date + 31536000;
+
+    // Only registered underwriters sell coverage
+    assert_eq!(
+        client.try_underwrite(
+            &insurer,
+            &warranty_id,
+            &terms,
+            &covered_until,
+            &token_address,
+            &200
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.register_underwriter(&insurer);
+    assert!(client.is_underwriter(&insurer));
+
+    // The coverage has to reach past the manufacturer's term
+    assert_eq!(
+        client.try_underwrite(
+            &insurer,
+            &warranty_id,
+            &terms,
+            &expiration_date,
+            &token_address,
+            &200
+        ),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    client.underwrite(
+        &insurer,
+        &warranty_id,
+        &terms,
+        &covered_until,
+        &token_address,
+        &200,
+    );
+    assert_eq!(token.balance(&owner), 800);
+    assert_eq!(token.balance(&insurer), 200);
+    assert_eq!(
+        client.get_underwriting(&warranty_id),
+        Some(Underwriting {
+            underwriter: insurer.clone(),
+            terms: terms.clone(),
+            covered_until,
+            premium_token: token_address.clone(),
+            premium: 200,
+            underwritten_at: current_time,
+        })
+    );
+    assert_eq!(
+        client.try_underwrite(
+            &insurer,
+            &warranty_id,
+            &terms,
+            &covered_until,
+            &token_address,
+            &200
+        ),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // Removed underwriters sell no more, but their coverage stays
+    client.remove_underwriter(&insurer);
+    assert!(!client.is_underwriter(&insurer));
+    assert!(client.get_underwriting(&warranty_id).is_some());
+}
+
+/// Register, transfer and revoke a warranty at the given event verbosity.
+/// The published events land in this test's snapshot under
+/// `test_snapshots/`, which pins down each format.
+fn run_event_lifecycle(verbosity: Option<EventVerbosity>) {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_event_verbosity(), EventVerbosity::Full);
+    if let Some(verbosity) = verbosity {
+        client.set_event_verbosity(&verbosity);
+        assert_eq!(client.get_event_verbosity(), verbosity);
+    }
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        String::from_str(&env, "Manufacturer"),
+        current_time,
+        current_time + 31536000,
+    );
+    let warranty_id = client.register_warranty(&owner, &input);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+    client.revoke_warranty(&warranty_id, &None);
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.owner, buyer);
+    assert_eq!(warranty.status, WarrantyStatus::Revoked);
+}
+
+#[test]
+fn test_event_verbosity_full() {
+    run_event_lifecycle(None);
+}
+
+#[test]
+fn test_event_verbosity_minimal() {
+    run_event_lifecycle(Some(EventVerbosity::Minimal));
+}
+
+mod recording_insurer {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Env, Vec};
+
+    #[contract]
+    pub struct RecordingInsurer;
+
+    #[contractimpl]
+    impl RecordingInsurer {
+        pub fn on_claim_approved(env: Env, warranty_id: u64, policy_id: u64, claim_id: u64) {
+            let mut seen = Self::seen(env.clone());
+            seen.push_back((warranty_id, policy_id, claim_id));
+            env.storage().instance().set(&symbol_short!("seen"), &seen);
+        }
+
+        pub fn seen(env: Env) -> Vec<(u64, u64, u64)> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("seen"))
+                .unwrap_or(Vec::new(&env))
+        }
+    }
+}
+
+#[test]
+fn test_claims() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+    let insurer_id = env.register(recording_insurer::RecordingInsurer, ());
+    let insurer = recording_insurer::RecordingInsurerClient::new(&env, &insurer_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        manufacturer.clone(),
+        current_time,
+        current_time + 31536000,
+    );
+    let warranty_id = client.register_warranty(&owner, &input);
+    client.attach_policy(&warranty_id, &insurer_id, &42, &None);
+    assert!(client.get_claims(&warranty_id).is_empty());
+
+    let description = String::from_str(&env, "Screen flickers");
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, ""),
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let first = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    let second = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!((first, second), (1, 2));
+
+    let claims = client.get_claims(&warranty_id);
+    assert_eq!(claims.len(), 2);
+    let claim = claims.get(0).unwrap();
+    assert_eq!(claim.claimant, owner);
+    assert_eq!(claim.description, description);
+    assert_eq!(claim.status, ClaimStatus::Filed);
+    assert_eq!(claim.filed_at, current_time);
+    assert_eq!(claim.decided_at, None);
+
+    // Only the admin or the manufacturer's issuer decides claims
+    assert_eq!(
+        client.try_approve_claim(&stranger, &first, &None, &None, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_resolve_claim(&issuer, &first, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.approve_claim(&issuer, &first, &None, &None, &None);
+    assert_eq!(
+        insurer.seen(),
+        Vec::from_array(&env, [(warranty_id, 42u64, first)])
+    );
+    client.reject_claim(&admin, &second, &DenialReason::EvidenceInsufficient, &None);
+    assert_eq!(
+        client.try_approve_claim(&admin, &second, &None, &None, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    env.ledger().set_timestamp(current_time + 100);
+    client.resolve_claim(&admin, &first, &None);
+    let claims = client.get_claims(&warranty_id);
+    let resolved = claims.get(0).unwrap();
+    assert_eq!(resolved.status, ClaimStatus::Resolved);
+    assert_eq!(resolved.decided_at, Some(current_time));
+    assert_eq!(resolved.resolved_at, Some(current_time + 100));
+    assert_eq!(resolved.denial, ClaimDenial::NotDenied);
+    let rejected = claims.get(1).unwrap();
+    assert_eq!(rejected.status, ClaimStatus::Rejected);
+    assert_eq!(
+        rejected.denial,
+        ClaimDenial::Denied(DenialReason::EvidenceInsufficient)
+    );
+    assert_eq!(
+        client.try_approve_claim(&admin, &99, &None, &None, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    // An insurer that cannot take the callback does not block the approval
+    let uninsured = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN124"),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    client.attach_policy(&uninsured, &Address::generate(&env), &7, &None);
+    let third = client
+        .file_claim(
+            &uninsured,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    client.approve_claim(&issuer, &third, &None, &None, &None);
+    assert_eq!(
+        client.get_claims(&uninsured).get(0).unwrap().status,
+        ClaimStatus::Approved
+    );
+
+    // No claims once the warranty is out of force
+    env.ledger().set_timestamp(current_time + 31536001);
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotActive))
+    );
+    env.ledger().set_timestamp(current_time + 100);
+    client.revoke_warranty(&warranty_id, &None);
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotActive))
+    );
+}
+
+#[test]
+fn test_claim_payouts() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    let token = token::Client::new(&env, &token_address);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    let first = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    let second = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+
+    assert_eq!(
+        client.try_fund_claim_pool(&issuer, &token_address, &0, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.fund_claim_pool(&issuer, &token_address, &500, &None);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 500);
+    assert_eq!(token.balance(&contract_id), 500);
+
+    // The approval pays the owner out of the issuer's pool
+    let payout = |amount: i128| {
+        Some(ClaimPayout {
+            token: token_address.clone(),
+            amount,
+        })
+    };
+    client.approve_claim(&admin, &first, &None, &payout(200), &None);
+    assert_eq!(token.balance(&owner), 200);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 300);
+
+    // Adjusters may approve less than the claimant asked for, never more
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &Some(0),
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let partial = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &Some(150),
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_approve_claim(&admin, &partial, &None, &payout(151), &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&admin, &partial, &None, &payout(100), &None);
+    let claim = client.get_claims(&warranty_id).get(2).unwrap();
+    assert_eq!(
+        (claim.requested_amount, claim.approved_amount),
+        (Some(150), Some(100))
+    );
+    assert_eq!(token.balance(&owner), 300);
+
+    // A payout the pool cannot cover leaves the claim undecided
+    assert_eq!(
+        client.try_approve_claim(&issuer, &second, &None, &payout(400), &None),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+    let claim = client.get_claims(&warranty_id).get(1).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Filed);
+    assert_eq!(claim.approved_amount, None);
+
+    assert_eq!(
+        client.try_withdraw_claim_pool(&issuer, &token_address, &201, &None),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+    client.withdraw_claim_pool(&issuer, &token_address, &200, &None);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 0);
+    assert_eq!(token.balance(&issuer), 700);
+}
+
+#[test]
+fn test_claim_payout_requires_payer() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+    client.set_namespace_issuer(&name, &Some(issuer.clone()));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&maker, &1_000);
+    client.fund_claim_pool(&maker, &token_address, &1_000, &None);
+    let payout = |amount: i128| {
+        Some(ClaimPayout {
+            token: token_address.clone(),
+            amount,
+        })
+    };
+
+    let warranty_id = client.manufacturer_issue_warranty(
+        &maker,
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            name.clone(),
+            current_time,
+            current_time + 31536000,
+        )
+        .with_price(600),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    let first = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    let second = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+
+    // The namespace issuer reviews claims but cannot spend the maker's pool
+    assert_eq!(
+        client.try_approve_claim(&issuer, &first, &None, &payout(600), &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(client.get_pool_balance(&maker, &token_address), 1_000);
+    client.approve_claim(&issuer, &first, &None, &None, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!((claim.approved_amount, claim.paid_amount), (None, 0));
+
+    // Even the payer cannot pay out more than the product cost
+    assert_eq!(
+        client.try_approve_claim(&maker, &second, &None, &payout(601), &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&maker, &second, &None, &payout(400), &None);
+    assert_eq!(client.get_paid_out(&warranty_id), 400);
+
+    // Earlier payouts count against the limit
+    let third = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_approve_claim(&maker, &third, &None, &payout(201), &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&maker, &third, &None, &payout(200), &None);
+    assert_eq!(client.get_paid_out(&warranty_id), 600);
+    assert_eq!(client.get_pool_balance(&maker, &token_address), 400);
+    assert_eq!(
+        token::Client::new(&env, &token_address).balance(&owner),
+        600
+    );
+}
+
+#[test]
+fn test_verified_manufacturer_decides_claims() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &String::from_str(&env, "Acme"));
+
+    // No namespace issuer is set, so only the issuing manufacturer and the
+    // admin can decide
+    let warranty_id = client.manufacturer_issue_warranty(
+        &maker,
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Acme"),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+
+    assert_eq!(
+        client.try_approve_claim(&stranger, &claim_id, &None, &None, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.approve_claim(&maker, &claim_id, &None, &None, &None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(0).unwrap().status,
+        ClaimStatus::Approved
+    );
+}
+
+#[test]
+fn test_feedback() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+
+    let register = |serial: &str, expiration_date: u64| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                expiration_date,
+            ),
+        )
+    };
+    let repaired = register("SN1", current_time + 31536000);
+    let short = register("SN2", current_time + 3600);
+    let claim_id = client
+        .file_claim(
+            &repaired,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+
+    let scores = FeedbackScores {
+        overall: 4,
+        service: 5,
+        product_quality: 3,
+    };
+    let on_claim = FeedbackSubject::Claim(claim_id);
+    assert_eq!(
+        client.try_submit_feedback(&on_claim, &scores, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    client.approve_claim(&admin, &claim_id, &None, &None, &None);
+    client.resolve_claim(&admin, &claim_id, &None);
+    assert_eq!(
+        client.try_submit_feedback(
+            &on_claim,
+            &FeedbackScores {
+                overall: 6,
+                ..scores.clone()
+            },
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.submit_feedback(&on_claim, &scores, &None);
+    assert_eq!(
+        client.try_submit_feedback(&on_claim, &scores, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // Warranties are rated once they expired, and owners wait between ratings
+    let on_warranty = FeedbackSubject::Warranty(short);
+    assert_eq!(
+        client.try_submit_feedback(&on_warranty, &scores, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    advance_time(&env, 3601);
+    assert_eq!(
+        client.try_submit_feedback(&on_warranty, &scores, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    advance_time(&env, 86400);
+    client.submit_feedback(
+        &on_warranty,
+        &FeedbackScores {
+            overall: 2,
+            service: 1,
+            product_quality: 2,
+        },
+        &None,
+    );
+
+    assert_eq!(
+        client.get_manufacturer_rating(&manufacturer),
+        ManufacturerRating {
+            responses: 2,
+            overall_total: 6,
+            service_total: 6,
+            product_quality_total: 5,
+        }
+    );
+    env.as_contract(&contract_id, || {
+        let key = FeedbackKey::Rating(manufacturer.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+}
+
+#[test]
+fn test_export_owner_data() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let seller = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let center = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_service_center(&center, &true);
+
+    let register = |registrant: &Address, serial: &str| {
+        client.register_warranty(
+            registrant,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let bought = register(&seller, "SN1");
+    client.transfer_ownership(&bought, &owner, &None);
+    let archived = register(&owner, "SN2");
+    client.revoke_warranty(&archived, &None);
+    client.compact_owner_index(&owner, &None);
+    assert_eq!(client.get_archived_warranties(&owner).len(), 1);
+    let repaired = register(&owner, "SN3");
+    client.file_claim(
+        &repaired,
+        &ClaimCategory::Defect,
+        &String::from_str(&env, "Screen flickers"),
+        &env.ledger().timestamp(),
+        &None,
+        &None,
+    );
+    client.add_service_record(
+        &repaired,
+        &center,
+        &String::from_str(&env, "Replaced screen"),
+        &current_time,
+        &vec![&env],
+        &None,
+    );
+
+    assert_eq!(
+        client.try_export_owner_data(&owner, &None, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    let first = client.export_owner_data(&owner, &None, &2);
+    assert_eq!(first.warranties.len(), 2);
+    let transferred = first.warranties.get(0).unwrap();
+    assert_eq!(transferred.warranty.id, bought);
+    assert_eq!(transferred.transfers.len(), 1);
+    assert_eq!(transferred.transfers.get(0).unwrap().from, seller);
+    assert_eq!(first.warranties.get(1).unwrap().warranty.id, archived);
+
+    let second = client.export_owner_data(&owner, &first.next.cursor(), &2);
+    assert_eq!(second.next, NextPage::Done);
+    assert_eq!(second.warranties.len(), 1);
+    let last = second.warranties.get(0).unwrap();
+    assert_eq!(last.warranty.id, repaired);
+    assert_eq!(last.claims.len(), 1);
+    assert_eq!(last.service_records.len(), 1);
+    assert!(last.transfers.is_empty());
+
+    assert!(client
+        .export_owner_data(&seller, &None, &2)
+        .warranties
+        .is_empty());
+}
+
+#[test]
+fn test_agents_and_operators() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let retailer = Address::generate(&env);
+    let insurer = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let sold = register("SN1");
+    let other = register("SN2");
+
+    client.approve_agent(&sold, &Some(retailer.clone()), &None);
+    assert_eq!(client.get_approved(&sold), Some(retailer.clone()));
+    env.as_contract(&contract_id, || {
+        let key = AgentKey::Approved(sold);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    assert!(client
+        .get_allowed_actions(&sold, &retailer)
+        .contains(Symbol::new(&env, "agent_transfer_ownership")));
+
+    // The agent acts for the owner on that warranty only
+    let description = String::from_str(&env, "Screen flickers");
+    let claim_id = client
+        .agent_file_claim(
+            &retailer,
+            &sold,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(client.get_claims(&sold).get(0).unwrap().claimant, owner);
+    assert_eq!(claim_id, 1);
+    assert_eq!(
+        client.try_agent_update_status(&retailer, &other, &WarrantyStatus::Revoked, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // Operators act on all of the owner's warranties
+    assert!(!client.is_operator(&owner, &insurer));
+    client.set_operator(&owner, &insurer, &true);
+    assert!(client.is_operator(&owner, &insurer));
+    client.agent_update_status(&insurer, &other, &WarrantyStatus::Revoked, &None);
+    assert_eq!(
+        client.get_warranty(&other).unwrap().status,
+        WarrantyStatus::Revoked
+    );
+
+    // Approvals end with the transfer; operators only act for their owner
+    client.agent_transfer_ownership(&retailer, &sold, &buyer, &None);
+    assert_eq!(client.get_warranty(&sold).unwrap().owner, buyer);
+    assert_eq!(client.get_approved(&sold), None);
+    assert_eq!(
+        client.try_agent_update_status(&retailer, &sold, &WarrantyStatus::Revoked, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_agent_update_status(&insurer, &sold, &WarrantyStatus::Revoked, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    client.set_operator(&owner, &insurer, &false);
+    assert!(!client.is_operator(&owner, &insurer));
+}
+
+#[test]
+fn test_capacity() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.get_capacity(),
+        Capacity {
+            max_warranties: None,
+            registered: 0,
+        }
+    );
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    client.register_warranty(&owner, &input("SN1"));
+
+    assert_eq!(
+        client.try_set_capacity(&Some(0)),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_capacity(&Some(2));
+    client.register_warranty(&owner, &input("SN2"));
+    assert_eq!(
+        client.get_capacity(),
+        Capacity {
+            max_warranties: Some(2),
+            registered: 2,
+        }
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &input("SN3")),
+        Err(Ok(WarrantyError::CapacityReached))
+    );
+
+    // Raising the cap lets registrations continue
+    client.set_capacity(&Some(3));
+    client.register_warranty(&owner, &input("SN3"));
+    client.set_capacity(&None);
+    client.register_warranty(&owner, &input("SN4"));
+    assert_eq!(client.get_capacity().registered, 4);
+}
+
+#[test]
+fn test_registration_limits() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let neighbour = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    let tomorrow = current_time + 86400;
+    assert_eq!(
+        client.get_remaining_quota(&owner),
+        RemainingQuota {
+            owner: None,
+            global: None,
+            resets_at: tomorrow,
+        }
+    );
+
+    assert_eq!(
+        client.try_set_registration_limits(&RegistrationLimits {
+            per_owner_daily: Some(0),
+            global_daily: None,
+        }),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    client.set_registration_limits(&RegistrationLimits {
+        per_owner_daily: Some(2),
+        global_daily: Some(3),
+    });
+
+    client.register_warranty(&owner, &input("SN1"));
+    client.register_warranty(&owner, &input("SN2"));
+    assert_eq!(
+        client.get_remaining_quota(&owner),
+        RemainingQuota {
+            owner: Some(0),
+            global: Some(1),
+            resets_at: tomorrow,
+        }
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &input("SN3")),
+        Err(Ok(WarrantyError::RateLimited))
+    );
+
+    // The deployment's cap is shared by every owner
+    client.register_warranty(&neighbour, &input("SN4"));
+    assert_eq!(
+        client.try_register_warranty(&neighbour, &input("SN5")),
+        Err(Ok(WarrantyError::RateLimited))
+    );
+
+    // Both caps start over the next day
+    env.ledger().set_timestamp(tomorrow);
+    assert_eq!(client.get_remaining_quota(&owner).owner, Some(2));
+    client.register_warranty(&owner, &input("SN3"));
+    assert_eq!(client.get_warranty_count(), 4);
+}
+
+#[test]
+fn test_terms_templates() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let terms = WarrantyTerms {
+        coverage_type: CoverageType::PartsOnly,
+        max_claims: Some(1),
+        max_payout: Some(300),
+        transferable: false,
+        deductible: 50,
+        requires_attestation: false,
+    };
+    assert_eq!(
+        client.try_create_terms_template(&buyer, &manufacturer, &terms),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_create_terms_template(
+            &issuer,
+            &manufacturer,
+            &WarrantyTerms {
+                max_claims: Some(0),
+                ..terms.clone()
+            }
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let terms_id = client.create_terms_template(&issuer, &manufacturer, &terms);
+    let template = client.get_terms_template(&terms_id).unwrap();
+    assert_eq!(template.manufacturer, manufacturer);
+    assert_eq!(template.terms, terms);
+
+    let input = |serial: &str, manufacturer: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            String::from_str(&env, manufacturer),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+
+    // Templates only apply to their own manufacturer's registrations
+    assert_eq!(
+        client.try_register_warranty(&owner, &input("SN1", "Other").with_terms(terms_id)),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &input("SN1", "Manufacturer").with_terms(99)),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    // The template's transferability wins over the input's
+    let warranty_id = client.register_warranty(
+        &owner,
+        &input("SN1", "Manufacturer")
+            .with_terms(terms_id)
+            .with_transferable(true),
+    );
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.terms_id, Some(terms_id));
+    assert!(!warranty.transferable);
+    assert_eq!(client.get_warranty_terms(&warranty_id), Some(terms));
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &buyer, &None),
+        Err(Ok(WarrantyError::TransferNotAllowed))
+    );
+
+    // Payout estimates fall back to the template's deductible and cap
+    let estimate = client.estimate_payout(&warranty_id, &500);
+    assert_eq!(estimate.gross, 300);
+    assert_eq!(estimate.deductible, 50);
+
+    // The claim cap counts every claim filed against the warranty
+    let description = String::from_str(&env, "Screen flickers");
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    // Approved payouts cannot exceed the template's cap less the deductible
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+    let payout = |amount: i128| {
+        Some(ClaimPayout {
+            token: token_address.clone(),
+            amount,
+        })
+    };
+    assert_eq!(
+        client.try_approve_claim(&issuer, &claim_id, &None, &payout(251), &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&issuer, &claim_id, &None, &payout(250), &None);
+    assert_eq!(
+        token::Client::new(&env, &token_address).balance(&owner),
+        250
+    );
+
+    // Warranties registered without a template keep the defaults
+    let plain_id = client.register_warranty(&owner, &input("SN2", "Manufacturer"));
+    assert_eq!(client.get_warranty_terms(&plain_id), None);
+    assert!(client.get_warranty(&plain_id).unwrap().transferable);
+}
+
+#[test]
+fn test_retailer_attestation() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let retailer = Address::generate(&env);
+    let grey_market = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    // Only the admin and the manufacturer's namespace issuer manage the allowlist
+    assert_eq!(
+        client.try_set_retailer_authorized(&owner, &manufacturer, &retailer, &true),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_retailer_authorized(&issuer, &manufacturer, &retailer, &true);
+    client.set_retailer_authorized(&admin, &manufacturer, &grey_market, &true);
+    client.set_retailer_authorized(&issuer, &manufacturer, &grey_market, &false);
+    assert!(client.is_authorized_retailer(&manufacturer, &retailer));
+    assert!(!client.is_authorized_retailer(&manufacturer, &grey_market));
+
+    let terms_id = client.create_terms_template(
+        &issuer,
+        &manufacturer,
+        &WarrantyTerms {
+            coverage_type: CoverageType::PartsAndLabor,
+            max_claims: None,
+            max_payout: None,
+            transferable: true,
+            deductible: 0,
+            requires_attestation: true,
+        },
+    );
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        )
+        .with_terms(terms_id),
+    );
+
+    // Claims wait for the purchase to be attested
+    let description = String::from_str(&env, "Screen flickers");
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::NotAttested))
+    );
+
+    assert_eq!(
+        client.try_attest_purchase(&warranty_id, &grey_market, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.attest_purchase(&warranty_id, &retailer, &None);
+    let attestation = client
+        .get_warranty(&warranty_id)
+        .unwrap()
+        .attestation
+        .attested()
+        .unwrap();
+    assert_eq!(attestation.retailer, retailer);
+    assert_eq!(attestation.attested_at, current_time);
+    assert_eq!(
+        client.try_attest_purchase(&warranty_id, &retailer, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    let history = client.get_warranty_history(&warranty_id, &0, &10);
+    assert_eq!(
+        history.last().unwrap().details,
+        HistoryDetails::PurchaseAttested(retailer)
+    );
+
+    client.file_claim(
+        &warranty_id,
+        &ClaimCategory::Defect,
+        &description,
+        &env.ledger().timestamp(),
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn test_claim_arbitration() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    client.set_arbitrator(&arbitrator, &true, &None);
+    assert!(client.is_arbitrator(&arbitrator));
+    assert!(!client.is_arbitrator(&issuer));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+    let payout = Some(ClaimPayout {
+        token: token_address.clone(),
+        amount: 400,
+    });
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+
+    // Only rejected claims can be disputed, and only once
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_dispute_claim(&claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+    client.dispute_claim(&claim_id, &None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(0).unwrap().status,
+        ClaimStatus::Disputed
+    );
+    assert_eq!(
+        client.try_dispute_claim(&claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    // Reviewers cannot overrule the escalation themselves
+    assert_eq!(
+        client.try_rule_on_dispute(&issuer, &claim_id, &true, &payout, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_approve_claim(&issuer, &claim_id, &None, &None, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    // Upholding the dispute approves the claim and pays out of the pool
+    client.rule_on_dispute(&arbitrator, &claim_id, &true, &payout, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Approved);
+    assert_eq!(claim.denial, ClaimDenial::NotDenied);
+    assert_eq!(
+        token::Client::new(&env, &token_address).balance(&owner),
+        400
+    );
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 600);
+    let dispute = client.get_dispute(&claim_id).unwrap();
+    assert_eq!(dispute.arbitrator, Some(arbitrator.clone()));
+    assert_eq!(dispute.upheld, Some(true));
+    assert_eq!(dispute.ruled_at, Some(current_time));
+    client.resolve_claim(&issuer, &claim_id, &None);
+
+    // A dismissed dispute leaves the rejection final
+    let second_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    client.reject_claim(&issuer, &second_id, &DenialReason::FraudSuspected, &None);
+    client.dispute_claim(&second_id, &None);
+    assert_eq!(
+        client.try_rule_on_dispute(&arbitrator, &second_id, &false, &payout, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.rule_on_dispute(&arbitrator, &second_id, &false, &None, &None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(1).unwrap().status,
+        ClaimStatus::Rejected
+    );
+    assert_eq!(
+        client.try_dispute_claim(&second_id, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(client.get_dispute(&(second_id + 1)), None);
+    env.as_contract(&contract_id, || {
+        let key = ArbitrationKey::Dispute(second_id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // A rejection can only be disputed for 30 days
+    let third_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    client.reject_claim(&issuer, &third_id, &DenialReason::OutOfCoverage, &None);
+    env.ledger().set_timestamp(current_time + 30 * 86400 + 1);
+    assert_eq!(
+        client.try_dispute_claim(&third_id, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    env.ledger().set_timestamp(current_time + 30 * 86400);
+    client.dispute_claim(&third_id, &None);
+}
+
+#[test]
+fn test_open_obligations() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    client.set_arbitrator(&arbitrator, &true, &None);
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+
+    let register = |serial: &str, manufacturer: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let first_id = register("SN1", &manufacturer);
+    let second_id = register("SN2", &manufacturer);
+    register("SN3", &String::from_str(&env, "Other"));
+    let obligations = |active_warranties, open_claims, pending_settlement_amounts| Obligations {
+        active_warranties,
+        open_claims,
+        pending_settlement_amounts,
+    };
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 0, 0)
+    );
+
+    // Open claims count until resolved, less what was already paid
+    let description = String::from_str(&env, "Screen flickers");
+    let claim_id = client
+        .file_claim(
+            &first_id,
+            &ClaimCategory::Defect,
+            &description,
+            &current_time,
+            &Some(500),
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 1, 500)
+    );
+    let payout = Some(ClaimPayout {
+        token: token_address.clone(),
+        amount: 200,
+    });
+    client.approve_claim(&issuer, &claim_id, &Some(500), &payout, &None);
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 1, 300)
+    );
+    client.resolve_claim(&issuer, &claim_id, &None);
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 0, 0)
+    );
+
+    // A rejected claim reopens while disputed
+    let claim_id = client
+        .file_claim(
+            &second_id,
+            &ClaimCategory::Defect,
+            &description,
+            &current_time,
+            &Some(100),
+            &None,
+        )
+        .claim_id;
+    client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 0, 0)
+    );
+    client.dispute_claim(&claim_id, &None);
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(2, 1, 100)
+    );
+    client.rule_on_dispute(&arbitrator, &claim_id, &false, &None, &None);
+
+    client.revoke_warranty(&second_id, &None);
+    assert_eq!(
+        client.get_open_obligations(&manufacturer),
+        obligations(1, 0, 0)
+    );
+    assert_eq!(
+        client.get_open_obligations(&String::from_str(&env, "Other")),
+        obligations(1, 0, 0)
+    );
+}
+
+#[test]
+fn test_payment_plans() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &current_time,
+            &Some(600),
+            &None,
+        )
+        .claim_id;
+    let interval = 30 * 86400;
+
+    // Plans settle approved claims, within what was asked for
+    assert_eq!(
+        client.try_create_payment_plan(&claim_id, &token_address, &200, &3, &interval, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    assert_eq!(
+        client.try_create_payment_plan(&claim_id, &token_address, &200, &0, &interval, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_create_payment_plan(&claim_id, &token_address, &250, &3, &interval, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_create_payment_plan(
+            &claim_id,
+            &token_address,
+            &1,
+            &(MAX_PLAN_INSTALLMENTS + 1),
+            &interval,
+            &None
+        ),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    client.create_payment_plan(&claim_id, &token_address, &200, &3, &interval, &None);
+    assert_eq!(
+        client.try_create_payment_plan(&claim_id, &token_address, &200, &3, &interval, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_resolve_claim(&issuer, &claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    client.pay_installment(&claim_id, &None);
+    assert_eq!(token_client.balance(&owner), 200);
+    assert_eq!(client.get_paid_out(&warranty_id), 200);
+    assert_eq!(
+        client
+            .get_open_obligations(&manufacturer)
+            .pending_settlement_amounts,
+        400
+    );
+    assert!(!client.check_payment_plan(&claim_id));
+
+    // A missed deadline is recorded, and late installments still pay
+    advance_time(&env, 2 * interval + 1);
+    assert!(client.check_payment_plan(&claim_id));
+    let plan = client.get_payment_plan(&claim_id).unwrap();
+    assert_eq!(plan.defaulted_at, Some(current_time + 2 * interval));
+    assert_eq!(plan.paid_count, 1);
+
+    client.pay_installment(&claim_id, &None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(0).unwrap().status,
+        ClaimStatus::Approved
+    );
+    client.pay_installment(&claim_id, &None);
+
+    // The final installment settles the claim
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Resolved);
+    assert_eq!(claim.approved_amount, Some(600));
+    assert_eq!(token_client.balance(&owner), 600);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 400);
+    assert_eq!(client.get_open_obligations(&manufacturer).open_claims, 0);
+    assert_eq!(
+        client.try_pay_installment(&claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_check_payment_plan(&(claim_id + 1)),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+fn test_claims_escrow() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+
+    assert_eq!(
+        client.try_fund_claims_escrow(&issuer, &token_address, &0, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.fund_claims_escrow(&issuer, &token_address, &500, &None);
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 500);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 0);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let file = |requested: Option<i128>| {
+        client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &current_time,
+                &requested,
+                &None,
+            )
+            .claim_id
+    };
+
+    // Only approved claims are settled, for what was asked for
+    let claim_id = file(Some(300));
+    assert_eq!(
+        client.try_settle_claim(&claim_id, &token_address, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    client.settle_claim(&claim_id, &token_address, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Resolved);
+    assert_eq!(claim.approved_amount, Some(300));
+    assert_eq!(token_client.balance(&owner), 300);
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 200);
+    assert_eq!(client.get_paid_out(&warranty_id), 300);
+
+    // Settlements the escrow cannot cover leave the claim approved
+    let claim_id = file(Some(300));
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    assert_eq!(
+        client.try_settle_claim(&claim_id, &token_address, &None),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+    assert_eq!(
+        client
+            .get_open_obligations(&manufacturer)
+            .pending_settlement_amounts,
+        300
+    );
+
+    // A partial approval settles for the approved amount only
+    let claim_id = file(Some(180));
+    assert_eq!(
+        client.try_approve_claim(&issuer, &claim_id, &Some(181), &None, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.approve_claim(&issuer, &claim_id, &Some(120), &None, &None);
+    assert_eq!(
+        client
+            .get_open_obligations(&manufacturer)
+            .pending_settlement_amounts,
+        420
+    );
+    client.settle_claim(&claim_id, &token_address, &None);
+    let claim = client.get_claims(&warranty_id).get(2).unwrap();
+    assert_eq!(
+        (
+            claim.requested_amount,
+            claim.approved_amount,
+            claim.paid_amount
+        ),
+        (Some(180), Some(120), 120)
+    );
+    assert_eq!(token_client.balance(&owner), 420);
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 80);
+    assert_eq!(client.get_paid_out(&warranty_id), 420);
+    assert_eq!(
+        client
+            .get_open_obligations(&manufacturer)
+            .pending_settlement_amounts,
+        300
+    );
+
+    let claim_id = file(None);
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    assert_eq!(
+        client.try_settle_claim(&claim_id, &token_address, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_escrow_auto_topup() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    token_client.approve(&issuer, &contract_id, &600, &1_000);
+
+    assert_eq!(
+        client.try_set_auto_topup(&issuer, &token_address, &-1, &300),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_auto_topup(&issuer, &token_address, &100, &300);
+    assert_eq!(
+        client.get_auto_topup(&issuer, &token_address),
+        Some(AutoTopUp {
+            floor: 100,
+            amount: 300
+        })
+    );
+    client.fund_claims_escrow(&issuer, &token_address, &200, &None);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let settle = |requested: i128| {
+        let claim_id = client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &current_time,
+                &Some(requested),
+                &None,
+            )
+            .claim_id;
+        client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+        client.try_settle_claim(&claim_id, &token_address, &None)
+    };
+
+    // Settlements that would go below the floor refill the escrow first
+    assert!(settle(150).is_ok());
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 350);
+    assert!(settle(500).is_ok());
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 150);
+    assert_eq!(token_client.allowance(&issuer, &contract_id), 0);
+
+    // Once the allowance is used up, settlements rely on what is escrowed
+    assert!(settle(100).is_ok());
+    assert_eq!(client.get_escrow_balance(&issuer, &token_address), 50);
+    assert_eq!(settle(100), Err(Ok(WarrantyError::InsufficientFunds)));
+
+    client.set_auto_topup(&issuer, &token_address, &0, &0);
+    assert_eq!(client.get_auto_topup(&issuer, &token_address), None);
+}
+
+#[test]
+fn test_claim_velocity() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let first = register("SN1");
+    let second = register("SN2");
+    let file = |warranty_id: u64| {
+        client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &env.ledger().timestamp(),
+                &None,
+                &None,
+            )
+            .claim_id
+    };
+
+    assert_eq!(
+        client.try_set_velocity_thresholds(&Some(VelocityThresholds {
+            max_claims_30d: 0,
+            max_claims_90d: 3,
+        })),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_velocity_thresholds(&Some(VelocityThresholds {
+        max_claims_30d: 2,
+        max_claims_90d: 3,
+    }));
+
+    // Velocity counts claims per serial number and per owner
+    let claim_id = file(first);
+    assert!(!client.is_held_for_review(&claim_id));
+    let claim_id = file(second);
+    assert!(!client.is_held_for_review(&claim_id));
+    let signals = client.get_fraud_signals(&first);
+    assert_eq!(signals.serial_claims_30d, 1);
+    assert_eq!(signals.owner_claims_30d, 2);
+    assert_eq!(signals.owner_claims_90d, 2);
+    assert!(signals.requires_review);
+
+    // A claim filed at a threshold is held until a reviewer clears it
+    let claim_id = file(first);
+    assert!(client.is_held_for_review(&claim_id));
+    assert_eq!(
+        client.try_approve_claim(&issuer, &claim_id, &None, &None, &None),
+        Err(Ok(WarrantyError::ReviewRequired))
+    );
+    assert_eq!(
+        client.try_clear_claim_review(&stranger, &claim_id, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.clear_claim_review(&issuer, &claim_id, &None);
+    assert!(!client.is_held_for_review(&claim_id));
+    assert_eq!(
+        client.try_clear_claim_review(&issuer, &claim_id, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+
+    // Claims leave the 30-day count first, then the 90-day one
+    advance_time(&env, 31 * 86400);
+    let signals = client.get_fraud_signals(&second);
+    assert_eq!(signals.owner_claims_30d, 0);
+    assert_eq!(signals.owner_claims_90d, 3);
+    assert!(signals.requires_review);
+    advance_time(&env, 60 * 86400);
+    let signals = client.get_fraud_signals(&second);
+    assert_eq!(signals.owner_claims_90d, 0);
+    assert!(!signals.requires_review);
+    let claim_id = file(second);
+    assert!(!client.is_held_for_review(&claim_id));
+
+    client.set_velocity_thresholds(&None);
+    assert_eq!(client.get_velocity_thresholds(), None);
+}
+
+#[test]
+fn test_chargeback() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    client.set_arbitrator(&arbitrator, &true, &None);
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    let asset = token::StellarAssetClient::new(&env, &token_address);
+    asset.mint(&issuer, &1_000);
+    asset.mint(&admin, &100);
+    client.fund_claim_pool(&issuer, &token_address, &500, &None);
+
+    // The owner has keeper earnings in the payout token
+    client.set_keeper_reward_token(&token_address);
+    client.set_keeper_fee(&KeeperTask::TtlBump, &50);
+    client.fund_keeper_pool(&admin, &100);
+    client.set_keeper(&owner, &true);
+    client.keeper_bump_ttl(&owner);
+    assert_eq!(client.get_keeper_earnings(&owner), 50);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let file = || {
+        client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &current_time,
+                &Some(300),
+                &None,
+            )
+            .claim_id
+    };
+
+    // Only claims that paid out can be charged back
+    let unpaid_id = file();
+    client.approve_claim(&issuer, &unpaid_id, &None, &None, &None);
+    assert_eq!(
+        client.try_chargeback(&arbitrator, &unpaid_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    let claim_id = file();
+    let payout = Some(ClaimPayout {
+        token: token_address.clone(),
+        amount: 300,
+    });
+    client.approve_claim(&issuer, &claim_id, &None, &payout, &None);
+    // A paid claim still open for settlement is not a chargeback
+    assert_eq!(
+        client.try_chargeback(&arbitrator, &claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.resolve_claim(&issuer, &claim_id, &None);
+    assert_eq!(
+        client.try_chargeback(&issuer, &claim_id, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_repay_chargeback(&owner, &claim_id, &100, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.chargeback(&arbitrator, &claim_id, &None);
+
+    // The payout is owed back, and nothing else held for the owner is taken
+    let chargeback = client.get_chargeback(&claim_id).unwrap();
+    assert_eq!(chargeback.amount, 300);
+    assert_eq!(chargeback.recovered, 0);
+    assert_eq!(chargeback.payer, issuer);
+    assert_eq!(chargeback.token, token_address);
+    assert_eq!(client.get_keeper_earnings(&owner), 50);
+    assert_eq!(client.get_keeper_pool(), 100);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 200);
+
+    // Repayments go to the issuer's pool, up to what was paid out
+    assert_eq!(token_client.balance(&owner), 300);
+    client.repay_chargeback(&owner, &claim_id, &250, &None);
+    assert_eq!(
+        client.try_repay_chargeback(&owner, &claim_id, &51, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_repay_chargeback(&owner, &claim_id, &0, &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.repay_chargeback(&owner, &claim_id, &50, &None);
+    assert_eq!(client.get_chargeback(&claim_id).unwrap().recovered, 300);
+    assert_eq!(token_client.balance(&owner), 0);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 500);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(1).unwrap().status,
+        ClaimStatus::ChargedBack
+    );
+    assert_eq!(
+        client.try_chargeback(&arbitrator, &claim_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    // The owner's later claims are held for review
+    let signals = client.get_fraud_signals(&warranty_id);
+    assert_eq!(signals.owner_chargebacks, 1);
+    assert!(signals.requires_review);
+    let held_id = file();
+    assert_eq!(
+        client.try_approve_claim(&issuer, &held_id, &None, &None, &None),
+        Err(Ok(WarrantyError::ReviewRequired))
+    );
+}
+
+#[test]
+fn test_consortium() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let first_issuer = Address::generate(&env);
+    let second_issuer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let panelist = Address::generate(&env);
+    let first_name = String::from_str(&env, "First");
+    let second_name = String::from_str(&env, "Second");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&first_name, &Some(first_issuer.clone()));
+    client.set_namespace_issuer(&second_name, &Some(second_issuer.clone()));
+    client.set_arbitrator(&arbitrator, &true, &None);
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&first_issuer, &1_000);
+
+    // Membership
+    let consortium_id =
+        client.create_consortium(&operator, &String::from_str(&env, "Appliances"), &None);
+    client.add_consortium_member(&consortium_id, &first_issuer, &None);
+    client.add_consortium_member(&consortium_id, &second_issuer, &None);
+    let other_id = client.create_consortium(&operator, &String::from_str(&env, "Other"), &None);
+    assert_eq!(
+        client.try_add_consortium_member(&other_id, &first_issuer, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.get_member_consortium(&second_issuer),
+        Some(consortium_id)
+    );
+    assert_eq!(
+        client.get_consortium(&consortium_id).unwrap().members.len(),
+        2
+    );
+    assert_eq!(
+        client.try_fund_consortium_escrow(&owner, &token_address, &100, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.set_consortium_claim_limit(
+        &consortium_id,
+        &Some(ClaimLimit {
+            max_claims: 3,
+            period_secs: 86400,
+        }),
+    );
+
+    let register = |manufacturer: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, "SN1"),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let first = register(&first_name);
+    let second = register(&second_name);
+    let file = |warranty_id: u64, requested: Option<i128>| {
+        client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &current_time,
+                &requested,
+                &None,
+            )
+            .claim_id
+    };
+
+    // Settlements a member's own escrow cannot cover come out of the shared one
+    client.fund_consortium_escrow(&first_issuer, &token_address, &500, &None);
+    let claim_id = file(first, Some(300));
+    client.approve_claim(&first_issuer, &claim_id, &None, &None, &None);
+    assert_eq!(
+        client
+            .get_member_account(&consortium_id, &first_issuer, &token_address)
+            .liabilities,
+        300
+    );
+    client.settle_claim(&claim_id, &token_address, &None);
+    assert_eq!(token_client.balance(&owner), 300);
+    assert_eq!(
+        client.get_consortium_escrow(&consortium_id, &token_address),
+        200
+    );
+    assert_eq!(
+        client.get_member_account(&consortium_id, &first_issuer, &token_address),
+        MemberAccount {
+            contributed: 500,
+            drawn: 300,
+            withdrawn: 0,
+            liabilities: 0,
+        }
+    );
+
+    // The shared escrow backs every member, token by token
+    let status = client.get_solvency(&second_issuer);
+    assert_eq!(status.collateral, 200);
+    assert_eq!(
+        status.collateral_by_token.get(token_address.clone()),
+        Some(200)
+    );
+
+    // Once the panel has anyone on it, only the panel rules on members' disputes
+    client.set_consortium_arbitrator(&consortium_id, &panelist, &true);
+    let claim_id = file(second, None);
+    client.reject_claim(
+        &second_issuer,
+        &claim_id,
+        &DenialReason::OutOfCoverage,
+        &None,
+    );
+    client.dispute_claim(&claim_id, &None);
+    assert_eq!(
+        client.try_rule_on_dispute(&arbitrator, &claim_id, &false, &None, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.rule_on_dispute(&panelist, &claim_id, &false, &None, &None);
+    assert_eq!(
+        client.get_claims(&second).get(0).unwrap().status,
+        ClaimStatus::Rejected
+    );
+
+    // The claim cap counts the owner's claims across all members
+    file(first, None);
+    assert_eq!(
+        client.try_file_claim(
+            &second,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &current_time,
+            &None,
+            &None,
+        ),
+        Err(Ok(WarrantyError::RateLimited))
+    );
+
+    client.remove_consortium_member(&second_issuer, &consortium_id, &second_issuer, &None);
+    assert_eq!(client.get_member_consortium(&second_issuer), None);
+    assert_eq!(
+        client.try_remove_consortium_member(&owner, &consortium_id, &first_issuer, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // A departing member takes back what it paid in and did not draw
+    assert_eq!(
+        client.try_withdraw_consortium_share(&first_issuer, &consortium_id, &token_address, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_withdraw_consortium_share(&second_issuer, &consortium_id, &token_address, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.remove_consortium_member(&operator, &consortium_id, &first_issuer, &None);
+    client.withdraw_consortium_share(&first_issuer, &consortium_id, &token_address, &None);
+    assert_eq!(token_client.balance(&first_issuer), 700);
+    assert_eq!(
+        client
+            .get_member_account(&consortium_id, &first_issuer, &token_address)
+            .withdrawn,
+        200
+    );
+    assert_eq!(
+        client.try_withdraw_consortium_share(&first_issuer, &consortium_id, &token_address, &None),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+}
+
+#[test]
+fn test_solvency() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &200, &None);
+
+    assert_eq!(client.get_solvency_threshold(), 10_000);
+    assert_eq!(
+        client.try_set_solvency_threshold(&0),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let status = client.get_solvency(&issuer);
+    assert_eq!(status.liabilities, 0);
+    assert_eq!(status.collateral, 200);
+    assert_eq!(status.ratio_bps, 0);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &current_time,
+            &Some(500),
+            &None,
+        )
+        .claim_id;
+
+    // Only approved claims count as liabilities
+    assert_eq!(client.get_solvency(&issuer).liabilities, 0);
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    let status = client.get_solvency(&issuer);
+    assert_eq!(status.liabilities, 500);
+    assert_eq!(status.ratio_bps, 25_000);
+    assert!(status.under_collateralized);
+
+    // Escrow counts as collateral alongside the pool
+    client.fund_claims_escrow(&issuer, &token_address, &500, &None);
+    let status = client.get_solvency(&issuer);
+    assert_eq!(status.collateral, 700);
+    assert_eq!(status.ratio_bps, 7_142);
+    assert!(!status.under_collateralized);
+
+    client.set_solvency_threshold(&5_000);
+    assert!(client.get_solvency(&issuer).under_collateralized);
+
+    // Settled claims are no longer owed
+    client.settle_claim(&claim_id, &token_address, &None);
+    let status = client.get_solvency(&issuer);
+    assert_eq!(status.liabilities, 0);
+    assert_eq!(status.collateral, 200);
+    assert!(!status.under_collateralized);
+}
+
+mod price_oracle {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol};
+
+    #[contract]
+    pub struct PriceOracle;
+
+    #[contractimpl]
+    impl PriceOracle {
+        pub fn set_rate(env: Env, rate: i128) {
+            env.storage().instance().set(&symbol_short!("rate"), &rate);
+        }
+
+        pub fn rate(env: Env, _currency: Symbol, _token: Address) -> i128 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("rate"))
+                .unwrap_or(0)
+        }
+    }
+}
+
+#[test]
+fn test_settlement_rates() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+    let oracle_id = env.register(price_oracle::PriceOracle, ());
+    let oracle = price_oracle::PriceOracleClient::new(&env, &oracle_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+
+    let register = |serial: &str, currency: Option<Symbol>| {
+        let mut input = WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            manufacturer.clone(),
+            current_time,
+            current_time + 31536000,
+        );
+        if let Some(currency) = currency {
+            input = input.with_currency(currency);
+        }
+        let warranty_id = client.register_warranty(&owner, &input);
+        let claim_id = client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &String::from_str(&env, "Screen flickers"),
+                &current_time,
+                &Some(300),
+                &None,
+            )
+            .claim_id;
+        (warranty_id, claim_id)
+    };
+    let payout = Some(ClaimPayout {
+        token: token_address.clone(),
+        amount: 100,
+    });
+
+    // Without an oracle, payouts record no rate
+    let (warranty_id, claim_id) = register("SN1", Some(symbol_short!("USD")));
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().currency,
+        Some(symbol_short!("USD"))
+    );
+    client.approve_claim(&issuer, &claim_id, &None, &payout, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.settlement_rate, None);
+    assert_eq!(claim.settlement_token, None);
+
+    // An oracle without a usable rate blocks the payout
+    client.set_price_oracle(&Some(oracle_id.clone()));
+    assert_eq!(client.get_price_oracle(), Some(oracle_id.clone()));
+    let (warranty_id, claim_id) = register("SN2", Some(symbol_short!("USD")));
+    assert_eq!(
+        client.try_approve_claim(&issuer, &claim_id, &None, &payout, &None),
+        Err(Ok(WarrantyError::RateUnavailable))
+    );
+
+    oracle.set_rate(&12_500_000);
+    client.approve_claim(&issuer, &claim_id, &None, &payout, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.settlement_token, Some(token_address.clone()));
+    assert_eq!(claim.settlement_rate, Some(12_500_000));
+    assert_eq!(claim.rate_quoted_at, Some(current_time));
+
+    // Warranties naming no currency need no rate
+    let (warranty_id, claim_id) = register("SN3", None);
+    client.approve_claim(&issuer, &claim_id, &None, &payout, &None);
+    assert_eq!(
+        client
+            .get_claims(&warranty_id)
+            .get(0)
+            .unwrap()
+            .settlement_rate,
+        None
+    );
+
+    // Each installment records the rate it was paid at
+    let (warranty_id, claim_id) = register("SN4", Some(symbol_short!("USD")));
+    client.approve_claim(&issuer, &claim_id, &None, &None, &None);
+    client.create_payment_plan(&claim_id, &token_address, &100, &2, &86400, &None);
+    client.pay_installment(&claim_id, &None);
+    advance_time(&env, 86400);
+    oracle.set_rate(&13_000_000);
+    client.pay_installment(&claim_id, &None);
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.settlement_rate, Some(13_000_000));
+    assert_eq!(claim.rate_quoted_at, Some(current_time + 86400));
+
+    client.set_price_oracle(&None);
+    assert_eq!(client.get_price_oracle(), None);
+}
+
+#[test]
+fn test_dispute_panel() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let panel = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    for arbitrator in panel.iter() {
+        client.set_arbitrator(arbitrator, &true, &None);
+    }
+
+    assert_eq!(client.get_dispute_quorum(), 1);
+    assert_eq!(
+        client.try_set_dispute_quorum(&0),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_dispute_quorum(&(MAX_DISPUTE_QUORUM + 1)),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    client.set_dispute_quorum(&3);
+    assert_eq!(client.get_dispute_quorum(), 3);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    let disputed_claim = || {
+        let claim_id = client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &description,
+                &env.ledger().timestamp(),
+                &None,
+                &None,
+            )
+            .claim_id;
+        client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+        client.dispute_claim(&claim_id, &None);
+        claim_id
+    };
+
+    // Two of three votes carry the ruling, and each arbitrator votes once
+    let claim_id = disputed_claim();
+    client.vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Uphold, &None);
+    assert_eq!(
+        client.try_vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Uphold, &None),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_vote_on_dispute(&issuer, &claim_id, &DisputeOutcome::Uphold, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.vote_on_dispute(&panel[1], &claim_id, &DisputeOutcome::Dismiss, &None);
+    let dispute = client.get_dispute(&claim_id).unwrap();
+    assert_eq!(dispute.quorum, 3);
+    assert_eq!(dispute.votes_cast, 2);
+    assert_eq!(dispute.upheld, None);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(0).unwrap().status,
+        ClaimStatus::Disputed
+    );
+    assert_eq!(
+        client.get_dispute_vote(&claim_id, &panel[1]),
+        Some(DisputeOutcome::Dismiss)
+    );
+    assert_eq!(client.get_dispute_vote(&claim_id, &panel[2]), None);
+
+    client.rule_on_dispute(&panel[2], &claim_id, &true, &None, &None);
+    let dispute = client.get_dispute(&claim_id).unwrap();
+    assert_eq!(dispute.arbitrator, Some(panel[2].clone()));
+    assert_eq!(dispute.upheld, Some(true));
+    assert_eq!(dispute.votes_cast, 3);
+    assert_eq!(
+        client.get_claims(&warranty_id).get(0).unwrap().status,
+        ClaimStatus::Approved
+    );
+    client.resolve_claim(&issuer, &claim_id, &None);
+
+    // A lowered quorum applies to disputes raised afterwards
+    client.set_dispute_quorum(&1);
+    let claim_id = disputed_claim();
+    client.vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Dismiss, &None);
+    assert_eq!(client.get_dispute(&claim_id).unwrap().upheld, Some(false));
+    assert_eq!(
+        client.try_vote_on_dispute(&panel[1], &claim_id, &DisputeOutcome::Uphold, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_arbitrator_fees() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let panel = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    for arbitrator in panel.iter() {
+        client.set_arbitrator(arbitrator, &true, &None);
+    }
+    client.set_dispute_quorum(&3);
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&owner, &1_000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    client.fund_claim_pool(&issuer, &token_address, &1_000, &None);
+
+    assert_eq!(client.get_dispute_fee(), None);
+    let fee = DisputeFee {
+        token: token_address.clone(),
+        amount: 101,
+    };
+    assert_eq!(
+        client.try_set_dispute_fee(&Some(DisputeFee {
+            token: token_address.clone(),
+            amount: 0,
+        })),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.set_dispute_fee(&Some(fee.clone()));
+    assert_eq!(client.get_dispute_fee(), Some(fee));
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    let disputed_claim = || {
+        let claim_id = client
+            .file_claim(
+                &warranty_id,
+                &ClaimCategory::Defect,
+                &description,
+                &env.ledger().timestamp(),
+                &None,
+                &None,
+            )
+            .claim_id;
+        client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+        client.dispute_claim(&claim_id, &None);
+        claim_id
+    };
+
+    // The owner puts the fee down and loses it with the dispute
+    let claim_id = disputed_claim();
+    assert_eq!(token_client.balance(&owner), 899);
+    let dispute = client.get_dispute(&claim_id).unwrap();
+    assert_eq!(dispute.fee, 101);
+    assert_eq!(dispute.fee_token, Some(token_address.clone()));
+    assert_eq!(dispute.disputed_by, owner);
+    client.vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Dismiss, &None);
+    client.vote_on_dispute(&panel[2], &claim_id, &DisputeOutcome::Uphold, &None);
+    assert_eq!(client.get_dispute_fee_pool(&token_address), 0);
+    client.vote_on_dispute(&panel[1], &claim_id, &DisputeOutcome::Dismiss, &None);
+
+    // The voters for the ruling share it, the deciding voter taking the rest
+    assert_eq!(client.get_arbitrator_fees(&panel[0], &token_address), 50);
+    assert_eq!(client.get_arbitrator_fees(&panel[1], &token_address), 51);
+    assert_eq!(client.get_arbitrator_fees(&panel[2], &token_address), 0);
+    assert_eq!(client.get_dispute_fee_pool(&token_address), 101);
+    assert_eq!(token_client.balance(&owner), 899);
+
+    // An upheld dispute returns the owner's fee and charges the claim pool
+    let claim_id = disputed_claim();
+    client.vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Uphold, &None);
+    client.vote_on_dispute(&panel[2], &claim_id, &DisputeOutcome::Uphold, &None);
+    assert_eq!(token_client.balance(&owner), 899);
+    assert_eq!(client.get_pool_balance(&issuer, &token_address), 899);
+    assert_eq!(client.get_arbitrator_fees(&panel[0], &token_address), 100);
+    assert_eq!(client.get_arbitrator_fees(&panel[2], &token_address), 51);
+    assert_eq!(client.get_dispute_fee_pool(&token_address), 202);
+
+    assert_eq!(
+        client.withdraw_arbitrator_fees(&panel[0], &token_address),
+        100
+    );
+    assert_eq!(token_client.balance(&panel[0]), 100);
+    assert_eq!(client.get_arbitrator_fees(&panel[0], &token_address), 0);
+    assert_eq!(client.get_dispute_fee_pool(&token_address), 102);
+    assert_eq!(
+        client.withdraw_arbitrator_fees(&panel[0], &token_address),
+        0
+    );
+
+    // A claim pool that cannot cover the fee holds up an upholding ruling
+    client.withdraw_claim_pool(&issuer, &token_address, &899, &None);
+    let claim_id = disputed_claim();
+    client.vote_on_dispute(&panel[0], &claim_id, &DisputeOutcome::Uphold, &None);
+    assert_eq!(
+        client.try_vote_on_dispute(&panel[1], &claim_id, &DisputeOutcome::Uphold, &None),
+        Err(Ok(WarrantyError::InsufficientFunds))
+    );
+
+    client.set_dispute_fee(&None);
+    assert_eq!(client.get_dispute_fee(), None);
+}
+
+#[test]
+fn test_claim_window() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_claim_window_secs(), 30 * 86400);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    advance_time(&env, 60 * 86400);
+    let now = current_time + 60 * 86400;
+
+    // Incidents must fall between the purchase and now
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &(current_time - 1),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &(now + 1),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    // Claims are only valid for the window after the incident
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &(now - 30 * 86400 - 1),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &(now - 30 * 86400),
+            &None,
+            &None,
+        )
+        .claim_id;
+    let claim = client.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.claim_id, claim_id);
+    assert_eq!(claim.incident_date, now - 30 * 86400);
+    assert_eq!(claim.filed_at, now);
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().claims_filed, 1);
+
+    // A window of zero lifts the deadline
+    client.set_claim_window_secs(&0);
+    client.file_claim(
+        &warranty_id,
+        &ClaimCategory::Defect,
+        &description,
+        &current_time,
+        &None,
+        &None,
+    );
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().claims_filed, 2);
+    assert_eq!(client.describe().claim_window_secs, 0);
+}
+
+#[test]
+fn test_transfer_claim_cooldown() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_transfer_claim_cooldown_secs(), 0);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let description = String::from_str(&env, "Screen flickers");
+    let claims_open_at = || {
+        client
+            .get_warranty_summary(&warranty_id)
+            .unwrap()
+            .claims_open_at
+    };
+
+    // Without a cooldown the new owner can claim right away
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert_eq!(claims_open_at(), None);
+    client.file_claim(
+        &warranty_id,
+        &ClaimCategory::Defect,
+        &description,
+        &current_time,
+        &None,
+        &None,
+    );
+    env.as_contract(&contract_id, || {
+        let key = ClaimKey::TransferredAt(warranty_id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    client.set_transfer_claim_cooldown_secs(&(14 * 86400));
+    assert_eq!(claims_open_at(), Some(current_time + 14 * 86400));
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &current_time,
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+
+    // Every change of hands restarts the cooldown
+    advance_time(&env, 7 * 86400);
+    client.transfer_ownership(&warranty_id, &owner, &None);
+    let transferred_at = current_time + 7 * 86400;
+    assert_eq!(claims_open_at(), Some(transferred_at + 14 * 86400));
+    advance_time(&env, 14 * 86400 - 1);
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &transferred_at,
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    advance_time(&env, 1);
+    assert_eq!(claims_open_at(), None);
+    client.file_claim(
+        &warranty_id,
+        &ClaimCategory::Defect,
+        &description,
+        &transferred_at,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn test_claim_limit() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let other = String::from_str(&env, "Other");
+
+    env.mock_all_auths();
+    let register = |serial: &str, manufacturer: &String| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                manufacturer.clone(),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let first = register("SN1", &manufacturer);
+    let second = register("SN2", &manufacturer);
+    let elsewhere = register("SN3", &other);
+    let description = String::from_str(&env, "Screen flickers");
+    let file = |warranty_id: u64| {
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &current_time,
+            &None,
+            &None,
+        )
+    };
+
+    // Uncapped until the admin sets a limit
+    assert_eq!(
+        client.get_claim_quota(&owner, &manufacturer),
+        ClaimQuota {
+            remaining: None,
+            next_release_at: None,
+        }
+    );
+    assert_eq!(
+        client.try_set_claim_limit(
+            &manufacturer,
+            &Some(ClaimLimit {
+                max_claims: 0,
+                period_secs: 86400,
+            })
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let limit = ClaimLimit {
+        max_claims: 2,
+        period_secs: 7 * 86400,
+    };
+    client.set_claim_limit(&manufacturer, &Some(limit.clone()));
+    assert_eq!(client.get_claim_limit(&manufacturer), Some(limit));
+    env.as_contract(&contract_id, || {
+        let key = ClaimKey::Limit(manufacturer.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // The cap spans every warranty of the manufacturer, and only its own
+    assert!(file(first).is_ok());
+    advance_time(&env, 86400);
+    assert!(file(second).is_ok());
+    assert_eq!(file(first), Err(Ok(WarrantyError::RateLimited)));
+    assert!(file(elsewhere).is_ok());
+    assert_eq!(
+        client.get_claim_quota(&owner, &manufacturer),
+        ClaimQuota {
+            remaining: Some(0),
+            next_release_at: Some(current_time + 7 * 86400),
+        }
+    );
+
+    // Claims stop counting once they leave the window
+    advance_time(&env, 6 * 86400);
+    assert_eq!(
+        client.get_claim_quota(&owner, &manufacturer),
+        ClaimQuota {
+            remaining: Some(1),
+            next_release_at: Some(current_time + 8 * 86400),
+        }
+    );
+    assert!(file(first).is_ok());
+    assert_eq!(file(second), Err(Ok(WarrantyError::RateLimited)));
+
+    client.set_claim_limit(&manufacturer, &None);
+    assert!(file(second).is_ok());
+}
+
+#[test]
+fn test_warranty_ttl() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Boiler"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 10 * 31536000,
+        ),
+    );
+
+    // Writes extend the record to the default TTL
+    let status = client.get_ttl_status(&warranty_id);
+    assert_eq!(status.live_until_ledger, WARRANTY_TTL_EXTEND_TO);
+    assert!(!status.expiring_soon);
+
+    // and so do the outbox and audit entries written alongside it
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        assert_eq!(
+            persistent.get_ttl(&DataKey::OutboxEntry(1)),
+            WARRANTY_TTL_EXTEND_TO
+        );
+        assert_eq!(
+            persistent.get_ttl(&AuditKey::Entry(1)),
+            WARRANTY_TTL_EXTEND_TO
+        );
+    });
+
+    let max_ttl = env.ledger().get().max_entry_ttl;
+    env.ledger().with_mut(|li| li.sequence_number = 2_700_000);
+    env.as_contract(&contract_id, || {
+        env.storage().instance().extend_ttl(max_ttl, max_ttl);
+    });
+    assert!(client.get_ttl_status(&warranty_id).expiring_soon);
+
+    assert_eq!(
+        client.try_extend_warranty_ttl(&warranty_id, &0),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_extend_warranty_ttl(&warranty_id, &(max_ttl + 1)),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    let status = client.extend_warranty_ttl(&warranty_id, &5_000_000);
+    assert_eq!(status.live_until_ledger, 7_700_000);
+    assert_eq!(status.current_ledger, 2_700_000);
+    assert!(!status.expiring_soon);
+
+    // The owner's index is extended along with the record
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        assert_eq!(
+            persistent.get_ttl(&DataKey::Warranty(warranty_id)),
+            5_000_000
+        );
+        assert_eq!(
+            persistent.get_ttl(&DataKey::OwnerWarranties(owner.clone())),
+            5_000_000
+        );
+    });
+
+    // A shorter extension never shortens what is already recorded
+    let status = client.extend_warranty_ttl(&warranty_id, &1_000_000);
+    assert_eq!(status.live_until_ledger, 7_700_000);
+}
+
+#[test]
+fn test_warranty_history() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    advance_time(&env, 3600);
+    env.ledger().set_sequence_number(20);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+    env.ledger().set_sequence_number(21);
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Dead pixels"),
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    env.ledger().set_sequence_number(22);
+    client.reject_claim(&admin, &claim_id, &DenialReason::OutOfCoverage, &None);
+    client.revoke_warranty(&warranty_id, &None);
+
+    let history = client.get_warranty_history(&warranty_id, &0, &10);
+    let summary: std::vec::Vec<(Address, Symbol, HistoryDetails)> = history
+        .iter()
+        .map(|entry| (entry.actor, entry.action, entry.details))
+        .collect();
+    assert_eq!(
+        summary,
+        std::vec![
+            (
+                owner.clone(),
+                symbol_short!("register"),
+                HistoryDetails::Registered
+            ),
+            (
+                owner.clone(),
+                symbol_short!("transfer"),
+                HistoryDetails::Transferred(owner, buyer.clone())
+            ),
+            (
+                buyer.clone(),
+                symbol_short!("claim"),
+                HistoryDetails::ClaimFiled(claim_id)
+            ),
+            (
+                admin,
+                symbol_short!("claim"),
+                HistoryDetails::ClaimDecided(claim_id, ClaimStatus::Rejected)
+            ),
+            (
+                buyer,
+                symbol_short!("status"),
+                HistoryDetails::StatusChanged(WarrantyStatus::Active, WarrantyStatus::Revoked)
+            ),
+        ]
+    );
+    assert_eq!(history.get(0).unwrap().timestamp, current_time);
+    assert_eq!(history.get(1).unwrap().timestamp, current_time + 3600);
+
+    // Changes sharing a close time are told apart by their ledger
+    let anchors: std::vec::Vec<(u64, u32)> = history
+        .iter()
+        .skip(1)
+        .map(|entry| (entry.timestamp, entry.ledger))
+        .collect();
+    assert_eq!(
+        anchors,
+        std::vec![
+            (current_time + 3600, 20),
+            (current_time + 3600, 21),
+            (current_time + 3600, 22),
+            (current_time + 3600, 22),
+        ]
+    );
+    let audit = client.read_audit_log(&None, &10).entries;
+    assert_eq!(audit.get(1).unwrap().ledger, 20);
+    assert_eq!(audit.get(2).unwrap().ledger, 21);
+
+    // Pages start at the requested entry
+    let page = client.get_warranty_history(&warranty_id, &3, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap(), history.get(3).unwrap());
+    assert!(client
+        .get_warranty_history(&warranty_id, &5, &10)
+        .is_empty());
+    assert_eq!(
+        client.try_get_warranty_history(&warranty_id, &0, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+}
+
+#[test]
+fn test_pause_and_freeze() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    let warranty_id = client.register_warranty(&owner, &input("SN1"));
+    let description = String::from_str(&env, "Hinge cracked");
+
+    // Pausing stops state changes but leaves reads and admin calls alone
+    client.pause();
+    assert!(client.is_paused());
+    assert_eq!(
+        client.try_register_warranty(&owner, &input("SN2")),
+        Err(Ok(WarrantyError::Paused))
+    );
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &buyer, &None),
+        Err(Ok(WarrantyError::Paused))
+    );
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::Paused))
+    );
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+    client.set_edit_window_secs(&3600);
+    client.unpause();
+    assert!(!client.is_paused());
+    client.register_warranty(&owner, &input("SN2"));
+
+    // A frozen warranty cannot change hands or take claims
+    assert_eq!(
+        client.try_freeze_warranty(&99),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.freeze_warranty(&warranty_id);
+    assert!(client.is_frozen(&warranty_id));
+    env.as_contract(&contract_id, || {
+        let key = PauseKey::Frozen(warranty_id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &buyer, &None),
+        Err(Ok(WarrantyError::Frozen))
+    );
+    assert_eq!(
+        client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None
+        ),
+        Err(Ok(WarrantyError::Frozen))
+    );
+
+    client.unfreeze_warranty(&warranty_id);
+    assert!(!client.is_frozen(&warranty_id));
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+
+    // Freezing also holds up decisions on claims already filed
+    client.freeze_warranty(&warranty_id);
+    assert_eq!(
+        client.try_approve_claim(&admin, &claim_id, &None, &None, &None),
+        Err(Ok(WarrantyError::Frozen))
+    );
+    client.unfreeze_warranty(&warranty_id);
+    client.approve_claim(&admin, &claim_id, &None, &None, &None);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+}
+
+#[test]
+fn test_nft_interface() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "Manufacturer"),
+                current_time,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let first = register("SN1");
+    register("SN2");
+
+    assert_eq!(client.name(), String::from_str(&env, "Warranty"));
+    assert_eq!(client.symbol(), String::from_str(&env, "WRNTY"));
+    assert_eq!(client.balance_of(&owner), 2);
+    assert_eq!(client.owner_of(&first), owner);
+    assert_eq!(client.try_owner_of(&99), Err(Ok(WarrantyError::NotFound)));
+
+    assert_eq!(client.token_uri(&first), String::from_str(&env, ""));
+    client.set_token_base_uri(&String::from_str(&env, "https://example.com/w/"));
+    assert_eq!(
+        client.token_uri(&first),
+        String::from_str(&env, "https://example.com/w/1")
+    );
+
+    // Only the owner can send a token, and sending keeps the indexes in sync
+    assert_eq!(
+        client.try_transfer(&buyer, &buyer, &first),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.transfer(&owner, &buyer, &first);
+    assert_eq!(client.owner_of(&first), buyer);
+    assert_eq!(client.balance_of(&owner), 1);
+    assert_eq!(client.balance_of(&buyer), 1);
+    assert_eq!(
+        client.get_warranties_by_owner(&buyer, &None),
+        vec![&env, first]
+    );
+    assert_eq!(client.get_transfer_history(&first).len(), 1);
+}
+
+#[test]
+fn test_uniqueness_rules() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.get_uniqueness_rule(&manufacturer),
+        UniquenessRule::Serial
+    );
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Phone"),
+        String::from_str(&env, "SN123"),
+        manufacturer.clone(),
+        current_time,
+        current_time + 31536000,
+    );
+    let mut other_product = input.clone();
+    other_product.product_name = String::from_str(&env, "Tablet");
+
+    // Serials are unique per manufacturer by default
+    let phone = client.register_warranty(&owner, &input);
+    assert_eq!(
+        client
+            .get_warranty_by_serial(&manufacturer, &input.serial_number)
+            .unwrap()
+            .id,
+        phone
+    );
+    assert_eq!(
+        client.get_warranty_by_serial(&manufacturer, &String::from_str(&env, "SN999")),
+        None
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &input),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &other_product),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // Other manufacturers have their own serial space
+    let mut other_manufacturer = input.clone();
+    other_manufacturer.manufacturer = String::from_str(&env, "Other");
+    client.register_warranty(&owner, &other_manufacturer);
+
+    // Consumables can opt out and share serials
+    client.set_uniqueness_rule(&manufacturer, &UniquenessRule::Unconstrained);
+    env.as_contract(&contract_id, || {
+        let key = UniquenessKey::Rule(manufacturer.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    client.register_warranty(&owner, &input);
+    client.register_warranty(&owner, &input);
+
+    let mut refill = input.clone();
+    refill.serial_number = String::from_str(&env, "SN200");
+    let mut other_refill = refill.clone();
+    other_refill.product_name = String::from_str(&env, "Tablet");
+
+    client.set_uniqueness_rule(&manufacturer, &UniquenessRule::SerialAndProduct);
+    let first = client.register_warranty(&owner, &refill);
+    client.register_warranty(&owner, &other_refill);
+    assert_eq!(
+        client.try_register_warranty(&owner, &refill),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // An amended serial releases the reservation and moves the lookup
+    let amendment = WarrantyAmendment {
+        product_name: None,
+        serial_number: Some(String::from_str(&env, "SN201")),
+        purchase_date: None,
+        expiration_date: None,
+    };
+    client.amend_warranty(&first, &amendment, &None);
+    assert_eq!(
+        client
+            .get_warranty_by_serial(&manufacturer, &String::from_str(&env, "SN201"))
+            .unwrap()
+            .id,
+        first
+    );
+    client.register_warranty(&owner, &refill);
+}
+
+#[test]
+fn test_verification() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Phone"),
+        String::from_str(&env, "SN123"),
+        manufacturer.clone(),
+        current_time,
+        current_time + 31536000,
+    );
+    let warranty_id = client.register_warranty(&owner, &input);
+
+    let valid = VerificationResult {
+        is_valid: true,
+        owner: Some(owner.clone()),
+        expiration_date: current_time + 31536000,
+        manufacturer: manufacturer.clone(),
+    };
+    assert_eq!(client.verify(&warranty_id), valid);
+    assert_eq!(
+        client.verify_by_serial(&manufacturer, &input.serial_number),
+        valid
+    );
+
+    // Unknown warranties answer instead of failing
+    let unknown = VerificationResult {
+        is_valid: false,
+        owner: None,
+        expiration_date: 0,
+        manufacturer: String::from_str(&env, ""),
+    };
+    assert_eq!(client.verify(&999), unknown);
+    assert_eq!(
+        client.verify_by_serial(&manufacturer, &String::from_str(&env, "SN999")),
+        unknown
+    );
+
+    // Frozen warranties are not valid while under investigation
+    client.freeze_warranty(&warranty_id);
+    assert!(!client.verify(&warranty_id).is_valid);
+    client.unfreeze_warranty(&warranty_id);
+    assert!(client.verify(&warranty_id).is_valid);
+
+    // Expiry counts without anyone updating the status
+    env.ledger().set_timestamp(current_time + 31536001);
+    assert_eq!(
+        client.verify(&warranty_id),
+        VerificationResult {
+            is_valid: false,
+            ..valid
+        }
+    );
+
+    let mut second = input.clone();
+    second.serial_number = String::from_str(&env, "SN124");
+    second.expiration_date = current_time + 2 * 31536000;
+    let revoked = client.register_warranty(&owner, &second);
+    assert!(client.verify(&revoked).is_valid);
+    client.revoke_warranty(&revoked, &None);
+    assert!(!client.verify(&revoked).is_valid);
+    assert!(
+        !client
+            .verify_by_serial(&manufacturer, &second.serial_number)
+            .is_valid
+    );
+}
+
+#[test]
+fn test_manufacturer_registry() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let impostor = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+    assert_eq!(
+        client.get_manufacturer(&maker),
+        Some(Manufacturer {
+            address: maker.clone(),
+            name: name.clone(),
+            registered_at: current_time,
+        })
+    );
+    assert_eq!(
+        client.try_register_manufacturer(&impostor, &name),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    // Issued warranties carry the registered name, whatever the input says
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        String::from_str(&env, "Not Acme"),
+        current_time,
+        current_time + 31536000,
+    );
+    let issued = client.manufacturer_issue_warranty(&maker, &owner, &input);
+    let warranty = client.get_warranty(&issued).unwrap();
+    assert_eq!(warranty.manufacturer, name);
+    assert_eq!(warranty.verified_manufacturer, Some(maker.clone()));
+
+    let self_registered = client.register_warranty(&owner, &input);
+    assert_eq!(
+        client
+            .get_warranty(&self_registered)
+            .unwrap()
+            .verified_manufacturer,
+        None
+    );
+
+    assert_eq!(
+        client.try_manufacturer_issue_warranty(&impostor, &owner, &input),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    client.remove_manufacturer(&maker);
+    assert_eq!(client.get_manufacturer(&maker), None);
+    assert_eq!(
+        client.try_manufacturer_issue_warranty(&maker, &owner, &input),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.get_warranty(&issued).unwrap().verified_manufacturer,
+        Some(maker)
+    );
+    client.register_manufacturer(&impostor, &name);
+}
+
+#[test]
+fn test_issuer_delegates() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let subsidiary = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+    let eu = String::from_str(&env, "EU");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+    assert_eq!(
+        client.try_add_issuer_delegate(&stranger, &subsidiary, &eu),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.add_issuer_delegate(&maker, &subsidiary, &eu);
+    assert_eq!(
+        client.get_issuer_delegate(&maker, &subsidiary),
+        Some(IssuerDelegate {
+            region: eu.clone(),
+            added_at: current_time,
+        })
+    );
+
+    // Delegates issue in the manufacturer's name and their own region
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        String::from_str(&env, "Not Acme"),
+        current_time,
+        current_time + 31536000,
+    );
+    let issued = client.delegate_issue_warranty(&subsidiary, &maker, &owner, &input);
+    let warranty = client.get_warranty(&issued).unwrap();
+    assert_eq!(warranty.manufacturer, name);
+    assert_eq!(warranty.verified_manufacturer, Some(maker.clone()));
+    assert_eq!(warranty.region, Some(eu.clone()));
+    assert_eq!(
+        client.get_issuing_delegate(&issued),
+        Some(subsidiary.clone())
+    );
+    assert_eq!(
+        client.try_delegate_issue_warranty(
+            &subsidiary,
+            &maker,
+            &owner,
+            &input.clone().with_region(String::from_str(&env, "US")),
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_delegate_issue_warranty(&stranger, &maker, &owner, &input),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // Revocations name the delegate in the history
+    let mut us_input = input.clone().with_region(String::from_str(&env, "US"));
+    us_input.serial_number = String::from_str(&env, "SN456");
+    let elsewhere = client.manufacturer_issue_warranty(&maker, &owner, &us_input);
+    assert_eq!(
+        client.try_delegate_revoke_warranty(&subsidiary, &elsewhere, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.delegate_revoke_warranty(&subsidiary, &issued, &None);
+    assert_eq!(
+        client.get_warranty(&issued).unwrap().status,
+        WarrantyStatus::Revoked
+    );
+    let history = client.get_warranty_history(&issued, &0, &10);
+    assert_eq!(history.last().unwrap().actor, subsidiary);
+
+    // Withdrawn delegations stop further issuance
+    client.remove_issuer_delegate(&maker, &subsidiary);
+    assert_eq!(client.get_issuer_delegate(&maker, &subsidiary), None);
+    assert_eq!(
+        client.try_delegate_issue_warranty(&subsidiary, &maker, &owner, &input),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_remove_issuer_delegate(&maker, &subsidiary),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    assert_eq!(client.get_issuing_delegate(&issued), Some(subsidiary));
+}
+
+#[test]
+fn test_supersede_manufacturer() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let successor = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+    client.set_namespace_issuer(&name, &Some(maker.clone()));
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            name.clone(),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    let issued = client.manufacturer_issue_warranty(&maker, &owner, &input("SN1"));
+
+    assert_eq!(
+        client.try_supersede_manufacturer(&owner, &successor),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    client.supersede_manufacturer(&maker, &successor);
+
+    // The registry, the name and the issuer role move to the new address
+    assert_eq!(client.get_manufacturer(&maker), None);
+    assert_eq!(
+        client.get_manufacturer(&successor),
+        Some(Manufacturer {
+            address: successor.clone(),
+            name: name.clone(),
+            registered_at: current_time,
+        })
+    );
+    assert_eq!(client.get_namespace_issuer(&name), Some(successor.clone()));
+    assert_eq!(client.get_current_manufacturer(&maker), successor);
+    assert_eq!(client.get_current_manufacturer(&successor), successor);
+
+    // History stays with the old address, responsibility moves on
+    assert_eq!(
+        client.get_warranty(&issued).unwrap().verified_manufacturer,
+        Some(maker.clone())
+    );
+    let receipt = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(
+        client.try_attach_proof(&issued, &maker, &receipt, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.attach_proof(&issued, &successor, &receipt, &None);
+
+    assert_eq!(
+        client.try_manufacturer_issue_warranty(&maker, &owner, &input("SN2")),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.manufacturer_issue_warranty(&successor, &owner, &input("SN2"));
+
+    // A retired address cannot come back
+    assert_eq!(
+        client.try_supersede_manufacturer(&successor, &maker),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_register_manufacturer(&maker, &String::from_str(&env, "Other")),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+}
+
+#[test]
+fn test_product_catalog() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let other_maker = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let model_id = String::from_str(&env, "AC-100");
+    let laptops = Some(String::from_str(&env, "Laptop"));
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &String::from_str(&env, "Acme"));
+    client.register_manufacturer(&other_maker, &String::from_str(&env, "Globex"));
+
+    // Only registered manufacturers list models, each model once
+    let name = String::from_str(&env, "Acme Book 14");
+    assert_eq!(
+        client.try_add_product(&stranger, &model_id, &name, &laptops, &63072000),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_product(&maker, &model_id, &name, &laptops, &0),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.add_product(&maker, &model_id, &name, &laptops, &63072000);
+    assert_eq!(
+        client.try_add_product(&maker, &model_id, &name, &laptops, &63072000),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.get_product(&model_id),
+        Some(Product {
+            model_id: model_id.clone(),
+            name: name.clone(),
+            manufacturer: String::from_str(&env, "Acme"),
+            category: laptops.clone(),
+            default_warranty_duration: 63072000,
+            added_at: current_time,
+        })
+    );
+
+    // The catalog fills in the name, manufacturer and expiration
+    let register = |serial: &str| {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "typed by hand"),
+                String::from_str(&env, serial),
+                String::from_str(&env, "acme inc."),
+                current_time - 86400,
+                0,
+            )
+            .with_model(model_id.clone()),
+        )
+    };
+    let first = register("SN1");
+    let second = register("SN2");
+    let warranty = client.get_warranty(&first).unwrap();
+    assert_eq!(warranty.product_name, name);
+    assert_eq!(warranty.manufacturer, String::from_str(&env, "Acme"));
+    assert_eq!(warranty.category, laptops);
+    assert_eq!(warranty.expiration_date, current_time - 86400 + 63072000);
+    assert_eq!(warranty.model_id, Some(model_id.clone()));
+
+    assert_eq!(
+        client.get_warranties_by_model(&model_id, &None, &10).ids,
+        vec![&env, first, second]
+    );
+    assert!(client
+        .get_warranties_by_model(&String::from_str(&env, "AC-200"), &None, &10)
+        .ids
+        .is_empty());
+
+    // Unknown models and other manufacturers' models are refused
+    assert_eq!(
+        client.try_register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                name.clone(),
+                String::from_str(&env, "SN3"),
+                String::from_str(&env, "Acme"),
+                current_time,
+                current_time + 1,
+            )
+            .with_model(String::from_str(&env, "AC-200")),
+        ),
+        Err(Ok(WarrantyError::NotFound))
+    );
+    assert_eq!(
+        client.try_manufacturer_issue_warranty(
+            &other_maker,
+            &owner,
+            &WarrantyInput::new(
+                name,
+                String::from_str(&env, "SN4"),
+                String::from_str(&env, "Globex"),
+                current_time,
+                current_time + 1,
+            )
+            .with_model(model_id),
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_recall_and_void() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            name.clone(),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    let first = client.manufacturer_issue_warranty(&maker, &owner, &input("SN1"));
+    let second = client.manufacturer_issue_warranty(&maker, &owner, &input("SN2"));
+    let self_registered = client.register_warranty(&owner, &input("SN3"));
+
+    let batch = Vec::from_array(
+        &env,
+        [
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "SN3"),
+            String::from_str(&env, "SN9"),
+        ],
+    );
+    assert_eq!(
+        client.try_recall_products(&stranger, &batch),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(client.recall_products(&maker, &batch), 2);
+    assert_eq!(client.recall_products(&maker, &batch), 0);
+
+    let status = |id: u64| client.get_warranty(&id).unwrap().status;
+    assert_eq!(status(first), WarrantyStatus::Recalled);
+    assert_eq!(status(self_registered), WarrantyStatus::Recalled);
+    assert_eq!(status(second), WarrantyStatus::Active);
+    assert_eq!(
+        client.get_recall_info(&first),
+        Some(RecallInfo {
+            manufacturer: maker.clone(),
+            status: WarrantyStatus::Recalled,
+            reason: None,
+            recorded_at: current_time,
+        })
+    );
+    assert_eq!(client.get_recall_info(&second), None);
+
+    // Owners cannot undo a recall
+    assert_eq!(
+        client.try_set_to_active(&first, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    // Only the issuing manufacturer voids
+    let reason = String::from_str(&env, "Serial number was never sold");
+    assert_eq!(
+        client.try_void_warranty(&self_registered, &reason, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_void_warranty(&second, &String::from_str(&env, ""), &None),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.void_warranty(&second, &reason, &None);
+    client.void_warranty(&first, &reason, &None);
+    assert_eq!(status(second), WarrantyStatus::Voided);
+    assert_eq!(status(first), WarrantyStatus::Voided);
+    assert_eq!(
+        client.get_recall_info(&second),
+        Some(RecallInfo {
+            manufacturer: maker,
+            status: WarrantyStatus::Voided,
+            reason: Some(reason.clone()),
+            recorded_at: current_time,
+        })
+    );
+    assert_eq!(
+        client.try_void_warranty(&second, &reason, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+}
+
+#[test]
+fn test_scheduled_revocation() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let name = String::from_str(&env, "Acme");
+
+    env.mock_all_auths();
+    client.register_manufacturer(&maker, &name);
+
+    let input = |serial: &str| {
+        WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, serial),
+            name.clone(),
+            current_time,
+            current_time + 31536000,
+        )
+    };
+    let paid = client.manufacturer_issue_warranty(&maker, &owner, &input("SN1"));
+    let unpaid = client.manufacturer_issue_warranty(&maker, &owner, &input("SN2"));
+    let self_registered = client.register_warranty(&owner, &input("SN3"));
+
+    let reason = String::from_str(&env, "Payment not cleared");
+    let deadline = current_time + 86400;
+
+    // Only issuers schedule, with a reason and a future time
+    assert_eq!(
+        client.try_schedule_revocation(&self_registered, &deadline, &reason),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_schedule_revocation(&paid, &deadline, &String::from_str(&env, "")),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_schedule_revocation(&paid, &current_time, &reason),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    client.schedule_revocation(&paid, &deadline, &reason);
+    client.schedule_revocation(&unpaid, &deadline, &reason);
+    assert_eq!(
+        client.get_scheduled_revocation(&unpaid),
+        Some(ScheduledRevocation {
+            revoke_at: deadline,
+            reason: reason.clone(),
+            scheduled_by: maker.clone(),
+        })
+    );
+    assert_eq!(
+        client.try_schedule_revocation(&unpaid, &deadline, &reason),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    env.as_contract(&contract_id, || {
+        let key = RevocationKey::Scheduled(unpaid);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // Payment for the first one clears in time
+    client.cancel_revocation(&paid);
+    assert_eq!(client.get_scheduled_revocation(&paid), None);
+    assert_eq!(
+        client.try_cancel_revocation(&paid),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    assert_eq!(
+        client.try_execute_revocation(&unpaid, &None),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+    env.ledger().set_timestamp(deadline);
+    let receipt = client.execute_revocation(&unpaid, &None);
+    assert_eq!(receipt.warranty_ids, vec![&env, unpaid]);
+    assert_eq!(
+        client.get_warranty(&unpaid).unwrap().status,
+        WarrantyStatus::Revoked
+    );
+    assert_eq!(
+        client.get_warranty(&paid).unwrap().status,
+        WarrantyStatus::Active
+    );
+    assert_eq!(client.get_scheduled_revocation(&unpaid), None);
+    assert_eq!(
+        client.try_execute_revocation(&paid, &None),
+        Err(Ok(WarrantyError::NotFound))
+    );
+
+    let history = client.get_warranty_history(&unpaid, &0, &10);
+    let last = history.last().unwrap();
+    assert_eq!(last.actor, maker);
+    assert_eq!(
+        last.details,
+        HistoryDetails::StatusChanged(WarrantyStatus::Active, WarrantyStatus::Revoked)
+    );
+}
+
+#[test]
+fn test_set_admin() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let successor = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_admin(), Some(admin.clone()));
+
+    client.set_admin(&successor);
+    assert_eq!(client.get_admin(), Some(successor.clone()));
+    client.set_cooling_off_secs(&3600);
+    assert_eq!(client.get_cooling_off_secs(), 3600);
+}
+
+#[test]
+fn test_council() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let acme = String::from_str(&env, "Acme");
+    let members = vec![&env, alice.clone(), bob.clone(), carol.clone()];
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.try_enable_council(&members, &4),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+    client.enable_council(&members, &2);
+    assert_eq!(
+        client.get_council(),
+        Some(Council {
+            members: members.clone(),
+            threshold: 2,
+        })
+    );
+
+    // The admin can no longer change anything alone
+    assert_eq!(
+        client.try_set_cooling_off_secs(&3600),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(client.try_pause(), Err(Ok(WarrantyError::Unauthorized)));
+    assert_eq!(
+        client.try_set_keeper_fee(&KeeperTask::ExpirySweep, &5),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_register_manufacturer(&maker, &acme),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_admin(&outsider),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let register = CouncilAction::RegisterManufacturer(maker.clone(), acme.clone());
+    let proposal_id = client.propose(&alice, &register);
+    assert_eq!(client.get_manufacturer(&maker), None);
+    assert_eq!(
+        client.try_approve_proposal(&alice, &proposal_id),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_approve_proposal(&outsider, &proposal_id),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert!(client.approve_proposal(&bob, &proposal_id));
+    assert_eq!(client.get_manufacturer(&maker).unwrap().name, acme);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert!(proposal.executed);
+    assert_eq!(proposal.approvals, vec![&env, alice.clone(), bob.clone()]);
+    assert_eq!(
+        client.try_approve_proposal(&carol, &proposal_id),
+        Err(Ok(WarrantyError::AlreadyExists))
+    );
+
+    assert_eq!(
+        client.try_propose(
+            &alice,
+            &CouncilAction::SetCouncil(vec![&env, alice.clone()], 2)
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+
+    // Settings change through a SetConfig proposal
+    let cooling_off = client.propose(
+        &alice,
+        &CouncilAction::SetConfig(ConfigChange::SetCoolingOffSecs(3600)),
+    );
+    assert_eq!(client.get_cooling_off_secs(), 0);
+    assert!(client.approve_proposal(&carol, &cooling_off));
+    assert_eq!(client.get_cooling_off_secs(), 3600);
+    assert_eq!(
+        client.try_propose(
+            &alice,
+            &CouncilAction::SetConfig(ConfigChange::SetKeeperFee(KeeperTask::TtlBump, -1))
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+
+    // Proposals lapse after a week
+    let limits = ReadLimits {
+        max_page_size: 20,
+        max_batch_size: 10,
+    };
+    let stale = client.propose(&alice, &CouncilAction::SetReadLimits(limits));
+    advance_time(&env, 7 * 86400 + 1);
+    assert_eq!(
+        client.try_approve_proposal(&bob, &stale),
+        Err(Ok(WarrantyError::OutsideWindow))
+    );
+
+    // Dissolving the council hands its powers back to the admin
+    let dissolve = client.propose(&bob, &CouncilAction::SetCouncil(vec![&env], 0));
+    assert!(client.approve_proposal(&carol, &dissolve));
+    assert_eq!(client.get_council(), None);
+    client.remove_manufacturer(&maker);
+    assert_eq!(client.get_manufacturer(&maker), None);
+    client.set_cooling_off_secs(&60);
+    assert_eq!(client.get_cooling_off_secs(), 60);
+}
+
+#[test]
+fn test_deferred_activation() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    let year: u64 = 31536000;
+    env.ledger().set_timestamp(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        String::from_str(&env, "SN123"),
+        String::from_str(&env, "Manufacturer"),
+        current_time,
+        0,
+    )
+    .with_deferred_activation(year);
+    let warranty_id = client.register_warranty(&owner, &input);
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.status, WarrantyStatus::Pending);
+    assert_eq!(warranty.activation_duration, Some(year));
+    assert_eq!(warranty.expiration_date, u64::MAX);
+    env.as_contract(&contract_id, || {
+        // Analytics bucket the coverage to come, not the open-ended expiration
+        assert_eq!(duration_bucket_months(&warranty), 12);
+    });
+
+    // Time on the shelf does not count against coverage
+    let first_use = current_time + 2 * year;
+    env.ledger().set_timestamp(first_use);
+    assert!(!client.is_warranty_expired(&warranty_id));
+
+    // Activation brings back a pending warranty compacted by older releases
+    client.compact_owner_index(&owner, &None);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, warranty_id]
+    );
+    env.as_contract(&contract_id, || {
+        write_index(&env, &DataKey::OwnerWarranties(owner.clone()), &vec![&env]);
+        write_index(
+            &env,
+            &DataKey::ArchivedOwnerWarranties(owner.clone()),
+            &vec![&env, warranty_id],
+        );
+    });
+    client.activate(&warranty_id, &None);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, warranty_id]
+    );
+    assert!(client.get_archived_warranties(&owner).is_empty());
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.status, WarrantyStatus::Active);
+    assert_eq!(warranty.activated_at, Some(first_use));
+    assert_eq!(warranty.activation_duration, None);
+    assert_eq!(warranty.expiration_date, first_use + year);
+    assert_eq!(
+        client.try_activate(&warranty_id, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+
+    let regular = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN124"),
+            String::from_str(&env, "Manufacturer"),
+            first_use,
+            first_use + year,
+        ),
+    );
+    assert_eq!(
+        client.try_activate(&regular, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_register_warranty(&owner, &input.with_deferred_activation(0)),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_claim_messages() {
+    let env = Env::default();
+    let current_time: u64 = 1704067200;
+    env.ledger().set_timestamp(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Screen flickers"),
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert!(client.get_claim_messages(&claim_id).is_empty());
+
+    let uri = String::from_str(&env, "ipfs://thread/1");
+    let hash = BytesN::from_array(&env, &[1; 32]);
+    client.post_claim_message(&owner, &claim_id, &hash, &uri, &None);
+    advance_time(&env, 60);
+    client.post_claim_message(
+        &issuer,
+        &claim_id,
+        &BytesN::from_array(&env, &[2; 32]),
+        &uri,
+        &None,
+    );
+    assert_eq!(
+        client.try_post_claim_message(&stranger, &claim_id, &hash, &uri, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // The trail stays open after a decision, for disputes
+    client.reject_claim(&issuer, &claim_id, &DenialReason::Other, &None);
+    client.post_claim_message(
+        &admin,
+        &claim_id,
+        &BytesN::from_array(&env, &[3; 32]),
+        &uri,
+        &None,
+    );
+
+    // Arbitrators join the thread once the claim is disputed
+    let arbitrator = Address::generate(&env);
+    let other_arbitrator = Address::generate(&env);
+    client.set_arbitrator(&arbitrator, &true, &None);
+    client.set_arbitrator(&other_arbitrator, &true, &None);
+    assert_eq!(
+        client.try_post_claim_message(&arbitrator, &claim_id, &hash, &uri, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.dispute_claim(&claim_id, &None);
+    client.post_claim_message(&arbitrator, &claim_id, &hash, &uri, &None);
+    client.rule_on_dispute(&arbitrator, &claim_id, &false, &None, &None);
+    assert_eq!(
+        client.try_post_claim_message(&other_arbitrator, &claim_id, &hash, &uri, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let messages = client.get_claim_messages(&claim_id);
+    assert_eq!(messages.len(), 4);
+    assert_eq!(
+        messages.get(0).unwrap(),
+        ClaimMessage {
+            author: owner,
+            body_hash: hash,
+            uri: uri.clone(),
+            posted_at: current_time,
+        }
+    );
+    assert_eq!(messages.get(1).unwrap().author, issuer);
+    assert_eq!(messages.get(1).unwrap().posted_at, current_time + 60);
+    assert_eq!(messages.get(2).unwrap().author, admin);
+    assert_eq!(messages.get(3).unwrap().author, arbitrator);
+
+    // A thread holds at most 50 messages
+    for _ in 4..50 {
+        client.post_claim_message(
+            &admin,
+            &claim_id,
+            &BytesN::from_array(&env, &[4; 32]),
+            &uri,
+            &None,
+        );
+    }
+    assert_eq!(
+        client.try_post_claim_message(
+            &admin,
+            &claim_id,
+            &BytesN::from_array(&env, &[5; 32]),
+            &uri,
+            &None
+        ),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    assert_eq!(client.get_claim_messages(&claim_id).len(), 50);
+}
+
+mod screening {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Vec};
+
+    #[contract]
+    pub struct Screening;
+
+    #[contractimpl]
+    impl Screening {
+        pub fn deny(env: Env, party: Address) {
+            let mut denied = Self::denied(env.clone());
+            denied.push_back(party);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("denied"), &denied);
+        }
+
+        pub fn check_allowed(env: Env, from: Address, to: Address) -> bool {
+            let denied = Self::denied(env);
+            !denied.contains(&from) && !denied.contains(&to)
+        }
+
+        pub fn denied(env: Env) -> Vec<Address> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("denied"))
+                .unwrap_or(Vec::new(&env))
+        }
+    }
+}
+
+#[test]
+fn test_compliance_hook() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+    let screening_id = env.register(screening::Screening, ());
+    let screening = screening::ScreeningClient::new(&env, &screening_id);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let sanctioned = Address::generate(&env);
+
+    env.mock_all_auths();
+    assert_eq!(client.get_compliance_hook(), None);
+    client.set_compliance_hook(&Some(screening_id.clone()));
+    assert_eq!(client.get_compliance_hook(), Some(screening_id));
+    screening.deny(&sanctioned);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &sanctioned, &None),
+        Err(Ok(WarrantyError::TransferDenied))
+    );
+    client.initiate_transfer(&warranty_id, &sanctioned, &None);
+    assert_eq!(
+        client.try_accept_transfer(&warranty_id, &None),
+        Err(Ok(WarrantyError::TransferDenied))
+    );
+    assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+    client.transfer_ownership(&warranty_id, &buyer, &None);
+
+    // Settling a claim pays the claimant, so it is screened as well
+    let claim_id = client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Dead pixel"),
+            &env.ledger().timestamp(),
+            &None,
+            &None,
+        )
+        .claim_id;
+    client.approve_claim(&admin, &claim_id, &None, &None, &None);
+    screening.deny(&buyer);
+    assert_eq!(
+        client.try_resolve_claim(&admin, &claim_id, &None),
+        Err(Ok(WarrantyError::TransferDenied))
+    );
+
+    // A hook that cannot answer blocks the move rather than aborting the call
+    client.set_compliance_hook(&Some(Address::generate(&env)));
+    assert_eq!(
+        client.try_transfer_ownership(&warranty_id, &owner, &None),
+        Err(Ok(WarrantyError::TransferDenied))
+    );
+
+    client.set_compliance_hook(&None);
+    client.resolve_claim(&admin, &claim_id, &None);
+    client.transfer_ownership(&warranty_id, &sanctioned, &None);
+}
+
+#[test]
+fn test_service_history() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400 * 30;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let shop = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    client.set_service_center(&shop, &true);
+    assert!(client.is_service_center(&shop));
+    // The history cap does not follow the read page size
+    client.set_read_limits(&ReadLimits {
+        max_page_size: 1,
+        max_batch_size: 1,
+    });
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer,
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+    assert!(client.get_service_history(&warranty_id).is_empty());
+
+    let fan = String::from_str(&env, "Replaced fan");
+    let firmware = String::from_str(&env, "Firmware update");
+    client.add_service_record(
+        &warranty_id,
+        &shop,
+        &fan,
+        &(current_time - 3600),
+        &vec![&env],
+        &None,
+    );
+    // Logged later, but done earlier
+    client.add_service_record(
+        &warranty_id,
+        &issuer,
+        &firmware,
+        &(base_timestamp + 86400),
+        &vec![&env],
+        &None,
+    );
+
+    let history = client.get_service_history(&warranty_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get(0).unwrap(),
+        ServiceRecord {
+            technician: issuer,
+            description: firmware,
+            date: base_timestamp + 86400,
+            recorded_at: current_time,
+            parts: vec![&env],
+        }
+    );
+    assert_eq!(history.get(1).unwrap().technician, shop);
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        assert_eq!(
+            persistent.get_ttl(&ServiceKey::History(warranty_id)),
+            persistent.get_ttl(&DataKey::Warranty(warranty_id))
+        );
+    });
+
+    assert_eq!(
+        client.try_add_service_record(
+            &warranty_id,
+            &stranger,
+            &fan,
+            &current_time,
+            &vec![&env],
+            &None
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_service_record(
+            &warranty_id,
+            &shop,
+            &fan,
+            &(current_time + 1),
+            &vec![&env],
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+    assert_eq!(
+        client.try_add_service_record(
+            &warranty_id,
+            &shop,
+            &fan,
+            &(base_timestamp - 1),
+            &vec![&env],
+            &None
+        ),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+
+    client.set_service_center(&shop, &false);
+    assert_eq!(
+        client.try_add_service_record(&warranty_id, &shop, &fan, &current_time, &vec![&env], &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_capability_discovery() {
+    let env = Env::default();
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_interface_version(), 40);
+    assert!(client.supports(&Symbol::new(&env, "outbox")));
+    assert!(client.supports(&Symbol::new(&env, "deterministic_ids")));
+    assert!(!client.supports(&Symbol::new(&env, "claims")));
+    assert!(!client.supports(&Symbol::new(&env, "marketplace")));
+}
+
+#[test]
+fn test_storage_stats() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let mut ids = std::vec::Vec::new();
+    for i in 0..3 {
+        ids.push(client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        ));
+    }
+    client.delete_warranty(&ids[0], &None);
+    client.report_counterfeit(&admin, &ids[1], &BytesN::from_array(&env, &[1; 32]));
+
+    assert_eq!(
+        client.get_storage_stats(),
+        StorageStats {
+            registered: 3,
+            live_warranties: 2,
+            removed_warranties: 1,
+            id_index_entries: 2,
+            counterfeit_reports: 1,
+            pending_reports: 1,
+            hooks: 0,
+            outbox_entries: 4,
+            stored_claims: 0,
+            open_claims: 0,
+        }
+    );
+
+    // Claims are stored until discarded, and open until decided for good
+    let mut claim_ids = std::vec::Vec::new();
+    for _ in 0..3 {
+        claim_ids.push(
+            client
+                .file_claim(
+                    &ids[2],
+                    &ClaimCategory::Defect,
+                    &String::from_str(&env, "Broken"),
+                    &current_time,
+                    &Some(100),
+                    &None,
+                )
+                .claim_id,
+        );
+    }
+    client.approve_claim(&admin, &claim_ids[1], &None, &None, &None);
+    client.reject_claim(&admin, &claim_ids[2], &DenialReason::OutOfCoverage, &None);
+    let stats = client.get_storage_stats();
+    assert_eq!(stats.stored_claims, 3);
+    assert_eq!(stats.open_claims, 2);
+}
+
+#[test]
+fn test_describe() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    let description = client.describe();
+    assert_eq!(description.admin, Some(admin.clone()));
+    assert_eq!(description.latest_outbox_seq, 0);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+    client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 31536000,
+        ),
+    );
+
+    let description = client.describe();
+    assert_eq!(description.name, String::from_str(&env, "Warranty Tracker"));
+    assert_eq!(
+        description.interface_version,
+        client.get_interface_version()
+    );
+    assert_eq!(description.admin, Some(admin));
+    assert_eq!(description.read_limits, client.get_read_limits());
+    assert_eq!(description.cooling_off_secs, 3600);
+    assert_eq!(description.stats, client.get_storage_stats());
+    assert_eq!(description.latest_outbox_seq, 1);
+}
+
+#[test]
+fn test_read_limits() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let defaults = client.get_read_limits();
+    assert_eq!(defaults.max_page_size, 100);
+    assert_eq!(defaults.max_batch_size, 50);
+
+    client.set_read_limits(&ReadLimits {
+        max_page_size: 10,
+        max_batch_size: 5,
+    });
+    assert_eq!(client.get_read_limits().max_page_size, 10);
+
+    assert_eq!(
+        client.try_get_warranties_by_owner_page(&owner, &None, &11),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    assert_eq!(
+        client.try_get_warranty_ids_page(&None, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    assert_eq!(client.get_warranty_ids_page(&None, &10).ids.len(), 0);
+
+    assert_eq!(
+        client.try_set_read_limits(&ReadLimits {
+            max_page_size: 0,
+            max_batch_size: 5,
+        }),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+}
+
+#[test]
+fn test_export_and_verify_certificate() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    let certificate = client.export_certificate(&warranty_id);
+    assert!(client.verify_certificate(&certificate));
+
+    // Garbage does not decode
+    assert!(client
+        .try_verify_certificate(&Bytes::from_slice(&env, &[1, 2, 3]))
+        .is_err());
+
+    // Any later change to the warranty invalidates older certificates
+    client.revoke_warranty(&warranty_id, &None);
+    assert!(!client.verify_certificate(&certificate));
+    assert!(client.verify_certificate(&client.export_certificate(&warranty_id)));
+}
+
+#[test]
+fn test_counterfeit_reports() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let genuine_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    let fake_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN2"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    let dismissed_report = client.report_counterfeit(&reporter, &genuine_id, &evidence);
+    let confirmed_report = client.report_counterfeit(&reporter, &fake_id, &evidence);
+    assert_eq!(
+        client.get_pending_reports(&None, &10),
+        WarrantyIdPage {
+            ids: vec![&env, dismissed_report, confirmed_report],
+            next: NextPage::Done,
+        }
+    );
+
+    // Pages examine `limit` reports each
+    let first = client.get_pending_reports(&None, &1);
+    assert_eq!(first.ids, vec![&env, dismissed_report]);
+    let second = client.get_pending_reports(&first.next.cursor(), &1);
+    assert_eq!(second.ids, vec![&env, confirmed_report]);
+    assert_eq!(second.next, NextPage::Done);
+
+    client.dismiss_counterfeit(&dismissed_report);
+    client.confirm_counterfeit(&confirmed_report, &None);
+
+    assert!(client.get_pending_reports(&None, &10).ids.is_empty());
+    assert_eq!(client.get_storage_stats().pending_reports, 0);
+    assert_eq!(
+        client
+            .get_counterfeit_report(&confirmed_report)
+            .unwrap()
+            .status,
+        ReportStatus::Confirmed
+    );
+    assert_eq!(
+        client.get_warranty(&genuine_id).unwrap().status,
+        WarrantyStatus::Active
+    );
+    assert_eq!(
+        client.get_warranty(&fake_id).unwrap().status,
+        WarrantyStatus::Revoked
+    );
+    assert_eq!(
+        client.get_reporter_reputation(&reporter),
+        ReporterReputation {
+            confirmed: 1,
+            dismissed: 1,
+        }
+    );
+
+    // A resolved report cannot be resolved again
+    assert!(client.try_dismiss_counterfeit(&confirmed_report).is_err());
+
+    // Confirming a report follows the status rules, so final states stay
+    let cancelled_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN3"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    client.cancel_warranty(&cancelled_id, &None);
+    let late_report = client.report_counterfeit(&reporter, &cancelled_id, &evidence);
+    assert_eq!(
+        client.try_confirm_counterfeit(&late_report, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.get_warranty(&cancelled_id).unwrap().status,
+        WarrantyStatus::Cancelled
+    );
+
+    // Reporters are capped per day
+    let spammer = Address::generate(&env);
+    for _ in 0..MAX_DAILY_REPORTS {
+        client.report_counterfeit(&spammer, &genuine_id, &evidence);
+    }
+    assert_eq!(
+        client.try_report_counterfeit(&spammer, &genuine_id, &evidence),
+        Err(Ok(WarrantyError::RateLimited))
+    );
+    advance_time(&env, 86400);
+    client.report_counterfeit(&spammer, &genuine_id, &evidence);
+}
+
+#[test]
+fn test_purchase_date_skew_tolerance() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    assert_eq!(client.get_purchase_date_skew(), 3600);
+
+    // A POS clock 10 minutes ahead of the ledger is tolerated
+    client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            String::from_str(&env, "Manufacturer"),
+            current_time + 600,
+            expiration_date,
+        ),
+    );
+
+    client.set_purchase_date_skew(&0);
+    let result = client.try_register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN2"),
+            String::from_str(&env, "Manufacturer"),
+            current_time + 600,
+            expiration_date,
+        ),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")] // InvalidDates
+fn test_register_warranty_beyond_skew() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            current_time + 7200,
+            current_time + 31536000,
+        ),
+    );
+}
+
+#[test]
+fn test_max_purchase_age() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400 * 365 * 3; // 3 years after base
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let strict = String::from_str(&env, "StrictCorp");
+    let lenient = String::from_str(&env, "LenientCorp");
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let one_year: u64 = 86400 * 365;
+    client.set_max_purchase_age(&strict, &Some(one_year));
+    assert_eq!(client.get_max_purchase_age(&strict), Some(one_year));
+    assert_eq!(client.get_max_purchase_age(&lenient), None);
+
+    // A purchase from three years ago is too old for StrictCorp...
+    let result = client.try_register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            strict.clone(),
+            base_timestamp,
+            expiration_date,
+        ),
+    );
+    assert!(result.is_err());
+
+    // ...but fine for a manufacturer without a window
+    client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN1"),
+            lenient.clone(),
+            base_timestamp,
+            expiration_date,
+        ),
+    );
+
+    // Recent purchases are still accepted by StrictCorp
+    client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN2"),
+            strict.clone(),
+            current_time - 86400,
+            expiration_date,
+        ),
+    );
+
+    client.set_max_purchase_age(&strict, &None);
+    assert_eq!(client.get_max_purchase_age(&strict), None);
+}
+
+#[test]
+fn test_register_warranty_deterministic() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let product_name = String::from_str(&env, "Product");
+    let serial_number = String::from_str(&env, "SN123");
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let expected_id = client.get_deterministic_id(&manufacturer, &serial_number);
+    assert!(expected_id >= 1 << 63);
+
+    let warranty_id = client.register_warranty_deterministic(
+        &owner,
+        &WarrantyInput::new(
+            product_name.clone(),
+            serial_number.clone(),
+            manufacturer.clone(),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    assert_eq!(warranty_id, expected_id);
+    assert_eq!(
+        client
+            .get_warranty_by_product(&manufacturer, &serial_number)
+            .unwrap()
+            .id,
+        warranty_id
+    );
+    assert_eq!(
+        client.get_warranty_by_product(&manufacturer, &String::from_str(&env, "SN999")),
+        None
+    );
+
+    // Sequential IDs keep counting and indexes stay sorted
+    let sequential_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            product_name.clone(),
+            String::from_str(&env, "SN456"),
+            manufacturer.clone(),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    assert_eq!(sequential_id, 2);
+    assert_eq!(client.get_warranty_count(), 2);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, sequential_id, warranty_id]
+    );
+
+    // The same product cannot be registered twice
+    assert!(client
+        .try_register_warranty_deterministic(
+            &owner,
+            &WarrantyInput::new(
+                product_name.clone(),
+                serial_number.clone(),
+                manufacturer.clone(),
+                purchase_date,
+                expiration_date
+            )
+        )
+        .is_err());
+
+    // Once the record is gone, the product maps back to the same ID
+    client.delete_warranty(&warranty_id, &None);
+    let reregistered_id = client.register_warranty_deterministic(
+        &owner,
+        &WarrantyInput::new(
+            product_name.clone(),
+            serial_number.clone(),
+            manufacturer.clone(),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    assert_eq!(reregistered_id, warranty_id);
+    assert!(matches!(
+        client.get_warranty_record(&warranty_id),
+        WarrantyRecord::Live(_)
+    ));
+}
+
+#[test]
+fn test_delete_warranty_within_cooling_off() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let typo_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Prodcut"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    let kept_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN124"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    assert_eq!(client.get_warranty(&typo_id).unwrap().registrant, owner);
+
+    client.delete_warranty(&typo_id, &None);
+
+    assert_eq!(client.get_warranty(&typo_id), None);
+    assert_eq!(
+        client.get_warranty_record(&typo_id),
+        WarrantyRecord::Removed(Tombstone {
+            warranty_id: typo_id,
+            removed_by: owner.clone(),
+            removed_at: current_time,
+            removed_ledger: 0,
+            merged_into: None,
+        })
+    );
+    assert_eq!(client.get_warranty_record(&99), WarrantyRecord::NotFound);
+    assert!(matches!(
+        client.get_warranty_record(&kept_id),
+        WarrantyRecord::Live(_)
+    ));
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, kept_id]
+    );
+    assert_eq!(
+        client.get_warranty_ids_page(&None, &10).ids,
+        vec![&env, kept_id]
+    );
+
+    // Past the window the record can only be revoked
+    advance_time(&env, 3601);
+    assert!(client.try_delete_warranty(&kept_id, &None).is_err());
+}
+
+#[test]
+fn test_reregister_after_delete() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let serial = String::from_str(&env, "SN-1");
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&owner, &1_000);
+    client.set_rent_terms(&Some(RentTerms {
+        token: token_address.clone(),
+        free_secs: 365 * 86400,
+        daily_rent: 1,
+        bump_fee: 0,
+    }));
+
+    let input = WarrantyInput::new(
+        String::from_str(&env, "Product"),
+        serial.clone(),
+        manufacturer.clone(),
+        current_time,
+        current_time + 465 * 86400,
+    );
+    let removed_id = client.register_warranty(&owner, &input);
+    client.top_up_rent(&owner, &removed_id, &50);
+    assert_eq!(token_client.balance(&owner), 850);
+    client.set_beneficiary(&removed_id, &heir, &86400, &None);
+    client.bind_device_key(&removed_id, &BytesN::from_array(&env, &[7; 32]));
+    client.freeze_warranty(&removed_id);
+
+    client.delete_warranty(&removed_id, &None);
+
+    // Unspent rent goes back and nothing kept for the record is left
+    assert_eq!(token_client.balance(&owner), 1_000);
+    assert_eq!(client.get_rent_balance(&removed_id), 0);
+    assert_eq!(client.get_beneficiary(&removed_id), None);
+    assert_eq!(client.get_device_key(&removed_id), None);
+    assert!(!client.is_frozen(&removed_id));
+    assert_eq!(client.get_warranty_by_serial(&manufacturer, &serial), None);
+
+    // The serial can be registered again from a clean slate
+    let new_id = client.register_warranty(&owner, &input);
+    assert_ne!(new_id, removed_id);
+    assert_eq!(
+        client
+            .get_warranty_by_serial(&manufacturer, &serial)
+            .unwrap()
+            .id,
+        new_id
+    );
+    assert_eq!(client.get_rent_balance(&new_id), 100);
+    assert_eq!(client.get_beneficiary(&new_id), None);
+    assert!(!client.is_frozen(&new_id));
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, new_id]
+    );
+}
+
+#[test]
+fn test_preregister_and_activate() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "LineWorks");
+
+    env.mock_all_auths();
+
+    let serial_hash = |serial: &[u8]| -> BytesN<32> {
+        env.crypto().sha256(&Bytes::from_slice(&env, serial)).into()
+    };
+    let entry = |serial: &[u8]| PreRegEntry {
+        serial_hash: serial_hash(serial),
+        product_name: String::from_str(&env, "Drill"),
+        duration_secs: 31536000,
+    };
+
+    let batch = vec![&env, entry(b"D-1"), entry(b"D-2")];
+    // Only the manufacturer itself may seed units under its name
+    assert_eq!(
+        client.try_preregister_batch(&issuer, &manufacturer, &batch),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.register_manufacturer(&issuer, &manufacturer);
+    assert_eq!(
+        client.try_preregister_batch(&issuer, &String::from_str(&env, "Other"), &batch),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_preregister_batch(
+            &issuer,
+            &manufacturer,
+            &vec![
+                &env,
+                PreRegEntry {
+                    duration_secs: 0,
+                    ..entry(b"D-0")
+                }
+            ]
+        ),
+        Err(Ok(WarrantyError::InvalidInput))
+    );
+
+    assert_eq!(client.preregister_batch(&issuer, &manufacturer, &batch), 2);
+    // Resending the same upload stores nothing new
+    assert_eq!(client.preregister_batch(&issuer, &manufacturer, &batch), 0);
+
+    assert_eq!(
+        client.try_preregister_batch(&issuer, &manufacturer, &Vec::new(&env)),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    client.set_read_limits(&ReadLimits {
+        max_page_size: 10,
+        max_batch_size: 1,
+    });
+    assert_eq!(
+        client.try_preregister_batch(&issuer, &manufacturer, &batch),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    advance_time(&env, 600);
+    let warranty_id = client.activate_preregistered(
+        &buyer,
+        &issuer,
+        &String::from_str(&env, "D-1"),
+        &(current_time - 60),
+    );
+    assert_eq!(
+        client.get_activation_stats(&manufacturer),
+        ActivationStats {
+            preregistered: 2,
+            activated: 1,
+            total_activation_secs: 600,
+            average_activation_secs: 600,
+        }
+    );
+    env.as_contract(&contract_id, || {
+        let key = DataKey::ActivationStats(manufacturer.clone());
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.owner, buyer);
+    assert_eq!(warranty.manufacturer, manufacturer);
+    assert_eq!(warranty.expiration_date, current_time - 60 + 31536000);
+    assert_eq!(
+        client.get_preregistration(&issuer, &serial_hash(b"D-1")),
+        None
+    );
+    assert!(client
+        .get_preregistration(&issuer, &serial_hash(b"D-2"))
+        .is_some());
+
+    // Each unit activates once, and only under its own issuer
+    assert!(client
+        .try_activate_preregistered(
+            &buyer,
+            &issuer,
+            &String::from_str(&env, "D-1"),
+            &current_time
+        )
+        .is_err());
+    assert!(client
+        .try_activate_preregistered(
+            &buyer,
+            &admin,
+            &String::from_str(&env, "D-2"),
+            &current_time
+        )
+        .is_err());
+}
+
+#[test]
+fn test_merge_warranties() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let insurer = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(
+        &String::from_str(&env, "Manufacturer"),
+        &Some(issuer.clone()),
+    );
+
+    let register = |owner: &Address, serial: &str, manufacturer: &str| {
+        client.register_warranty(
+            owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, serial),
+                String::from_str(&env, manufacturer),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        )
+    };
+    let keep_id = register(&owner, "SN123", "Manufacturer");
+    let remove_id = register(&owner, "SN132", "Manufacturer");
+    let foreign_id = register(&other, "SN213", "Manufacturer");
+    let unrelated_id = register(&owner, "SN456", "Manufacturer");
+    let other_maker_id = register(&owner, "SN123", "Other");
+
+    client.attach_policy(&remove_id, &insurer, &7, &None);
+
+    // Same owner, same manufacturer, serials at most a typo apart
+    assert!(client
+        .try_merge_warranties(&keep_id, &keep_id, &issuer, &None)
+        .is_err());
+    for duplicate_id in [foreign_id, unrelated_id, other_maker_id] {
+        assert!(client
+            .try_merge_warranties(&keep_id, &duplicate_id, &issuer, &None)
+            .is_err());
+    }
+
+    // Only the issuer or the admin confirms
+    assert_eq!(
+        client.try_merge_warranties(&keep_id, &remove_id, &owner, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // Frozen records and open claims block the merge
+    client.freeze_warranty(&remove_id);
+    assert_eq!(
+        client.try_merge_warranties(&keep_id, &remove_id, &issuer, &None),
+        Err(Ok(WarrantyError::Frozen))
+    );
+    client.unfreeze_warranty(&remove_id);
+    let claim_id = client
+        .file_claim(
+            &remove_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Broken hinge"),
+            &current_time,
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        client.try_merge_warranties(&keep_id, &remove_id, &issuer, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+
+    client.merge_warranties(&keep_id, &remove_id, &issuer, &None);
+
+    // The settled claim moved with the unit
+    let claims = client.get_claims(&keep_id);
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims.get(0).unwrap().claim_id, claim_id);
+    assert_eq!(claims.get(0).unwrap().warranty_id, keep_id);
+    assert_eq!(client.get_warranty(&keep_id).unwrap().claims_filed, 1);
+
+    assert_eq!(client.get_warranty(&remove_id), None);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, keep_id, unrelated_id, other_maker_id]
+    );
+    assert_eq!(client.get_attached_policy(&keep_id).unwrap().policy_id, 7);
+    assert_eq!(client.get_attached_policy(&remove_id), None);
+    match client.get_warranty_record(&remove_id) {
+        WarrantyRecord::Removed(tombstone) => {
+            assert_eq!(tombstone.merged_into, Some(keep_id));
+            assert_eq!(tombstone.removed_by, owner);
+        }
+        record => panic!("expected tombstone, got {:?}", record),
+    }
+}
+
+#[test]
+fn test_amend_warranty() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner1,
+        &WarrantyInput::new(
+            String::from_str(&env, "Lpatop"),
+            String::from_str(&env, "SN12345"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+
+    client.amend_warranty(
+        &warranty_id,
+        &WarrantyAmendment {
+            product_name: Some(String::from_str(&env, "Laptop")),
+            serial_number: Some(String::from_str(&env, "SN123456")),
+            purchase_date: None,
+            expiration_date: None,
+        },
+        &None,
+    );
+
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.product_name, String::from_str(&env, "Laptop"));
+    assert_eq!(warranty.serial_number, String::from_str(&env, "SN123456"));
+    assert_eq!(warranty.purchase_date, purchase_date);
+
+    // Every corrected field is recorded, and the serial is indexed anew
+    let history = client.get_warranty_history(&warranty_id, &0, &10);
+    assert_eq!(
+        history
+            .iter()
+            .skip(1)
+            .map(|entry| entry.details)
+            .collect::<std::vec::Vec<_>>(),
+        [
+            HistoryDetails::ProductNameChanged(
+                String::from_str(&env, "Lpatop"),
+                String::from_str(&env, "Laptop"),
+            ),
+            HistoryDetails::SerialChanged(
+                String::from_str(&env, "SN12345"),
+                String::from_str(&env, "SN123456"),
+            ),
+        ]
+    );
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    assert_eq!(
+        client
+            .get_warranty_by_serial(&manufacturer, &String::from_str(&env, "SN123456"))
+            .map(|warranty| warranty.id),
+        Some(warranty_id)
+    );
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().persistent().has(&DataKey::SerialIndex(
+            manufacturer.clone(),
+            String::from_str(&env, "SN12345"),
+        )));
+    });
+    assert_eq!(
+        client
+            .read_audit_log(&None, &10)
+            .entries
+            .last()
+            .unwrap()
+            .action,
+        symbol_short!("amend")
+    );
+
+    // Invalid dates are rejected like at registration
+    let result = client.try_amend_warranty(
+        &warranty_id,
+        &WarrantyAmendment {
+            product_name: None,
+            serial_number: None,
+            purchase_date: None,
+            expiration_date: Some(purchase_date),
+        },
+        &None,
+    );
+    assert!(result.is_err());
+
+    // The first transfer closes the edit window, even once it comes back
+    let rename = WarrantyAmendment {
+        product_name: Some(String::from_str(&env, "Desktop")),
+        serial_number: None,
+        purchase_date: None,
+        expiration_date: None,
+    };
+    client.transfer_ownership(&warranty_id, &owner2, &None);
+    let result = client.try_amend_warranty(&warranty_id, &rename, &None);
+    assert!(result.is_err());
+    client.transfer_ownership(&warranty_id, &owner1, &None);
+    let result = client.try_amend_warranty(&warranty_id, &rename, &None);
+    assert!(result.is_err());
+
+    // So does the first claim
+    let claimed_id = client.register_warranty(
+        &owner1,
+        &WarrantyInput::new(
+            String::from_str(&env, "Phone"),
+            String::from_str(&env, "SN-P1"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    client.file_claim(
+        &claimed_id,
+        &ClaimCategory::Defect,
+        &String::from_str(&env, "Cracked screen"),
+        &current_time,
+        &None,
+        &None,
+    );
+    let result = client.try_amend_warranty(&claimed_id, &rename, &None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_rebuild_owner_index() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+
+    for i in 0..3 {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                purchase_date,
+                expiration_date,
+            ),
+        );
+    }
+
+    // Corrupt the index: drop id 2, duplicate id 1 and add an unknown id
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DataKey::OwnerWarranties(owner.clone()),
+            &vec![&env, 1u64, 1, 3, 99],
+        );
+    });
+
+    assert_eq!(client.rebuild_owner_index(&owner, &1, &3), 2);
+    assert_eq!(client.rebuild_owner_index(&owner, &99, &99), 1);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 1, 2, 3]
+    );
+
+    // Rebuilding a healthy index is a no-op
+    assert_eq!(client.rebuild_owner_index(&owner, &1, &3), 0);
+
+    assert_eq!(
+        client.try_rebuild_owner_index(&owner, &1, &100),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+
+    // Co-owner indexes are rebuilt too
+    let partner = Address::generate(&env);
+    client.add_co_owner(&1, &partner, &None);
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(
+            &DataKey::CoOwnedWarranties(partner.clone()),
+            &vec![&env, 2u64],
+        );
+    });
+    assert_eq!(client.rebuild_owner_index(&partner, &1, &3), 2);
+    assert_eq!(
+        client.get_warranties_by_owner(&partner, &None),
+        vec![&env, 1]
+    );
+    assert_eq!(client.rebuild_owner_index(&partner, &1, &3), 0);
+}
+
+#[test]
+fn test_rebuild_serial_index() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let serial = |i: u32| String::from_str(&env, &std::format!("SN-{i}"));
+
+    env.mock_all_auths();
+    for i in 1..=3 {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                serial(i),
+                manufacturer.clone(),
+                base_timestamp,
+                current_time + 31536000,
+            ),
+        );
+    }
+
+    // Corrupt the index: drop SN-1 and point SN-2 at a missing warranty
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        storage.remove(&DataKey::SerialIndex(manufacturer.clone(), serial(1)));
+        storage.set(
+            &DataKey::SerialIndex(manufacturer.clone(), serial(2)),
+            &99u64,
+        );
+    });
+    assert_eq!(
+        client.get_warranty_by_serial(&manufacturer, &serial(1)),
+        None
+    );
+
+    assert_eq!(client.rebuild_serial_index(&1, &3), 2);
+    for i in 1..=3 {
+        assert_eq!(
+            client
+                .get_warranty_by_serial(&manufacturer, &serial(i))
+                .unwrap()
+                .id,
+            i as u64
+        );
+    }
+    assert_eq!(client.rebuild_serial_index(&1, &3), 0);
+
+    assert_eq!(
+        client.try_rebuild_serial_index(&1, &100),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+}
+
+#[test]
+fn test_compact_owner_index() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let purchase_date = base_timestamp;
+    let expiration_date = current_time + 31536000;
+
+    env.mock_all_auths();
+    client.set_read_limits(&ReadLimits {
+        max_page_size: 10,
+        max_batch_size: 2,
+    });
+    client.set_namespace_issuer(
+        &String::from_str(&env, "Manufacturer"),
+        &Some(maker.clone()),
+    );
+
+    for i in 0..4 {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                purchase_date,
+                expiration_date,
+            ),
+        );
+    }
+    client.revoke_warranty(&1, &None);
+    client.set_to_expired(&4, &None);
+
+    // Two entries per batch
+    let next = client.compact_owner_index(&owner, &None);
+    assert_eq!(
+        next,
+        Some(Cursor {
+            last_id: 2,
+            bucket: 0
+        })
+    );
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 2, 3, 4]
+    );
+    assert_eq!(client.compact_owner_index(&owner, &next), None);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 2, 3]
+    );
+    assert_eq!(client.get_archived_warranties(&owner), vec![&env, 1, 4]);
+
+    // Archived entries are not lost by an index rebuild
+    assert_eq!(client.rebuild_owner_index(&owner, &1, &2), 0);
+
+    // Reactivation brings a warranty back to the hot index
+    client.reactivate_warranty(&4, &maker, &None);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 2, 3, 4]
+    );
+    assert_eq!(client.get_archived_warranties(&owner), vec![&env, 1]);
+
+    // Pending and suspended warranties can still come into force, so stay
+    let maker = Address::generate(&env);
+    client.set_namespace_issuer(
+        &String::from_str(&env, "Manufacturer"),
+        &Some(maker.clone()),
+    );
+    let pending = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN-P"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            0,
+        )
+        .with_deferred_activation(31536000),
+    );
+    let suspended = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN-S"),
+            String::from_str(&env, "Manufacturer"),
+            purchase_date,
+            expiration_date,
+        ),
+    );
+    client.suspend_warranty(&suspended, &maker, &None);
+    let mut cursor = client.compact_owner_index(&owner, &None);
+    while cursor.is_some() {
+        cursor = client.compact_owner_index(&owner, &cursor);
+    }
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 2, 3, 4, pending, suspended]
+    );
+    assert_eq!(client.get_archived_warranties(&owner), vec![&env, 1]);
+
+    // Keepers compact too, earning the fee per warranty archived
+    let keeper = Address::generate(&env);
+    client.set_keeper_fee(&KeeperTask::IndexCompaction, &5);
+    assert_eq!(
+        client.try_keeper_compact_owner_index(&keeper, &owner, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    client.set_keeper(&keeper, &true);
+    client.set_to_expired(&3, &None);
+    let mut cursor = client.keeper_compact_owner_index(&keeper, &owner, &None);
+    while cursor.is_some() {
+        cursor = client.keeper_compact_owner_index(&keeper, &owner, &cursor);
+    }
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, 2, 4, pending, suspended]
+    );
+    assert_eq!(client.get_archived_warranties(&owner), vec![&env, 1, 3]);
+    assert_eq!(client.get_keeper_earnings(&keeper), 5);
+}
+
+#[test]
+fn test_expire_batch() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    // Ids 1-3 lapse after an hour, id 4 lasts a year
+    for (i, expiration_date) in [
+        current_time + 3600,
+        current_time + 3600,
+        current_time + 3600,
+        current_time + 31536000,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                expiration_date,
+            ),
+        );
+    }
+    client.revoke_warranty(&2, &None);
+
+    assert_eq!(client.expire_batch(&1, &4, &10), 0);
+
+    advance_time(&env, 3601);
+    assert_eq!(client.expire_batch(&1, &4, &1), 1);
+    assert_eq!(client.expire_batch(&1, &4, &10), 1);
+    assert_eq!(client.expire_batch(&1, &4, &10), 0);
+
+    assert_eq!(
+        client.get_warranty(&1).unwrap().status,
+        WarrantyStatus::Expired
+    );
+    assert_eq!(
+        client.get_warranty(&2).unwrap().status,
+        WarrantyStatus::Revoked
+    );
+    assert_eq!(
+        client.get_warranty(&3).unwrap().status,
+        WarrantyStatus::Expired
+    );
+    assert_eq!(
+        client.get_warranty(&4).unwrap().status,
+        WarrantyStatus::Active
+    );
+
+    assert_eq!(
+        client.try_expire_batch(&1, &100, &10),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+}
+
+#[test]
+fn test_sweep_expired() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    // Odd ids lapse after an hour, even ids last a year
+    for i in 1..=5u64 {
+        let expiration_date = if i % 2 == 1 {
+            current_time + 3600
+        } else {
+            current_time + 31536000
+        };
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                expiration_date,
+            ),
+        );
+    }
+    // Deterministic ids sit far past the sequential range
+    let deterministic_id = client.register_warranty_deterministic(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN-D"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 3600,
+        ),
+    );
+    advance_time(&env, 3601);
+
+    // A keeper pages through the index without anyone's signature
+    env.set_auths(&[]);
+    let mut cursor = None;
+    let mut expired = 0;
+    let mut pages = 0;
+    loop {
+        let page = client.sweep_expired(&cursor, &2);
+        expired += page.expired;
+        pages += 1;
+        cursor = page.next.cursor();
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(expired, 4);
+    assert_eq!(pages, 3);
+    assert_eq!(client.sweep_expired(&None, &10).expired, 0);
+
+    for i in 1..=5u64 {
+        let expected = if i % 2 == 1 {
+            WarrantyStatus::Expired
+        } else {
+            WarrantyStatus::Active
+        };
+        assert_eq!(client.get_warranty(&i).unwrap().status, expected);
+    }
+    assert_eq!(
+        client.get_warranty(&deterministic_id).unwrap().status,
+        WarrantyStatus::Expired
+    );
+
+    assert_eq!(
+        client.try_sweep_expired(&None, &0),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+    assert_eq!(
+        client.try_sweep_expired(&None, &51),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+}
+
+#[test]
+fn test_export_range_and_stats() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    for i in 1..=4u64 {
+        client.register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                String::from_str(&env, "Product"),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                String::from_str(&env, "Manufacturer"),
+                base_timestamp,
+                current_time + 3600 * i,
+            ),
+        );
+    }
+    let stats = client.get_stats();
+    assert_eq!(stats.total, 4);
+    assert_eq!(stats.active, 4);
+    assert_eq!(stats.by_status, map![&env, (WarrantyStatus::Active, 4)]);
+
+    // Counters follow every stored status change
+    client.revoke_warranty(&2, &None);
+    advance_time(&env, 3601);
+    client.expire_warranty(&1);
+    let stats = client.get_stats();
+    assert_eq!(stats.total, 4);
+    assert_eq!(stats.active, 2);
+    assert_eq!(stats.expired, 1);
+    assert_eq!(stats.revoked, 1);
+    assert_eq!(
+        stats.by_status,
+        map![
+            &env,
+            (WarrantyStatus::Active, 2),
+            (WarrantyStatus::Expired, 1),
+            (WarrantyStatus::Revoked, 1)
+        ]
+    );
+    env.as_contract(&contract_id, || {
+        let key = StatsKey::Status(WarrantyStatus::Active);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+    });
+
+    // Exports skip IDs without a record
+    let exported = client.export_range(&1, &10);
+    assert_eq!(exported.len(), 4);
+    assert_eq!(exported.get(1).unwrap(), client.get_warranty(&2).unwrap());
+    assert_eq!(client.export_range(&5, &10).len(), 0);
+    assert_eq!(
+        client.try_export_range(&1, &100),
+        Err(Ok(WarrantyError::LimitExceeded))
+    );
+    assert_eq!(
+        client.try_export_range(&2, &1),
+        Err(Ok(WarrantyError::InvalidLimit))
+    );
+}
+
+#[test]
+fn test_expire_warranty() {
+    let base_timestamp: u64 = 1704067200;
+    let current_time = base_timestamp + 86400;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            base_timestamp,
+            current_time + 3600,
+        ),
+    );
+    assert!(!client.expire_warranty(&warranty_id));
+
+    // Reads keep the stored status until someone expires it
+    advance_time(&env, 3601);
+    assert!(client.is_warranty_expired(&warranty_id));
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().status,
+        WarrantyStatus::Active
+    );
+
+    assert!(client.expire_warranty(&warranty_id));
+    assert_eq!(
+        client.get_warranty(&warranty_id).unwrap().status,
+        WarrantyStatus::Expired
+    );
+    assert!(!client.expire_warranty(&warranty_id));
+    assert_eq!(
+        client.try_expire_warranty(&99),
+        Err(Ok(WarrantyError::NotFound))
+    );
+}
+
+#[test]
+fn test_fuzz_registration_edge_cases() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let skew = client.get_purchase_date_skew();
+
+    env.mock_all_auths();
+
+    let long_name = String::from_bytes(&env, &[b'x'; 4096]);
+    let names = [
+        String::from_str(&env, ""),
+        String::from_str(&env, "序列号-№42-🔧"),
+        String::from_str(&env, "\0\n\t"),
+        long_name,
+    ];
+
+    let mut timestamps = std::vec![
+        (0, 0),
+        (0, 1),
+        (0, u64::MAX),
+        (u64::MAX, u64::MAX),
+        (u64::MAX - 1, u64::MAX),
+        (current_time, current_time),
+        (current_time, current_time + 1),
+        (current_time + skew, u64::MAX),
+        (current_time + skew + 1, u64::MAX),
+        (1, 0),
+    ];
+    // Deterministic pseudo-random pairs around the interesting boundaries
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..40 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let purchase = current_time
+            .wrapping_add((seed >> 48) % 7200)
+            .wrapping_sub(3600);
+        let expiration = purchase
+            .wrapping_add((seed >> 8) % 172800)
+            .wrapping_sub(86400);
+        timestamps.push((purchase, expiration));
+    }
+
+    let mut registered = 0u64;
+    for (i, (purchase_date, expiration_date)) in timestamps.iter().enumerate() {
+        let name = &names[i % names.len()];
+        let result = client.try_register_warranty(
+            &owner,
+            &WarrantyInput::new(
+                name.clone(),
+                String::from_str(&env, &std::format!("SN-{i}")),
+                name.clone(),
+                *purchase_date,
+                *expiration_date,
+            ),
+        );
+
+        let valid =
+            expiration_date > purchase_date && *purchase_date <= current_time.saturating_add(skew);
+        assert_eq!(
+            result.is_ok(),
+            valid,
+            "inputs {purchase_date} {expiration_date}"
+        );
+
+        if let Ok(Ok(warranty_id)) = result {
+            registered += 1;
+            let warranty = client.get_warranty(&warranty_id).unwrap();
+            assert_eq!(warranty.product_name, *name);
+            assert_eq!(warranty.purchase_date, *purchase_date);
+            assert_eq!(warranty.expiration_date, *expiration_date);
+            let expected_status = if *expiration_date < current_time {
+                WarrantyStatus::Expired
+            } else {
+                WarrantyStatus::Active
+            };
+            assert_eq!(warranty.status, expected_status);
+        }
+    }
+
+    // Rejected registrations must leave no trace in the indexes
+    assert_eq!(client.get_warranty_count(), registered);
+    let owner_ids = client.get_warranties_by_owner(&owner, &None);
+    assert_eq!(owner_ids.len() as u64, registered);
+    assert_eq!(client.get_warranty_ids_page(&None, &100).ids, owner_ids);
+    for warranty_id in owner_ids.iter() {
+        assert_eq!(client.get_warranty(&warranty_id).unwrap().owner, owner);
+    }
+}
+
+#[test]
+fn test_fuzz_amendment_edge_cases() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let contract_id = env.register(
+        WarrantyTracker,
+        (Address::generate(&env), None::<DeploymentConfig>),
+    );
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            String::from_str(&env, "Manufacturer"),
+            current_time,
+            current_time + 31536000,
+        ),
+    );
+    let original = client.get_warranty(&warranty_id).unwrap();
+
+    for (purchase_date, expiration_date) in [
+        (Some(u64::MAX), None),
+        (None, Some(0)),
+        (Some(0), Some(0)),
+        (Some(u64::MAX), Some(u64::MAX)),
+    ] {
+        let result = client.try_amend_warranty(
+            &warranty_id,
+            &WarrantyAmendment {
+                product_name: Some(String::from_bytes(&env, &[0xff; 64])),
+                serial_number: None,
+                purchase_date,
+                expiration_date,
+            },
+            &None,
+        );
+        assert!(result.is_err());
+        // Failed amendments must not leave partial writes behind
+        assert_eq!(client.get_warranty(&warranty_id).unwrap(), original);
+    }
+
+    client.amend_warranty(
+        &warranty_id,
+        &WarrantyAmendment {
+            product_name: None,
+            serial_number: Some(String::from_str(&env, "序列号-🔧")),
+            purchase_date: Some(0),
+            expiration_date: Some(u64::MAX),
+        },
+        &None,
+    );
+    let warranty = client.get_warranty(&warranty_id).unwrap();
+    assert_eq!(warranty.serial_number, String::from_str(&env, "序列号-🔧"));
+    assert_eq!(warranty.status, WarrantyStatus::Active);
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, warranty_id]
+    );
+}
+
+#[test]
+fn test_fuzz_extension_edge_cases() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer,
+            current_time,
+            current_time + 86400,
+        ),
+    );
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for step in 0..48 {
+        // Let coverage lapse now and then, so extensions revive it
+        advance_time(&env, rng.next(3 * 86400));
+        let now = env.ledger().timestamp();
+        let before = client.get_warranty(&warranty_id).unwrap();
+        let current = before.expiration_date;
+
+        let expiration_date = match step {
+            0 => 0,
+            1 => now,
+            2 => current,
+            3 => current - 1,
+            4 => u64::MAX - 1,
+            _ => (current.min(now))
+                .saturating_add(rng.next(4 * 86400))
+                .saturating_sub(86400),
+        };
+        let result = client.try_extend_warranty(&warranty_id, &issuer, &expiration_date, &None);
+
+        if expiration_date > current && expiration_date > now {
+            assert!(result.is_ok(), "step {step}: {expiration_date}");
+            let warranty = client.get_warranty(&warranty_id).unwrap();
+            assert_eq!(warranty.expiration_date, expiration_date);
+            assert_eq!(warranty.status, WarrantyStatus::Active);
+        } else {
+            assert_eq!(
+                result,
+                Err(Ok(WarrantyError::InvalidDates)),
+                "step {step}: {expiration_date}"
+            );
+            assert_eq!(client.get_warranty(&warranty_id).unwrap(), before);
+        }
+    }
+
+    // Nothing lies beyond the last representable date
+    client.extend_warranty(&warranty_id, &issuer, &u64::MAX, &None);
+    assert_eq!(
+        client.try_extend_warranty(&warranty_id, &issuer, &u64::MAX, &None),
+        Err(Ok(WarrantyError::InvalidDates))
+    );
+    assert_eq!(
+        client.get_warranties_by_owner(&owner, &None),
+        vec![&env, warranty_id]
+    );
+}
+
+#[test]
+fn test_fuzz_claim_amount_edge_cases() {
+    let current_time: u64 = 1704067200;
+    let env = test_env(current_time);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "Manufacturer");
+    let price: i128 = 1_000_000;
+
+    env.mock_all_auths();
+    client.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &(2 * price));
+    client.fund_claims_escrow(&issuer, &token_address, &(2 * price), &None);
+
+    let warranty_id = client.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "Product"),
+            String::from_str(&env, "SN123"),
+            manufacturer,
+            current_time,
+            current_time + 31536000,
+        )
+        .with_price(price),
+    );
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut paid = 0i128;
+    for step in 0..40 {
+        let requested = match step {
+            0 => i128::MIN,
+            1 => -1,
+            2 => 0,
+            3 => i128::MAX,
+            4 => 1,
+            _ => 1 + rng.next(200_000) as i128,
+        };
+        let filed = client.try_file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Broken"),
+            &current_time,
+            &Some(requested),
+            &None,
+        );
+        let Ok(Ok(filed)) = filed else {
+            assert!(requested <= 0, "step {step}: {requested}");
+            assert_eq!(filed, Err(Ok(WarrantyError::InvalidInput)));
+            continue;
+        };
+        assert!(requested > 0);
+        let claim_id = filed.claim_id;
+
+        let approved_amount = match rng.next(5) {
+            0 => None,
+            1 => Some(-1),
+            2 => Some(0),
+            3 => Some(requested.saturating_add(1)),
+            _ => Some(rng.next(requested.min(200_000) as u64 + 1) as i128),
+        };
+        let approved = approved_amount.unwrap_or(requested);
+        let result = client.try_approve_claim(&issuer, &claim_id, &approved_amount, &None, &None);
+
+        // Approvals stay within the request and what the warranty has left
+        if approved < 0 || approved > requested || approved > price - paid {
+            assert_eq!(
+                result,
+                Err(Ok(WarrantyError::InvalidInput)),
+                "step {step}: {approved} of {requested}"
+            );
+            client.reject_claim(&issuer, &claim_id, &DenialReason::OutOfCoverage, &None);
+            continue;
+        }
+        assert!(result.is_ok(), "step {step}: {approved} of {requested}");
+
+        // Settling pays exactly what was approved, never the request
+        let balance = token_client.balance(&owner);
+        let settled = client.try_settle_claim(&claim_id, &token_address, &None);
+        let claim = client
+            .get_claims(&warranty_id)
+            .iter()
+            .find(|claim| claim.claim_id == claim_id)
+            .unwrap();
+        if approved == 0 {
+            assert_eq!(settled, Err(Ok(WarrantyError::InvalidInput)));
+            assert_eq!(claim.status, ClaimStatus::Approved);
+            assert_eq!(token_client.balance(&owner), balance);
+        } else {
+            assert!(settled.is_ok(), "step {step}: {approved}");
+            assert_eq!(claim.status, ClaimStatus::Resolved);
+            assert_eq!(token_client.balance(&owner), balance + approved);
+            paid += approved;
+        }
+        assert_eq!(claim.approved_amount, Some(approved));
+        assert_eq!(claim.paid_amount, token_client.balance(&owner) - balance);
+    }
+
+    // The warranty never pays out more than the product cost
+    assert!(paid > 0 && paid <= price);
+    assert_eq!(token_client.balance(&owner), paid);
+    assert_eq!(
+        client
+            .get_claims(&warranty_id)
+            .iter()
+            .map(|claim| claim.paid_amount)
+            .sum::<i128>(),
+        paid
+    );
+    assert_eq!(
+        client.get_escrow_balance(&issuer, &token_address),
+        2 * price - paid
+    );
+}
@@ -0,0 +1,1135 @@
+#![cfg(test)]
+//! Every mutating entry point called with the wrong signer.
+//!
+//! These tests use `mock_auths` so only the signer named in each case
+//! authorizes the call; `mock_all_auths` would let any signer through.
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    xdr::{ScErrorCode, ScErrorType},
+    Address, Env, Error, IntoVal, InvokeError, String, Val, Vec,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: WarrantyTrackerClient<'a>,
+    /// Registered the warranty, then sold it on
+    registrant: Address,
+    /// Current owner of the warranty
+    owner: Address,
+    stranger: Address,
+    warranty_id: u64,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let admin = Address::generate(&env);
+    let client = WarrantyTrackerClient::new(
+        &env,
+        &env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>)),
+    );
+
+    let registrant = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.set_cooling_off_secs(&3600);
+    let warranty_id = client.register_warranty(
+        &registrant,
+        &WarrantyInput::new(
+            String::from_str(&env, "Laptop"),
+            String::from_str(&env, "LT-1"),
+            String::from_str(&env, "Manufacturer"),
+            1704000000,
+            1767225600,
+        ),
+    );
+    client.transfer_ownership(&warranty_id, &owner, &None);
+
+    Setup {
+        env,
+        client,
+        registrant,
+        owner,
+        stranger,
+        warranty_id,
+    }
+}
+
+impl Setup<'_> {
+    /// Authorize the next call to `fn_name` as `signer` only
+    fn sign_as(&self, signer: &Address, fn_name: &str, args: Vec<Val>) {
+        self.sign_as_all(&[signer], fn_name, args);
+    }
+
+    /// Authorize the next call to `fn_name` as each of `signers` only
+    fn sign_as_all(&self, signers: &[&Address], fn_name: &str, args: Vec<Val>) {
+        let invoke = MockAuthInvoke {
+            contract: &self.client.address,
+            fn_name,
+            args,
+            sub_invokes: &[],
+        };
+        let auths: std::vec::Vec<MockAuth> = signers
+            .iter()
+            .map(|address| MockAuth {
+                address,
+                invoke: &invoke,
+            })
+            .collect();
+        self.env.mock_auths(&auths);
+    }
+}
+
+/// What an entry point that cannot fail otherwise returns when a required
+/// signature is missing; the host narrows the underlying `Auth` error down
+/// to this one. Entry points returning `Result` abort instead.
+fn missing_auth() -> Error {
+    Error::from_type_and_code(ScErrorType::Context, ScErrorCode::InvalidAction)
+}
+
+#[test]
+fn test_owner_entry_points_reject_other_signers() {
+    let s = setup();
+    let id = s.warranty_id;
+    let hash = BytesN::from_array(&s.env, &[7; 32]);
+
+    // The registrant no longer owns the warranty
+    let signer = &s.registrant;
+
+    s.sign_as(
+        signer,
+        "transfer_ownership",
+        (id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_transfer_ownership(&id, signer, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "cancel_warranty",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_cancel_warranty(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "revoke_warranty",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_revoke_warranty(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_to_expired",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_to_expired(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "add_co_owner",
+        (id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_add_co_owner(&id, signer, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_visibility",
+        (id, true, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_visibility(&id, &true, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "grant_viewer",
+        (id, signer.clone(), 1767225600u64, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_grant_viewer(&id, signer, &1767225600, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_beneficiary",
+        (id, signer.clone(), 86400u64, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_beneficiary(&id, signer, &86400, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_reversion",
+        (id, signer.clone(), 1767225600u64).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_reversion(&id, signer, &1767225600),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "attach_policy",
+        (id, signer.clone(), 1u64, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_attach_policy(&id, signer, &1, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "bind_device_key",
+        (id, hash.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_bind_device_key(&id, &hash),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "initiate_handover",
+        (id, signer.clone(), hash.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_initiate_handover(&id, signer, &hash),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "link_warranties",
+        (id, id, LinkType::Related, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_link_warranties(&id, &id, &LinkType::Related, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "unlink_warranty", (id,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_unlink_warranty(&id),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "prove_ownership",
+        (id, hash.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_prove_ownership(&id, &hash),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "initiate_transfer",
+        (id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_initiate_transfer(&id, signer, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let allowlist = Vec::from_array(&s.env, [signer.clone()]);
+    s.sign_as(
+        signer,
+        "set_transfer_allowlist",
+        (s.owner.clone(), allowlist.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_transfer_allowlist(&s.owner, &allowlist),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let description = String::from_str(&s.env, "Screen flickers");
+    let incident_date = s.env.ledger().timestamp();
+    s.sign_as(
+        signer,
+        "file_claim",
+        (
+            id,
+            ClaimCategory::Defect,
+            description.clone(),
+            incident_date,
+            None::<i128>,
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert!(s
+        .client
+        .try_file_claim(
+            &id,
+            &ClaimCategory::Defect,
+            &description,
+            &incident_date,
+            &None,
+            &None
+        )
+        .is_err());
+    assert!(s.client.get_claims(&id).is_empty());
+
+    s.sign_as(
+        signer,
+        "approve_agent",
+        (id, Some(signer.clone()), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_approve_agent(&id, &Some(signer.clone()), &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_operator",
+        (s.owner.clone(), signer.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_operator(&s.owner, signer, &true),
+        Err(Err(InvokeError::Abort))
+    );
+
+    // Agents must be approved by the current owner
+    s.sign_as(
+        signer,
+        "agent_transfer_ownership",
+        (signer.clone(), id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_agent_transfer_ownership(signer, &id, signer, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "export_owner_data",
+        (s.owner.clone(), None::<Cursor>, 10u32).into_val(&s.env),
+    );
+    assert!(s
+        .client
+        .try_export_owner_data(&s.owner, &None, &10)
+        .is_err());
+
+    s.sign_as(
+        signer,
+        "transfer",
+        (s.owner.clone(), signer.clone(), id).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_transfer(&s.owner, signer, &id),
+        Err(Err(InvokeError::Abort))
+    );
+
+    // Only the named recipient can take up or turn down an offer
+    s.env.mock_all_auths();
+    s.client.initiate_transfer(&id, &s.stranger, &None);
+    s.sign_as(
+        signer,
+        "accept_transfer",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_accept_transfer(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+    s.sign_as(
+        signer,
+        "decline_transfer",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_decline_transfer(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+    assert!(s.client.get_pending_transfer(&id).is_some());
+
+    s.sign_as(
+        signer,
+        "compact_owner_index",
+        (s.owner.clone(), None::<Cursor>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_compact_owner_index(&s.owner, &None),
+        Err(Ok(missing_auth()))
+    );
+
+    assert_eq!(s.client.get_warranty(&id).unwrap().owner, s.owner);
+}
+
+#[test]
+fn test_registrant_entry_points_reject_other_signers() {
+    let s = setup();
+    let id = s.warranty_id;
+
+    // The owner bought the warranty but did not register it
+    let signer = &s.owner;
+
+    s.sign_as(
+        signer,
+        "delete_warranty",
+        (id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_delete_warranty(&id, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let lang = Symbol::new(&s.env, "de");
+    let name = String::from_str(&s.env, "Notebook");
+    s.sign_as(
+        signer,
+        "set_localized_name",
+        (id, lang.clone(), name.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_localized_name(&id, &lang, &name),
+        Err(Err(InvokeError::Abort))
+    );
+
+    // Coverage terms are set by the issuing side, not even the registrant
+    let terms = CoverageTerms {
+        deductible: 0,
+        max_payout: None,
+        excluded_categories: Vec::new(&s.env),
+        category_caps: Map::new(&s.env),
+    };
+    for caller in [signer, &s.registrant] {
+        s.sign_as(
+            caller,
+            "set_coverage_terms",
+            (caller.clone(), id, terms.clone()).into_val(&s.env),
+        );
+        assert_eq!(
+            s.client.try_set_coverage_terms(caller, &id, &terms),
+            Err(Ok(WarrantyError::Unauthorized))
+        );
+    }
+
+    assert!(s.client.get_warranty(&id).is_some());
+}
+
+#[test]
+fn test_admin_entry_points_reject_other_signers() {
+    let s = setup();
+    let signer = &s.stranger;
+
+    s.sign_as(signer, "add_hook", (signer.clone(),).into_val(&s.env));
+    assert_eq!(s.client.try_add_hook(signer), Err(Err(InvokeError::Abort)));
+
+    s.sign_as(signer, "prune_audit_log", (1u64,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_prune_audit_log(&1),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "set_admin", (signer.clone(),).into_val(&s.env));
+    assert_eq!(s.client.try_set_admin(signer), Err(Err(InvokeError::Abort)));
+
+    // The admin alone cannot hand the role to an address that did not sign
+    let admin = s.client.get_admin().unwrap();
+    s.sign_as(&admin, "set_admin", (signer.clone(),).into_val(&s.env));
+    assert_eq!(s.client.try_set_admin(signer), Err(Err(InvokeError::Abort)));
+
+    let wasm_hash = BytesN::from_array(&s.env, &[7; 32]);
+    s.sign_as(signer, "upgrade", (wasm_hash.clone(),).into_val(&s.env));
+    assert_eq!(
+        s.client.try_upgrade(&wasm_hash),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "set_cooling_off_secs", (0u64,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_set_cooling_off_secs(&0),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "set_analytics_enabled", (true,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_set_analytics_enabled(&true),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_logistics_provider",
+        (signer.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_logistics_provider(signer, &true),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_keeper",
+        (signer.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_keeper(signer, &true),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let manufacturer = String::from_str(&s.env, "Manufacturer");
+    let issuer = Some(signer.clone());
+    s.sign_as(
+        signer,
+        "set_namespace_issuer",
+        (manufacturer.clone(), issuer.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_namespace_issuer(&manufacturer, &issuer),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let hook = Some(signer.clone());
+    s.sign_as(
+        signer,
+        "set_compliance_hook",
+        (hook.clone(),).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_compliance_hook(&hook),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_service_center",
+        (signer.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_service_center(signer, &true),
+        Err(Err(InvokeError::Abort))
+    );
+
+    let members = Vec::from_array(&s.env, [signer.clone()]);
+    s.sign_as(
+        signer,
+        "enable_council",
+        (members.clone(), 1u32).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_enable_council(&members, &1),
+        Err(Err(InvokeError::Abort))
+    );
+
+    // The old manufacturer's signature is not enough without the admin's
+    s.sign_as(
+        signer,
+        "supersede_manufacturer",
+        (signer.clone(), s.owner.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_supersede_manufacturer(signer, &s.owner),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "set_capacity", (Some(10u64),).into_val(&s.env));
+    assert!(s.client.try_set_capacity(&Some(10)).is_err());
+
+    s.sign_as(signer, "pause", ().into_val(&s.env));
+    assert_eq!(s.client.try_pause(), Err(Err(InvokeError::Abort)));
+    assert!(!s.client.is_paused());
+
+    s.sign_as(signer, "freeze_warranty", (s.warranty_id,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_freeze_warranty(&s.warranty_id),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "set_arbitrator",
+        (signer.clone(), true, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_arbitrator(signer, &true, &None),
+        Err(Err(InvokeError::Abort))
+    );
+    assert!(!s.client.is_arbitrator(signer));
+
+    s.sign_as(signer, "set_claim_window_secs", (0u64,).into_val(&s.env));
+    assert_eq!(
+        s.client.try_set_claim_window_secs(&0),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(
+        signer,
+        "register_underwriter",
+        (signer.clone(),).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_underwriter(signer),
+        Err(Err(InvokeError::Abort))
+    );
+    assert!(!s.client.is_underwriter(signer));
+
+    let limits = RegistrationLimits {
+        per_owner_daily: Some(1),
+        global_daily: None,
+    };
+    s.sign_as(
+        signer,
+        "set_registration_limits",
+        (limits.clone(),).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_set_registration_limits(&limits),
+        Err(Err(InvokeError::Abort))
+    );
+
+    s.sign_as(signer, "prune_outbox", (1u64,).into_val(&s.env));
+    assert_eq!(s.client.try_prune_outbox(&1), Err(Err(InvokeError::Abort)));
+
+    // The would-be operator signs, but only the admin creates consortiums
+    let name = String::from_str(&s.env, "Appliances");
+    s.sign_as(
+        signer,
+        "create_consortium",
+        (signer.clone(), name.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_create_consortium(signer, &name, &None),
+        Err(Err(InvokeError::Abort))
+    );
+
+    assert!(s.client.get_hooks().is_empty());
+    assert!(!s.client.is_keeper(signer));
+}
+
+#[test]
+fn test_consortium_panel_changes_need_every_member() {
+    let s = setup();
+    let operator = Address::generate(&s.env);
+    let member = Address::generate(&s.env);
+    let arbitrator = Address::generate(&s.env);
+
+    s.env.mock_all_auths();
+    let consortium_id =
+        s.client
+            .create_consortium(&operator, &String::from_str(&s.env, "Appliances"), &None);
+    s.client
+        .add_consortium_member(&consortium_id, &member, &None);
+
+    // The operator alone cannot seat an arbitrator the member never agreed to
+    s.sign_as(
+        &operator,
+        "set_consortium_arbitrator",
+        (consortium_id, arbitrator.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_set_consortium_arbitrator(&consortium_id, &arbitrator, &true),
+        Err(Err(InvokeError::Abort))
+    );
+    assert!(s
+        .client
+        .get_consortium(&consortium_id)
+        .unwrap()
+        .panel
+        .is_empty());
+}
+
+#[test]
+fn test_service_center_entry_points() {
+    let s = setup();
+    let id = s.warranty_id;
+    let center = Address::generate(&s.env);
+    let description = String::from_str(&s.env, "Replaced fan");
+    let part = PartInfo {
+        part_number: String::from_str(&s.env, "FAN-2"),
+        serial_number: String::from_str(&s.env, "F-1"),
+        covered: true,
+        coverage_secs: 86400,
+    };
+    let service_args = |technician: &Address| -> Vec<Val> {
+        (
+            id,
+            technician.clone(),
+            description.clone(),
+            1704067200u64,
+            Vec::<u64>::new(&s.env),
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env)
+    };
+
+    s.env.mock_all_auths();
+    s.client.set_service_center(&center, &true);
+
+    // Someone else cannot log work in an authorized center's name
+    s.sign_as(&s.stranger, "add_service_record", service_args(&center));
+    assert_eq!(
+        s.client.try_add_service_record(
+            &id,
+            &center,
+            &description,
+            &1704067200,
+            &Vec::new(&s.env),
+            &None
+        ),
+        Err(Err(InvokeError::Abort))
+    );
+    s.sign_as(
+        &s.owner,
+        "register_part_warranty",
+        (id, center.clone(), part.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_part_warranty(&id, &center, &part),
+        Err(Err(InvokeError::Abort))
+    );
+    assert!(s.client.get_service_history(&id).is_empty());
+
+    // The center signing for itself is enough to log work, but a part
+    // warranty is issued to the owner, who signs as well
+    s.sign_as(&center, "add_service_record", service_args(&center));
+    s.client.add_service_record(
+        &id,
+        &center,
+        &description,
+        &1704067200,
+        &Vec::new(&s.env),
+        &None,
+    );
+    s.sign_as(
+        &center,
+        "register_part_warranty",
+        (id, center.clone(), part.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_part_warranty(&id, &center, &part),
+        Err(Err(InvokeError::Abort))
+    );
+    s.sign_as_all(
+        &[&center, &s.owner],
+        "register_part_warranty",
+        (id, center.clone(), part.clone()).into_val(&s.env),
+    );
+    let part_id = s.client.register_part_warranty(&id, &center, &part);
+    assert_eq!(s.client.get_service_history(&id).len(), 1);
+    assert_eq!(
+        s.client.get_part_warranties(&id),
+        Vec::from_array(&s.env, [part_id])
+    );
+
+    // Once the admin withdraws the role its own signature is not enough
+    s.env.mock_all_auths();
+    s.client.set_service_center(&center, &false);
+    s.sign_as(&center, "add_service_record", service_args(&center));
+    assert_eq!(
+        s.client.try_add_service_record(
+            &id,
+            &center,
+            &description,
+            &1704067200,
+            &Vec::new(&s.env),
+            &None
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    s.sign_as_all(
+        &[&center, &s.owner],
+        "register_part_warranty",
+        (id, center.clone(), part.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_part_warranty(&id, &center, &part),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+    assert_eq!(s.client.get_service_history(&id).len(), 1);
+}
+
+#[test]
+fn test_role_entry_points_reject_addresses_without_the_role() {
+    let s = setup();
+    let id = s.warranty_id;
+    let hash = BytesN::from_array(&s.env, &[7; 32]);
+
+    // The stranger signs for itself but holds none of the roles
+    let signer = &s.stranger;
+
+    s.sign_as(
+        signer,
+        "attest_compliance",
+        (signer.clone(), id, true, hash.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_attest_compliance(signer, &id, &true, &hash),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "assign_local_id",
+        (signer.clone(), id, 4711u64).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_assign_local_id(signer, &id, &4711),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let description = String::from_str(&s.env, "Replaced fan");
+    s.sign_as(
+        signer,
+        "add_service_record",
+        (
+            id,
+            signer.clone(),
+            description.clone(),
+            1704067200u64,
+            Vec::<u64>::new(&s.env),
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_add_service_record(
+            &id,
+            signer,
+            &description,
+            &1704067200,
+            &Vec::new(&s.env),
+            &None
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let part = PartInfo {
+        part_number: String::from_str(&s.env, "FAN-2"),
+        serial_number: String::from_str(&s.env, "F-1"),
+        covered: true,
+        coverage_secs: 86400,
+    };
+    s.sign_as(
+        signer,
+        "register_part_warranty",
+        (id, signer.clone(), part.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_part_warranty(&id, signer, &part),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let serials = Vec::from_array(&s.env, [String::from_str(&s.env, "LT-1")]);
+    s.sign_as(
+        signer,
+        "recall_products",
+        (signer.clone(), serials.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_recall_products(signer, &serials),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let region = String::from_str(&s.env, "EU");
+    s.sign_as(
+        signer,
+        "add_issuer_delegate",
+        (signer.clone(), s.owner.clone(), region.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_add_issuer_delegate(signer, &s.owner, &region),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "delegate_revoke_warranty",
+        (signer.clone(), id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_delegate_revoke_warranty(signer, &id, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let serial = String::from_str(&s.env, "LT-1");
+    s.sign_as(
+        signer,
+        "resolve_serial_conflict",
+        (signer.clone(), serial.clone(), id, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_resolve_serial_conflict(signer, &serial, &id, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "attach_proof",
+        (id, signer.clone(), hash.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_attach_proof(&id, signer, &hash, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let key = Symbol::new(&s.env, "image");
+    let uri = String::from_str(&s.env, "ipfs://bafyimage");
+    s.sign_as(
+        signer,
+        "attach_metadata",
+        (
+            id,
+            signer.clone(),
+            key.clone(),
+            uri.clone(),
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_attach_metadata(&id, signer, &key, &uri, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let manufacturer = String::from_str(&s.env, "Manufacturer");
+    let terms = WarrantyTerms {
+        coverage_type: CoverageType::PartsAndLabor,
+        max_claims: None,
+        max_payout: None,
+        transferable: true,
+        deductible: 0,
+        requires_attestation: false,
+    };
+    s.sign_as(
+        signer,
+        "create_terms_template",
+        (signer.clone(), manufacturer.clone(), terms.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_create_terms_template(signer, &manufacturer, &terms),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "set_retailer_authorized",
+        (signer.clone(), manufacturer.clone(), signer.clone(), true).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_set_retailer_authorized(signer, &manufacturer, signer, &true),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "attest_purchase",
+        (id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_attest_purchase(&id, signer, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "rule_on_dispute",
+        (
+            signer.clone(),
+            1u64,
+            true,
+            None::<ClaimPayout>,
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_rule_on_dispute(signer, &1, &true, &None, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "vote_on_dispute",
+        (
+            signer.clone(),
+            1u64,
+            DisputeOutcome::Dismiss,
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_vote_on_dispute(signer, &1, &DisputeOutcome::Dismiss, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "reactivate_warranty",
+        (id, signer.clone(), None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_reactivate_warranty(&id, signer, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "extend_warranty",
+        (id, signer.clone(), u64::MAX, None::<BytesN<16>>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_extend_warranty(&id, signer, &u64::MAX, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "co_owner_file_claim",
+        (
+            signer.clone(),
+            id,
+            ClaimCategory::Defect,
+            String::from_str(&s.env, "Broken"),
+            1704067200u64,
+            None::<i128>,
+            None::<BytesN<16>>,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_co_owner_file_claim(
+            signer,
+            &id,
+            &ClaimCategory::Defect,
+            &String::from_str(&s.env, "Broken"),
+            &1704067200,
+            &None,
+            &None,
+        ),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let action = CouncilAction::SetAdmin(signer.clone());
+    s.sign_as(
+        signer,
+        "propose",
+        (signer.clone(), action.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_propose(signer, &action),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "keeper_bump_ttl",
+        (signer.clone(),).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_keeper_bump_ttl(signer),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "keeper_expire_batch",
+        (signer.clone(), id, id, 1u32).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_keeper_expire_batch(signer, &id, &id, &1),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "keeper_extend_warranty_ttl",
+        (signer.clone(), id).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_keeper_extend_warranty_ttl(signer, &id),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    s.sign_as(
+        signer,
+        "keeper_compact_owner_index",
+        (signer.clone(), s.owner.clone(), None::<Cursor>).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_keeper_compact_owner_index(signer, &s.owner, &None),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    let manufacturer = String::from_str(&s.env, "Manufacturer");
+    let hashes = Vec::from_array(&s.env, [hash.clone()]);
+    s.sign_as(
+        signer,
+        "mint_voucher",
+        (
+            signer.clone(),
+            manufacturer.clone(),
+            hashes.clone(),
+            86400u64,
+            1767225600u64,
+        )
+            .into_val(&s.env),
+    );
+    assert_eq!(
+        s.client
+            .try_mint_voucher(signer, &manufacturer, &hashes, &86400, &1767225600),
+        Err(Ok(WarrantyError::Unauthorized))
+    );
+
+    // Naming a store as retailer takes the store's signature too
+    let input = WarrantyInput::new(
+        String::from_str(&s.env, "Laptop"),
+        String::from_str(&s.env, "LT-2"),
+        manufacturer,
+        1704000000,
+        1767225600,
+    )
+    .with_retailer(s.registrant.clone());
+    s.sign_as(
+        signer,
+        "register_warranty",
+        (signer.clone(), input.clone()).into_val(&s.env),
+    );
+    assert_eq!(
+        s.client.try_register_warranty(signer, &input),
+        Err(Err(InvokeError::Abort))
+    );
+
+    assert!(s.client.get_compliance_trail(&id).is_empty());
+    assert_eq!(s.client.get_local_id(&id), None);
+}
@@ -0,0 +1,513 @@
+#![cfg(test)]
+//! Exact topics and payloads of the events each flow publishes.
+//!
+//! Indexers decode these events without reading storage, so a change to
+//! any of them is a breaking change and must show up here.
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, Env, Event, String, Val, Vec,
+};
+
+struct Setup<'a> {
+    env: Env,
+    contract_id: Address,
+    client: WarrantyTrackerClient<'a>,
+    admin: Address,
+    owner: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>));
+    let client = WarrantyTrackerClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    Setup {
+        env,
+        contract_id,
+        client,
+        admin,
+        owner,
+    }
+}
+
+/// An event as `env.events().all()` reports it once the contract published it
+trait Published {
+    fn published(&self, s: &Setup) -> (Address, Vec<Val>, Val);
+}
+
+impl<T: Event> Published for T {
+    fn published(&self, s: &Setup) -> (Address, Vec<Val>, Val) {
+        (
+            s.contract_id.clone(),
+            self.topics(&s.env),
+            self.data(&s.env),
+        )
+    }
+}
+
+/// Events the contract itself published in the last invocation
+fn contract_events(s: &Setup) -> Vec<(Address, Vec<Val>, Val)> {
+    let mut events = Vec::new(&s.env);
+    for event in s.env.events().all().iter() {
+        if event.0 == s.contract_id {
+            events.push_back(event);
+        }
+    }
+    events
+}
+
+fn register(s: &Setup, serial_number: &str) -> u64 {
+    s.client.register_warranty(
+        &s.owner,
+        &WarrantyInput::new(
+            String::from_str(&s.env, "Laptop"),
+            String::from_str(&s.env, serial_number),
+            String::from_str(&s.env, "Manufacturer"),
+            1704000000,
+            1767225600,
+        ),
+    )
+}
+
+#[test]
+fn test_register_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyRegistered {
+                warranty_id,
+                owner: s.owner.clone(),
+                product_name: String::from_str(&s.env, "Laptop"),
+                manufacturer: String::from_str(&s.env, "Manufacturer"),
+                expiration_date: 1767225600,
+            }
+            .published(&s),
+            Mint {
+                to: s.owner.clone(),
+                token_id: warranty_id,
+            }
+            .published(&s),
+        ]
+    );
+
+    // Analytics follow the lifecycle events and carry no IDs or addresses
+    s.client.set_analytics_enabled(&true);
+    let warranty_id = register(&s, "LT-2");
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyRegistered {
+                warranty_id,
+                owner: s.owner.clone(),
+                product_name: String::from_str(&s.env, "Laptop"),
+                manufacturer: String::from_str(&s.env, "Manufacturer"),
+                expiration_date: 1767225600,
+            }
+            .published(&s),
+            Mint {
+                to: s.owner.clone(),
+                token_id: warranty_id,
+            }
+            .published(&s),
+            RegistrationAnalytics {
+                category: None,
+                duration_bucket_months: 24,
+                region: None,
+            }
+            .published(&s),
+        ]
+    );
+}
+
+#[test]
+fn test_transfer_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let buyer = Address::generate(&s.env);
+
+    s.client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyTransferred {
+                warranty_id,
+                from: s.owner.clone(),
+                to: buyer.clone(),
+            }
+            .published(&s),
+            Transfer {
+                from: s.owner.clone(),
+                to: buyer,
+                token_id: warranty_id,
+            }
+            .published(&s),
+        ]
+    );
+}
+
+#[test]
+fn test_status_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+
+    s.client.set_to_expired(&warranty_id, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyStatusChanged {
+                warranty_id,
+                owner: s.owner.clone(),
+                previous: WarrantyStatus::Active,
+                status: WarrantyStatus::Expired,
+            }
+            .published(&s)
+        ]
+    );
+
+    s.client.set_cooling_off_secs(&3600);
+    s.client.delete_warranty(&warranty_id, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            Burn {
+                from: s.owner.clone(),
+                token_id: warranty_id,
+            }
+            .published(&s)
+        ]
+    );
+}
+
+#[test]
+fn test_revocation_events() {
+    let s = setup();
+    let maker = Address::generate(&s.env);
+    let name = String::from_str(&s.env, "Acme");
+    s.client.register_manufacturer(&maker, &name);
+    let warranty_id = s.client.manufacturer_issue_warranty(
+        &maker,
+        &s.owner,
+        &WarrantyInput::new(
+            String::from_str(&s.env, "Laptop"),
+            String::from_str(&s.env, "LT-1"),
+            name,
+            1704000000,
+            1767225600,
+        ),
+    );
+    let reason = String::from_str(&s.env, "Payment not cleared");
+
+    s.client
+        .schedule_revocation(&warranty_id, &1704153600, &reason);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            RevocationScheduled {
+                warranty_id,
+                revoke_at: 1704153600,
+                reason: reason.clone(),
+            }
+            .published(&s)
+        ]
+    );
+
+    s.client.cancel_revocation(&warranty_id);
+    assert_eq!(
+        s.env.events().all(),
+        vec![&s.env, RevocationCancelled { warranty_id }.published(&s)]
+    );
+
+    s.client
+        .schedule_revocation(&warranty_id, &1704153600, &reason);
+    s.env.ledger().set_timestamp(1704153600);
+    s.client.execute_revocation(&warranty_id, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyStatusChanged {
+                warranty_id,
+                owner: s.owner.clone(),
+                previous: WarrantyStatus::Active,
+                status: WarrantyStatus::Revoked,
+            }
+            .published(&s),
+            RevocationExecuted {
+                warranty_id,
+                reason,
+            }
+            .published(&s),
+        ]
+    );
+}
+
+#[test]
+fn test_claim_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let description = String::from_str(&s.env, "Screen flickers");
+    let claim_event = |claim_id: u64, status: ClaimStatus, denial_reason: Option<DenialReason>| {
+        WarrantyClaimUpdated {
+            warranty_id,
+            claim_id,
+            status,
+            denial_reason,
+        }
+        .published(&s)
+    };
+
+    let approved = s
+        .client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &1704067200,
+            &None,
+            &None,
+        )
+        .claim_id;
+    assert_eq!(
+        s.env.events().all(),
+        vec![&s.env, claim_event(approved, ClaimStatus::Filed, None)]
+    );
+    s.client
+        .approve_claim(&s.admin, &approved, &None, &None, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            claim_event(approved, ClaimStatus::Approved, None),
+            ClaimPayoutApproved {
+                warranty_id,
+                claim_id: approved,
+                requested_amount: None,
+                approved_amount: 0,
+                difference: 0,
+            }
+            .published(&s),
+        ]
+    );
+
+    let rejected = s
+        .client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &description,
+            &1704067200,
+            &None,
+            &None,
+        )
+        .claim_id;
+    s.client
+        .reject_claim(&s.admin, &rejected, &DenialReason::OutOfCoverage, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            claim_event(
+                rejected,
+                ClaimStatus::Rejected,
+                Some(DenialReason::OutOfCoverage)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_solvency_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let issuer = Address::generate(&s.env);
+    s.client.set_namespace_issuer(
+        &String::from_str(&s.env, "Manufacturer"),
+        &Some(issuer.clone()),
+    );
+    let token_address = s
+        .env
+        .register_stellar_asset_contract_v2(s.admin.clone())
+        .address();
+    token::StellarAssetClient::new(&s.env, &token_address).mint(&issuer, &1_000);
+    s.client
+        .fund_claim_pool(&issuer, &token_address, &100, &None);
+
+    let claim_id = s
+        .client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&s.env, "Screen flickers"),
+            &1704067200,
+            &Some(500),
+            &None,
+        )
+        .claim_id;
+    s.client
+        .approve_claim(&s.admin, &claim_id, &None, &None, &None);
+
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            WarrantyClaimUpdated {
+                warranty_id,
+                claim_id,
+                status: ClaimStatus::Approved,
+                denial_reason: None,
+            }
+            .published(&s),
+            ClaimPayoutApproved {
+                warranty_id,
+                claim_id,
+                requested_amount: Some(500),
+                approved_amount: 500,
+                difference: 0,
+            }
+            .published(&s),
+            SolvencyWarning {
+                manufacturer: issuer.clone(),
+                liabilities: 500,
+                collateral: 100,
+            }
+            .published(&s),
+        ]
+    );
+
+    // Issuers already warned are not warned again
+    s.client
+        .withdraw_claim_pool(&issuer, &token_address, &50, &None);
+    assert_eq!(contract_events(&s), vec![&s.env]);
+}
+
+#[test]
+fn test_payout_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let issuer = Address::generate(&s.env);
+    s.client.set_namespace_issuer(
+        &String::from_str(&s.env, "Manufacturer"),
+        &Some(issuer.clone()),
+    );
+    let token_address = s
+        .env
+        .register_stellar_asset_contract_v2(s.admin.clone())
+        .address();
+    token::StellarAssetClient::new(&s.env, &token_address).mint(&issuer, &1_000);
+    s.client
+        .fund_claim_pool(&issuer, &token_address, &1_000, &None);
+
+    let claim_id = s
+        .client
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&s.env, "Screen flickers"),
+            &1704067200,
+            &Some(500),
+            &None,
+        )
+        .claim_id;
+    s.client.approve_claim(
+        &s.admin,
+        &claim_id,
+        &None,
+        &Some(ClaimPayout {
+            token: token_address,
+            amount: 350,
+        }),
+        &None,
+    );
+
+    // The token's own transfer event is left out
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            WarrantyClaimUpdated {
+                warranty_id,
+                claim_id,
+                status: ClaimStatus::Approved,
+                denial_reason: None,
+            }
+            .published(&s),
+            ClaimPayoutApproved {
+                warranty_id,
+                claim_id,
+                requested_amount: Some(500),
+                approved_amount: 350,
+                difference: 150,
+            }
+            .published(&s),
+        ]
+    );
+}
+
+#[test]
+fn test_minimal_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let buyer = Address::generate(&s.env);
+
+    // Lifecycle events shrink to the action and ID; NFT events are unchanged
+    s.client.set_event_verbosity(&EventVerbosity::Minimal);
+    s.client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyActivity {
+                action: Symbol::new(&s.env, "transferred"),
+                warranty_id,
+            }
+            .published(&s),
+            Transfer {
+                from: s.owner.clone(),
+                to: buyer,
+                token_id: warranty_id,
+            }
+            .published(&s),
+        ]
+    );
+}
+
+#[test]
+fn test_private_events() {
+    let s = setup();
+    let warranty_id = register(&s, "LT-1");
+    let buyer = Address::generate(&s.env);
+
+    // Private warranties publish no addresses and no NFT events
+    s.client.set_visibility(&warranty_id, &true, &None);
+    s.client.transfer_ownership(&warranty_id, &buyer, &None);
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            WarrantyActivity {
+                action: Symbol::new(&s.env, "transferred"),
+                warranty_id,
+            }
+            .published(&s)
+        ]
+    );
+}
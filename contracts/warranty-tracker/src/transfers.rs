@@ -0,0 +1,482 @@
+//! Ownership transfers, handovers, transfer allowlists, inheritance and
+//! reversion.
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Vec};
+
+use super::*;
+
+pub(crate) fn transfer_ownership(
+    env: Env,
+    warranty_id: u64,
+    new_owner: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &new_owner)?;
+    change_owner(&env, &warranty.owner.clone(), warranty, &new_owner, None)?;
+
+    notify_change(&env, symbol_short!("transfer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn transfer_with_condition(
+    env: Env,
+    warranty_id: u64,
+    new_owner: Address,
+    condition: ConditionGrade,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    if condition == ConditionGrade::Unreported {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &new_owner)?;
+    change_owner(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        &new_owner,
+        Some(condition),
+    )?;
+
+    notify_change(&env, symbol_short!("transfer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn initiate_transfer(
+    env: Env,
+    warranty_id: u64,
+    new_owner: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &new_owner)?;
+
+    store_pending_transfer(
+        &env,
+        warranty_id,
+        &PendingTransfer {
+            from: warranty.owner,
+            to: new_owner,
+            initiated_at: env.ledger().timestamp(),
+        },
+    );
+
+    notify_change(&env, symbol_short!("offer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn accept_transfer(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    let pending = get_pending_transfer(env.clone(), warranty_id).ok_or(WarrantyError::NotFound)?;
+
+    pending.to.require_auth();
+    record_activity(&env, &pending.to);
+
+    if pending.from != warranty.owner {
+        return Err(WarrantyError::Unauthorized);
+    }
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &pending.to)?;
+    change_owner(&env, &pending.to, warranty, &pending.to, None)?;
+
+    notify_change(&env, symbol_short!("transfer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn cancel_transfer(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    remove_pending_transfer(&env, warranty_id);
+
+    notify_change(&env, symbol_short!("withdraw"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn decline_transfer(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let pending = get_pending_transfer(env.clone(), warranty_id).ok_or(WarrantyError::NotFound)?;
+
+    pending.to.require_auth();
+    record_activity(&env, &pending.to);
+
+    remove_pending_transfer(&env, warranty_id);
+
+    notify_change(&env, symbol_short!("decline"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_pending_transfer(env: Env, warranty_id: u64) -> Option<PendingTransfer> {
+    pending_transfer(&env, warranty_id)
+}
+
+pub(crate) fn get_transfer_history(env: Env, warranty_id: u64) -> Vec<TransferRecord> {
+    if is_private(&env, warranty_id) {
+        return Vec::new(&env);
+    }
+    read_record(&env, &DataKey::TransferHistory(warranty_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn initiate_handover(
+    env: Env,
+    warranty_id: u64,
+    buyer: Address,
+    sale_price_hash: BytesN<32>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "handovers")?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    check_transfer(&env, &warranty, &buyer)?;
+
+    write_record(
+        &env,
+        &DataKey::PendingHandover(warranty_id),
+        &Handover {
+            seller: warranty.owner,
+            buyer,
+            sale_price_hash,
+            initiated_at: env.ledger().timestamp(),
+            completed_at: 0,
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn accept_handover(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "handovers")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let mut handover: Handover =
+        read_record(&env, &DataKey::PendingHandover(warranty_id)).ok_or(WarrantyError::NotFound)?;
+
+    handover.buyer.require_auth();
+    record_activity(&env, &handover.buyer);
+
+    if handover.seller != warranty.owner {
+        return Err(WarrantyError::Unauthorized);
+    }
+    check_transfer(&env, &warranty, &handover.buyer)?;
+    change_owner(&env, &handover.buyer, warranty, &handover.buyer, None)?;
+
+    handover.completed_at = env.ledger().timestamp();
+    let history_key = DataKey::Handovers(warranty_id);
+    let mut history: Vec<Handover> = read_record(&env, &history_key).unwrap_or(Vec::new(&env));
+    history.push_back(handover);
+    write_record(&env, &history_key, &history);
+
+    notify_change(&env, symbol_short!("handover"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn cancel_handover(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    remove_record(&env, &DataKey::PendingHandover(warranty_id));
+    Ok(())
+}
+
+pub(crate) fn get_pending_handover(env: Env, warranty_id: u64) -> Option<Handover> {
+    read_record(&env, &DataKey::PendingHandover(warranty_id))
+}
+
+pub(crate) fn get_handovers(env: Env, warranty_id: u64) -> Vec<Handover> {
+    read_record(&env, &DataKey::Handovers(warranty_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn agent_transfer_ownership(
+    env: Env,
+    agent: Address,
+    warranty_id: u64,
+    new_owner: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "agents")?;
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    require_agent(&env, &agent, &warranty)?;
+
+    refresh_status(&env, &mut warranty);
+    check_transfer(&env, &warranty, &new_owner)?;
+    change_owner(&env, &agent, warranty, &new_owner, None)?;
+
+    notify_change(&env, symbol_short!("transfer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_transfer_allowlist(
+    env: Env,
+    owner: Address,
+    allowlist: Vec<Address>,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    owner.require_auth();
+    record_activity(&env, &owner);
+
+    let key = DataKey::TransferAllowlist(owner);
+    if allowlist.is_empty() {
+        remove_record(&env, &key);
+    } else {
+        write_record(&env, &key, &allowlist);
+    }
+    Ok(())
+}
+
+pub(crate) fn get_transfer_allowlist(env: Env, owner: Address) -> Vec<Address> {
+    read_record(&env, &DataKey::TransferAllowlist(owner)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn set_beneficiary(
+    env: Env,
+    warranty_id: u64,
+    beneficiary: Address,
+    inactivity_secs: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "inheritance")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if inactivity_secs == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if beneficiary == warranty.owner {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    write_record(
+        &env,
+        &DataKey::Beneficiary(warranty_id),
+        &Beneficiary {
+            beneficiary,
+            inactivity_secs,
+        },
+    );
+
+    notify_change(&env, symbol_short!("benefic"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn remove_beneficiary(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    remove_record(&env, &DataKey::Beneficiary(warranty_id));
+    Ok(())
+}
+
+pub(crate) fn get_beneficiary(env: Env, warranty_id: u64) -> Option<Beneficiary> {
+    read_record(&env, &DataKey::Beneficiary(warranty_id))
+}
+
+pub(crate) fn get_last_activity(env: Env, owner: Address) -> Option<u64> {
+    read_record(&env, &DataKey::LastActivity(owner))
+}
+
+pub(crate) fn claim_inherited(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "inheritance")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let beneficiary: Beneficiary =
+        read_record(&env, &DataKey::Beneficiary(warranty_id)).ok_or(WarrantyError::NotFound)?;
+
+    beneficiary.beneficiary.require_auth();
+    record_activity(&env, &beneficiary.beneficiary);
+
+    let last_activity: u64 = read_record(&env, &DataKey::LastActivity(warranty.owner.clone()))
+        .unwrap_or(warranty.created_at);
+    if env.ledger().timestamp() < last_activity.saturating_add(beneficiary.inactivity_secs) {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    change_owner(
+        &env,
+        &beneficiary.beneficiary,
+        warranty,
+        &beneficiary.beneficiary,
+        None,
+    )?;
+
+    notify_change(&env, symbol_short!("inherit"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_reversion(
+    env: Env,
+    warranty_id: u64,
+    revert_to: Address,
+    revert_after: u64,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "reversion")?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if revert_after <= env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+    let key = ReversionKey::Scheduled(warranty_id);
+    if let Some(existing) = read_record::<_, Reversion>(&env, &key) {
+        if existing.set_by != warranty.owner {
+            return Err(WarrantyError::AlreadyExists);
+        }
+    }
+
+    write_record(
+        &env,
+        &key,
+        &Reversion {
+            revert_to,
+            revert_after,
+            set_by: warranty.owner,
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn get_reversion(env: Env, warranty_id: u64) -> Option<Reversion> {
+    read_record(&env, &ReversionKey::Scheduled(warranty_id))
+}
+
+pub(crate) fn execute_reversion(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "reversion")?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let key = ReversionKey::Scheduled(warranty_id);
+    let reversion: Reversion = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    if env.ledger().timestamp() < reversion.revert_after {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    remove_record(&env, &key);
+    let from = warranty.owner.clone();
+    if from != reversion.revert_to {
+        change_owner(
+            &env,
+            &reversion.revert_to,
+            warranty,
+            &reversion.revert_to,
+            None,
+        )?;
+        WarrantyReverted {
+            warranty_id,
+            from,
+            to: reversion.revert_to,
+        }
+        .publish(&env);
+    }
+
+    notify_change(&env, symbol_short!("revert"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
@@ -0,0 +1,292 @@
+//! Preregistered serials, warranty vouchers and pre-issued warranties
+//! claimed by their buyers.
+use soroban_sdk::{symbol_short, Address, Bytes, BytesN, Env, String, Vec};
+
+use super::*;
+
+pub(crate) fn preregister_batch(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    entries: Vec<PreRegEntry>,
+) -> Result<u32, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "preregistration")?;
+
+    issuer.require_auth();
+    if !speaks_for_manufacturer(&env, &issuer, &manufacturer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if entries.is_empty() {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if entries.len() > read_limits(&env).max_batch_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    let mut stored = 0;
+    for entry in entries.iter() {
+        if entry.duration_secs == 0 {
+            return Err(WarrantyError::InvalidInput);
+        }
+        let key = DataKey::PreRegistration(issuer.clone(), entry.serial_hash.clone());
+        if env.storage().persistent().has(&key) {
+            continue;
+        }
+        env.storage().persistent().set(
+            &key,
+            &PreRegistration {
+                issuer: issuer.clone(),
+                manufacturer: manufacturer.clone(),
+                product_name: entry.product_name,
+                duration_secs: entry.duration_secs,
+                preregistered_at: env.ledger().timestamp(),
+            },
+        );
+        stored += 1;
+    }
+
+    if stored > 0 {
+        let stats_key = DataKey::ActivationStats(manufacturer);
+        let mut stats: ActivationStats = read_record(&env, &stats_key).unwrap_or_default();
+        stats.preregistered += stored as u64;
+        write_record(&env, &stats_key, &stats);
+    }
+
+    Ok(stored)
+}
+
+pub(crate) fn get_preregistration(
+    env: Env,
+    issuer: Address,
+    serial_hash: BytesN<32>,
+) -> Option<PreRegistration> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PreRegistration(issuer, serial_hash))
+}
+
+pub(crate) fn activate_preregistered(
+    env: Env,
+    owner: Address,
+    issuer: Address,
+    serial_number: String,
+    purchase_date: u64,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "preregistration")?;
+
+    let serial_hash: BytesN<32> = env.crypto().sha256(&serial_number.to_bytes()).into();
+    let key = DataKey::PreRegistration(issuer, serial_hash);
+    let prereg: PreRegistration = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(WarrantyError::NotFound)?;
+    env.storage().persistent().remove(&key);
+
+    let stats_key = DataKey::ActivationStats(prereg.manufacturer.clone());
+    let mut stats: ActivationStats = read_record(&env, &stats_key).unwrap_or_default();
+    stats.activated += 1;
+    stats.total_activation_secs += env
+        .ledger()
+        .timestamp()
+        .saturating_sub(prereg.preregistered_at);
+    stats.average_activation_secs = stats.total_activation_secs / stats.activated;
+    write_record(&env, &stats_key, &stats);
+
+    let expiration_date = purchase_date.saturating_add(prereg.duration_secs);
+    create_warranty(
+        &env,
+        None,
+        owner,
+        WarrantyInput::new(
+            prereg.product_name,
+            serial_number,
+            prereg.manufacturer,
+            purchase_date,
+            expiration_date,
+        ),
+        None,
+    )
+}
+
+pub(crate) fn mint_voucher(
+    env: Env,
+    issuer: Address,
+    manufacturer: String,
+    code_hashes: Vec<BytesN<32>>,
+    duration_secs: u64,
+    valid_until: u64,
+) -> Result<Vec<BytesN<32>>, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "vouchers")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), manufacturer.clone()) != Some(issuer.clone()) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if code_hashes.is_empty() {
+        return Err(WarrantyError::InvalidLimit);
+    }
+    if code_hashes.len() > read_limits(&env).max_batch_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    if duration_secs == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if valid_until < env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+
+    let mut minted = Vec::new(&env);
+    for code_hash in code_hashes.iter() {
+        let key = VoucherKey::Voucher(code_hash.clone());
+        if env.storage().persistent().has(&key) {
+            continue;
+        }
+        env.storage().persistent().set(
+            &key,
+            &Voucher {
+                issuer: issuer.clone(),
+                manufacturer: manufacturer.clone(),
+                duration_secs,
+                valid_until,
+                minted_at: env.ledger().timestamp(),
+                redeemed_by: None,
+            },
+        );
+        minted.push_back(code_hash);
+    }
+    Ok(minted)
+}
+
+pub(crate) fn get_voucher(env: Env, code_hash: BytesN<32>) -> Option<Voucher> {
+    env.storage()
+        .persistent()
+        .get(&VoucherKey::Voucher(code_hash))
+}
+
+pub(crate) fn redeem_voucher(
+    env: Env,
+    warranty_id: u64,
+    code: Bytes,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "vouchers")?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    let key = VoucherKey::Voucher(env.crypto().sha256(&code).into());
+    let mut voucher: Voucher = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(WarrantyError::NotFound)?;
+    if voucher.redeemed_by.is_some() {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    if env.ledger().timestamp() > voucher.valid_until {
+        return Err(WarrantyError::OutsideWindow);
+    }
+    if voucher.manufacturer != warranty.manufacturer {
+        return Err(WarrantyError::InvalidInput);
+    }
+    refresh_status(&env, &mut warranty);
+    if warranty.status != WarrantyStatus::Active {
+        return Err(WarrantyError::NotActive);
+    }
+
+    let previous_expiration = warranty.expiration_date;
+    warranty.expiration_date = warranty
+        .expiration_date
+        .saturating_add(voucher.duration_secs);
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    record_history(
+        &env,
+        warranty_id,
+        &warranty.owner,
+        symbol_short!("extend"),
+        HistoryDetails::ExpirationChanged(previous_expiration, warranty.expiration_date),
+    );
+
+    voucher.redeemed_by = Some(warranty_id);
+    env.storage().persistent().set(&key, &voucher);
+
+    notify_change(&env, symbol_short!("extend"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn pre_issue_warranty(
+    env: Env,
+    issuer: Address,
+    input: WarrantyInput,
+    code_hash: BytesN<32>,
+) -> Result<BytesN<32>, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claim_codes")?;
+
+    issuer.require_auth();
+    if roles::get_namespace_issuer(env.clone(), input.manufacturer.clone()) != Some(issuer.clone())
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+    let key = ClaimCodeKey::Code(code_hash.clone());
+    if env.storage().persistent().has(&key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    let warranty_id = insert_warranty(
+        &env,
+        None,
+        env.current_contract_address(),
+        issuer,
+        input,
+        None,
+    )?;
+    env.storage().persistent().set(&key, &warranty_id);
+    Ok(code_hash)
+}
+
+pub(crate) fn get_pre_issued(env: Env, code_hash: BytesN<32>) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&ClaimCodeKey::Code(code_hash))
+}
+
+pub(crate) fn claim_warranty(
+    env: Env,
+    code_preimage: Bytes,
+    owner: Address,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "claim_codes")?;
+
+    owner.require_auth();
+    record_activity(&env, &owner);
+
+    let key = ClaimCodeKey::Code(env.crypto().sha256(&code_preimage).into());
+    let warranty_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(WarrantyError::NotFound)?;
+    env.storage().persistent().remove(&key);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let manufacturer = warranty.manufacturer.clone();
+    change_owner(&env, &owner, warranty, &owner, None)?;
+    issue_badge(&env, &owner, &manufacturer, warranty_id);
+
+    notify_change(&env, symbol_short!("transfer"), warranty_id, None);
+
+    Ok(warranty_id)
+}
@@ -0,0 +1,3401 @@
+//! Warranty records and their lifecycle.
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contracttype, symbol_short, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
+};
+
+use super::*;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyData {
+    pub id: u64,
+    pub owner: Address,
+    /// Address that registered the warranty
+    pub registrant: Address,
+    pub product_name: String,
+    pub serial_number: String,
+    pub manufacturer: String,
+    pub purchase_date: u64,
+    pub expiration_date: u64,
+    pub status: WarrantyStatus,
+    pub created_at: u64,
+    /// Ledger sequence the warranty was registered in
+    pub created_ledger: u32,
+    /// Ledger sequence of the most recent status or ownership change
+    pub last_modified_ledger: u32,
+    pub category: Option<String>,
+    /// Purchase price in the smallest unit of the sale currency
+    pub price: Option<i128>,
+    /// Currency `price` and claim amounts are denominated in, e.g. `USD`
+    pub currency: Option<Symbol>,
+    /// SHA-256 of the purchase receipt
+    pub receipt_hash: Option<BytesN<32>>,
+    /// Free-form description of what the warranty covers
+    pub coverage: Option<String>,
+    /// Whether `transfer_ownership` may move the warranty
+    pub transferable: bool,
+    /// Whether `get_warranty` hides the details from other readers
+    pub private: bool,
+    /// Product name translations keyed by language code, e.g. `de` or `pt_BR`
+    pub localized_names: Map<Symbol, String>,
+    /// Production batch or lot, used to target recalls
+    pub batch_number: Option<String>,
+    pub manufacture_date: Option<u64>,
+    /// Market the product was sold in, e.g. `EU` or `US`
+    pub region: Option<String>,
+    /// Usage after which the warranty ends even if the date has not passed
+    pub usage_limit: Option<u64>,
+    /// Unit of `usage_limit`, e.g. `km` or `hours`
+    pub usage_unit: Option<Symbol>,
+    /// Latest reading reported through `report_usage`
+    pub usage_reading: Option<u64>,
+    /// Promotional campaign whose bonus coverage was applied at registration
+    pub campaign_id: Option<u64>,
+    /// Version of the manufacturer's policy document in force at registration
+    pub policy_version: Option<u32>,
+    /// Registered manufacturer that issued the warranty, or None if the
+    /// owner registered it and the manufacturer name is unverified
+    pub verified_manufacturer: Option<Address>,
+    /// Coverage length waiting for `activate`; `expiration_date` is
+    /// `u64::MAX` until then
+    pub activation_duration: Option<u64>,
+    /// When the owner activated a warranty registered with deferred activation
+    pub activated_at: Option<u64>,
+    /// Terms template the warranty was registered under
+    pub terms_id: Option<u64>,
+    /// Authorized retailer's confirmation of the sale, if one was given
+    pub attestation: SaleAttestation,
+    /// Claims filed against the warranty over its lifetime
+    pub claims_filed: u32,
+    /// Addresses holding the warranty jointly with the owner; they can file
+    /// claims and read it in full, but only the owner transfers or revokes
+    pub co_owners: Vec<Address>,
+    /// Catalog model the warranty was registered for
+    pub model_id: Option<String>,
+}
+
+/// Everything needed to register a warranty
+///
+/// Start from `WarrantyInput::new` with the core fields and chain the
+/// `with_*` methods for the optional ones.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyInput {
+    pub product_name: String,
+    pub serial_number: String,
+    pub manufacturer: String,
+    pub purchase_date: u64,
+    pub expiration_date: u64,
+    pub category: Option<String>,
+    pub price: Option<i128>,
+    pub currency: Option<Symbol>,
+    pub receipt_hash: Option<BytesN<32>>,
+    pub coverage: Option<String>,
+    /// Defaults to transferable when unset
+    pub transferable: Option<bool>,
+    pub batch_number: Option<String>,
+    pub manufacture_date: Option<u64>,
+    pub region: Option<String>,
+    pub usage_limit: Option<u64>,
+    pub usage_unit: Option<Symbol>,
+    /// Store that sold the product; must co-sign the registration
+    pub retailer: Option<Address>,
+    /// Coverage length for products whose clock starts at first use; the
+    /// warranty stays Pending until `activate` and `expiration_date` is ignored
+    pub activation_duration: Option<u64>,
+    /// Manufacturer terms template the warranty is registered under
+    pub terms_id: Option<u64>,
+    /// Catalog model of the product; its name, manufacturer and default
+    /// coverage replace `product_name`, `manufacturer` and `expiration_date`
+    pub model_id: Option<String>,
+    /// Caller reference echoed into the outbox and audit log, not stored
+    pub correlation_id: Option<BytesN<16>>,
+}
+
+impl WarrantyInput {
+    pub fn new(
+        product_name: String,
+        serial_number: String,
+        manufacturer: String,
+        purchase_date: u64,
+        expiration_date: u64,
+    ) -> Self {
+        WarrantyInput {
+            product_name,
+            serial_number,
+            manufacturer,
+            purchase_date,
+            expiration_date,
+            category: None,
+            price: None,
+            currency: None,
+            receipt_hash: None,
+            coverage: None,
+            transferable: None,
+            batch_number: None,
+            manufacture_date: None,
+            region: None,
+            usage_limit: None,
+            usage_unit: None,
+            retailer: None,
+            activation_duration: None,
+            terms_id: None,
+            model_id: None,
+            correlation_id: None,
+        }
+    }
+
+    pub fn with_category(mut self, category: String) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn with_price(mut self, price: i128) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn with_currency(mut self, currency: Symbol) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn with_receipt_hash(mut self, receipt_hash: BytesN<32>) -> Self {
+        self.receipt_hash = Some(receipt_hash);
+        self
+    }
+
+    pub fn with_coverage(mut self, coverage: String) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
+    pub fn with_transferable(mut self, transferable: bool) -> Self {
+        self.transferable = Some(transferable);
+        self
+    }
+
+    pub fn with_batch(mut self, batch_number: String, manufacture_date: Option<u64>) -> Self {
+        self.batch_number = Some(batch_number);
+        self.manufacture_date = manufacture_date;
+        self
+    }
+
+    pub fn with_region(mut self, region: String) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn with_usage_limit(mut self, usage_limit: u64, usage_unit: Symbol) -> Self {
+        self.usage_limit = Some(usage_limit);
+        self.usage_unit = Some(usage_unit);
+        self
+    }
+
+    pub fn with_retailer(mut self, retailer: Address) -> Self {
+        self.retailer = Some(retailer);
+        self
+    }
+
+    pub fn with_deferred_activation(mut self, duration_secs: u64) -> Self {
+        self.activation_duration = Some(duration_secs);
+        self
+    }
+
+    pub fn with_terms(mut self, terms_id: u64) -> Self {
+        self.terms_id = Some(terms_id);
+        self
+    }
+
+    pub fn with_model(mut self, model_id: String) -> Self {
+        self.model_id = Some(model_id);
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: BytesN<16>) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WarrantyStatus {
+    Active,
+    Expired,
+    Revoked,
+    /// Registered but not yet in force
+    Pending,
+    /// Temporarily out of force, e.g. while a subscription is unpaid
+    Suspended,
+    /// Declared invalid by the issuer
+    Voided,
+    /// Ended early, e.g. because the product was returned
+    Cancelled,
+    /// Superseded by the warranty of a replacement product
+    Replaced,
+    /// Pulled by the manufacturer because the product is part of a recall
+    Recalled,
+}
+
+/// Which registrations of a manufacturer count as duplicates
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UniquenessRule {
+    /// Any number of warranties per serial number, e.g. for consumables
+    Unconstrained,
+    /// One live warranty per serial number
+    Serial,
+    /// One live warranty per serial number and product name, for
+    /// manufacturers that reuse serials across product lines
+    SerialAndProduct,
+}
+
+/// Corrections to a freshly registered warranty; `None` leaves a field as is
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyAmendment {
+    pub product_name: Option<String>,
+    pub serial_number: Option<String>,
+    pub purchase_date: Option<u64>,
+    pub expiration_date: Option<u64>,
+}
+
+/// Beneficiary who inherits a warranty once its owner goes inactive
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Beneficiary {
+    pub beneficiary: Address,
+    pub inactivity_secs: u64,
+}
+
+/// Scheduled return of a warranty, e.g. to the lessor when a lease ends
+///
+/// Unlike a beneficiary, a reversion survives transfers, so a lessor can
+/// schedule it and then hand the warranty to the lessee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reversion {
+    pub revert_to: Address,
+    /// Timestamp from which anyone can execute the reversion
+    pub revert_after: u64,
+    /// Owner who scheduled it, the only one who can replace it
+    pub set_by: Address,
+}
+
+/// Revocation an issuer scheduled, e.g. for a warranty granted while its
+/// payment clears
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledRevocation {
+    /// Timestamp from which anyone can execute the revocation
+    pub revoke_at: u64,
+    pub reason: String,
+    /// Manufacturer that scheduled it, the only one who can cancel it
+    pub scheduled_by: Address,
+}
+
+/// Warranties found claiming the same serial number, e.g. records imported
+/// from before the serial index, kept until and after an arbitrator rules
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SerialConflict {
+    pub manufacturer: String,
+    pub serial_number: String,
+    /// Claimants in the order they were found, the index holder first
+    pub warranty_ids: Vec<u64>,
+    pub recorded_at: u64,
+    /// Warranty the arbitrator let keep the serial number
+    pub winner_id: Option<u64>,
+    pub arbitrator: Option<Address>,
+    pub resolved_at: Option<u64>,
+}
+
+/// Portable snapshot of a warranty, exported as XDR
+///
+/// `contract` binds the certificate to the deployment that issued it and
+/// `issued_ledger` anchors it to the ledger it was exported in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyCertificate {
+    pub contract: Address,
+    pub warranty: WarrantyData,
+    pub issued_at: u64,
+    pub issued_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReportStatus {
+    Pending,
+    Confirmed,
+    Dismissed,
+}
+
+/// Suspected-counterfeit report awaiting admin review
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterfeitReport {
+    pub id: u64,
+    pub warranty_id: u64,
+    pub reporter: Address,
+    pub evidence_hash: BytesN<32>,
+    pub status: ReportStatus,
+    pub created_at: u64,
+}
+
+/// Track record of a counterfeit reporter
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReporterReputation {
+    pub confirmed: u32,
+    pub dismissed: u32,
+}
+
+/// Left behind when a warranty record is removed, so removed IDs can be told
+/// apart from IDs that were never issued
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tombstone {
+    pub warranty_id: u64,
+    pub removed_by: Address,
+    pub removed_at: u64,
+    pub removed_ledger: u32,
+    /// Set when the record was merged into another warranty
+    pub merged_into: Option<u64>,
+}
+
+/// One unit in a manufacturer's pre-registration upload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreRegEntry {
+    /// SHA-256 of the serial number
+    pub serial_hash: BytesN<32>,
+    pub product_name: String,
+    /// Coverage length, counted from the purchase date at activation
+    pub duration_secs: u64,
+}
+
+/// An unclaimed warranty waiting for its first owner
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreRegistration {
+    pub issuer: Address,
+    pub manufacturer: String,
+    pub product_name: String,
+    pub duration_secs: u64,
+    pub preregistered_at: u64,
+}
+
+/// How much of a manufacturer's pre-registered inventory buyers activated
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ActivationStats {
+    pub preregistered: u64,
+    pub activated: u64,
+    /// Sum of the time between pre-registration and activation
+    pub total_activation_secs: u64,
+    pub average_activation_secs: u64,
+}
+
+/// Warranty counts per status, kept up to date as warranties change
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyStats {
+    /// Warranty records currently stored
+    pub total: u64,
+    pub active: u64,
+    pub expired: u64,
+    pub revoked: u64,
+    /// Count for every status at least one warranty is in
+    pub by_status: Map<WarrantyStatus, u64>,
+}
+
+/// A manufacturer's outstanding warranty liability
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Obligations {
+    /// Warranties under the manufacturer name stored as Active
+    pub active_warranties: u64,
+    /// Claims filed, approved or disputed and not yet resolved or rejected
+    pub open_claims: u32,
+    /// Amounts claimants asked for on open claims, less what was paid out
+    pub pending_settlement_amounts: i128,
+}
+
+/// How well a manufacturer's claim pools and escrow cover its approved
+/// claims
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolvencyStatus {
+    /// Owed on approved claims the manufacturer pays and has not settled
+    pub liabilities: i128,
+    /// Claim pool, escrow and consortium escrow balances in the best-funded
+    /// token
+    pub collateral: i128,
+    /// Claim pool, escrow and consortium escrow balances in each token
+    pub collateral_by_token: Map<Address, i128>,
+    /// Liabilities in basis points of collateral, `u32::MAX` when there is
+    /// no collateral to cover them
+    pub ratio_bps: u32,
+    pub threshold_bps: u32,
+    /// Whether `ratio_bps` is past `threshold_bps`
+    pub under_collateralized: bool,
+}
+
+/// Registrations a retailer made in one 30-day bucket
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetailerBucket {
+    /// Registration time divided by `SECS_PER_MONTH`
+    pub bucket: u64,
+    pub registrations: u64,
+    /// Sum of the purchase prices declared on those registrations
+    pub price_total: i128,
+}
+
+/// An authorized retailer's confirmation that it sold the product
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseAttestation {
+    pub retailer: Address,
+    pub attested_at: u64,
+}
+
+/// Whether an authorized retailer confirmed a warranty's sale
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SaleAttestation {
+    Unattested,
+    Attested(PurchaseAttestation),
+}
+
+impl SaleAttestation {
+    /// The retailer's confirmation, if one was given
+    pub fn attested(&self) -> Option<PurchaseAttestation> {
+        match self {
+            SaleAttestation::Unattested => None,
+            SaleAttestation::Attested(attestation) => Some(attestation.clone()),
+        }
+    }
+}
+
+/// A component replaced during a repair, registered under its own warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartInfo {
+    pub part_number: String,
+    pub serial_number: String,
+    /// Whether the replacement was paid for under the parent warranty
+    pub covered: bool,
+    /// Coverage length of the part, counted from registration
+    pub coverage_secs: u64,
+}
+
+/// How a part warranty relates to the warranty of the product it went into
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartRecord {
+    pub parent_id: u64,
+    pub part_number: String,
+    pub covered: bool,
+}
+
+/// How a linked warranty depends on its parent
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LinkType {
+    /// The link is recorded for reference only
+    Related,
+    /// The child counts as expired once the parent is revoked
+    ExpiresOnParentRevocation,
+    /// The child counts as expired once the parent's coverage has ended,
+    /// however that happened. A pending or suspended parent has not ended.
+    ExpiresWithParent,
+}
+
+/// Dependency of a warranty on another, e.g. an accessory on its device
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyLink {
+    pub parent_id: u64,
+    pub link_type: LinkType,
+    pub linked_at: u64,
+}
+
+/// Prepaid coverage extension sold ahead of time, e.g. as a gift card
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Voucher {
+    pub issuer: Address,
+    /// Only warranties from this manufacturer can redeem the voucher
+    pub manufacturer: String,
+    /// Added to the expiration date on redemption
+    pub duration_secs: u64,
+    /// Last timestamp the voucher can be redeemed at
+    pub valid_until: u64,
+    pub minted_at: u64,
+    /// The warranty the voucher was spent on
+    pub redeemed_by: Option<u64>,
+}
+
+/// Bonus coverage granted to registrations made while the campaign runs
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Campaign {
+    pub issuer: Address,
+    /// Only registrations for this manufacturer take part
+    pub manufacturer: String,
+    /// Added to the expiration date of each matching registration
+    pub extra_months: u32,
+    /// First timestamp registrations count towards the campaign
+    pub start: u64,
+    /// Last timestamp registrations count towards the campaign
+    pub end: u64,
+    /// Restricts the campaign to one product category when set
+    pub category_filter: Option<String>,
+}
+
+/// How many registrations a campaign picked up
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CampaignStats {
+    pub registrations: u64,
+    /// Coverage added across all of those registrations
+    pub bonus_secs_granted: u64,
+}
+
+/// What a terms template covers
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CoverageType {
+    PartsOnly,
+    PartsAndLabor,
+    /// The product is swapped rather than repaired
+    Replacement,
+}
+
+/// Standard terms a manufacturer registers warranties under
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyTerms {
+    pub coverage_type: CoverageType,
+    /// Claims a warranty may have filed against it over its lifetime, if capped
+    pub max_claims: Option<u32>,
+    /// Cap on what a single approved claim pays out, if any
+    pub max_payout: Option<i128>,
+    /// Whether warranties under these terms can change hands
+    pub transferable: bool,
+    /// Amount the owner bears on every claim
+    pub deductible: i128,
+    /// Whether claims are refused until a retailer attests the purchase
+    pub requires_attestation: bool,
+}
+
+/// Reusable terms published by a manufacturer's namespace issuer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermsTemplate {
+    pub terms_id: u64,
+    /// Only registrations for this manufacturer can use the template
+    pub manufacturer: String,
+    pub terms: WarrantyTerms,
+    pub created_at: u64,
+}
+
+/// Product model a registered manufacturer listed in the catalog
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Product {
+    pub model_id: String,
+    pub name: String,
+    /// Registered name of the manufacturer that listed the model
+    pub manufacturer: String,
+    pub category: Option<String>,
+    /// Coverage a warranty for the model gets, counted from the purchase date
+    pub default_warranty_duration: u64,
+    pub added_at: u64,
+}
+
+/// "Verified Customer" badge held by an address for one manufacturer
+///
+/// Badges are keyed by the address that registered, so they stay put when
+/// the warranty itself changes hands.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Badge {
+    /// The first warranty the holder registered with the manufacturer
+    pub warranty_id: u64,
+    pub issued_at: u64,
+}
+
+/// One version of a manufacturer's published terms or FAQ
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyDoc {
+    /// Starts at 1 and increases with every publication
+    pub version: u32,
+    /// SHA-256 of the document
+    pub hash: BytesN<32>,
+    /// Where the document can be fetched, e.g. an IPFS or HTTPS URI
+    pub uri: String,
+    /// Timestamp from which the document applies to new registrations
+    pub effective_date: u64,
+    pub published_at: u64,
+}
+
+/// What an issuer charges for extended warranties in one product category
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricingTable {
+    /// Receives the payments
+    pub issuer: Address,
+    pub token: Address,
+    /// Price per coverage length in 30-day months
+    pub prices: Map<u32, i128>,
+}
+
+/// A price offer for an extended warranty, valid until `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub quote_id: u64,
+    pub manufacturer: String,
+    pub category: String,
+    pub duration_months: u32,
+    pub price: i128,
+    pub token: Address,
+    /// Receives the payment
+    pub issuer: Address,
+    pub expires_at: u64,
+}
+
+/// A resale of a warranty from one owner to the next, recorded separately
+/// from plain transfers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Handover {
+    pub seller: Address,
+    pub buyer: Address,
+    /// Hash of the agreed sale price, so the price itself stays off-chain
+    pub sale_price_hash: BytesN<32>,
+    pub initiated_at: u64,
+    /// Zero until the buyer accepts
+    pub completed_at: u64,
+}
+
+/// A transfer offered by the owner and waiting for the recipient to accept
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub initiated_at: u64,
+}
+
+/// Physical condition of the product, as attested by the sender of a transfer
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConditionGrade {
+    New,
+    LikeNew,
+    Good,
+    Fair,
+    ForParts,
+    /// The sender did not attest the condition
+    Unreported,
+}
+
+/// One change of ownership
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferRecord {
+    pub from: Address,
+    pub to: Address,
+    pub condition: ConditionGrade,
+    pub transferred_at: u64,
+}
+
+/// An event reported and signed by the device bound to a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeviceEvent {
+    pub event_code: u32,
+    pub nonce: u64,
+    pub logged_at: u64,
+}
+
+/// A logistics provider's statement about storage or transport conditions
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceAttestation {
+    pub provider: Address,
+    /// Whether conditions stayed within the product's requirements
+    pub compliant: bool,
+    /// Hash of the supporting sensor log or report
+    pub evidence_hash: BytesN<32>,
+    pub attested_at: u64,
+}
+
+/// Canonical pointer to a warranty held by any tracker deployment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantyRef {
+    pub contract: Address,
+    pub id: u64,
+}
+
+impl WarrantyRef {
+    pub fn new(contract: Address, id: u64) -> Self {
+        Self { contract, id }
+    }
+
+    /// Resolve the reference by calling the tracker it points at
+    ///
+    /// For use by other contracts. A tracker resolving one of its own
+    /// references must use `resolve_warranty_ref` instead, since contracts
+    /// cannot call themselves.
+    pub fn resolve(&self, env: &Env) -> Option<WarrantySummary> {
+        WarrantyTrackerClient::new(env, &self.contract).get_warranty_summary(&self.id)
+    }
+}
+
+/// Compact answer to "is this warranty good?" for marketplaces checking
+/// coverage from their own contracts
+///
+/// Unknown warranties come back with `is_valid` false and no owner.
+/// Private warranties come back with their expiration and manufacturer
+/// blanked, as from `get_warranty`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationResult {
+    /// Whether the warranty covers the product right now: Active once its
+    /// dates, usage and link policy are applied, and not frozen
+    pub is_valid: bool,
+    /// None for unknown and private warranties
+    pub owner: Option<Address>,
+    pub expiration_date: u64,
+    pub manufacturer: String,
+}
+
+/// What other contracts need to know about a referenced warranty
+///
+/// Private warranties come back with their product details and owner
+/// blanked, as from `get_warranty`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WarrantySummary {
+    pub warranty_ref: WarrantyRef,
+    pub owner: Address,
+    pub product_name: String,
+    pub manufacturer: String,
+    pub status: WarrantyStatus,
+    pub expiration_date: u64,
+    pub transferable: bool,
+    /// When claims open again after the warranty changed hands, while the
+    /// post-transfer cooldown runs
+    pub claims_open_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum WarrantyRecord {
+    NotFound,
+    Live(WarrantyData),
+    Removed(Tombstone),
+}
+
+/// Who asks for a status change, which decides the transitions open to it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum StatusAuthority {
+    /// The owner, or an agent acting for it
+    Owner,
+    /// The manufacturer that issued the warranty
+    Manufacturer,
+    /// The admin, e.g. confirming a counterfeit report
+    Admin,
+}
+
+/// Whether `authority` may move a warranty from `from` to `to`.
+///
+/// Voided, Cancelled and Replaced are final. Only a manufacturer's recall
+/// leads to Recalled, which can still be voided or cancelled. Owners can
+/// expire, revoke and cancel, but an expired, revoked or suspended warranty
+/// only comes back when its manufacturer reactivates it, and only the
+/// manufacturer suspends, voids or marks a warranty replaced.
+pub(crate) fn status_transition_allowed(
+    from: &WarrantyStatus,
+    to: &WarrantyStatus,
+    authority: StatusAuthority,
+) -> bool {
+    use WarrantyStatus::*;
+
+    if from == to {
+        return true;
+    }
+    if *to == Recalled {
+        return false;
+    }
+    let allowed = match from {
+        Active => *to != Pending,
+        Expired | Revoked => matches!(to, Active | Expired | Revoked),
+        Pending => matches!(to, Active | Cancelled),
+        Suspended => matches!(to, Active | Expired | Revoked | Voided | Cancelled),
+        Recalled => matches!(to, Voided | Cancelled),
+        Voided | Cancelled | Replaced => false,
+    };
+    allowed
+        && match authority {
+            StatusAuthority::Owner => {
+                !matches!(from, Expired | Revoked | Suspended)
+                    && !matches!(to, Suspended | Voided | Replaced)
+            }
+            StatusAuthority::Manufacturer | StatusAuthority::Admin => true,
+        }
+}
+
+/// Move `warranty` to `status` on behalf of `authority`, once `actor` has
+/// been authorized.
+pub(crate) fn apply_status(
+    env: &Env,
+    actor: &Address,
+    mut warranty: WarrantyData,
+    status: WarrantyStatus,
+    authority: StatusAuthority,
+) -> Result<(), WarrantyError> {
+    check_status_transition(&warranty.status, &status, authority)?;
+    let previous = warranty.status.clone();
+    if status == WarrantyStatus::Active {
+        start_deferred_coverage(env, &mut warranty);
+    }
+    warranty.status = status;
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(env, &warranty);
+    record_status_change(
+        env,
+        &warranty,
+        actor,
+        symbol_short!("status"),
+        previous.clone(),
+    );
+    publish_status_change(env, &warranty, previous);
+
+    if warranty.status == WarrantyStatus::Active {
+        unarchive_warranty(env, &warranty.owner, warranty.id);
+    }
+    Ok(())
+}
+
+/// Reject status changes `status_transition_allowed` does not permit.
+pub(crate) fn check_status_transition(
+    from: &WarrantyStatus,
+    to: &WarrantyStatus,
+    authority: StatusAuthority,
+) -> Result<(), WarrantyError> {
+    if !status_transition_allowed(from, to, authority) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    Ok(())
+}
+
+/// Run the extra checks a category brings along, e.g. VIN validation for
+/// vehicles. Categories without rules of their own pass unchecked.
+pub(crate) fn validate_category(
+    env: &Env,
+    category: &Option<String>,
+    serial_number: &String,
+) -> Result<(), WarrantyError> {
+    let Some(category) = category else {
+        return Ok(());
+    };
+    if *category == String::from_str(env, VEHICLE_CATEGORY) && !is_valid_vin(serial_number) {
+        return Err(WarrantyError::InvalidInput);
+    }
+    Ok(())
+}
+
+/// Check a 17-character VIN against the ISO 3779 check digit in position 9.
+pub(crate) fn is_valid_vin(vin: &String) -> bool {
+    const WEIGHTS: [u32; 17] = [8, 7, 6, 5, 4, 3, 2, 10, 0, 9, 8, 7, 6, 5, 4, 3, 2];
+
+    if vin.len() != 17 {
+        return false;
+    }
+    let mut chars = [0u8; 17];
+    vin.copy_into_slice(&mut chars);
+
+    let mut sum = 0;
+    for (&c, weight) in chars.iter().zip(WEIGHTS) {
+        let value = match c {
+            b'0'..=b'9' => (c - b'0') as u32,
+            b'A'..=b'H' => (c - b'A') as u32 + 1,
+            b'J'..=b'N' => (c - b'J') as u32 + 1,
+            b'P' => 7,
+            b'R' => 9,
+            b'S'..=b'Z' => (c - b'S') as u32 + 2,
+            // I, O and Q are never used, nor is anything outside A-Z and 0-9
+            _ => return false,
+        };
+        sum += value * weight;
+    }
+
+    let check = match sum % 11 {
+        10 => b'X',
+        digit => b'0' + digit as u8,
+    };
+    chars[8] == check
+}
+
+/// Fail with `AlreadyExists` if the manufacturer's uniqueness rule already
+/// gives this serial number to another live warranty.
+pub(crate) fn check_serial_free(
+    env: &Env,
+    manufacturer: &String,
+    serial_number: &String,
+    product_name: &String,
+    except: Option<u64>,
+) -> Result<(), WarrantyError> {
+    match serial_holder(env, manufacturer, serial_number, product_name, except) {
+        Some(_) => Err(WarrantyError::AlreadyExists),
+        None => Ok(()),
+    }
+}
+
+/// The live warranty other than `except` that the manufacturer's uniqueness
+/// rule gives this serial number to. Warranties that were deleted, or whose
+/// serial or product was amended, no longer hold it.
+pub(crate) fn serial_holder(
+    env: &Env,
+    manufacturer: &String,
+    serial_number: &String,
+    product_name: &String,
+    except: Option<u64>,
+) -> Option<u64> {
+    let rule = settings::get_uniqueness_rule(env.clone(), manufacturer.clone());
+    let holder_id: Option<u64> = match rule {
+        UniquenessRule::Unconstrained => return None,
+        UniquenessRule::Serial => env.storage().persistent().get(&DataKey::SerialIndex(
+            manufacturer.clone(),
+            serial_number.clone(),
+        )),
+        UniquenessRule::SerialAndProduct => {
+            env.storage()
+                .persistent()
+                .get(&UniquenessKey::SerialAndProduct(
+                    manufacturer.clone(),
+                    serial_number.clone(),
+                    product_name.clone(),
+                ))
+        }
+    };
+    let holder = holder_id
+        .filter(|holder_id| Some(*holder_id) != except)
+        .and_then(|holder_id| load_warranty(env, holder_id))?;
+    (holder.manufacturer == *manufacturer
+        && holder.serial_number == *serial_number
+        && (rule != UniquenessRule::SerialAndProduct || holder.product_name == *product_name))
+        .then_some(holder.id)
+}
+
+/// Record that `warranty` claims a serial number `holder_id` already holds,
+/// reopening the conflict if it was resolved before. The holder keeps the
+/// serial index entry until an arbitrator rules.
+pub(crate) fn open_serial_conflict(env: &Env, warranty: &WarrantyData, holder_id: u64) {
+    let key = ConflictKey::Serial(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+    );
+    let mut conflict = read_record(env, &key).unwrap_or(SerialConflict {
+        manufacturer: warranty.manufacturer.clone(),
+        serial_number: warranty.serial_number.clone(),
+        warranty_ids: Vec::new(env),
+        recorded_at: env.ledger().timestamp(),
+        winner_id: None,
+        arbitrator: None,
+        resolved_at: None,
+    });
+    for warranty_id in [holder_id, warranty.id] {
+        if !conflict.warranty_ids.contains(warranty_id) {
+            conflict.warranty_ids.push_back(warranty_id);
+        }
+    }
+    conflict.winner_id = None;
+    conflict.arbitrator = None;
+    conflict.resolved_at = None;
+    write_record(env, &key, &conflict);
+}
+
+/// The live warranty the serial index points at for a product, if any.
+pub(crate) fn find_by_serial(
+    env: &Env,
+    manufacturer: &String,
+    serial_number: &String,
+) -> Option<WarrantyData> {
+    let warranty_id: u64 = env.storage().persistent().get(&DataKey::SerialIndex(
+        manufacturer.clone(),
+        serial_number.clone(),
+    ))?;
+    load_warranty(env, warranty_id).filter(|warranty| {
+        warranty.manufacturer == *manufacturer && warranty.serial_number == *serial_number
+    })
+}
+
+/// Judge a warranty for `verify`, reading storage only.
+pub(crate) fn verification(env: &Env, warranty: Option<WarrantyData>) -> VerificationResult {
+    let Some(warranty) = warranty else {
+        return VerificationResult {
+            is_valid: false,
+            owner: None,
+            expiration_date: 0,
+            manufacturer: String::from_str(env, ""),
+        };
+    };
+    let is_valid = effective_status(env, &warranty) == WarrantyStatus::Active
+        && !has_record(env, &PauseKey::Frozen(warranty.id));
+    let owner = (!warranty.private).then(|| warranty.owner.clone());
+    let warranty = public_view(env, warranty);
+    VerificationResult {
+        is_valid,
+        owner,
+        expiration_date: warranty.expiration_date,
+        manufacturer: warranty.manufacturer,
+    }
+}
+
+/// Point the serial index, and the product reservation if the manufacturer
+/// uses `SerialAndProduct`, at `warranty`.
+pub(crate) fn record_serial(env: &Env, warranty: &WarrantyData) {
+    let key = DataKey::SerialIndex(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+    );
+    env.storage().persistent().set(&key, &warranty.id);
+    extend_persistent(env, &key);
+
+    if settings::get_uniqueness_rule(env.clone(), warranty.manufacturer.clone())
+        == UniquenessRule::SerialAndProduct
+    {
+        env.storage().persistent().set(
+            &UniquenessKey::SerialAndProduct(
+                warranty.manufacturer.clone(),
+                warranty.serial_number.clone(),
+                warranty.product_name.clone(),
+            ),
+            &warranty.id,
+        );
+    }
+}
+
+/// Drop the serial index entry and product reservation held by `warranty`,
+/// e.g. before its serial number or product name changes.
+pub(crate) fn forget_serial(env: &Env, warranty: &WarrantyData) {
+    let key = DataKey::SerialIndex(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+    );
+    if env.storage().persistent().get(&key) == Some(warranty.id) {
+        env.storage().persistent().remove(&key);
+    }
+    let key = UniquenessKey::SerialAndProduct(
+        warranty.manufacturer.clone(),
+        warranty.serial_number.clone(),
+        warranty.product_name.clone(),
+    );
+    if env.storage().persistent().get(&key) == Some(warranty.id) {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Close the amendment window of a warranty for good, once it changed hands
+/// or had a claim filed.
+pub(crate) fn lock_amendments(env: &Env, warranty_id: u64) {
+    let key = AmendKey::Locked(warranty_id);
+    if !has_record(env, &key) {
+        write_record(env, &key, &true);
+    }
+}
+
+/// Start the clock of a warranty registered with deferred activation, so
+/// its stored duration runs from now. Other warranties are left as is.
+pub(crate) fn start_deferred_coverage(env: &Env, warranty: &mut WarrantyData) {
+    if let Some(duration) = warranty.activation_duration.take() {
+        let now = env.ledger().timestamp();
+        warranty.activated_at = Some(now);
+        warranty.expiration_date = now.saturating_add(duration);
+    }
+}
+
+/// Validate and store a new warranty. Sequential IDs are used unless the
+/// caller supplies one; the registration count advances either way.
+/// `verified_manufacturer` is the registered manufacturer issuing it, if any.
+pub(crate) fn create_warranty(
+    env: &Env,
+    warranty_id: Option<u64>,
+    owner: Address,
+    input: WarrantyInput,
+    verified_manufacturer: Option<Address>,
+) -> Result<u64, WarrantyError> {
+    owner.require_auth();
+    record_activity(env, &owner);
+
+    insert_warranty(
+        env,
+        warranty_id,
+        owner.clone(),
+        owner,
+        input,
+        verified_manufacturer,
+    )
+}
+
+/// Store a new warranty the callers have authorized. Pre-issued warranties
+/// are owned by the contract itself until `claim_warranty` binds a buyer.
+pub(crate) fn insert_warranty(
+    env: &Env,
+    warranty_id: Option<u64>,
+    owner: Address,
+    registrant: Address,
+    mut input: WarrantyInput,
+    verified_manufacturer: Option<Address>,
+) -> Result<u64, WarrantyError> {
+    if let Some(model_id) = &input.model_id {
+        require_feature(env, "product_catalog")?;
+        let product: Product = env
+            .storage()
+            .persistent()
+            .get(&CatalogKey::Product(model_id.clone()))
+            .ok_or(WarrantyError::NotFound)?;
+        if verified_manufacturer.is_some() && input.manufacturer != product.manufacturer {
+            return Err(WarrantyError::InvalidInput);
+        }
+        input.product_name = product.name;
+        input.manufacturer = product.manufacturer;
+        input.expiration_date = input
+            .purchase_date
+            .saturating_add(product.default_warranty_duration);
+        if product.category.is_some() {
+            input.category = product.category;
+        }
+    }
+    if let Some(duration) = input.activation_duration {
+        require_feature(env, "deferred_activation")?;
+        if duration == 0 {
+            return Err(WarrantyError::InvalidInput);
+        }
+        // Statutory minimums and campaign bonuses apply to the duration
+        input.expiration_date = input.purchase_date.saturating_add(duration);
+    }
+    if input.expiration_date <= input.purchase_date {
+        return Err(WarrantyError::InvalidDates);
+    }
+    validate_category(env, &input.category, &input.serial_number)?;
+    if let Some(retailer) = &input.retailer {
+        require_feature(env, "retailer_reports")?;
+        retailer.require_auth();
+    }
+    if input.usage_limit == Some(0) || input.price.is_some_and(|price| price < 0) {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let terms = match input.terms_id {
+        Some(terms_id) => {
+            require_feature(env, "terms_templates")?;
+            let template = terms_template(env, terms_id).ok_or(WarrantyError::NotFound)?;
+            if template.manufacturer != input.manufacturer {
+                return Err(WarrantyError::InvalidInput);
+            }
+            Some(template.terms)
+        }
+        None => None,
+    };
+    check_serial_free(
+        env,
+        &input.manufacturer,
+        &input.serial_number,
+        &input.product_name,
+        None,
+    )?;
+
+    let current_time = env.ledger().timestamp();
+    let skew = settings::get_purchase_date_skew(env.clone());
+    if input.purchase_date > current_time.saturating_add(skew) {
+        return Err(WarrantyError::InvalidDates);
+    }
+
+    if let Some(max_age) = settings::get_max_purchase_age(env.clone(), input.manufacturer.clone()) {
+        if current_time.saturating_sub(input.purchase_date) > max_age {
+            return Err(WarrantyError::InvalidDates);
+        }
+    }
+
+    let warranty_count: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::WarrantyCount)
+        .unwrap_or(0);
+    let max_warranties: Option<u64> = env.storage().instance().get(&DataKey::Capacity);
+    if max_warranties.is_some_and(|max| warranty_count >= max) {
+        return Err(WarrantyError::CapacityReached);
+    }
+    use_registration_quota(env, &owner)?;
+
+    let warranty_id = warranty_id.unwrap_or(warranty_count + 1);
+
+    let correlation_id = input.correlation_id.clone();
+    let requested_expiration = input.expiration_date;
+    if let Some(region) = input.region.clone() {
+        if let Some(min_duration) = statutory_minimum(env, region, input.category.clone()) {
+            let statutory_expiration = input.purchase_date.saturating_add(min_duration);
+            if input.expiration_date < statutory_expiration {
+                input.expiration_date = statutory_expiration;
+            }
+        }
+    }
+
+    let adjusted_expiration = input.expiration_date;
+    let campaign = if feature_enabled(env, "campaigns") {
+        matching_campaign(env, &input.manufacturer, &input.category)
+    } else {
+        None
+    };
+    if let Some((_, campaign)) = &campaign {
+        input.expiration_date = input
+            .expiration_date
+            .saturating_add(campaign.extra_months as u64 * SECS_PER_MONTH);
+    }
+
+    let policy_version = policy_in_force(env, &input.manufacturer).map(|doc| doc.version);
+
+    let activation_duration = input
+        .activation_duration
+        .map(|_| input.expiration_date - input.purchase_date);
+    let status = if activation_duration.is_some() {
+        WarrantyStatus::Pending
+    } else if input.expiration_date < current_time {
+        WarrantyStatus::Expired
+    } else {
+        WarrantyStatus::Active
+    };
+
+    let warranty = WarrantyData {
+        id: warranty_id,
+        owner: owner.clone(),
+        registrant,
+        product_name: input.product_name,
+        serial_number: input.serial_number,
+        manufacturer: input.manufacturer,
+        purchase_date: input.purchase_date,
+        expiration_date: if activation_duration.is_some() {
+            u64::MAX
+        } else {
+            input.expiration_date
+        },
+        status,
+        created_at: current_time,
+        created_ledger: env.ledger().sequence(),
+        last_modified_ledger: env.ledger().sequence(),
+        category: input.category,
+        price: input.price,
+        currency: input.currency,
+        receipt_hash: input.receipt_hash,
+        coverage: input.coverage,
+        transferable: match &terms {
+            Some(terms) => terms.transferable,
+            None => input.transferable.unwrap_or(true),
+        },
+        private: false,
+        localized_names: Map::new(env),
+        batch_number: input.batch_number,
+        manufacture_date: input.manufacture_date,
+        region: input.region,
+        usage_limit: input.usage_limit,
+        usage_unit: input.usage_unit,
+        usage_reading: None,
+        campaign_id: campaign.as_ref().map(|(campaign_id, _)| *campaign_id),
+        policy_version,
+        verified_manufacturer,
+        activation_duration,
+        activated_at: None,
+        terms_id: input.terms_id,
+        attestation: SaleAttestation::Unattested,
+        claims_filed: 0,
+        co_owners: Vec::new(env),
+        model_id: input.model_id,
+    };
+
+    save_warranty(env, &warranty);
+    remove_record(env, &DataKey::Tombstone(warranty_id));
+    record_serial(env, &warranty);
+    keepers::collect_rent(env, &warranty);
+
+    id_index_add(env, warranty_id);
+    index_add(env, &DataKey::OwnerWarranties(owner.clone()), warranty_id);
+    if let Some(batch_number) = warranty.batch_number.clone() {
+        index_add(
+            env,
+            &DataKey::BatchWarranties(warranty.manufacturer.clone(), batch_number),
+            warranty_id,
+        );
+    }
+    if let Some(model_id) = warranty.model_id.clone() {
+        index_add(env, &DataKey::ModelWarranties(model_id), warranty_id);
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKey::WarrantyCount, &(warranty_count + 1));
+
+    move_status_count(
+        env,
+        &warranty.manufacturer,
+        None,
+        Some(warranty.status.clone()),
+    );
+    if owner != env.current_contract_address() {
+        issue_badge(env, &owner, &warranty.manufacturer, warranty_id);
+    }
+
+    publish_registered(env, &warranty);
+    publish_mint(env, &warranty.owner, warranty_id);
+
+    if let Some(retailer) = input.retailer {
+        record_retail_sale(env, retailer, warranty.price.unwrap_or(0));
+    }
+
+    if let Some((campaign_id, campaign)) = campaign {
+        let key = CampaignKey::Stats(campaign_id);
+        let mut stats: CampaignStats = read_record(env, &key).unwrap_or_default();
+        stats.registrations = stats.registrations.saturating_add(1);
+        stats.bonus_secs_granted = stats
+            .bonus_secs_granted
+            .saturating_add(campaign.extra_months as u64 * SECS_PER_MONTH);
+        write_record(env, &key, &stats);
+    }
+
+    if adjusted_expiration != requested_expiration {
+        StatutoryAdjustment {
+            warranty_id,
+            requested_expiration,
+            adjusted_expiration,
+        }
+        .publish(env);
+    }
+
+    if settings::get_analytics_enabled(env.clone()) {
+        RegistrationAnalytics {
+            category: warranty.category.clone(),
+            duration_bucket_months: duration_bucket_months(&warranty),
+            region: warranty.region.clone(),
+        }
+        .publish(env);
+    }
+
+    record_history(
+        env,
+        warranty_id,
+        &warranty.registrant,
+        symbol_short!("register"),
+        HistoryDetails::Registered,
+    );
+    notify_change(env, symbol_short!("register"), warranty_id, correlation_id);
+
+    Ok(warranty_id)
+}
+
+/// Coverage length of `warranty` rounded down to 6, 12, 24 or 36 months
+/// (0 below 6). Warranties awaiting activation count the coverage they
+/// will get once activated, since their expiration is not set yet.
+pub(crate) fn duration_bucket_months(warranty: &WarrantyData) -> u32 {
+    let duration = warranty.activation_duration.unwrap_or(
+        warranty
+            .expiration_date
+            .saturating_sub(warranty.purchase_date),
+    );
+    match duration / SECS_PER_MONTH {
+        0..=5 => 0,
+        6..=11 => 6,
+        12..=23 => 12,
+        24..=35 => 24,
+        _ => 36,
+    }
+}
+
+/// The running campaign of `manufacturer` with the largest bonus that
+/// applies to `category`. Campaigns that have ended are dropped from the
+/// manufacturer's list along the way.
+pub(crate) fn matching_campaign(
+    env: &Env,
+    manufacturer: &String,
+    category: &Option<String>,
+) -> Option<(u64, Campaign)> {
+    let list_key = CampaignKey::Running(manufacturer.clone());
+    let campaign_ids: Vec<u64> = read_record(env, &list_key)?;
+    let now = env.ledger().timestamp();
+
+    let mut running = Vec::new(env);
+    let mut best: Option<(u64, Campaign)> = None;
+    for campaign_id in campaign_ids.iter() {
+        let Some(campaign) = catalog::get_campaign(env.clone(), campaign_id) else {
+            continue;
+        };
+        if campaign.end < now {
+            continue;
+        }
+        running.push_back(campaign_id);
+
+        let applies = campaign.start <= now
+            && (campaign.category_filter.is_none() || campaign.category_filter == *category);
+        if applies
+            && best
+                .as_ref()
+                .is_none_or(|(_, current)| campaign.extra_months > current.extra_months)
+        {
+            best = Some((campaign_id, campaign));
+        }
+    }
+
+    if running.len() != campaign_ids.len() {
+        write_record(env, &list_key, &running);
+    }
+    best
+}
+
+/// Give `owner` the manufacturer's badge on their first registration, if
+/// the manufacturer opted in.
+pub(crate) fn issue_badge(env: &Env, owner: &Address, manufacturer: &String, warranty_id: u64) {
+    if !feature_enabled(env, "customer_badges")
+        || !has_record(env, &BadgeKey::Enabled(manufacturer.clone()))
+    {
+        return;
+    }
+    let key = BadgeKey::Badge(owner.clone(), manufacturer.clone());
+    if has_record(env, &key) {
+        return;
+    }
+
+    write_record(
+        env,
+        &key,
+        &Badge {
+            warranty_id,
+            issued_at: env.ledger().timestamp(),
+        },
+    );
+    BadgeIssued {
+        owner: owner.clone(),
+        manufacturer: manufacturer.clone(),
+        warranty_id,
+    }
+    .publish(env);
+}
+
+/// The newest policy document of `manufacturer` whose effective date has
+/// been reached.
+pub(crate) fn policy_in_force(env: &Env, manufacturer: &String) -> Option<PolicyDoc> {
+    if !feature_enabled(env, "policy_docs") {
+        return None;
+    }
+    let docs: Vec<PolicyDoc> = env
+        .storage()
+        .persistent()
+        .get(&PolicyKey::Docs(manufacturer.clone()))?;
+    let now = env.ledger().timestamp();
+    docs.iter().rev().find(|doc| doc.effective_date <= now)
+}
+
+/// Count a registration towards the retailer's current bucket.
+pub(crate) fn record_retail_sale(env: &Env, retailer: Address, price: i128) {
+    let bucket = env.ledger().timestamp() / SECS_PER_MONTH;
+    let key = RetailerKey::Bucket(retailer, bucket);
+    let mut totals: RetailerBucket = read_record(env, &key).unwrap_or(RetailerBucket {
+        bucket,
+        registrations: 0,
+        price_total: 0,
+    });
+    totals.registrations = totals.registrations.saturating_add(1);
+    totals.price_total = totals.price_total.saturating_add(price);
+    write_record(env, &key, &totals);
+}
+
+/// Minimum coverage the law grants products of `category` sold in `region`,
+/// falling back to the region-wide minimum.
+pub(crate) fn statutory_minimum(
+    env: &Env,
+    region: String,
+    category: Option<String>,
+) -> Option<u64> {
+    if category.is_some() {
+        let minimum = read_record(env, &DataKey::StatutoryMinimum(region.clone(), category));
+        if minimum.is_some() {
+            return minimum;
+        }
+    }
+    read_record(env, &DataKey::StatutoryMinimum(region, None))
+}
+
+/// Hash `(manufacturer, serial_number)` into a warranty ID in the
+/// deterministic half of the ID space.
+pub(crate) fn derive_warranty_id(env: &Env, manufacturer: &String, serial_number: &String) -> u64 {
+    let digest = env
+        .crypto()
+        .sha256(&(manufacturer.clone(), serial_number.clone()).to_xdr(env))
+        .to_array();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(prefix) | DETERMINISTIC_ID_FLAG
+}
+
+/// Load the records of one page of IDs, keeping those in `status` if one is
+/// given. A filtered page can therefore hold fewer records than it had IDs.
+pub(crate) fn page_records(
+    env: &Env,
+    page: WarrantyIdPage,
+    status: Option<WarrantyStatus>,
+) -> WarrantyPage {
+    let mut warranties = Vec::new(env);
+    for warranty_id in page.ids.iter() {
+        let Some(warranty) = load_warranty(env, warranty_id) else {
+            continue;
+        };
+        if status
+            .as_ref()
+            .is_some_and(|status| *status != warranty.status)
+        {
+            continue;
+        }
+        warranties.push_back(public_view(env, warranty));
+    }
+    WarrantyPage {
+        warranties,
+        next: page.next,
+    }
+}
+
+/// What anyone may see of a warranty: everything if it is public, otherwise
+/// only its ID and status. The owner and registrant of a private warranty
+/// read as the contract's own address, so the record names no one.
+pub(crate) fn public_view(env: &Env, warranty: WarrantyData) -> WarrantyData {
+    if !warranty.private {
+        return warranty;
+    }
+    let empty = String::from_str(env, "");
+    WarrantyData {
+        owner: env.current_contract_address(),
+        registrant: env.current_contract_address(),
+        product_name: empty.clone(),
+        serial_number: empty.clone(),
+        manufacturer: empty,
+        purchase_date: 0,
+        expiration_date: 0,
+        category: None,
+        price: None,
+        currency: None,
+        receipt_hash: None,
+        coverage: None,
+        localized_names: Map::new(env),
+        batch_number: None,
+        manufacture_date: None,
+        region: None,
+        usage_limit: None,
+        usage_unit: None,
+        usage_reading: None,
+        campaign_id: None,
+        policy_version: None,
+        verified_manufacturer: None,
+        activation_duration: None,
+        activated_at: None,
+        terms_id: None,
+        attestation: SaleAttestation::Unattested,
+        claims_filed: 0,
+        co_owners: Vec::new(env),
+        model_id: None,
+        ..warranty
+    }
+}
+
+/// Whether `viewer` may read `warranty` in full: its owner, a co-owner, its
+/// registrant or an address holding an unexpired grant.
+pub(crate) fn can_view(env: &Env, viewer: &Address, warranty: &WarrantyData) -> bool {
+    if *viewer == warranty.owner
+        || *viewer == warranty.registrant
+        || warranty.co_owners.contains(viewer)
+    {
+        return true;
+    }
+    let grants: Map<Address, u64> =
+        read_record(env, &DataKey::ViewerGrants(warranty.id)).unwrap_or(Map::new(env));
+    grants
+        .get(viewer.clone())
+        .is_some_and(|expires_at| env.ledger().timestamp() < expires_at)
+}
+
+/// Whether the warranty is stored and private.
+pub(crate) fn is_private(env: &Env, warranty_id: u64) -> bool {
+    load_warranty(env, warranty_id).is_some_and(|warranty| warranty.private)
+}
+
+/// The IDs in `warranty_ids` whose warranties are public or readable in
+/// full by `viewer`, so listings keyed by address do not reveal who holds
+/// a private warranty.
+pub(crate) fn visible_ids(env: &Env, warranty_ids: Vec<u64>, viewer: Option<&Address>) -> Vec<u64> {
+    let mut visible = Vec::new(env);
+    for warranty_id in warranty_ids.iter() {
+        if load_warranty(env, warranty_id).is_some_and(|warranty| {
+            !warranty.private || viewer.is_some_and(|viewer| can_view(env, viewer, &warranty))
+        }) {
+            visible.push_back(warranty_id);
+        }
+    }
+    visible
+}
+
+pub(crate) fn terms_template(env: &Env, terms_id: u64) -> Option<TermsTemplate> {
+    env.storage()
+        .persistent()
+        .get(&TermsKey::Template(terms_id))
+}
+
+/// Terms of the template a warranty was registered under, if any
+pub(crate) fn warranty_terms(env: &Env, warranty: &WarrantyData) -> Option<WarrantyTerms> {
+    warranty
+        .terms_id
+        .and_then(|terms_id| terms_template(env, terms_id))
+        .map(|template| template.terms)
+}
+
+/// Whether the latest usage reading went past the warranty's usage limit.
+pub(crate) fn usage_exceeded(warranty: &WarrantyData) -> bool {
+    match (warranty.usage_limit, warranty.usage_reading) {
+        (Some(limit), Some(reading)) => reading > limit,
+        _ => false,
+    }
+}
+
+/// Status an Active warranty should have according to its expiration date,
+/// usage and link policy. Only lapsing is automatic: an Expired warranty
+/// stays Expired until `extend_warranty` or a status change revives it, and
+/// other statuses are returned unchanged.
+pub(crate) fn effective_status(env: &Env, warranty: &WarrantyData) -> WarrantyStatus {
+    if warranty.status == WarrantyStatus::Active && coverage_ended(env, warranty) {
+        WarrantyStatus::Expired
+    } else {
+        warranty.status.clone()
+    }
+}
+
+/// Whether `warranty` is past its expiration date, over its usage limit or
+/// ended along with its parent.
+pub(crate) fn coverage_ended(env: &Env, warranty: &WarrantyData) -> bool {
+    warranty.expiration_date < env.ledger().timestamp()
+        || usage_exceeded(warranty)
+        || ended_with_parent(env, warranty)
+}
+
+/// Whether the link policy of `warranty` ends its coverage because of the
+/// state of its parent. Parents are looked at as they are now, so nothing
+/// has to be written when a parent changes.
+pub(crate) fn ended_with_parent(env: &Env, warranty: &WarrantyData) -> bool {
+    let Some(link) = read_record::<_, WarrantyLink>(env, &LinkKey::Parent(warranty.id)) else {
+        return false;
+    };
+    let Some(parent) = load_warranty(env, link.parent_id) else {
+        return false;
+    };
+    match link.link_type {
+        LinkType::Related => false,
+        LinkType::ExpiresOnParentRevocation => parent.status == WarrantyStatus::Revoked,
+        LinkType::ExpiresWithParent => !matches!(
+            effective_status(env, &parent),
+            WarrantyStatus::Active | WarrantyStatus::Pending | WarrantyStatus::Suspended
+        ),
+    }
+}
+
+/// Show an Active warranty whose parent ended its coverage as Expired,
+/// without touching storage.
+pub(crate) fn with_link_policy(env: &Env, mut warranty: WarrantyData) -> WarrantyData {
+    if warranty.status == WarrantyStatus::Active && ended_with_parent(env, &warranty) {
+        warranty.status = WarrantyStatus::Expired;
+    }
+    warranty
+}
+
+/// Drop the links of a removed warranty, both to its parent and from its
+/// children.
+pub(crate) fn remove_links(env: &Env, warranty_id: u64) {
+    if let Some(link) = read_record::<_, WarrantyLink>(env, &LinkKey::Parent(warranty_id)) {
+        unlink_child(env, link.parent_id, warranty_id);
+    }
+    let children: Vec<u64> =
+        read_record(env, &LinkKey::Children(warranty_id)).unwrap_or(Vec::new(env));
+    for child_id in children.iter() {
+        remove_record(env, &LinkKey::Parent(child_id));
+    }
+    remove_record(env, &LinkKey::Children(warranty_id));
+}
+
+/// Drop the part records of a removed warranty, both its own entry in its
+/// parent's list and the links back from its parts.
+fn remove_parts(env: &Env, warranty_id: u64) {
+    if let Some(part) = read_record::<_, PartRecord>(env, &PartKey::Part(warranty_id)) {
+        let parts_key = PartKey::Parts(part.parent_id);
+        let parts: Vec<u64> = read_record(env, &parts_key).unwrap_or(Vec::new(env));
+        write_record(env, &parts_key, &without_id(env, &parts, warranty_id));
+        remove_record(env, &PartKey::Part(warranty_id));
+    }
+    let parts: Vec<u64> = read_record(env, &PartKey::Parts(warranty_id)).unwrap_or(Vec::new(env));
+    for part_id in parts.iter() {
+        remove_record(env, &PartKey::Part(part_id));
+    }
+    remove_record(env, &PartKey::Parts(warranty_id));
+}
+
+/// Remove the link from `child_id` to `parent_id`.
+pub(crate) fn unlink_child(env: &Env, parent_id: u64, child_id: u64) {
+    let children_key = LinkKey::Children(parent_id);
+    let children: Vec<u64> = read_record(env, &children_key).unwrap_or(Vec::new(env));
+    write_record(env, &children_key, &without_id(env, &children, child_id));
+    remove_record(env, &LinkKey::Parent(child_id));
+}
+
+/// Replace a stale stored status with the effective one, saving and
+/// announcing the change.
+pub(crate) fn refresh_status(env: &Env, warranty: &mut WarrantyData) {
+    let status = effective_status(env, warranty);
+    if status == warranty.status {
+        return;
+    }
+    let previous = core::mem::replace(&mut warranty.status, status);
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(env, warranty);
+    record_status_change(
+        env,
+        warranty,
+        &env.current_contract_address(),
+        symbol_short!("status"),
+        previous.clone(),
+    );
+    publish_status_change(env, warranty, previous);
+}
+
+/// Store a usage reading, expiring an active warranty once it passes the
+/// usage limit.
+pub(crate) fn record_usage(
+    env: &Env,
+    mut warranty: WarrantyData,
+    reading: u64,
+) -> Result<(), WarrantyError> {
+    if warranty.usage_limit.is_none() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if warranty.usage_reading.is_some_and(|last| reading < last) {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let previous = warranty.status.clone();
+    warranty.usage_reading = Some(reading);
+    if usage_exceeded(&warranty) && warranty.status == WarrantyStatus::Active {
+        warranty.status = WarrantyStatus::Expired;
+    }
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(env, &warranty);
+    record_status_change(
+        env,
+        &warranty,
+        &env.current_contract_address(),
+        symbol_short!("usage"),
+        previous.clone(),
+    );
+    publish_status_change(env, &warranty, previous);
+    Ok(())
+}
+
+/// Verify a message signed by the warranty's bound device and consume its
+/// nonce, so the message cannot be replayed.
+pub(crate) fn verify_device_message(
+    env: &Env,
+    warranty_id: u64,
+    nonce: u64,
+    message: &Bytes,
+    signature: &BytesN<64>,
+) -> Result<(), WarrantyError> {
+    let public_key =
+        devices::get_device_key(env.clone(), warranty_id).ok_or(WarrantyError::NotFound)?;
+
+    let last_nonce: Option<u64> = read_record(env, &DataKey::DeviceNonce(warranty_id));
+    if last_nonce.is_some_and(|last| nonce <= last) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    env.crypto().ed25519_verify(&public_key, message, signature);
+
+    write_record(env, &DataKey::DeviceNonce(warranty_id), &nonce);
+    Ok(())
+}
+
+/// Close a pending counterfeit report and update the reporter's reputation.
+pub(crate) fn resolve_report(
+    env: &Env,
+    report_id: u64,
+    outcome: ReportStatus,
+) -> Result<CounterfeitReport, WarrantyError> {
+    let mut report = load_report(env, report_id).ok_or(WarrantyError::NotFound)?;
+    if report.status != ReportStatus::Pending {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    let reputation_key = DataKey::ReporterReputation(report.reporter.clone());
+    let mut reputation: ReporterReputation = read_record(env, &reputation_key).unwrap_or_default();
+    if outcome == ReportStatus::Confirmed {
+        reputation.confirmed += 1;
+    } else {
+        reputation.dismissed += 1;
+    }
+    write_record(env, &reputation_key, &reputation);
+
+    report.status = outcome;
+    save_report(env, &report);
+    count_pending_report(env, false);
+
+    Ok(report)
+}
+
+/// Record that `address` just performed an authenticated action.
+pub(crate) fn record_activity(env: &Env, address: &Address) {
+    write_record(
+        env,
+        &DataKey::LastActivity(address.clone()),
+        &env.ledger().timestamp(),
+    );
+}
+
+/// Remove a warranty record, every index entry pointing at it and the state
+/// kept for it, leaving a tombstone behind. Rent still held for the record
+/// goes back to its registrant; its history, device log and compliance trail
+/// stay readable like the tombstone.
+pub(crate) fn remove_warranty(
+    env: &Env,
+    warranty: &WarrantyData,
+    removed_by: &Address,
+    merged_into: Option<u64>,
+) {
+    let warranty_id = warranty.id;
+
+    delete_warranty_record(env, warranty_id);
+    move_status_count(
+        env,
+        &warranty.manufacturer,
+        Some(warranty.status.clone()),
+        None,
+    );
+    publish_burn(env, warranty);
+    keepers::refund_rent(env, warranty);
+
+    id_index_remove(env, warranty_id);
+    forget_serial(env, warranty);
+    index_remove(
+        env,
+        &DataKey::OwnerWarranties(warranty.owner.clone()),
+        warranty_id,
+    );
+    index_remove(
+        env,
+        &DataKey::ArchivedOwnerWarranties(warranty.owner.clone()),
+        warranty_id,
+    );
+    for co_owner in warranty.co_owners.iter() {
+        index_remove(env, &DataKey::CoOwnedWarranties(co_owner), warranty_id);
+    }
+    if let Some(model_id) = warranty.model_id.clone() {
+        index_remove(env, &DataKey::ModelWarranties(model_id), warranty_id);
+    }
+    if let Some(batch_number) = warranty.batch_number.clone() {
+        index_remove(
+            env,
+            &DataKey::BatchWarranties(warranty.manufacturer.clone(), batch_number),
+            warranty_id,
+        );
+    }
+
+    remove_record(env, &DataKey::Beneficiary(warranty_id));
+    remove_record(env, &DataKey::ViewerGrants(warranty_id));
+    remove_record(env, &DataKey::AttachedPolicy(warranty_id));
+    remove_record(env, &DataKey::PendingHandover(warranty_id));
+    remove_pending_transfer(env, warranty_id);
+    remove_record(env, &AgentKey::Approved(warranty_id));
+    remove_record(env, &AmendKey::Locked(warranty_id));
+    env.storage()
+        .persistent()
+        .remove(&MetadataKey::Entries(warranty_id));
+    remove_record(env, &DataKey::CoverageTerms(warranty_id));
+    env.storage()
+        .persistent()
+        .remove(&UnderwritingKey::Record(warranty_id));
+    remove_links(env, warranty_id);
+    remove_parts(env, warranty_id);
+    remove_record(env, &ClaimKey::Claims(warranty_id));
+    remove_record(env, &ClaimKey::PaidOut(warranty_id));
+    remove_record(env, &ClaimKey::TransferredAt(warranty_id));
+    remove_record(env, &DataKey::TransferHistory(warranty_id));
+    remove_record(env, &DataKey::Handovers(warranty_id));
+    remove_record(env, &PauseKey::Frozen(warranty_id));
+    remove_record(env, &ReversionKey::Scheduled(warranty_id));
+    remove_record(env, &RevocationKey::Scheduled(warranty_id));
+    remove_record(env, &DataKey::DeviceKey(warranty_id));
+    remove_record(env, &DataKey::DeviceNonce(warranty_id));
+    if let Some(local_id) = read_record::<_, u64>(env, &NamespaceKey::LocalId(warranty_id)) {
+        remove_record(
+            env,
+            &NamespaceKey::Warranty(warranty.manufacturer.clone(), local_id),
+        );
+        remove_record(env, &NamespaceKey::LocalId(warranty_id));
+    }
+
+    let tombstone = Tombstone {
+        warranty_id,
+        removed_by: removed_by.clone(),
+        removed_at: env.ledger().timestamp(),
+        removed_ledger: env.ledger().sequence(),
+        merged_into,
+    };
+    write_record(env, &DataKey::Tombstone(warranty_id), &tombstone);
+}
+
+/// Reject a transfer of `warranty` to `new_owner` that its status, terms or
+/// the owner's allowlist forbid.
+pub(crate) fn check_transfer(
+    env: &Env,
+    warranty: &WarrantyData,
+    new_owner: &Address,
+) -> Result<(), WarrantyError> {
+    require_not_frozen(env, warranty.id)?;
+    if warranty.status != WarrantyStatus::Active {
+        return Err(WarrantyError::NotActive);
+    }
+    if !warranty.transferable {
+        return Err(WarrantyError::TransferNotAllowed);
+    }
+
+    let allowlist: Vec<Address> =
+        read_record(env, &DataKey::TransferAllowlist(warranty.owner.clone()))
+            .unwrap_or(Vec::new(env));
+    if !allowlist.is_empty() && !allowlist.contains(new_owner) {
+        return Err(WarrantyError::TransferNotAllowed);
+    }
+    Ok(())
+}
+
+/// Hand `warranty` to `new_owner` on behalf of `actor`, updating the owner
+/// indexes, recording the transfer and dropping arrangements made by the
+/// previous owner.
+pub(crate) fn change_owner(
+    env: &Env,
+    actor: &Address,
+    mut warranty: WarrantyData,
+    new_owner: &Address,
+    condition: Option<ConditionGrade>,
+) -> Result<(), WarrantyError> {
+    let warranty_id = warranty.id;
+    require_not_frozen(env, warranty_id)?;
+    let old_owner = warranty.owner.clone();
+    check_compliance(env, &old_owner, new_owner)?;
+
+    let history_key = DataKey::TransferHistory(warranty_id);
+    let mut history: Vec<TransferRecord> = read_record(env, &history_key).unwrap_or(Vec::new(env));
+    history.push_back(TransferRecord {
+        from: old_owner.clone(),
+        to: new_owner.clone(),
+        condition: condition.unwrap_or(ConditionGrade::Unreported),
+        transferred_at: env.ledger().timestamp(),
+    });
+    write_record(env, &history_key, &history);
+    write_record(
+        env,
+        &ClaimKey::TransferredAt(warranty_id),
+        &env.ledger().timestamp(),
+    );
+
+    warranty.owner = new_owner.clone();
+    warranty.last_modified_ledger = env.ledger().sequence();
+    clear_co_owners(env, &mut warranty);
+
+    save_warranty(env, &warranty);
+    record_history(
+        env,
+        warranty_id,
+        actor,
+        symbol_short!("transfer"),
+        HistoryDetails::Transferred(old_owner.clone(), new_owner.clone()),
+    );
+    publish_transferred(env, warranty_id, &old_owner, new_owner);
+    publish_nft_transfer(env, &old_owner, new_owner, warranty_id);
+
+    move_owner_index(env, warranty_id, &old_owner, new_owner);
+    remove_record(env, &DataKey::Beneficiary(warranty_id));
+    remove_record(env, &DataKey::ViewerGrants(warranty_id));
+    remove_record(env, &DataKey::PendingHandover(warranty_id));
+    remove_pending_transfer(env, warranty_id);
+    remove_record(env, &AgentKey::Approved(warranty_id));
+    lock_amendments(env, warranty_id);
+    Ok(())
+}
+
+/// Drop every co-owner of `warranty`, e.g. when it changes hands.
+pub(crate) fn clear_co_owners(env: &Env, warranty: &mut WarrantyData) {
+    for co_owner in warranty.co_owners.iter() {
+        index_remove(env, &DataKey::CoOwnedWarranties(co_owner), warranty.id);
+    }
+    warranty.co_owners = Vec::new(env);
+}
+
+/// Flip Active warranties in `from_id..=to_id` whose effective status is
+/// Expired, at most `limit` of them.
+pub(crate) fn expire_range(
+    env: &Env,
+    from_id: u64,
+    to_id: u64,
+    limit: u32,
+) -> Result<u32, WarrantyError> {
+    check_batch_range(env, from_id, to_id)?;
+    check_page_size(env, limit)?;
+
+    Ok(expire_ids(env, from_id..=to_id, limit))
+}
+
+/// Flip Active warranties among `ids` whose effective status is Expired,
+/// at most `limit` of them.
+pub(crate) fn expire_ids(env: &Env, ids: impl Iterator<Item = u64>, limit: u32) -> u32 {
+    let mut expired = Vec::new(env);
+    for warranty_id in ids {
+        if expired.len() == limit {
+            break;
+        }
+        let Some(mut warranty) = load_warranty(env, warranty_id) else {
+            continue;
+        };
+        if warranty.status != WarrantyStatus::Active
+            || effective_status(env, &warranty) != WarrantyStatus::Expired
+        {
+            continue;
+        }
+        warranty.status = WarrantyStatus::Expired;
+        warranty.last_modified_ledger = env.ledger().sequence();
+        save_warranty(env, &warranty);
+        expired.push_back(warranty);
+    }
+
+    for warranty in expired.iter() {
+        publish_expired(env, &warranty);
+    }
+    expired.len()
+}
+
+/// Read the per-status counters into a `WarrantyStats`.
+pub(crate) fn warranty_stats(env: &Env) -> WarrantyStats {
+    let mut stats = WarrantyStats {
+        total: 0,
+        active: 0,
+        expired: 0,
+        revoked: 0,
+        by_status: Map::new(env),
+    };
+    for status in [
+        WarrantyStatus::Active,
+        WarrantyStatus::Expired,
+        WarrantyStatus::Revoked,
+        WarrantyStatus::Pending,
+        WarrantyStatus::Suspended,
+        WarrantyStatus::Voided,
+        WarrantyStatus::Cancelled,
+        WarrantyStatus::Replaced,
+        WarrantyStatus::Recalled,
+    ] {
+        let count: u64 = read_record(env, &StatsKey::Status(status.clone())).unwrap_or(0);
+        if count == 0 {
+            continue;
+        }
+        match status {
+            WarrantyStatus::Active => stats.active = count,
+            WarrantyStatus::Expired => stats.expired = count,
+            WarrantyStatus::Revoked => stats.revoked = count,
+            _ => {}
+        }
+        stats.total += count;
+        stats.by_status.set(status, count);
+    }
+    stats
+}
+
+/// Announce that a sweep moved `warranty` from Active to Expired.
+pub(crate) fn publish_expired(env: &Env, warranty: &WarrantyData) {
+    WarrantyExpired {
+        warranty_id: warranty.id,
+        expiration_date: warranty.expiration_date,
+    }
+    .publish(env);
+    record_status_change(
+        env,
+        warranty,
+        &env.current_contract_address(),
+        symbol_short!("status"),
+        WarrantyStatus::Active,
+    );
+    publish_status_change(env, warranty, WarrantyStatus::Active);
+    notify_change(env, symbol_short!("status"), warranty.id, None);
+}
+
+pub(crate) fn register_warranty(
+    env: Env,
+    owner: Address,
+    input: WarrantyInput,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+
+    create_warranty(&env, None, owner, input, None)
+}
+
+pub(crate) fn register_warranty_deterministic(
+    env: Env,
+    owner: Address,
+    input: WarrantyInput,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty_id = derive_warranty_id(&env, &input.manufacturer, &input.serial_number);
+
+    // Also covers the unlikely hash collision with another product
+    if load_warranty(&env, warranty_id).is_some() {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    create_warranty(&env, Some(warranty_id), owner, input, None)
+}
+
+pub(crate) fn get_deterministic_id(env: Env, manufacturer: String, serial_number: String) -> u64 {
+    derive_warranty_id(&env, &manufacturer, &serial_number)
+}
+
+pub(crate) fn register_part_warranty(
+    env: Env,
+    parent_id: u64,
+    technician: Address,
+    part: PartInfo,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "part_warranties")?;
+
+    technician.require_auth();
+
+    let parent = require_warranty(&env, parent_id)?;
+    if !can_service(&env, &technician, &parent) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if parent.status != WarrantyStatus::Active {
+        return Err(WarrantyError::NotActive);
+    }
+    if part.coverage_secs == 0 {
+        return Err(WarrantyError::InvalidInput);
+    }
+    let parts_key = PartKey::Parts(parent_id);
+    let mut parts: Vec<u64> = read_record(&env, &parts_key).unwrap_or(Vec::new(&env));
+    if parts.len() >= MAX_PART_WARRANTIES {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    let now = env.ledger().timestamp();
+    let warranty_id = create_warranty(
+        &env,
+        None,
+        parent.owner,
+        WarrantyInput::new(
+            part.part_number.clone(),
+            part.serial_number,
+            parent.manufacturer,
+            now,
+            now.saturating_add(part.coverage_secs),
+        ),
+        None,
+    )?;
+
+    parts.push_back(warranty_id);
+    write_record(&env, &parts_key, &parts);
+    write_record(
+        &env,
+        &PartKey::Part(warranty_id),
+        &PartRecord {
+            parent_id,
+            part_number: part.part_number,
+            covered: part.covered,
+        },
+    );
+
+    Ok(warranty_id)
+}
+
+pub(crate) fn get_part_warranties(env: Env, parent_id: u64) -> Vec<u64> {
+    read_record(&env, &PartKey::Parts(parent_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn get_part_record(env: Env, warranty_id: u64) -> Option<PartRecord> {
+    read_record(&env, &PartKey::Part(warranty_id))
+}
+
+pub(crate) fn link_warranties(
+    env: Env,
+    parent_id: u64,
+    child_id: u64,
+    link_type: LinkType,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "warranty_links")?;
+
+    let since = audit_tail(&env);
+
+    let parent = require_warranty(&env, parent_id)?;
+    let child = require_warranty(&env, child_id)?;
+
+    child.owner.require_auth();
+    record_activity(&env, &child.owner);
+
+    if parent.owner != child.owner {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if has_record(&env, &LinkKey::Parent(child_id)) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    // Walk up from the parent so a link can never close a cycle
+    let mut ancestor_id = parent_id;
+    loop {
+        if ancestor_id == child_id {
+            return Err(WarrantyError::InvalidInput);
+        }
+        match get_warranty_link(env.clone(), ancestor_id) {
+            Some(link) => ancestor_id = link.parent_id,
+            None => break,
+        }
+    }
+
+    let children_key = LinkKey::Children(parent_id);
+    let mut children: Vec<u64> = read_record(&env, &children_key).unwrap_or(Vec::new(&env));
+    if children.len() >= MAX_LINKED_WARRANTIES {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    children.push_back(child_id);
+    write_record(&env, &children_key, &children);
+    write_record(
+        &env,
+        &LinkKey::Parent(child_id),
+        &WarrantyLink {
+            parent_id,
+            link_type,
+            linked_at: env.ledger().timestamp(),
+        },
+    );
+
+    notify_change(&env, symbol_short!("link"), child_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn unlink_warranty(env: Env, child_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let child = require_warranty(&env, child_id)?;
+
+    child.owner.require_auth();
+    record_activity(&env, &child.owner);
+
+    let link = get_warranty_link(env.clone(), child_id).ok_or(WarrantyError::NotFound)?;
+    unlink_child(&env, link.parent_id, child_id);
+    Ok(())
+}
+
+pub(crate) fn get_warranty_link(env: Env, child_id: u64) -> Option<WarrantyLink> {
+    read_record(&env, &LinkKey::Parent(child_id))
+}
+
+pub(crate) fn get_linked_warranties(env: Env, parent_id: u64) -> Vec<u64> {
+    read_record(&env, &LinkKey::Children(parent_id)).unwrap_or(Vec::new(&env))
+}
+
+pub(crate) fn recall_products(
+    env: Env,
+    manufacturer: Address,
+    serial_numbers: Vec<String>,
+) -> Result<u32, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "batch_recalls")?;
+    let registered = require_manufacturer(&env, &manufacturer)?;
+    if serial_numbers.len() > read_limits(&env).max_page_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    let mut recalled = 0;
+    for serial_number in serial_numbers.iter() {
+        let Some(warranty) = env
+            .storage()
+            .persistent()
+            .get::<_, u64>(&DataKey::SerialIndex(
+                registered.name.clone(),
+                serial_number.clone(),
+            ))
+            .and_then(|warranty_id| load_warranty(&env, warranty_id))
+            .filter(|warranty| {
+                warranty.manufacturer == registered.name && warranty.serial_number == serial_number
+            })
+        else {
+            continue;
+        };
+        if !matches!(
+            warranty.status,
+            WarrantyStatus::Active
+                | WarrantyStatus::Expired
+                | WarrantyStatus::Pending
+                | WarrantyStatus::Suspended
+        ) {
+            continue;
+        }
+
+        let warranty_id = warranty.id;
+        pull_warranty(
+            &env,
+            warranty,
+            RecallInfo {
+                manufacturer: manufacturer.clone(),
+                status: WarrantyStatus::Recalled,
+                reason: None,
+                recorded_at: env.ledger().timestamp(),
+            },
+        );
+        notify_change(&env, symbol_short!("recall"), warranty_id, None);
+        recalled += 1;
+    }
+    Ok(recalled)
+}
+
+pub(crate) fn void_warranty(
+    env: Env,
+    warranty_id: u64,
+    reason: String,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let Some(issuer) = warranty.verified_manufacturer.as_ref() else {
+        return Err(WarrantyError::Unauthorized);
+    };
+    let manufacturer = current_manufacturer(&env, issuer);
+    manufacturer.require_auth();
+
+    if reason.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if matches!(
+        warranty.status,
+        WarrantyStatus::Voided | WarrantyStatus::Cancelled | WarrantyStatus::Replaced
+    ) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+
+    pull_warranty(
+        &env,
+        warranty,
+        RecallInfo {
+            manufacturer,
+            status: WarrantyStatus::Voided,
+            reason: Some(reason),
+            recorded_at: env.ledger().timestamp(),
+        },
+    );
+    notify_change(&env, symbol_short!("void"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn get_recall_info(env: Env, warranty_id: u64) -> Option<RecallInfo> {
+    env.storage()
+        .persistent()
+        .get(&ManufacturerKey::Recall(warranty_id))
+}
+
+pub(crate) fn schedule_revocation(
+    env: Env,
+    warranty_id: u64,
+    revoke_at: u64,
+    reason: String,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let Some(issuer) = warranty.verified_manufacturer.as_ref() else {
+        return Err(WarrantyError::Unauthorized);
+    };
+    let manufacturer = current_manufacturer(&env, issuer);
+    manufacturer.require_auth();
+
+    if reason.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+    if revoke_at <= env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+    if warranty.status == WarrantyStatus::Revoked {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    check_status_transition(
+        &warranty.status,
+        &WarrantyStatus::Revoked,
+        StatusAuthority::Manufacturer,
+    )?;
+    let key = RevocationKey::Scheduled(warranty_id);
+    if has_record(&env, &key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    write_record(
+        &env,
+        &key,
+        &ScheduledRevocation {
+            revoke_at,
+            reason: reason.clone(),
+            scheduled_by: manufacturer,
+        },
+    );
+    RevocationScheduled {
+        warranty_id,
+        revoke_at,
+        reason,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+pub(crate) fn cancel_revocation(env: Env, warranty_id: u64) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let key = RevocationKey::Scheduled(warranty_id);
+    let scheduled: ScheduledRevocation = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    current_manufacturer(&env, &scheduled.scheduled_by).require_auth();
+
+    remove_record(&env, &key);
+    RevocationCancelled { warranty_id }.publish(&env);
+    Ok(())
+}
+
+pub(crate) fn get_scheduled_revocation(env: Env, warranty_id: u64) -> Option<ScheduledRevocation> {
+    read_record(&env, &RevocationKey::Scheduled(warranty_id))
+}
+
+pub(crate) fn execute_revocation(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    let key = RevocationKey::Scheduled(warranty_id);
+    let scheduled: ScheduledRevocation = read_record(&env, &key).ok_or(WarrantyError::NotFound)?;
+    if env.ledger().timestamp() < scheduled.revoke_at {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    remove_record(&env, &key);
+    if warranty.status != WarrantyStatus::Revoked {
+        apply_status(
+            &env,
+            &scheduled.scheduled_by,
+            warranty,
+            WarrantyStatus::Revoked,
+            StatusAuthority::Manufacturer,
+        )?;
+        notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+    }
+    RevocationExecuted {
+        warranty_id,
+        reason: scheduled.reason,
+    }
+    .publish(&env);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn manufacturer_issue_warranty(
+    env: Env,
+    manufacturer: Address,
+    owner: Address,
+    mut input: WarrantyInput,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "manufacturer_registry")?;
+
+    input.manufacturer = require_manufacturer(&env, &manufacturer)?.name;
+    create_warranty(&env, None, owner, input, Some(manufacturer))
+}
+
+pub(crate) fn delegate_issue_warranty(
+    env: Env,
+    delegate: Address,
+    manufacturer: Address,
+    owner: Address,
+    mut input: WarrantyInput,
+) -> Result<u64, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "manufacturer_registry")?;
+
+    let (registered, delegation) = require_delegate(&env, &delegate, &manufacturer)?;
+    if input
+        .region
+        .as_ref()
+        .is_some_and(|region| *region != delegation.region)
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+    input.manufacturer = registered.name;
+    input.region = Some(delegation.region);
+
+    let warranty_id = create_warranty(&env, None, owner, input, Some(manufacturer))?;
+    write_record(&env, &DelegateKey::IssuedBy(warranty_id), &delegate);
+    Ok(warranty_id)
+}
+
+pub(crate) fn delegate_revoke_warranty(
+    env: Env,
+    delegate: Address,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    let Some(issuer) = warranty.verified_manufacturer.as_ref() else {
+        return Err(WarrantyError::Unauthorized);
+    };
+    let manufacturer = current_manufacturer(&env, issuer);
+    let (_, delegation) = require_delegate(&env, &delegate, &manufacturer)?;
+    if warranty.region != Some(delegation.region) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    apply_status(
+        &env,
+        &delegate,
+        warranty,
+        WarrantyStatus::Revoked,
+        StatusAuthority::Manufacturer,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn assign_local_id(
+    env: Env,
+    issuer: Address,
+    warranty_id: u64,
+    local_id: u64,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "namespaced_ids")?;
+
+    issuer.require_auth();
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    if roles::get_namespace_issuer(env.clone(), warranty.manufacturer.clone()) != Some(issuer) {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let local_key = NamespaceKey::LocalId(warranty_id);
+    if has_record(&env, &local_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    let ns_key = NamespaceKey::Warranty(warranty.manufacturer, local_id);
+    if has_record(&env, &ns_key) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+
+    write_record(&env, &ns_key, &warranty_id);
+    write_record(&env, &local_key, &local_id);
+    Ok(())
+}
+
+pub(crate) fn get_local_id(env: Env, warranty_id: u64) -> Option<u64> {
+    read_record(&env, &NamespaceKey::LocalId(warranty_id))
+}
+
+pub(crate) fn extend_warranty(
+    env: Env,
+    warranty_id: u64,
+    manufacturer: Address,
+    expiration_date: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "coverage_extensions")?;
+
+    let since = audit_tail(&env);
+
+    manufacturer.require_auth();
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+    if !is_warranty_manufacturer(&env, &manufacturer, &warranty) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    require_not_frozen(&env, warranty_id)?;
+    if !matches!(
+        warranty.status,
+        WarrantyStatus::Active | WarrantyStatus::Expired
+    ) {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    if expiration_date <= warranty.expiration_date || expiration_date <= env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+
+    let previous_expiration = warranty.expiration_date;
+    let previous = warranty.status.clone();
+    warranty.expiration_date = expiration_date;
+    warranty.status = if coverage_ended(&env, &warranty) {
+        WarrantyStatus::Expired
+    } else {
+        WarrantyStatus::Active
+    };
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    record_history(
+        &env,
+        warranty_id,
+        &manufacturer,
+        symbol_short!("extend"),
+        HistoryDetails::ExpirationChanged(previous_expiration, expiration_date),
+    );
+    record_status_change(
+        &env,
+        &warranty,
+        &manufacturer,
+        symbol_short!("extend"),
+        previous.clone(),
+    );
+    publish_status_change(&env, &warranty, previous);
+    if warranty.status == WarrantyStatus::Active {
+        unarchive_warranty(&env, &warranty.owner, warranty_id);
+    }
+
+    notify_change(&env, symbol_short!("extend"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_visibility(
+    env: Env,
+    warranty_id: u64,
+    private: bool,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    warranty.private = private;
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+
+    notify_change(&env, symbol_short!("visible"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn grant_viewer(
+    env: Env,
+    warranty_id: u64,
+    viewer: Address,
+    expires_at: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if expires_at <= env.ledger().timestamp() {
+        return Err(WarrantyError::InvalidDates);
+    }
+
+    let key = DataKey::ViewerGrants(warranty_id);
+    let mut grants: Map<Address, u64> = read_record(&env, &key).unwrap_or(Map::new(&env));
+    grants.set(viewer, expires_at);
+    write_record(&env, &key, &grants);
+
+    notify_change(&env, symbol_short!("viewer"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn revoke_viewer(
+    env: Env,
+    warranty_id: u64,
+    viewer: Address,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    let key = DataKey::ViewerGrants(warranty_id);
+    if let Some(mut grants) = read_record::<_, Map<Address, u64>>(&env, &key) {
+        grants.remove(viewer);
+        write_record(&env, &key, &grants);
+    }
+    Ok(())
+}
+
+pub(crate) fn add_co_owner(
+    env: Env,
+    warranty_id: u64,
+    co_owner: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "co_ownership")?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if co_owner == warranty.owner || warranty.co_owners.contains(&co_owner) {
+        return Err(WarrantyError::AlreadyExists);
+    }
+    if warranty.co_owners.len() >= MAX_CO_OWNERS {
+        return Err(WarrantyError::LimitExceeded);
+    }
+
+    warranty.co_owners.push_back(co_owner.clone());
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    index_add(&env, &DataKey::CoOwnedWarranties(co_owner), warranty_id);
+
+    notify_change(&env, symbol_short!("coowner"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn remove_co_owner(
+    env: Env,
+    warranty_id: u64,
+    co_owner: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    let Some(index) = warranty.co_owners.first_index_of(&co_owner) else {
+        return Err(WarrantyError::NotFound);
+    };
+
+    warranty.co_owners.remove(index);
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    index_remove(&env, &DataKey::CoOwnedWarranties(co_owner), warranty_id);
+
+    notify_change(&env, symbol_short!("coowner"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_localized_name(
+    env: Env,
+    warranty_id: u64,
+    lang: Symbol,
+    name: String,
+) -> Result<(), WarrantyError> {
+    require_not_paused(&env)?;
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.registrant.require_auth();
+    record_activity(&env, &warranty.registrant);
+
+    if name.is_empty() {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    warranty.localized_names.set(lang, name);
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    Ok(())
+}
+
+pub(crate) fn delete_warranty(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.registrant.require_auth();
+    record_activity(&env, &warranty.registrant);
+
+    let cooling_off_secs = settings::get_cooling_off_secs(env.clone());
+    if cooling_off_secs == 0
+        || env.ledger().timestamp() > warranty.created_at.saturating_add(cooling_off_secs)
+    {
+        return Err(WarrantyError::OutsideWindow);
+    }
+
+    remove_warranty(&env, &warranty, &warranty.registrant, None);
+
+    notify_change(&env, symbol_short!("delete"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+/// Whether two serial numbers are the same or one typo apart: a single
+/// character added, dropped or changed, or two neighbours swapped.
+fn within_one_typo(a: &String, b: &String) -> bool {
+    let (a, b) = (a.to_bytes(), b.to_bytes());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    // Past the common prefix and suffix, only the typo is left
+    let mut prefix = 0;
+    while prefix < short.len() && short.get_unchecked(prefix) == long.get_unchecked(prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < short.len() - prefix
+        && short.get_unchecked(short.len() - 1 - suffix)
+            == long.get_unchecked(long.len() - 1 - suffix)
+    {
+        suffix += 1;
+    }
+    match (short.len() - prefix - suffix, long.len() - prefix - suffix) {
+        (0, 0) | (0, 1) | (1, 1) => true,
+        (2, 2) => {
+            short.get_unchecked(prefix) == long.get_unchecked(prefix + 1)
+                && short.get_unchecked(prefix + 1) == long.get_unchecked(prefix)
+        }
+        _ => false,
+    }
+}
+
+/// Claims on a warranty, failing with `InvalidStatusTransition` if any is
+/// still open: drafted, filed, approved or disputed.
+fn closed_claims(env: &Env, warranty_id: u64) -> Result<Vec<ClaimData>, WarrantyError> {
+    let claims = claims::get_claims(env.clone(), warranty_id)?;
+    for claim in claims.iter() {
+        if !matches!(
+            claim.status,
+            ClaimStatus::Rejected | ClaimStatus::Resolved | ClaimStatus::ChargedBack
+        ) {
+            return Err(WarrantyError::InvalidStatusTransition);
+        }
+    }
+    Ok(claims)
+}
+
+pub(crate) fn merge_warranties(
+    env: Env,
+    keep_id: u64,
+    remove_id: u64,
+    issuer: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "merges")?;
+
+    let since = audit_tail(&env);
+
+    if keep_id == remove_id {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let mut kept = require_warranty(&env, keep_id)?;
+    let removed = require_warranty(&env, remove_id)?;
+
+    kept.owner.require_auth();
+    record_activity(&env, &kept.owner);
+
+    // The issuer confirms both records are the same unit
+    issuer.require_auth();
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    if admin.as_ref() != Some(&issuer)
+        && roles::get_namespace_issuer(env.clone(), kept.manufacturer.clone()).as_ref()
+            != Some(&issuer)
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    if removed.owner != kept.owner
+        || removed.manufacturer != kept.manufacturer
+        || !within_one_typo(&removed.serial_number, &kept.serial_number)
+    {
+        return Err(WarrantyError::InvalidInput);
+    }
+    require_not_frozen(&env, keep_id)?;
+    require_not_frozen(&env, remove_id)?;
+    closed_claims(&env, keep_id)?;
+    let moved_claims = closed_claims(&env, remove_id)?;
+
+    // Settled claims follow the unit, and count against its claim and
+    // payout caps
+    let claims_key = ClaimKey::Claims(keep_id);
+    let mut claim_ids: Vec<u64> = read_record(&env, &claims_key).unwrap_or(Vec::new(&env));
+    if claim_ids.len() + moved_claims.len() > read_limits(&env).max_page_size {
+        return Err(WarrantyError::LimitExceeded);
+    }
+    for mut claim in moved_claims.iter() {
+        claim.warranty_id = keep_id;
+        save_claim(&env, &claim);
+        claim_ids.push_back(claim.claim_id);
+    }
+    if !moved_claims.is_empty() {
+        write_record(&env, &claims_key, &claim_ids);
+        let paid = claims::paid_out(&env, keep_id) + claims::paid_out(&env, remove_id);
+        write_record(&env, &ClaimKey::PaidOut(keep_id), &paid);
+    }
+    kept.claims_filed += removed.claims_filed;
+
+    if !has_record(&env, &DataKey::AttachedPolicy(keep_id)) {
+        if let Some(policy) =
+            read_record::<_, AttachedPolicy>(&env, &DataKey::AttachedPolicy(remove_id))
+        {
+            write_record(&env, &DataKey::AttachedPolicy(keep_id), &policy);
+        }
+    }
+    if !has_record(&env, &DataKey::Beneficiary(keep_id)) {
+        if let Some(beneficiary) =
+            read_record::<_, Beneficiary>(&env, &DataKey::Beneficiary(remove_id))
+        {
+            write_record(&env, &DataKey::Beneficiary(keep_id), &beneficiary);
+        }
+    }
+
+    kept.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &kept);
+
+    remove_warranty(&env, &removed, &kept.owner, Some(keep_id));
+
+    notify_change(&env, symbol_short!("merge"), remove_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn amend_warranty(
+    env: Env,
+    warranty_id: u64,
+    amendment: WarrantyAmendment,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.registrant.require_auth();
+    record_activity(&env, &warranty.registrant);
+
+    let current_time = env.ledger().timestamp();
+    let edit_window_secs = settings::get_edit_window_secs(env.clone());
+    if current_time > warranty.created_at.saturating_add(edit_window_secs) {
+        return Err(WarrantyError::OutsideWindow);
+    }
+    if warranty.owner != warranty.registrant
+        || warranty.claims_filed > 0
+        || has_record(&env, &AmendKey::Locked(warranty_id))
+    {
+        return Err(WarrantyError::Unauthorized);
+    }
+
+    let original = warranty.clone();
+    if let Some(product_name) = amendment.product_name {
+        warranty.product_name = product_name;
+    }
+    if let Some(serial_number) = amendment.serial_number {
+        validate_category(&env, &warranty.category, &serial_number)?;
+        warranty.serial_number = serial_number;
+    }
+    if let Some(purchase_date) = amendment.purchase_date {
+        warranty.purchase_date = purchase_date;
+    }
+    let identity_changed = warranty.product_name != original.product_name
+        || warranty.serial_number != original.serial_number;
+    let previous_expiration = warranty.expiration_date;
+    if let Some(expiration_date) = amendment.expiration_date {
+        warranty.expiration_date = expiration_date;
+    }
+
+    if warranty.expiration_date <= warranty.purchase_date {
+        return Err(WarrantyError::InvalidDates);
+    }
+    if identity_changed {
+        check_serial_free(
+            &env,
+            &warranty.manufacturer,
+            &warranty.serial_number,
+            &warranty.product_name,
+            Some(warranty_id),
+        )?;
+    }
+    let skew = settings::get_purchase_date_skew(env.clone());
+    if warranty.purchase_date > current_time.saturating_add(skew) {
+        return Err(WarrantyError::InvalidDates);
+    }
+    let previous = warranty.status.clone();
+    warranty.status = effective_status(&env, &warranty);
+
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    if identity_changed {
+        forget_serial(&env, &original);
+        record_serial(&env, &warranty);
+    }
+
+    let mut changes = Vec::new(&env);
+    if warranty.product_name != original.product_name {
+        changes.push_back(HistoryDetails::ProductNameChanged(
+            original.product_name.clone(),
+            warranty.product_name.clone(),
+        ));
+    }
+    if warranty.serial_number != original.serial_number {
+        changes.push_back(HistoryDetails::SerialChanged(
+            original.serial_number.clone(),
+            warranty.serial_number.clone(),
+        ));
+    }
+    if warranty.purchase_date != original.purchase_date {
+        changes.push_back(HistoryDetails::PurchaseDateChanged(
+            original.purchase_date,
+            warranty.purchase_date,
+        ));
+    }
+    if warranty.expiration_date != previous_expiration {
+        changes.push_back(HistoryDetails::ExpirationChanged(
+            previous_expiration,
+            warranty.expiration_date,
+        ));
+    }
+    for details in changes.iter() {
+        record_history(
+            &env,
+            warranty_id,
+            &warranty.registrant,
+            symbol_short!("amend"),
+            details,
+        );
+    }
+    record_status_change(
+        &env,
+        &warranty,
+        &warranty.registrant,
+        symbol_short!("amend"),
+        previous.clone(),
+    );
+    publish_status_change(&env, &warranty, previous);
+
+    notify_change(&env, symbol_short!("amend"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn attach_metadata(
+    env: Env,
+    warranty_id: u64,
+    author: Address,
+    key: Symbol,
+    value: String,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "metadata")?;
+    let since = audit_tail(&env);
+
+    author.require_auth();
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    if author != warranty.owner && !is_warranty_manufacturer(&env, &author, &warranty) {
+        return Err(WarrantyError::Unauthorized);
+    }
+    if value.len() > MAX_METADATA_VALUE_LEN {
+        return Err(WarrantyError::InvalidInput);
+    }
+
+    let metadata_key = MetadataKey::Entries(warranty_id);
+    let mut metadata: Map<Symbol, String> = env
+        .storage()
+        .persistent()
+        .get(&metadata_key)
+        .unwrap_or(Map::new(&env));
+    if value.is_empty() {
+        metadata.remove(key);
+    } else {
+        if !metadata.contains_key(key.clone()) && metadata.len() >= MAX_METADATA_ENTRIES {
+            return Err(WarrantyError::LimitExceeded);
+        }
+        metadata.set(key, value);
+    }
+    if metadata.is_empty() {
+        env.storage().persistent().remove(&metadata_key);
+    } else {
+        env.storage().persistent().set(&metadata_key, &metadata);
+    }
+
+    notify_change(&env, symbol_short!("metadata"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn agent_update_status(
+    env: Env,
+    agent: Address,
+    warranty_id: u64,
+    status: WarrantyStatus,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "agents")?;
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+    require_agent(&env, &agent, &warranty)?;
+
+    apply_status(&env, &agent, warranty, status, StatusAuthority::Owner)?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn revoke_warranty(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    apply_status(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        WarrantyStatus::Revoked,
+        StatusAuthority::Owner,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_to_active(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    apply_status(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        WarrantyStatus::Active,
+        StatusAuthority::Owner,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn activate(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+    require_feature(&env, "deferred_activation")?;
+
+    let since = audit_tail(&env);
+
+    let mut warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    if warranty.activation_duration.is_none() {
+        return Err(WarrantyError::InvalidStatusTransition);
+    }
+    check_status_transition(
+        &warranty.status,
+        &WarrantyStatus::Active,
+        StatusAuthority::Owner,
+    )?;
+    let previous = warranty.status.clone();
+    start_deferred_coverage(&env, &mut warranty);
+    warranty.status = WarrantyStatus::Active;
+    warranty.last_modified_ledger = env.ledger().sequence();
+    save_warranty(&env, &warranty);
+    record_status_change(
+        &env,
+        &warranty,
+        &warranty.owner,
+        symbol_short!("activate"),
+        previous.clone(),
+    );
+    publish_status_change(&env, &warranty, previous);
+    unarchive_warranty(&env, &warranty.owner, warranty_id);
+
+    notify_change(&env, symbol_short!("activate"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn set_to_expired(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    apply_status(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        WarrantyStatus::Expired,
+        StatusAuthority::Owner,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn cancel_warranty(
+    env: Env,
+    warranty_id: u64,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    require_not_paused(&env)?;
+
+    let since = audit_tail(&env);
+
+    let warranty = require_warranty(&env, warranty_id)?;
+
+    warranty.owner.require_auth();
+    record_activity(&env, &warranty.owner);
+
+    apply_status(
+        &env,
+        &warranty.owner.clone(),
+        warranty,
+        WarrantyStatus::Cancelled,
+        StatusAuthority::Owner,
+    )?;
+
+    notify_change(&env, symbol_short!("status"), warranty_id, correlation_id);
+
+    Ok(receipt_since(&env, since))
+}
+
+pub(crate) fn reactivate_warranty(
+    env: Env,
+    warranty_id: u64,
+    manufacturer: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    set_status_as_manufacturer(
+        env,
+        warranty_id,
+        manufacturer,
+        WarrantyStatus::Active,
+        correlation_id,
+    )
+}
+
+pub(crate) fn suspend_warranty(
+    env: Env,
+    warranty_id: u64,
+    manufacturer: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    set_status_as_manufacturer(
+        env,
+        warranty_id,
+        manufacturer,
+        WarrantyStatus::Suspended,
+        correlation_id,
+    )
+}
+
+pub(crate) fn mark_replaced(
+    env: Env,
+    warranty_id: u64,
+    manufacturer: Address,
+    correlation_id: Option<BytesN<16>>,
+) -> Result<MutationReceipt, WarrantyError> {
+    set_status_as_manufacturer(
+        env,
+        warranty_id,
+        manufacturer,
+        WarrantyStatus::Replaced,
+        correlation_id,
+    )
+}
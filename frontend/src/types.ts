@@ -8,12 +8,19 @@ export interface WarrantyData {
   expiration_date: string;
   status: WarrantyStatus;
   created_at: string;
+  model_id?: string;
 }
 
 export enum WarrantyStatus {
   Active = "Active",
   Expired = "Expired",
   Revoked = "Revoked",
+  Pending = "Pending",
+  Suspended = "Suspended",
+  Voided = "Voided",
+  Cancelled = "Cancelled",
+  Replaced = "Replaced",
+  Recalled = "Recalled",
 }
 
 export interface ContractConfig {
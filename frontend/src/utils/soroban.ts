@@ -94,18 +94,6 @@ export class WarrantyTrackerClient {
         const errorStr =
           typeof error === "string" ? error : JSON.stringify(error);
 
-        if (
-          method === "update_status" &&
-          errorStr.includes("UnreachableCodeReached")
-        ) {
-          throw new Error(
-            `Failed to update warranty status: The contract simulation is failing due to an enum parameter encoding issue. ` +
-              `The contract code allows status changes, but Soroban's simulation cannot properly handle the enum parameter. ` +
-              `This is a known limitation with contracttype enums during simulation. ` +
-              `Please use the "Revoke Warranty" button for Revoked status, which uses a different method that works.`,
-          );
-        }
-
         // Extract more detailed error information for other methods
         let errorMessage = `Transaction simulation failed: ${JSON.stringify(
           error,
@@ -338,6 +326,7 @@ export class WarrantyTrackerClient {
     purchaseDate: string,
     expirationDate: string,
     signerAddress: string,
+    modelId?: string,
   ): Promise<string> {
     // Validate address format
     if (!owner || !owner.startsWith("G") || owner.length !== 56) {
@@ -380,14 +369,45 @@ export class WarrantyTrackerClient {
     }
 
     try {
-      const args = [
-        Address.fromString(owner).toScVal(),
-        xdr.ScVal.scvString(productName),
-        xdr.ScVal.scvString(serialNumber),
-        xdr.ScVal.scvString(manufacturer),
-        xdr.ScVal.scvU64(xdr.Uint64.fromString(purchaseTimestamp.toString())),
-        xdr.ScVal.scvU64(xdr.Uint64.fromString(expirationTimestamp.toString())),
-      ];
+      // WarrantyInput struct: map entries must be sorted by field name, and
+      // unset optional fields are encoded as void
+      const field = (name: string, val: xdr.ScVal) =>
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(name), val });
+      const input = xdr.ScVal.scvMap([
+        field("activation_duration", xdr.ScVal.scvVoid()),
+        field("batch_number", xdr.ScVal.scvVoid()),
+        field("category", xdr.ScVal.scvVoid()),
+        field("correlation_id", xdr.ScVal.scvVoid()),
+        field("coverage", xdr.ScVal.scvVoid()),
+        field(
+          "expiration_date",
+          xdr.ScVal.scvU64(
+            xdr.Uint64.fromString(expirationTimestamp.toString()),
+          ),
+        ),
+        field("manufacture_date", xdr.ScVal.scvVoid()),
+        field("manufacturer", xdr.ScVal.scvString(manufacturer)),
+        field(
+          "model_id",
+          modelId ? xdr.ScVal.scvString(modelId) : xdr.ScVal.scvVoid(),
+        ),
+        field("price", xdr.ScVal.scvVoid()),
+        field("product_name", xdr.ScVal.scvString(productName)),
+        field(
+          "purchase_date",
+          xdr.ScVal.scvU64(xdr.Uint64.fromString(purchaseTimestamp.toString())),
+        ),
+        field("receipt_hash", xdr.ScVal.scvVoid()),
+        field("region", xdr.ScVal.scvVoid()),
+        field("retailer", xdr.ScVal.scvVoid()),
+        field("serial_number", xdr.ScVal.scvString(serialNumber)),
+        field("terms_id", xdr.ScVal.scvVoid()),
+        field("transferable", xdr.ScVal.scvVoid()),
+        field("usage_limit", xdr.ScVal.scvVoid()),
+        field("usage_unit", xdr.ScVal.scvVoid()),
+      ]);
+
+      const args = [Address.fromString(owner).toScVal(), input];
 
       const result = await this.invokeContract(
         "register_warranty",
@@ -477,11 +497,14 @@ export class WarrantyTrackerClient {
                   warrantyData.status === "1" ||
                   warrantyData.status === "Expired"
                 ? WarrantyStatus.Expired
-                : WarrantyStatus.Revoked,
+                : Object.values(WarrantyStatus).includes(warrantyData.status)
+                  ? (warrantyData.status as WarrantyStatus)
+                  : WarrantyStatus.Revoked,
           created_at:
             warrantyData.created_at?.toString() ||
             warrantyData.createdAt?.toString() ||
             "",
+          model_id: warrantyData.model_id ?? undefined,
         };
 
         return data;
@@ -500,7 +523,8 @@ export class WarrantyTrackerClient {
         throw new Error("Invalid Stellar address format");
       }
 
-      const args = [Address.fromString(owner).toScVal()];
+      // No viewer: unsigned simulations list public warranties only
+      const args = [Address.fromString(owner).toScVal(), xdr.ScVal.scvVoid()];
       const simResponse = await this.rpc.simulateTransaction(
         new TransactionBuilder(
           new Account(
@@ -573,6 +597,7 @@ export class WarrantyTrackerClient {
     const args = [
       xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId)),
       Address.fromString(newOwner).toScVal(),
+      xdr.ScVal.scvVoid(),
     ];
     await this.invokeContract("transfer_ownership", args, signerAddress);
   }
@@ -598,12 +623,18 @@ export class WarrantyTrackerClient {
   }
 
   async setToActive(warrantyId: string, signerAddress: string): Promise<void> {
-    const args = [xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId))];
+    const args = [
+      xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId)),
+      xdr.ScVal.scvVoid(),
+    ];
     await this.invokeContract("set_to_active", args, signerAddress);
   }
 
   async setToExpired(warrantyId: string, signerAddress: string): Promise<void> {
-    const args = [xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId))];
+    const args = [
+      xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId)),
+      xdr.ScVal.scvVoid(),
+    ];
     await this.invokeContract("set_to_expired", args, signerAddress);
   }
 
@@ -611,7 +642,10 @@ export class WarrantyTrackerClient {
     warrantyId: string,
     signerAddress: string,
   ): Promise<void> {
-    const args = [xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId))];
+    const args = [
+      xdr.ScVal.scvU64(xdr.Uint64.fromString(warrantyId)),
+      xdr.ScVal.scvVoid(),
+    ];
     await this.invokeContract("revoke_warranty", args, signerAddress);
   }
 
@@ -643,6 +677,11 @@ export function getStatusColor(status: WarrantyStatus): string {
       return "bg-red-100 text-red-800 border-red-200";
     case WarrantyStatus.Revoked:
       return "bg-gray-100 text-gray-800 border-gray-200";
+    case WarrantyStatus.Pending:
+    case WarrantyStatus.Suspended:
+      return "bg-yellow-100 text-yellow-800 border-yellow-200";
+    case WarrantyStatus.Recalled:
+      return "bg-orange-100 text-orange-800 border-orange-200";
     default:
       return "bg-gray-100 text-gray-800 border-gray-200";
   }
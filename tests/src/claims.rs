@@ -0,0 +1,84 @@
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token, Address, Env, String,
+};
+use warranty_tracker::{
+    ClaimCategory, ClaimStatus, DeploymentConfig, WarrantyError, WarrantyInput, WarrantyTracker,
+    WarrantyTrackerClient,
+};
+
+use crate::contracts::{EventLog, EventLogClient};
+
+#[test]
+fn test_claim_settles_from_escrow_to_owner() {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let manufacturer = String::from_str(&env, "VeloWorks");
+
+    let tracker = WarrantyTrackerClient::new(
+        &env,
+        &env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>)),
+    );
+    let events = EventLogClient::new(&env, &env.register(EventLog, ()));
+    tracker.add_hook(&events.address);
+    tracker.set_namespace_issuer(&manufacturer, &Some(issuer.clone()));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&issuer, &1_000);
+    tracker.fund_claims_escrow(&issuer, &token_address, &1_000, &None);
+    assert_eq!(token.balance(&tracker.address), 1_000);
+
+    let warranty_id = tracker.register_warranty(
+        &owner,
+        &WarrantyInput::new(
+            String::from_str(&env, "E-bike"),
+            String::from_str(&env, "EB-0042"),
+            manufacturer,
+            1704000000,
+            1767225600,
+        ),
+    );
+    let claim_id = tracker
+        .file_claim(
+            &warranty_id,
+            &ClaimCategory::Defect,
+            &String::from_str(&env, "Motor cuts out"),
+            &1704067200,
+            &Some(400),
+            &None,
+        )
+        .claim_id;
+
+    // Only part of the request is approved, and only that is paid
+    tracker.approve_claim(&issuer, &claim_id, &Some(300), &None, &None);
+    assert_eq!(token.balance(&owner), 0);
+    tracker.settle_claim(&claim_id, &token_address, &None);
+
+    assert_eq!(token.balance(&owner), 300);
+    assert_eq!(token.balance(&tracker.address), 700);
+    assert_eq!(tracker.get_escrow_balance(&issuer, &token_address), 700);
+    let claim = tracker.get_claims(&warranty_id).get(0).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Resolved);
+    assert_eq!(claim.approved_amount, Some(300));
+    assert_eq!(claim.paid_amount, 300);
+    assert_eq!(
+        events.seen().last(),
+        Some((symbol_short!("settle"), warranty_id))
+    );
+
+    // A resolved claim cannot be paid twice
+    assert_eq!(
+        tracker.try_settle_claim(&claim_id, &token_address, &None),
+        Err(Ok(WarrantyError::InvalidStatusTransition))
+    );
+    assert_eq!(token.balance(&owner), 300);
+}
@@ -0,0 +1,45 @@
+//! Counterpart contracts deployed alongside the tracker.
+
+use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Symbol, Vec};
+use warranty_tracker::{WarrantyRef, WarrantyTrackerClient};
+
+/// Minimal marketplace settling a product sale in tokens and moving the
+/// warranty to the buyer in the same transaction
+#[contract]
+pub struct Marketplace;
+
+#[contractimpl]
+impl Marketplace {
+    pub fn buy(env: Env, warranty: WarrantyRef, token: Address, buyer: Address, price: i128) {
+        buyer.require_auth();
+
+        let seller = warranty.resolve(&env).expect("warranty not found").owner;
+
+        token::Client::new(&env, &token).transfer(&buyer, &seller, &price);
+        WarrantyTrackerClient::new(&env, &warranty.contract).transfer_ownership(
+            &warranty.id,
+            &buyer,
+            &None,
+        );
+    }
+}
+
+/// Hook recording every notification it receives from the tracker
+#[contract]
+pub struct EventLog;
+
+#[contractimpl]
+impl EventLog {
+    pub fn on_warranty_event(env: Env, event_type: Symbol, warranty_id: u64) {
+        let mut seen = Self::seen(env.clone());
+        seen.push_back((event_type, warranty_id));
+        env.storage().instance().set(&symbol_short!("seen"), &seen);
+    }
+
+    pub fn seen(env: Env) -> Vec<(Symbol, u64)> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("seen"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
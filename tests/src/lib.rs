@@ -0,0 +1,8 @@
+//! End-to-end tests that deploy the warranty tracker next to the contracts it
+//! interacts with and exercise real cross-contract calls.
+#![no_std]
+#![cfg(test)]
+
+mod claims;
+mod contracts;
+mod marketplace;
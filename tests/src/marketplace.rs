@@ -0,0 +1,155 @@
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env, String,
+};
+use warranty_tracker::{
+    DeploymentConfig, WarrantyError, WarrantyInput, WarrantyRef, WarrantyTracker,
+    WarrantyTrackerClient,
+};
+
+use crate::contracts::{EventLog, EventLogClient, Marketplace, MarketplaceClient};
+
+struct Setup<'a> {
+    env: Env,
+    tracker: WarrantyTrackerClient<'a>,
+    marketplace: MarketplaceClient<'a>,
+    events: EventLogClient<'a>,
+    token: token::Client<'a>,
+    seller: Address,
+    buyer: Address,
+    warranty_id: u64,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.ledger().set_timestamp(1704067200);
+    // The seller authorizes the warranty transfer inside the marketplace call
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let tracker = WarrantyTrackerClient::new(
+        &env,
+        &env.register(WarrantyTracker, (&admin, None::<DeploymentConfig>)),
+    );
+    let marketplace = MarketplaceClient::new(&env, &env.register(Marketplace, ()));
+    let events = EventLogClient::new(&env, &env.register(EventLog, ()));
+
+    let token_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+
+    tracker.add_hook(&events.address);
+
+    let warranty_id = tracker.register_warranty(
+        &seller,
+        &WarrantyInput::new(
+            String::from_str(&env, "E-bike"),
+            String::from_str(&env, "EB-0042"),
+            String::from_str(&env, "VeloWorks"),
+            1704000000,
+            1767225600,
+        ),
+    );
+
+    Setup {
+        env,
+        tracker,
+        marketplace,
+        events,
+        token,
+        seller,
+        buyer,
+        warranty_id,
+    }
+}
+
+#[test]
+fn test_marketplace_sale_moves_payment_and_warranty() {
+    let s = setup();
+
+    s.marketplace.buy(
+        &WarrantyRef::new(s.tracker.address.clone(), s.warranty_id),
+        &s.token.address,
+        &s.buyer,
+        &400,
+    );
+
+    assert_eq!(s.token.balance(&s.buyer), 600);
+    assert_eq!(s.token.balance(&s.seller), 400);
+
+    assert_eq!(
+        s.tracker.get_warranty(&s.warranty_id).unwrap().owner,
+        s.buyer
+    );
+    assert!(s
+        .tracker
+        .get_warranties_by_owner(&s.seller, &None)
+        .is_empty());
+    assert_eq!(
+        s.tracker.get_warranties_by_owner(&s.buyer, &None),
+        vec![&s.env, s.warranty_id]
+    );
+
+    assert_eq!(
+        s.events.seen(),
+        vec![
+            &s.env,
+            (symbol_short!("register"), s.warranty_id),
+            (symbol_short!("transfer"), s.warranty_id),
+        ]
+    );
+}
+
+#[test]
+fn test_rejected_transfer_rolls_back_payment() {
+    let s = setup();
+
+    // The seller only allows transfers to an address the buyer is not
+    let partner = Address::generate(&s.env);
+    s.tracker
+        .set_transfer_allowlist(&s.seller, &vec![&s.env, partner]);
+
+    let result = s.marketplace.try_buy(
+        &WarrantyRef::new(s.tracker.address.clone(), s.warranty_id),
+        &s.token.address,
+        &s.buyer,
+        &400,
+    );
+    assert_eq!(result, Err(Ok(WarrantyError::TransferNotAllowed.into())));
+
+    assert_eq!(s.token.balance(&s.buyer), 1_000);
+    assert_eq!(s.token.balance(&s.seller), 0);
+    assert_eq!(
+        s.tracker.get_warranty(&s.warranty_id).unwrap().owner,
+        s.seller
+    );
+}
+
+#[test]
+fn test_resolve_ref_held_by_another_deployment() {
+    let s = setup();
+
+    let other_admin = Address::generate(&s.env);
+    let other = WarrantyTrackerClient::new(
+        &s.env,
+        &s.env
+            .register(WarrantyTracker, (&other_admin, None::<DeploymentConfig>)),
+    );
+    let warranty_ref = s.tracker.get_warranty_ref(&s.warranty_id).unwrap();
+
+    let summary = other.resolve_warranty_ref(&warranty_ref).unwrap();
+    assert_eq!(summary.warranty_ref, warranty_ref);
+    assert_eq!(summary.owner, s.seller);
+    assert_eq!(summary.product_name, String::from_str(&s.env, "E-bike"));
+
+    assert_eq!(
+        other.resolve_warranty_ref(&WarrantyRef::new(s.tracker.address.clone(), 999)),
+        None
+    );
+}